@@ -15,6 +15,7 @@
 use std::borrow::Cow;
 use std::cell::OnceCell;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
@@ -34,6 +35,8 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 use bstr::ByteVec as _;
+use chrono::DateTime;
+use chrono::Local;
 use chrono::TimeZone;
 use clap::builder::MapValueParser;
 use clap::builder::NonEmptyStringValueParser;
@@ -65,6 +68,9 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::dsl_util::AliasDeclarationParser;
+use jj_lib::dsl_util::AliasesMap;
+use jj_lib::eol::EolConversionMode;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
@@ -83,6 +89,8 @@ use jj_lib::op_store::WorkspaceId;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
+use jj_lib::opset::OpsetAliasesMap;
+use jj_lib::opset::OpsetParseContext;
 use jj_lib::repo::merge_factories_map;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
@@ -163,6 +171,8 @@ use crate::diff_util::DiffRenderer;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::PlainTextFormatter;
+use crate::hooks::run_hook;
+use crate::hooks::Hook;
 use crate::merge_tools::DiffEditor;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
@@ -177,6 +187,7 @@ use crate::template_parser::TemplateDiagnostics;
 use crate::templater::PropertyPlaceholder;
 use crate::templater::TemplateRenderer;
 use crate::text_util;
+use crate::ui::format_error_with_sources;
 use crate::ui::ColorChoice;
 use crate::ui::Ui;
 
@@ -614,6 +625,21 @@ impl CommandHelper {
         )
     }
 
+    /// Resolves the given operation set expression without loading a repo.
+    ///
+    /// Revsets (e.g. in `touched()`) can't be resolved in this context.
+    pub fn resolve_op_for_load(
+        &self,
+        ui: &Ui,
+        repo_loader: &RepoLoader,
+        op_str: &str,
+    ) -> Result<Operation, CommandError> {
+        let aliases_map = load_opset_aliases(ui, self.settings().config())?;
+        let now = now_from_settings(self.settings());
+        let context = OpsetParseContext::new(&aliases_map, now.into(), None);
+        Ok(op_walk::resolve_op_for_load(repo_loader, &context, op_str)?)
+    }
+
     /// Resolves the current operation from the command-line argument.
     ///
     /// If no `--at-operation` is specified, the head operations will be
@@ -625,7 +651,7 @@ impl CommandHelper {
         repo_loader: &RepoLoader,
     ) -> Result<Operation, CommandError> {
         if let Some(op_str) = &self.data.global_args.at_operation {
-            Ok(op_walk::resolve_op_for_load(repo_loader, op_str)?)
+            self.resolve_op_for_load(ui, repo_loader, op_str)
         } else {
             op_heads_store::resolve_op_heads(
                 repo_loader.op_heads_store().as_ref(),
@@ -768,12 +794,14 @@ pub struct WorkspaceCommandEnvironment {
     command: CommandHelper,
     settings: UserSettings,
     revset_aliases_map: RevsetAliasesMap,
+    opset_aliases_map: OpsetAliasesMap,
     template_aliases_map: TemplateAliasesMap,
     path_converter: RepoPathUiConverter,
     workspace_id: WorkspaceId,
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    eol_conversion_mode: EolConversionMode,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -781,6 +809,7 @@ impl WorkspaceCommandEnvironment {
     fn new(ui: &Ui, command: &CommandHelper, workspace: &Workspace) -> Result<Self, CommandError> {
         let settings = workspace.settings();
         let revset_aliases_map = revset_util::load_revset_aliases(ui, settings.config())?;
+        let opset_aliases_map = load_opset_aliases(ui, settings.config())?;
         let template_aliases_map = load_template_aliases(ui, settings.config())?;
         let path_converter = RepoPathUiConverter::Fs {
             cwd: command.cwd().to_owned(),
@@ -790,12 +819,14 @@ impl WorkspaceCommandEnvironment {
             command: command.clone(),
             settings: settings.clone(),
             revset_aliases_map,
+            opset_aliases_map,
             template_aliases_map,
             path_converter,
             workspace_id: workspace.workspace_id().to_owned(),
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: settings.get("ui.conflict-marker-style")?,
+            eol_conversion_mode: settings.get("working-copy.eol-conversion")?,
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
@@ -815,13 +846,7 @@ impl WorkspaceCommandEnvironment {
             path_converter: &self.path_converter,
             workspace_id: &self.workspace_id,
         };
-        let now = if let Some(timestamp) = self.settings.commit_timestamp() {
-            chrono::Local
-                .timestamp_millis_opt(timestamp.timestamp.0)
-                .unwrap()
-        } else {
-            chrono::Local::now()
-        };
+        let now = now_from_settings(&self.settings);
         RevsetParseContext::new(
             &self.revset_aliases_map,
             self.settings.user_email(),
@@ -831,6 +856,20 @@ impl WorkspaceCommandEnvironment {
         )
     }
 
+    /// Creates opset parse context. Revsets in the opset expression will be
+    /// parsed by using the given `revset_parse_context`.
+    pub(crate) fn opset_parse_context<'a>(
+        &'a self,
+        revset_parse_context: &'a RevsetParseContext<'a>,
+    ) -> OpsetParseContext<'a> {
+        let now = now_from_settings(&self.settings);
+        OpsetParseContext::new(
+            &self.opset_aliases_map,
+            now.into(),
+            Some(revset_parse_context),
+        )
+    }
+
     /// Creates fresh new context which manages cache of short commit/change ID
     /// prefixes. New context should be created per repo view (or operation.)
     pub fn new_id_prefix_context(&self) -> IdPrefixContext {
@@ -858,6 +897,12 @@ impl WorkspaceCommandEnvironment {
         self.conflict_marker_style
     }
 
+    /// User-configured line ending conversion for files without
+    /// `.gitattributes`
+    pub fn eol_conversion_mode(&self) -> EolConversionMode {
+        self.eol_conversion_mode
+    }
+
     fn load_immutable_heads_expression(
         &self,
         ui: &Ui,
@@ -1214,6 +1259,7 @@ impl WorkspaceCommandHelper {
     pub fn checkout_options(&self) -> CheckoutOptions {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            eol_conversion_mode: self.env.eol_conversion_mode(),
         }
     }
 
@@ -1374,6 +1420,7 @@ to the current parents may contain changes from multiple commits.
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            eol_conversion_mode: self.env.eol_conversion_mode(),
         })
     }
 
@@ -1537,7 +1584,9 @@ to the current parents may contain changes from multiple commits.
     }
 
     pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
-        op_walk::resolve_op_with_repo(self.repo(), op_str)
+        let revset_parse_context = self.env.revset_parse_context();
+        let opset_parse_context = self.env.opset_parse_context(&revset_parse_context);
+        op_walk::resolve_op_with_repo(self.repo(), &opset_parse_context, op_str)
     }
 
     /// Resolve a revset to a single revision. Return an error if the revset is
@@ -1923,6 +1972,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 .snapshot(&options)
                 .map_err(snapshot_command_error)?
         };
+        let mut snapshot_commit = None;
         if new_tree_id != *wc_commit.tree_id() {
             let mut tx =
                 start_repo_transaction(&self.user_repo.repo, self.env.command.string_args());
@@ -1960,15 +2010,54 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 .commit("snapshot working copy")
                 .map_err(snapshot_command_error)?;
             self.user_repo = ReadonlyUserRepo::new(repo);
+            snapshot_commit = Some(commit);
         }
         locked_ws
             .finish(self.user_repo.repo.op_id().clone())
             .map_err(snapshot_command_error)?;
         print_snapshot_stats(ui, &stats, &self.env.path_converter)
             .map_err(snapshot_command_error)?;
+        if let Some(commit) = snapshot_commit {
+            self.run_post_snapshot_hook(ui, &commit)
+                .map_err(snapshot_command_error)?;
+        }
         Ok(stats)
     }
 
+    /// Runs the `post-snapshot` hook for the recorded working-copy `commit`.
+    /// The snapshot has already been committed, so a failing hook is only
+    /// reported as a warning.
+    fn run_post_snapshot_hook(&self, ui: &Ui, commit: &Commit) -> io::Result<()> {
+        let change_id = commit.change_id().reverse_hex();
+        let commit_id = commit.id().hex();
+        let tree_id = commit
+            .tree_id()
+            .to_merge()
+            .iter()
+            .map(|id| id.hex())
+            .join(",");
+        let hook_variables = HashMap::from([
+            ("change_id", change_id.as_str()),
+            ("commit_id", commit_id.as_str()),
+            ("tree_id", tree_id.as_str()),
+        ]);
+        let result = run_hook(
+            &self.env.settings,
+            Hook::PostSnapshot,
+            self.workspace_root(),
+            &hook_variables,
+            b"",
+        );
+        if let Err(err) = result {
+            writeln!(
+                ui.warning_default(),
+                "{}",
+                format_error_with_sources(err.error.as_ref())
+            )?;
+        }
+        Ok(())
+    }
+
     fn update_working_copy(
         &mut self,
         ui: &Ui,
@@ -2002,6 +2091,16 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         if let Some(stats) = stats {
             print_checkout_stats(ui, stats, new_commit)?;
         }
+        if self.settings().get_bool("git.update-submodules")? {
+            let old_tree = maybe_old_commit.map(|commit| commit.tree()).transpose()?;
+            crate::git_util::update_submodules(
+                ui,
+                &self.user_repo.repo,
+                self.workspace.workspace_root(),
+                old_tree.as_ref(),
+                &new_commit.tree()?,
+            )?;
+        }
         if Some(new_commit) != maybe_old_commit {
             if let Some(mut formatter) = ui.status_formatter() {
                 let conflicts = new_commit.tree()?.conflicts().collect_vec();
@@ -2850,8 +2949,28 @@ pub fn load_template_aliases(
     ui: &Ui,
     stacked_config: &StackedConfig,
 ) -> Result<TemplateAliasesMap, CommandError> {
-    let table_name = ConfigNamePathBuf::from_iter(["template-aliases"]);
-    let mut aliases_map = TemplateAliasesMap::new();
+    load_aliases_map(ui, stacked_config, "template-aliases")
+}
+
+pub fn load_opset_aliases(
+    ui: &Ui,
+    stacked_config: &StackedConfig,
+) -> Result<OpsetAliasesMap, CommandError> {
+    load_aliases_map(ui, stacked_config, "opset-aliases")
+}
+
+/// Loads aliases from the `table_name` table of all config layers.
+fn load_aliases_map<P>(
+    ui: &Ui,
+    stacked_config: &StackedConfig,
+    table_name: &str,
+) -> Result<AliasesMap<P, String>, CommandError>
+where
+    P: AliasDeclarationParser + Default,
+    P::Error: ToString,
+{
+    let table_name = ConfigNamePathBuf::from_iter([table_name]);
+    let mut aliases_map = AliasesMap::<P, String>::new();
     // Load from all config layers in order. 'f(x)' in default layer should be
     // overridden by 'f(a)' in user.
     for layer in stacked_config.layers() {
@@ -2883,6 +3002,16 @@ pub fn load_template_aliases(
     Ok(aliases_map)
 }
 
+/// Current time to be used in date patterns. It can be overridden by the
+/// `debug.commit-timestamp` setting.
+fn now_from_settings(settings: &UserSettings) -> DateTime<Local> {
+    if let Some(timestamp) = settings.commit_timestamp() {
+        Local.timestamp_millis_opt(timestamp.timestamp.0).unwrap()
+    } else {
+        Local::now()
+    }
+}
+
 /// Helper to reformat content of log-like commands.
 #[derive(Clone, Debug)]
 pub struct LogContentFormat {
@@ -23,6 +23,7 @@ use std::sync::Arc;
 use itertools::Itertools as _;
 use jj_lib::absorb::AbsorbError;
 use jj_lib::backend::BackendError;
+use jj_lib::bisect::BisectionError;
 use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
@@ -37,6 +38,10 @@ use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset::OpsetParseError;
+use jj_lib::opset::OpsetParseErrorKind;
+use jj_lib::patch::PatchApplyError;
+use jj_lib::patch::PatchParseError;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
@@ -64,6 +69,7 @@ use crate::description_util::TextEditError;
 use crate::diff_util::DiffRenderError;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
+use crate::hooks::HookError;
 use crate::merge_tools::ConflictResolveError;
 use crate::merge_tools::DiffEditError;
 use crate::merge_tools::MergeToolConfigError;
@@ -314,6 +320,10 @@ impl From<BackendError> for CommandError {
     fn from(err: BackendError) -> Self {
         match &err {
             BackendError::Unsupported(_) => user_error(err),
+            BackendError::FetchObject { .. } => user_error_with_hint(
+                err,
+                "The repository is a partial clone. Check your network connection to the remote.",
+            ),
             _ => internal_error_with_message("Unexpected error from backend", err),
         }
     }
@@ -376,6 +386,10 @@ impl From<OpsetEvaluationError> for CommandError {
             OpsetEvaluationError::OpHeadResolution(err) => err.into(),
             OpsetEvaluationError::OpHeadsStore(err) => err.into(),
             OpsetEvaluationError::OpStore(err) => err.into(),
+            OpsetEvaluationError::OpsetParse(err) => err.into(),
+            OpsetEvaluationError::RepoLoader(err) => err.into(),
+            OpsetEvaluationError::RevsetResolution(err) => err.into(),
+            OpsetEvaluationError::RevsetEvaluation(err) => err.into(),
         }
     }
 }
@@ -463,6 +477,12 @@ impl From<TextEditError> for CommandError {
     }
 }
 
+impl From<HookError> for CommandError {
+    fn from(err: HookError) -> Self {
+        user_error(err)
+    }
+}
+
 impl From<TempTextEditError> for CommandError {
     fn from(err: TempTextEditError) -> Self {
         let hint = err.path.as_ref().map(|path| {
@@ -485,6 +505,7 @@ mod git {
     use jj_lib::git::GitPushError;
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::UnexpectedGitBackendError;
+    use jj_lib::git_lfs::LfsError;
 
     use super::*;
 
@@ -538,6 +559,7 @@ jj currently does not support partial clones. To use jj with this repository, tr
             match err {
                 GitFetchError::NoSuchRemote(_) => user_error(err),
                 GitFetchError::InvalidBranchPattern(_) => user_error(err),
+                GitFetchError::DeepenUnsupported => user_error(err),
                 GitFetchError::InternalGitError(err) => map_git2_error(err),
                 GitFetchError::Subprocess(_) => user_error(err),
             }
@@ -549,10 +571,17 @@ jj currently does not support partial clones. To use jj with this repository, tr
             match err {
                 GitFetchPrepareError::Git2(err) => map_git2_error(err),
                 GitFetchPrepareError::UnexpectedBackend(_) => user_error(err),
+                GitFetchPrepareError::Backend(err) => err.into(),
             }
         }
     }
 
+    impl From<LfsError> for CommandError {
+        fn from(err: LfsError) -> Self {
+            user_error_with_message("Git LFS operation failed", err)
+        }
+    }
+
     impl From<GitPushError> for CommandError {
         fn from(err: GitPushError) -> Self {
             match err {
@@ -567,7 +596,7 @@ jj currently does not support partial clones. To use jj with this repository, tr
                     "Try fetching from the remote, then make the bookmark point to where you want \
                      it to be, and push again.",
                 ),
-                GitPushError::RefUpdateRejected(_) => user_error(err),
+                GitPushError::AtomicPushUnsupported => user_error(err),
                 GitPushError::InternalGitError(err) => map_git2_error(err),
                 GitPushError::Subprocess(_) => user_error(err),
                 GitPushError::UnexpectedBackend(_) => user_error(err),
@@ -651,6 +680,16 @@ impl From<RevsetParseError> for CommandError {
     }
 }
 
+impl From<OpsetParseError> for CommandError {
+    fn from(err: OpsetParseError) -> Self {
+        let hint = opset_parse_error_hint(&err);
+        let mut cmd_err =
+            user_error_with_message(format!("Failed to parse opset: {}", err.kind()), err);
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
 impl From<RevsetResolutionError> for CommandError {
     fn from(err: RevsetResolutionError) -> Self {
         let hint = revset_resolution_error_hint(&err);
@@ -721,6 +760,32 @@ impl From<AbsorbError> for CommandError {
     }
 }
 
+impl From<BisectionError> for CommandError {
+    fn from(err: BisectionError) -> Self {
+        match err {
+            BisectionError::RevsetEvaluation(err) => err.into(),
+            err @ (BisectionError::NoBadCommit | BisectionError::GoodDescendantOfBad { .. }) => {
+                user_error(err)
+            }
+        }
+    }
+}
+
+impl From<PatchParseError> for CommandError {
+    fn from(err: PatchParseError) -> Self {
+        user_error_with_message("Failed to parse patch", err)
+    }
+}
+
+impl From<PatchApplyError> for CommandError {
+    fn from(err: PatchApplyError) -> Self {
+        match err {
+            PatchApplyError::Backend(err) => err.into(),
+            err @ PatchApplyError::NotAFile { .. } => user_error(err),
+        }
+    }
+}
+
 fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
     let source = err.source()?;
     if let Some(source) = source.downcast_ref() {
@@ -729,6 +794,8 @@ fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
         file_pattern_parse_error_hint(source)
     } else if let Some(source) = source.downcast_ref() {
         fileset_parse_error_hint(source)
+    } else if let Some(source) = source.downcast_ref() {
+        opset_parse_error_hint(source)
     } else if let Some(source) = source.downcast_ref() {
         revset_parse_error_hint(source)
     } else if let Some(source) = source.downcast_ref() {
@@ -799,6 +866,20 @@ fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
     }
 }
 
+fn opset_parse_error_hint(err: &OpsetParseError) -> Option<String> {
+    // Only for the bottom error, which is usually the root cause
+    let bottom_err = iter::successors(Some(err), |e| e.origin()).last().unwrap();
+    match bottom_err.kind() {
+        OpsetParseErrorKind::NoSuchFunction {
+            name: _,
+            candidates,
+        } => format_similarity_hint(candidates),
+        OpsetParseErrorKind::InvalidFunctionArguments { .. }
+        | OpsetParseErrorKind::Expression(_) => find_source_parse_error_hint(bottom_err),
+        _ => None,
+    }
+}
+
 fn revset_parse_error_hint(err: &RevsetParseError) -> Option<String> {
     // Only for the bottom error, which is usually the root cause
     let bottom_err = iter::successors(Some(err), |e| e.origin()).last().unwrap();
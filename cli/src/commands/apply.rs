@@ -0,0 +1,145 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::patch::apply_patch;
+use jj_lib::patch::parse_patches;
+use jj_lib::patch::Patch;
+use jj_lib::patch::PatchParseError;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::merge_commit_trees;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Apply patches as new commits
+///
+/// The patches can be email messages in an mbox file, such as the output of
+/// `jj git format-patch` or `git format-patch --stdout`, or plain unified
+/// diffs. One commit is created for each patch. The author, date, and
+/// description are taken from the email headers if present.
+///
+/// If a hunk doesn't apply cleanly, the file is left in a conflicted state
+/// instead, which can be resolved later with `jj resolve`.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ApplyArgs {
+    /// Patch files to apply, or `-` to read from standard input
+    ///
+    /// Patches are read from standard input if no files are specified.
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    paths: Vec<PathBuf>,
+    /// The revision(s) to apply the patches on top of
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    destination: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut patches = vec![];
+    if args.paths.is_empty() {
+        patches.extend(parse_input(&read_input(None)?)?);
+    } else {
+        for path in &args.paths {
+            let path = (path != Path::new("-")).then(|| command.cwd().join(path));
+            patches.extend(parse_input(&read_input(path.as_deref())?)?);
+        }
+    }
+    let mut parents = vec![];
+    for revision_str in &args.destination {
+        let destination = workspace_command.resolve_single_rev(ui, revision_str)?;
+        parents.push(destination);
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let mut tree = merge_commit_trees(tx.repo(), &parents)?;
+    let mut new_commits = vec![];
+    for patch in &patches {
+        let result = apply_patch(tx.repo().store(), &tree, patch)?;
+        let new_parent_ids = parents.iter().map(|commit| commit.id().clone()).collect();
+        let mut commit_builder = tx
+            .repo_mut()
+            .new_commit(new_parent_ids, result.tree.id())
+            .set_description(&patch.description);
+        if let Some(author) = &patch.author {
+            commit_builder = commit_builder.set_author(author.clone());
+        }
+        let new_commit = commit_builder.write()?;
+        parents = vec![new_commit.clone()];
+        tree = result.tree;
+        new_commits.push((new_commit, result.conflicted_paths));
+    }
+
+    for (new_commit, conflicted_paths) in &new_commits {
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "Applied ")?;
+            tx.write_commit_summary(formatter.as_mut(), new_commit)?;
+            writeln!(formatter)?;
+        }
+        for path in conflicted_paths {
+            writeln!(
+                ui.warning_default(),
+                "Patch did not apply cleanly to {}",
+                tx.base_workspace_helper().format_file_path(path)
+            )?;
+        }
+    }
+    let tx_description = if new_commits.len() == 1 {
+        "apply 1 patch".to_owned()
+    } else {
+        format!("apply {} patches", new_commits.len())
+    };
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
+
+fn parse_input(content: &[u8]) -> Result<Vec<Patch>, CommandError> {
+    parse_patches(content).map_err(|err| match err {
+        PatchParseError::Empty => user_error("No patches found in input"),
+        err => err.into(),
+    })
+}
+
+fn read_input(path: Option<&Path>) -> Result<Vec<u8>, CommandError> {
+    let mut content = vec![];
+    if let Some(path) = path {
+        content = std::fs::read(path).map_err(|err| {
+            user_error_with_message(format!("Failed to read {}", path.display()), err)
+        })?;
+    } else {
+        io::stdin().read_to_end(&mut content)?;
+    }
+    Ok(content)
+}
@@ -0,0 +1,66 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+
+use super::resolve_marked_commits;
+use super::update_bisection;
+use super::BisectMark;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Mark revisions as good, bad, or skipped
+///
+/// If no revisions are given, the revision currently being evaluated is
+/// marked.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectMarkArgs {
+    /// The revisions to mark
+    #[arg(
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+pub fn cmd_bisect_mark(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectMarkArgs,
+    mark: BisectMark,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if !workspace_command.repo().view().bisect().is_active() {
+        return Err(user_error_with_hint(
+            "No bisection in progress",
+            "Use `jj bisect start` to start one.",
+        ));
+    }
+    let commit_ids = resolve_marked_commits(ui, &workspace_command, &args.revisions)?;
+    update_bisection(
+        ui,
+        &mut workspace_command,
+        format!(
+            "bisect: mark {} revisions as {}",
+            commit_ids.len(),
+            mark.as_str()
+        ),
+        |bisector| mark.apply(bisector, commit_ids),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,220 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod mark;
+mod reset;
+mod run;
+mod start;
+
+use std::io::Write as _;
+
+use clap::Subcommand;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Bisector;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use self::mark::cmd_bisect_mark;
+use self::mark::BisectMarkArgs;
+use self::reset::cmd_bisect_reset;
+use self::reset::BisectResetArgs;
+use self::run::cmd_bisect_run;
+use self::run::BisectRunArgs;
+use self::start::cmd_bisect_start;
+use self::start::BisectStartArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Find the first revision that introduced a change by binary search
+///
+/// Start by marking a revision that has the change as bad, and optionally a
+/// revision that doesn't have it as good. The range between them is then
+/// narrowed by checking out a revision in the middle of it on top of a new
+/// working-copy commit, which you mark as good or bad until the first bad
+/// revision is found. The root commit is always considered good.
+///
+/// The bisection state is recorded in the operation log, so `jj undo` reverts
+/// the last mark.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum BisectCommand {
+    /// Mark revisions as bad, meaning that they have the change
+    Bad(BisectMarkArgs),
+    /// Mark revisions as good, meaning that they don't have the change
+    Good(BisectMarkArgs),
+    Reset(BisectResetArgs),
+    Run(BisectRunArgs),
+    /// Mark revisions as skipped, meaning that they can't be evaluated
+    Skip(BisectMarkArgs),
+    Start(BisectStartArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &BisectCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        BisectCommand::Bad(args) => cmd_bisect_mark(ui, command, args, BisectMark::Bad),
+        BisectCommand::Good(args) => cmd_bisect_mark(ui, command, args, BisectMark::Good),
+        BisectCommand::Reset(args) => cmd_bisect_reset(ui, command, args),
+        BisectCommand::Run(args) => cmd_bisect_run(ui, command, args),
+        BisectCommand::Skip(args) => cmd_bisect_mark(ui, command, args, BisectMark::Skip),
+        BisectCommand::Start(args) => cmd_bisect_start(ui, command, args),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    fn as_str(self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skipped",
+        }
+    }
+
+    fn apply(self, bisector: &mut Bisector, commit_ids: Vec<CommitId>) {
+        match self {
+            BisectMark::Good => bisector.mark_good(commit_ids),
+            BisectMark::Bad => bisector.mark_bad(commit_ids),
+            BisectMark::Skip => bisector.mark_skipped(commit_ids),
+        }
+    }
+}
+
+/// Returns the commit the bisection currently asks to evaluate, if any.
+fn current_candidate(
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<Option<CommitId>, CommandError> {
+    let repo = workspace_command.repo();
+    let state = repo.view().bisect();
+    if !state.is_active() || state.bad_ids.is_empty() {
+        return Ok(None);
+    }
+    match Bisector::new(repo.as_ref(), state.clone()).next_step()? {
+        BisectionStep::Evaluate { commit_id, .. } => Ok(Some(commit_id)),
+        _ => Ok(None),
+    }
+}
+
+/// Resolves the revisions to mark, defaulting to the commit currently being
+/// evaluated.
+fn resolve_marked_commits(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    revisions: &[RevisionArg],
+) -> Result<Vec<CommitId>, CommandError> {
+    if !revisions.is_empty() {
+        return Ok(workspace_command
+            .parse_union_revsets(ui, revisions)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?);
+    }
+    match current_candidate(workspace_command)? {
+        Some(commit_id) => Ok(vec![commit_id]),
+        None => Err(user_error_with_hint(
+            "No revision is being evaluated",
+            "Specify the revisions to mark.",
+        )),
+    }
+}
+
+/// Applies `f` to the bisection state, records the new state, and checks out
+/// the next revision to evaluate. Returns the next step, or `None` if no
+/// revision has been marked as bad yet.
+fn update_bisection(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    description: String,
+    f: impl FnOnce(&mut Bisector),
+) -> Result<Option<BisectionStep>, CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let mut bisector = Bisector::new(tx.repo(), tx.repo().bisect());
+    f(&mut bisector);
+    let state = bisector.state().clone();
+    tx.repo_mut().set_bisect(state.clone());
+    if state.bad_ids.is_empty() {
+        writeln!(
+            ui.hint_default(),
+            "Mark a revision that has the change as bad to continue."
+        )?;
+        tx.finish(ui, description)?;
+        return Ok(None);
+    }
+    let step = Bisector::new(tx.repo(), state).next_step()?;
+    match &step {
+        BisectionStep::Evaluate {
+            commit_id,
+            remaining,
+        } => {
+            let commit = tx.repo().store().get_commit(commit_id)?;
+            let workspace_id = tx.base_workspace_helper().workspace_id().clone();
+            tx.repo_mut().check_out(workspace_id, &commit)?;
+            if let Some(mut formatter) = ui.status_formatter() {
+                write!(formatter, "Now evaluating: ")?;
+                tx.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+                writeln!(
+                    formatter,
+                    "{remaining} revisions remain, roughly {steps} steps left",
+                    steps = remaining.ilog2() + 1
+                )?;
+            }
+        }
+        BisectionStep::Found(commit_id) => {
+            let commit = tx.repo().store().get_commit(commit_id)?;
+            if let Some(mut formatter) = ui.status_formatter() {
+                write!(formatter, "The first bad revision is: ")?;
+                tx.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        BisectionStep::Indeterminate(commit_ids) => {
+            if let Some(mut formatter) = ui.status_formatter() {
+                writeln!(
+                    formatter,
+                    "The first bad revision could be any of these skipped revisions:"
+                )?;
+                for commit_id in commit_ids {
+                    let commit = tx.repo().store().get_commit(commit_id)?;
+                    write!(formatter, "  ")?;
+                    tx.write_commit_summary(formatter.as_mut(), &commit)?;
+                    writeln!(formatter)?;
+                }
+            }
+        }
+        BisectionStep::NotFound => {
+            writeln!(
+                ui.warning_default(),
+                "No bad revision was found. The revisions marked as bad have no common bad \
+                 ancestor."
+            )?;
+        }
+    }
+    tx.finish(ui, description)?;
+    Ok(Some(step))
+}
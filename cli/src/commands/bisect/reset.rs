@@ -0,0 +1,43 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::op_store::BisectState;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// End the bisection
+///
+/// The working copy is left at the revision that was last checked out.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectResetArgs {}
+
+pub fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if !workspace_command.repo().view().bisect().is_active() {
+        writeln!(ui.status(), "No bisection in progress")?;
+        return Ok(());
+    }
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut().set_bisect(BisectState::default());
+    tx.finish(ui, "bisect: reset")?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::process::Command;
+
+use jj_lib::bisect::BisectionStep;
+use jj_lib::repo::Repo as _;
+
+use super::current_candidate;
+use super::update_bisection;
+use super::BisectMark;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Run a command to mark each evaluated revision automatically
+///
+/// The command is run in the workspace root. An exit code of 0 marks the
+/// revision as good, 125 marks it as skipped, and any other code up to 127
+/// marks it as bad. Other exit codes, and termination by a signal, abort the
+/// bisection run.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectRunArgs {
+    /// The command to run, followed by its arguments
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+pub fn cmd_bisect_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectRunArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let Some(mut commit_id) = current_candidate(&workspace_command)? else {
+        return Err(user_error_with_hint(
+            "No revision is being evaluated",
+            "Use `jj bisect start` with --bad and --good revisions to start a bisection.",
+        ));
+    };
+    let Some((program, program_args)) = args.command.split_first() else {
+        return Err(user_error("No command to run"));
+    };
+    loop {
+        // Evaluate the candidate on top of a fresh working-copy commit.
+        let wc_commit_id = workspace_command.get_wc_commit_id().unwrap().clone();
+        let wc_commit = workspace_command.repo().store().get_commit(&wc_commit_id)?;
+        if wc_commit.parent_ids() != [commit_id.clone()] {
+            let commit = workspace_command.repo().store().get_commit(&commit_id)?;
+            let mut tx = workspace_command.start_transaction();
+            let workspace_id = tx.base_workspace_helper().workspace_id().clone();
+            tx.repo_mut().check_out(workspace_id, &commit)?;
+            tx.finish(ui, "bisect: check out revision to evaluate")?;
+        }
+
+        let status = Command::new(program)
+            .args(program_args)
+            .current_dir(workspace_command.workspace_root())
+            .status()
+            .map_err(|err| user_error(format!("Failed to run '{program}': {err}")))?;
+        let mark = match status.code() {
+            Some(0) => BisectMark::Good,
+            Some(125) => BisectMark::Skip,
+            Some(1..=127) => BisectMark::Bad,
+            _ => {
+                return Err(user_error(format!(
+                    "Command failed ({status}), aborting the bisection run"
+                )));
+            }
+        };
+        writeln!(
+            ui.status(),
+            "The revision is {} (command exited with {status})",
+            mark.as_str()
+        )?;
+
+        // Record any changes made by the command before checking out the next
+        // revision.
+        workspace_command.maybe_snapshot(ui)?;
+        let step = update_bisection(
+            ui,
+            &mut workspace_command,
+            format!("bisect: mark 1 revision as {}", mark.as_str()),
+            |bisector| mark.apply(bisector, vec![commit_id.clone()]),
+        )?;
+        match step {
+            Some(BisectionStep::Evaluate {
+                commit_id: next_id, ..
+            }) => commit_id = next_id,
+            _ => return Ok(()),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+
+use super::update_bisection;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Start a bisection
+///
+/// For example, `jj bisect start --bad @ --good v1.0` searches the range
+/// `v1.0..@`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectStartArgs {
+    /// Revisions that have the change
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    bad: Vec<RevisionArg>,
+    /// Revisions that don't have the change
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    good: Vec<RevisionArg>,
+}
+
+pub fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if workspace_command.repo().view().bisect().is_active() {
+        return Err(user_error_with_hint(
+            "A bisection is already in progress",
+            "Use `jj bisect reset` to end it first.",
+        ));
+    }
+    let bad_ids: Vec<_> = if args.bad.is_empty() {
+        vec![]
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, &args.bad)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
+    let good_ids: Vec<_> = if args.good.is_empty() {
+        vec![]
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, &args.good)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
+    if bad_ids.is_empty() && good_ids.is_empty() {
+        return Err(user_error_with_hint(
+            "No revisions to start the bisection from",
+            "Use --bad and --good to mark revisions.",
+        ));
+    }
+    update_bisection(
+        ui,
+        &mut workspace_command,
+        "bisect: start".to_owned(),
+        |bisector| {
+            bisector.mark_good(good_ids);
+            bisector.mark_bad(bad_ids);
+        },
+    )?;
+    Ok(())
+}
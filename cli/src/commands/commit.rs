@@ -22,9 +22,11 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
+use crate::hooks::run_commit_msg_hook;
 use crate::text_util::parse_author;
 use crate::ui::Ui;
 
@@ -140,6 +142,9 @@ new working-copy commit.
         let template = description_template(ui, &tx, "", &temp_commit)?;
         edit_description(&text_editor, &template)?
     };
+    let description = add_trailers(ui, &tx, &commit, &description)?;
+    let workspace_root = tx.base_workspace_helper().workspace_root();
+    run_commit_msg_hook(tx.settings(), workspace_root, &commit, &description)?;
     commit_builder.set_description(description);
     let new_commit = commit_builder.write(tx.repo_mut())?;
 
@@ -17,7 +17,6 @@ use std::io::Write as _;
 
 use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId;
-use jj_lib::op_walk;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -54,7 +53,7 @@ pub fn cmd_debug_operation(
     // even if e.g. the view object is broken.
     let workspace = command.load_workspace()?;
     let repo_loader = workspace.repo_loader();
-    let op = op_walk::resolve_op_for_load(repo_loader, &args.operation)?;
+    let op = command.resolve_op_for_load(ui, repo_loader, &args.operation)?;
     if args.display == OperationDisplay::Id {
         writeln!(ui.stdout(), "{}", op.id().hex())?;
         return Ok(());
@@ -28,11 +28,13 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
 use crate::description_util::ParsedBulkEditMessage;
+use crate::hooks::run_commit_msg_hook;
 use crate::text_util::parse_author;
 use crate::ui::Ui;
 
@@ -238,6 +240,14 @@ pub(crate) fn cmd_describe(
         }
     };
 
+    let commit_descriptions: Vec<(_, _)> = commit_descriptions
+        .into_iter()
+        .map(|(commit, description)| {
+            let description = add_trailers(ui, &tx, commit, &description)?;
+            Ok::<_, CommandError>((commit, description))
+        })
+        .try_collect()?;
+
     // Filter out unchanged commits to avoid rebasing descendants in
     // `transform_descendants` below unnecessarily.
     let commit_descriptions: HashMap<_, _> = commit_descriptions
@@ -252,6 +262,13 @@ pub(crate) fn cmd_describe(
         .map(|(commit, new_description)| (commit.id(), new_description))
         .collect();
 
+    for commit in &commits {
+        if let Some(description) = commit_descriptions.get(commit.id()) {
+            let workspace_root = tx.base_workspace_helper().workspace_root();
+            run_commit_msg_hook(tx.settings(), workspace_root, commit, description)?;
+        }
+    }
+
     let mut num_described = 0;
     let mut num_reparented = 0;
     // Even though `MutRepo::rewrite_commit` and `MutRepo::rebase_descendants` can
@@ -15,6 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::annotate::get_annotation_for_file;
+use jj_lib::annotate::get_first_parent_annotation_for_file;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::commit::Commit;
 use jj_lib::repo::Repo;
@@ -51,6 +52,12 @@ pub(crate) struct FileAnnotateArgs {
         add = ArgValueCandidates::new(complete::all_revisions)
     )]
     revision: Option<RevisionArg>,
+    /// Follow only the first parent of merge commits
+    ///
+    /// Lines introduced in side branches are attributed to the merge commits
+    /// instead of the commits in the side branches.
+    #[arg(long)]
+    first_parent: bool,
 }
 
 #[instrument(skip_all)]
@@ -85,7 +92,11 @@ pub(crate) fn cmd_file_annotate(
     // exclude the revisions, but will ignore diffs in those revisions as if
     // ancestor revisions had new content.
     let domain = RevsetExpression::all();
-    let annotation = get_annotation_for_file(repo.as_ref(), &starting_commit, &domain, &file_path)?;
+    let annotation = if args.first_parent {
+        get_first_parent_annotation_for_file(repo.as_ref(), &starting_commit, &domain, &file_path)?
+    } else {
+        get_annotation_for_file(repo.as_ref(), &starting_commit, &domain, &file_path)?
+    };
 
     render_file_annotation(repo.as_ref(), ui, &template, &annotation)?;
     Ok(())
@@ -0,0 +1,319 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::git;
+use jj_lib::git::GitPushOptions;
+use jj_lib::git::GitRefUpdate;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::trailer::parse_description_trailers;
+use jj_lib::trailer::Trailer;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::append_trailers;
+use crate::git_util::with_remote_git_callbacks;
+use crate::ui::Ui;
+
+/// Upload changes to Gerrit for code review
+///
+/// The selected revisions and their mutable ancestors are pushed to
+/// `refs/for/<remote-branch>` on the Gerrit remote, which creates or updates
+/// one Gerrit change per commit.
+///
+/// Gerrit identifies a change by the `Change-Id` trailer of its description.
+/// Commits without one get a `Change-Id` derived from their jj change ID
+/// before they're uploaded, so the Gerrit change stays the same when the
+/// commit is rewritten.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GerritUploadArgs {
+    /// The revisions to upload, along with their mutable ancestors
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The branch the changes are intended to land on
+    ///
+    /// This defaults to the `gerrit.default-remote-branch` setting.
+    #[arg(long, short = 'b')]
+    remote_branch: Option<String>,
+    /// The Gerrit remote to push to
+    ///
+    /// This defaults to the `gerrit.default-remote` setting. If that is not
+    /// configured, the remote is chosen like in `jj git push`.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<String>,
+    /// Set the topic of the uploaded changes
+    #[arg(long, value_parser = parse_push_option_value)]
+    topic: Option<String>,
+    /// Add a reviewer to the uploaded changes (can be repeated)
+    #[arg(
+        long = "reviewer",
+        value_name = "REVIEWER",
+        value_parser = parse_push_option_value
+    )]
+    reviewers: Vec<String>,
+    /// Mark the uploaded changes as work in progress
+    #[arg(long)]
+    wip: bool,
+    /// Only display what will be uploaded
+    #[arg(long)]
+    dry_run: bool,
+}
+
+const DEFAULT_REMOTE: &str = "origin";
+
+/// Prefix of the `Change-Id` trailers derived from jj change IDs. Gerrit
+/// expects 40 hex digits after the `I`, and jj change IDs have 32.
+const CHANGE_ID_PREFIX: &str = "I6a6a6964";
+
+pub fn cmd_gerrit_upload(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GerritUploadArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+
+    let remote = if let Some(name) = &args.remote {
+        name.clone()
+    } else {
+        get_default_remote(&workspace_command)?
+    };
+    let remote_branch = if let Some(branch) = &args.remote_branch {
+        branch.clone()
+    } else if let Some(branch) = workspace_command
+        .settings()
+        .get_string("gerrit.default-remote-branch")
+        .optional()?
+    {
+        branch
+    } else {
+        return Err(user_error_with_hint(
+            "No remote branch to upload to",
+            "Use --remote-branch or set `gerrit.default-remote-branch`",
+        ));
+    };
+    let refspec = format!(
+        "refs/for/{remote_branch}{}",
+        format_push_options(&args.topic, &args.reviewers, args.wip)
+    );
+
+    let revisions = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .expression()
+        .clone();
+    let to_upload: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(
+            revisions
+                .ancestors()
+                .minus(&workspace_command.env().immutable_expression()),
+        )
+        .evaluate_to_commits()?
+        .try_collect()?;
+    if to_upload.is_empty() {
+        writeln!(ui.status(), "No mutable revisions to upload.")?;
+        return Ok(());
+    }
+    for commit in &to_upload {
+        validate_commit_ready_to_upload(commit)?;
+    }
+    let heads: Vec<CommitId> = workspace_command
+        .attach_revset_evaluator(
+            revisions
+                .minus(&workspace_command.env().immutable_expression())
+                .heads(),
+        )
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+
+    let mut tx = workspace_command.start_transaction();
+    let to_rewrite: HashSet<&CommitId> = to_upload
+        .iter()
+        .filter(|commit| !has_change_id(commit))
+        .ids()
+        .collect();
+    let mut old_to_new_commits_map: HashMap<CommitId, CommitId> = HashMap::new();
+    let mut num_rebased_descendants = 0;
+    tx.repo_mut().transform_descendants(
+        to_rewrite.iter().copied().cloned().collect_vec(),
+        |rewriter| {
+            let old_commit_id = rewriter.old_commit().id().clone();
+            let commit = if to_rewrite.contains(&old_commit_id) {
+                let trailer = Trailer {
+                    key: "Change-Id".to_owned(),
+                    value: format!(
+                        "{CHANGE_ID_PREFIX}{}",
+                        rewriter.old_commit().change_id().hex()
+                    ),
+                };
+                let description = append_trailers(rewriter.old_commit().description(), &[trailer]);
+                rewriter.reparent().set_description(description).write()?
+            } else {
+                num_rebased_descendants += 1;
+                rewriter.reparent().write()?
+            };
+            old_to_new_commits_map.insert(old_commit_id, commit.id().clone());
+            Ok(())
+        },
+    )?;
+    let heads = heads
+        .into_iter()
+        .map(|id| old_to_new_commits_map.get(&id).cloned().unwrap_or(id))
+        .collect_vec();
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if !to_rewrite.is_empty() {
+            let (add, rebase) = if args.dry_run {
+                ("Would add", "Would rebase")
+            } else {
+                ("Added", "Rebased")
+            };
+            writeln!(
+                formatter,
+                "{add} Change-Id trailers to {} commits",
+                to_rewrite.len()
+            )?;
+            if num_rebased_descendants > 0 {
+                writeln!(
+                    formatter,
+                    "{rebase} {num_rebased_descendants} descendant commits"
+                )?;
+            }
+        }
+        writeln!(formatter, "Changes to upload to {remote}:")?;
+        for head in &heads {
+            writeln!(formatter, "  Push {} to {refspec}", short_commit_hash(head))?;
+        }
+    }
+
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+        return Ok(());
+    }
+
+    let git_settings = tx.settings().git_settings()?;
+    for head in &heads {
+        let update = GitRefUpdate {
+            qualified_name: refspec.clone(),
+            expected_current_target: None,
+            new_target: Some(gix::ObjectId::from_bytes_or_panic(head.as_bytes())),
+        };
+        let push_stats = with_remote_git_callbacks(ui, |cb| {
+            git::push_updates(
+                tx.repo(),
+                &git_settings,
+                &remote,
+                &[update],
+                &GitPushOptions::default(),
+                cb,
+            )
+        })?;
+        if let Some((_, reason)) = push_stats.rejected.first() {
+            return Err(user_error(format!(
+                "Failed to upload {}: {}",
+                short_commit_hash(head),
+                reason.as_deref().unwrap_or("rejected by the remote")
+            )));
+        }
+    }
+    if !to_rewrite.is_empty() {
+        let tx_description = format!("upload changes to {remote} for {remote_branch}");
+        tx.finish(ui, tx_description)?;
+    }
+    Ok(())
+}
+
+fn get_default_remote(workspace_command: &WorkspaceCommandHelper) -> Result<String, CommandError> {
+    let settings = workspace_command.settings();
+    if let Some(remote) = settings.get_string("gerrit.default-remote").optional()? {
+        Ok(remote)
+    } else if let Some(remote) = settings.get_string("git.push").optional()? {
+        Ok(remote)
+    } else {
+        let mut names = git::get_all_remote_names(workspace_command.repo().store())?;
+        match names.len() {
+            1 => Ok(names.pop().unwrap()),
+            _ => Ok(DEFAULT_REMOTE.to_owned()),
+        }
+    }
+}
+
+/// Checks that a topic or reviewer doesn't contain the separators of the
+/// `%topic=...,r=...` push options.
+fn parse_push_option_value(value: &str) -> Result<String, &'static str> {
+    if value.contains([',', '%']) {
+        Err("Value must not contain `,` or `%`")
+    } else {
+        Ok(value.to_owned())
+    }
+}
+
+/// Formats the `%topic=...,r=...,wip` suffix that Gerrit parses from the
+/// target ref name.
+fn format_push_options(topic: &Option<String>, reviewers: &[String], wip: bool) -> String {
+    let options = itertools::chain!(
+        topic.iter().map(|topic| format!("topic={topic}")),
+        reviewers.iter().map(|reviewer| format!("r={reviewer}")),
+        wip.then(|| "wip".to_owned()),
+    )
+    .collect_vec();
+    if options.is_empty() {
+        String::new()
+    } else {
+        format!("%{}", options.join(","))
+    }
+}
+
+fn has_change_id(commit: &Commit) -> bool {
+    parse_description_trailers(commit.description())
+        .iter()
+        .any(|trailer| trailer.key.eq_ignore_ascii_case("Change-Id"))
+}
+
+/// Gerrit rejects commits without description, and conflicts can't be
+/// represented in Git.
+fn validate_commit_ready_to_upload(commit: &Commit) -> Result<(), CommandError> {
+    let short_id = short_commit_hash(commit.id());
+    if commit.description().trim().is_empty() {
+        return Err(user_error(format!(
+            "Won't upload commit {short_id} since it has no description"
+        )));
+    }
+    if commit.has_conflict()? {
+        return Err(user_error(format!(
+            "Won't upload commit {short_id} since it has conflicts"
+        )));
+    }
+    Ok(())
+}
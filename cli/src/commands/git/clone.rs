@@ -20,6 +20,7 @@ use std::path::Path;
 
 use jj_lib::git;
 use jj_lib::git::GitFetch;
+use jj_lib::git::GitFetchDepth;
 use jj_lib::repo::Repo;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
@@ -29,10 +30,12 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::maybe_add_gitignore;
 use crate::git_util::absolute_git_url;
+use crate::git_util::download_lfs_objects;
 use crate::git_util::get_git_repo;
 use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
@@ -63,6 +66,23 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Create a partial clone omitting the objects excluded by the filter
+    ///
+    /// For example, `--filter=blob:none` omits all file contents. Missing
+    /// objects are fetched from the remote when they're needed. Only `blob:`
+    /// filters are supported. This requires `git.subprocess = true`.
+    #[arg(long, value_name = "FILTER", value_parser = parse_filter)]
+    filter: Option<String>,
+}
+
+/// Checks that the partial clone filter only omits file contents, which are
+/// the only objects fetched on demand.
+fn parse_filter(filter: &str) -> Result<String, &'static str> {
+    if filter.starts_with("blob:") {
+        Ok(filter.to_owned())
+    } else {
+        Err("Only `blob:` filters are supported")
+    }
 }
 
 fn clone_destination_for_source(source: &str) -> Option<&str> {
@@ -90,6 +110,12 @@ pub fn cmd_git_clone(
     if command.global_args().at_operation.is_some() {
         return Err(cli_error("--at-op is not respected"));
     }
+    if args.filter.is_some() && !command.settings().git_settings()?.subprocess {
+        return Err(user_error_with_hint(
+            "Partial clones require running git as a subprocess",
+            "Set `git.subprocess = true` to use --filter.",
+        ));
+    }
     let source = absolute_git_url(command.cwd(), &args.source)?;
     let wc_path_str = args
         .destination
@@ -116,8 +142,14 @@ pub fn cmd_git_clone(
 
     let clone_result = (|| -> Result<_, CommandError> {
         let workspace_command = init_workspace(ui, command, &canonical_wc_path, args.colocate)?;
-        let mut workspace_command =
-            configure_remote(ui, command, workspace_command, remote_name, &source)?;
+        let mut workspace_command = configure_remote(
+            ui,
+            command,
+            workspace_command,
+            remote_name,
+            &source,
+            args.filter.as_deref(),
+        )?;
         let default_branch = fetch_new_remote(ui, &mut workspace_command, remote_name, args.depth)?;
         Ok((workspace_command, default_branch))
     })();
@@ -194,9 +226,13 @@ fn configure_remote(
     workspace_command: WorkspaceCommandHelper,
     remote_name: &str,
     source: &str,
+    filter: Option<&str>,
 ) -> Result<WorkspaceCommandHelper, CommandError> {
     let git_repo = get_git_repo(workspace_command.repo().store())?;
     git::add_remote(&git_repo, remote_name, source)?;
+    if let Some(filter) = filter {
+        git::set_remote_partial_clone_filter(&git_repo, remote_name, filter)?;
+    }
     // Reload workspace to apply new remote configuration to
     // gix::ThreadSafeRepository behind the store.
     let workspace = command.load_workspace_at(
@@ -224,6 +260,7 @@ fn fetch_new_remote(
     let git_settings = workspace_command.settings().git_settings()?;
     let mut fetch_tx = workspace_command.start_transaction();
     let mut git_fetch = GitFetch::new(fetch_tx.repo_mut(), &git_settings)?;
+    let depth = depth.map_or(GitFetchDepth::Unchanged, GitFetchDepth::Depth);
     with_remote_git_callbacks(ui, |cb| {
         git_fetch.fetch(remote_name, &[StringPattern::everything()], cb, depth)
     })?;
@@ -231,6 +268,12 @@ fn fetch_new_remote(
         with_remote_git_callbacks(ui, |cb| git_fetch.get_default_branch(remote_name, cb))?;
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, fetch_tx.repo(), &import_stats, true)?;
+    download_lfs_objects(
+        ui,
+        fetch_tx.base_repo().as_ref(),
+        fetch_tx.repo(),
+        remote_name,
+    )?;
     fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
     Ok(default_branch)
 }
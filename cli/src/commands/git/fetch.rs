@@ -12,20 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::num::NonZeroU32;
+
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
+use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::git;
 use jj_lib::git::GitFetch;
+use jj_lib::git::GitFetchDepth;
+use jj_lib::git::GitImportStats;
 use jj_lib::repo::Repo;
 use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::internal_error;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
+use crate::git_util::download_lfs_objects;
 use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
 use crate::ui::Ui;
@@ -34,6 +42,11 @@ use crate::ui::Ui;
 ///
 /// If a working-copy commit gets abandoned, it will be given a new, empty
 /// commit. This is true in general; it is not specific to this command.
+///
+/// If the boundary of a shallow repository moves (with `--depth`, `--deepen`
+/// or `--unshallow`), the commits at the old boundary get new parents, and the
+/// whole commit index has to be rebuilt. This may take a while in large
+/// repositories.
 #[derive(clap::Args, Clone, Debug)]
 pub struct GitFetchArgs {
     /// Fetch only some of the branches
@@ -63,6 +76,20 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// Fetch at most this many commits from the tip of each branch
+    ///
+    /// This makes the repository shallow, or moves the boundary of an already
+    /// shallow repository.
+    #[arg(long, conflicts_with_all = ["deepen", "unshallow"])]
+    depth: Option<NonZeroU32>,
+    /// Fetch this many more commits past the boundary of a shallow repository
+    ///
+    /// This requires the `git.subprocess` setting to be enabled.
+    #[arg(long, value_name = "DEPTH", conflicts_with = "unshallow")]
+    deepen: Option<NonZeroU32>,
+    /// Fetch the full history of a shallow repository
+    #[arg(long)]
+    unshallow: bool,
 }
 
 #[tracing::instrument(skip(ui, command))]
@@ -79,8 +106,20 @@ pub fn cmd_git_fetch(
     } else {
         args.remotes.clone()
     };
+    let depth = if let Some(depth) = args.depth {
+        GitFetchDepth::Depth(depth)
+    } else if let Some(depth) = args.deepen {
+        GitFetchDepth::Deepen(depth)
+    } else if args.unshallow {
+        GitFetchDepth::Unshallow
+    } else {
+        GitFetchDepth::Unchanged
+    };
     let mut tx = workspace_command.start_transaction();
-    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
+    let import_stats = do_git_fetch(ui, &mut tx, &remotes, &args.branch, depth)?;
+    if !import_stats.reparented_commits.is_empty() {
+        rebuild_index(ui, &mut tx, &import_stats.reparented_commits)?;
+    }
     tx.finish(
         ui,
         format!("fetch from git remote(s) {}", remotes.iter().join(",")),
@@ -88,6 +127,35 @@ pub fn cmd_git_fetch(
     Ok(())
 }
 
+/// Rebuilds the index, which records the parents of the commits that were
+/// at the boundary of the shallow repository before fetching.
+fn rebuild_index(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    reparented_commits: &[CommitId],
+) -> Result<(), CommandError> {
+    let index_store = tx.base_repo().index_store().clone();
+    if tx
+        .repo_mut()
+        .reindex_reparented_commits(reparented_commits)?
+    {
+        // The indexes of the previous operations have stale parents. They
+        // will be rebuilt when needed. The rebuilt index of this transaction
+        // doesn't refer to them.
+        if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>()
+        {
+            default_index_store.reinit().map_err(internal_error)?;
+        }
+    } else {
+        writeln!(
+            ui.warning_default(),
+            "The shallow history changed, but indexes of type '{}' cannot be rebuilt",
+            index_store.name()
+        )?;
+    }
+    Ok(())
+}
+
 const DEFAULT_REMOTE: &str = "origin";
 
 fn get_default_fetch_remotes(
@@ -119,23 +187,28 @@ fn do_git_fetch(
     tx: &mut WorkspaceCommandTransaction,
     remotes: &[String],
     branch_names: &[StringPattern],
-) -> Result<(), CommandError> {
+    depth: GitFetchDepth,
+) -> Result<GitImportStats, CommandError> {
     let git_settings = tx.settings().git_settings()?;
     let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings)?;
 
     for remote_name in remotes {
         with_remote_git_callbacks(ui, |callbacks| {
-            git_fetch.fetch(remote_name, branch_names, callbacks, None)
+            git_fetch.fetch(remote_name, branch_names, callbacks, depth)
         })?;
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
+    for remote_name in remotes {
+        download_lfs_objects(ui, tx.base_repo().as_ref(), tx.repo(), remote_name)?;
+    }
     warn_if_branches_not_found(
         ui,
         tx,
         branch_names,
         &remotes.iter().map(StringPattern::exact).collect_vec(),
-    )
+    )?;
+    Ok(import_stats)
 }
 
 fn warn_if_branches_not_found(
@@ -0,0 +1,438 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use indexmap::IndexMap;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
+use jj_lib::file_util;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::rewrite::merge_commit_trees;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::diff_util::DiffFormat;
+use crate::diff_util::DiffRenderer;
+use crate::diff_util::DiffStatOptions;
+use crate::diff_util::UnifiedDiffOptions;
+use crate::formatter::PlainTextFormatter;
+use crate::time_util::format_rfc2822_timestamp;
+use crate::ui::Ui;
+
+/// Width of the diffstat included in patches, which matches Git's default.
+const STAT_WIDTH: usize = 72;
+
+/// Maximum length of an RFC 2047 encoded word, including the delimiters.
+const MAX_ENCODED_WORD_LEN: usize = 75;
+
+/// Export revisions as patches suitable for sending by email
+///
+/// Each revision is rendered as an RFC 2822 message with a Git-format diff, in
+/// the format produced by `git format-patch`. Revisions are ordered from
+/// parents to children, and numbered as `[PATCH n/m]` if there is more than
+/// one. Merge commits are skipped.
+///
+/// By default, the patches are written to stdout as a single mbox file. Use
+/// `--output-directory` to write one file per patch instead.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitFormatPatchArgs {
+    /// The revisions to export
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revisions: Vec<RevisionArg>,
+    /// Write the patches to files in this directory
+    ///
+    /// The files are named like `0001-<subject>.patch`. The directory is
+    /// created if it doesn't exist.
+    #[arg(long, short, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    output_directory: Option<PathBuf>,
+    /// Add a cover letter with a shortlog and diffstat of the patches
+    ///
+    /// The subject and body of the cover letter are placeholders to be filled
+    /// in before sending.
+    #[arg(long)]
+    cover_letter: bool,
+    /// Use this prefix instead of `PATCH` in the subject lines
+    #[arg(long, value_name = "PREFIX", default_value = "PATCH")]
+    subject_prefix: String,
+    /// Number the patches even if there is only one
+    #[arg(long, short)]
+    numbered: bool,
+}
+
+pub fn cmd_git_format_patch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitFormatPatchArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let mut commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    commits.reverse();
+    let (merges, commits): (Vec<_>, Vec<_>) = commits
+        .into_iter()
+        .partition(|commit| commit.parent_ids().len() > 1);
+    for commit in &merges {
+        writeln!(
+            ui.warning_default(),
+            "Skipping merge commit {}",
+            short_commit_hash(commit.id())
+        )?;
+    }
+    if commits.is_empty() {
+        return Err(user_error("No revisions to export"));
+    }
+
+    // Paths in patches are relative to the workspace root.
+    let workspace_root = workspace_command.workspace_root().to_owned();
+    let path_converter = RepoPathUiConverter::Fs {
+        cwd: workspace_root.clone(),
+        base: workspace_root,
+    };
+    let writer = PatchWriter::new(&workspace_command, &path_converter)?;
+    let total = commits.len();
+    let numbered = args.numbered || args.cover_letter || total > 1;
+    let subject_prefix = |index: usize| {
+        if numbered {
+            format!("[{} {index}/{total}]", args.subject_prefix)
+        } else {
+            format!("[{}]", args.subject_prefix)
+        }
+    };
+    let mut patches = vec![];
+    if args.cover_letter {
+        let author = workspace_command.settings().signature();
+        let content = writer.cover_letter(ui, &commits, &author, &subject_prefix(0))?;
+        patches.push(("cover-letter".to_owned(), content));
+    }
+    for (index, commit) in commits.iter().enumerate() {
+        let content = writer.patch(ui, commit, &subject_prefix(index + 1))?;
+        patches.push((
+            commit.description().lines().next().unwrap_or("").to_owned(),
+            content,
+        ));
+    }
+
+    if let Some(dir) = &args.output_directory {
+        let dir = command.cwd().join(dir);
+        file_util::create_or_reuse_dir(&dir).map_err(|err| {
+            user_error_with_message(format!("Failed to create {}", dir.display()), err)
+        })?;
+        let first_number = if args.cover_letter { 0 } else { 1 };
+        for (number, (subject, content)) in (first_number..).zip(&patches) {
+            let path = dir.join(patch_file_name(number, subject));
+            fs::write(&path, content).map_err(|err| {
+                user_error_with_message(format!("Failed to write {}", path.display()), err)
+            })?;
+            let display_path = file_util::relative_path(command.cwd(), &path);
+            writeln!(ui.stdout(), "{}", display_path.display())?;
+        }
+    } else {
+        ui.request_pager();
+        let mut stdout = ui.stdout();
+        for (_, content) in &patches {
+            stdout.write_all(content)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders commits as email messages.
+struct PatchWriter<'a> {
+    diff_stat_renderer: DiffRenderer<'a>,
+    git_diff_renderer: DiffRenderer<'a>,
+    workspace_command: &'a WorkspaceCommandHelper,
+}
+
+impl<'a> PatchWriter<'a> {
+    fn new(
+        workspace_command: &'a WorkspaceCommandHelper,
+        path_converter: &'a RepoPathUiConverter,
+    ) -> Result<Self, CommandError> {
+        let repo = workspace_command.repo().as_ref();
+        let conflict_marker_style = workspace_command.env().conflict_marker_style();
+        let new_renderer =
+            |format| DiffRenderer::new(repo, path_converter, conflict_marker_style, vec![format]);
+        let stat_options = DiffStatOptions::default();
+        let git_options = UnifiedDiffOptions::from_settings(workspace_command.settings())?;
+        Ok(PatchWriter {
+            diff_stat_renderer: new_renderer(DiffFormat::Stat(Box::new(stat_options))),
+            git_diff_renderer: new_renderer(DiffFormat::Git(Box::new(git_options))),
+            workspace_command,
+        })
+    }
+
+    fn patch(
+        &self,
+        ui: &Ui,
+        commit: &Commit,
+        subject_prefix: &str,
+    ) -> Result<Vec<u8>, CommandError> {
+        let mut output = vec![];
+        let (subject, body) = split_description(commit.description());
+        write_headers(
+            &mut output,
+            &commit.id().hex(),
+            commit.author(),
+            &format!("{subject_prefix} {subject}"),
+        )?;
+        if !body.is_empty() {
+            writeln!(output, "{body}")?;
+        }
+        writeln!(output, "---")?;
+        let mut stat = vec![];
+        let mut formatter = PlainTextFormatter::new(&mut stat);
+        self.diff_stat_renderer.show_patch(
+            ui,
+            &mut formatter,
+            commit,
+            &EverythingMatcher,
+            STAT_WIDTH,
+        )?;
+        write_indented_stat(&mut output, &stat)?;
+        writeln!(output)?;
+        let mut formatter = PlainTextFormatter::new(&mut output);
+        self.git_diff_renderer.show_patch(
+            ui,
+            &mut formatter,
+            commit,
+            &EverythingMatcher,
+            STAT_WIDTH,
+        )?;
+        writeln!(output)?;
+        Ok(output)
+    }
+
+    fn cover_letter(
+        &self,
+        ui: &Ui,
+        commits: &[Commit],
+        author: &Signature,
+        subject_prefix: &str,
+    ) -> Result<Vec<u8>, CommandError> {
+        let mut output = vec![];
+        write_headers(
+            &mut output,
+            &"0".repeat(40),
+            author,
+            &format!("{subject_prefix} *** SUBJECT HERE ***"),
+        )?;
+        writeln!(output, "*** BLURB HERE ***")?;
+        writeln!(output)?;
+
+        let mut subjects_by_author: IndexMap<&str, Vec<&str>> = IndexMap::new();
+        for commit in commits {
+            let (subject, _) = split_description(commit.description());
+            subjects_by_author
+                .entry(&commit.author().name)
+                .or_default()
+                .push(subject);
+        }
+        for (name, subjects) in &subjects_by_author {
+            writeln!(output, "{name} ({}):", subjects.len())?;
+            for subject in subjects {
+                writeln!(output, "  {subject}")?;
+            }
+            writeln!(output)?;
+        }
+
+        // The revisions may not form a linear stack, so diff from the roots'
+        // parents to the heads.
+        let repo = self.workspace_command.repo().as_ref();
+        let commit_ids: HashSet<&CommitId> = commits.iter().map(|commit| commit.id()).collect();
+        let root_parents: Vec<Commit> = commits
+            .iter()
+            .flat_map(|commit| commit.parents())
+            .filter_ok(|parent| !commit_ids.contains(parent.id()))
+            .process_results(|parents| parents.unique_by(|parent| parent.id().clone()).collect())?;
+        let parent_ids: HashSet<&CommitId> = commits
+            .iter()
+            .flat_map(|commit| commit.parent_ids())
+            .collect();
+        let heads = commits
+            .iter()
+            .filter(|commit| !parent_ids.contains(commit.id()))
+            .cloned()
+            .collect_vec();
+        let from_tree = merge_commit_trees(repo, &root_parents)?;
+        let to_tree = merge_commit_trees(repo, &heads)?;
+        let mut stat = vec![];
+        let mut formatter = PlainTextFormatter::new(&mut stat);
+        self.diff_stat_renderer.show_diff(
+            ui,
+            &mut formatter,
+            &from_tree,
+            &to_tree,
+            &EverythingMatcher,
+            &Default::default(),
+            STAT_WIDTH,
+        )?;
+        write_indented_stat(&mut output, &stat)?;
+        writeln!(output)?;
+        Ok(output)
+    }
+}
+
+fn write_headers(
+    output: &mut Vec<u8>,
+    id: &str,
+    author: &Signature,
+    subject: &str,
+) -> Result<(), CommandError> {
+    let date = format_rfc2822_timestamp(&author.timestamp)
+        .map_err(|err| user_error_with_message("Invalid author date", err))?;
+    // The date in the "From" line is a fixed magic value, as in Git.
+    writeln!(output, "From {id} Mon Sep 17 00:00:00 2001")?;
+    writeln!(
+        output,
+        "From: {} <{}>",
+        encode_header_word(&author.name),
+        author.email
+    )?;
+    writeln!(output, "Date: {date}")?;
+    writeln!(output, "Subject: {}", encode_header_word(subject))?;
+    writeln!(output)?;
+    Ok(())
+}
+
+/// Indents the diffstat lines by one space, as in Git.
+fn write_indented_stat(output: &mut Vec<u8>, stat: &[u8]) -> std::io::Result<()> {
+    for line in stat.split_inclusive(|&b| b == b'\n') {
+        output.push(b' ');
+        output.extend_from_slice(line);
+    }
+    Ok(())
+}
+
+/// Splits the description into the subject line and the trimmed body.
+fn split_description(description: &str) -> (&str, &str) {
+    let (subject, body) = description.split_once('\n').unwrap_or((description, ""));
+    (subject.trim(), body.trim())
+}
+
+/// Encodes non-ASCII header text as RFC 2047 encoded words.
+///
+/// Long text is split into multiple words at character boundaries, which are
+/// folded onto continuation lines.
+fn encode_header_word(text: &str) -> String {
+    const PREFIX: &str = "=?UTF-8?q?";
+    const SUFFIX: &str = "?=";
+    if text.is_ascii() {
+        return text.to_owned();
+    }
+    let max_payload_len = MAX_ENCODED_WORD_LEN - PREFIX.len() - SUFFIX.len();
+    let mut words = vec![];
+    let mut payload = String::new();
+    for c in text.chars() {
+        let mut encoded_char = String::new();
+        for &b in c.encode_utf8(&mut [0; 4]).as_bytes() {
+            match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'!' | b'*' | b'+' | b'-' | b'/' => {
+                    encoded_char.push(b as char);
+                }
+                b' ' => encoded_char.push('_'),
+                _ => encoded_char.push_str(&format!("={b:02X}")),
+            }
+        }
+        if payload.len() + encoded_char.len() > max_payload_len {
+            words.push(format!("{PREFIX}{payload}{SUFFIX}"));
+            payload.clear();
+        }
+        payload.push_str(&encoded_char);
+    }
+    words.push(format!("{PREFIX}{payload}{SUFFIX}"));
+    words.join("\n ")
+}
+
+/// Returns the file name of the patch, like `0001-fix-the-bug.patch`.
+fn patch_file_name(number: usize, subject: &str) -> String {
+    let mut slug = String::new();
+    for c in subject.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    // Keep the name short like Git does, and avoid a trailing separator.
+    let slug: String = slug.chars().take(52).collect();
+    let slug = slug.trim_end_matches(['-', '.']);
+    format!("{number:04}-{slug}.patch")
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_patch_file_name() {
+        assert_eq!(patch_file_name(1, "Fix the bug"), "0001-Fix-the-bug.patch");
+        assert_eq!(
+            patch_file_name(12, "cli: add `jj foo` (take 2)..."),
+            "0012-cli-add-jj-foo-take-2.patch"
+        );
+        assert_eq!(
+            patch_file_name(0, "cover-letter"),
+            "0000-cover-letter.patch"
+        );
+    }
+
+    #[test]
+    fn test_encode_header_word() {
+        assert_eq!(encode_header_word("plain text"), "plain text");
+        assert_eq!(
+            encode_header_word("Café au lait"),
+            "=?UTF-8?q?Caf=C3=A9_au_lait?="
+        );
+    }
+
+    #[test]
+    fn test_encode_header_word_long() {
+        let encoded = encode_header_word(&"日本語のテキスト".repeat(3));
+        assert_snapshot!(encoded, @r"
+        =?UTF-8?q?=E6=97=A5=E6=9C=AC=E8=AA=9E=E3=81=AE=E3=83=86=E3=82=AD=E3=82=B9?=
+         =?UTF-8?q?=E3=83=88=E6=97=A5=E6=9C=AC=E8=AA=9E=E3=81=AE=E3=83=86=E3=82=AD?=
+         =?UTF-8?q?=E3=82=B9=E3=83=88=E6=97=A5=E6=9C=AC=E8=AA=9E=E3=81=AE=E3=83=86?=
+         =?UTF-8?q?=E3=82=AD=E3=82=B9=E3=83=88?=
+        ");
+        for line in encoded.lines() {
+            let word = line.trim_start();
+            assert!(word.starts_with("=?UTF-8?q?") && word.ends_with("?="));
+            assert!(word.len() <= MAX_ENCODED_WORD_LEN);
+        }
+    }
+}
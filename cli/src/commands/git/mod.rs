@@ -15,6 +15,7 @@
 mod clone;
 mod export;
 mod fetch;
+mod format_patch;
 mod import;
 mod init;
 mod push;
@@ -37,6 +38,8 @@ use self::export::cmd_git_export;
 use self::export::GitExportArgs;
 use self::fetch::cmd_git_fetch;
 use self::fetch::GitFetchArgs;
+use self::format_patch::cmd_git_format_patch;
+use self::format_patch::GitFormatPatchArgs;
 use self::import::cmd_git_import;
 use self::import::GitImportArgs;
 use self::init::cmd_git_init;
@@ -67,6 +70,7 @@ pub enum GitCommand {
     Clone(GitCloneArgs),
     Export(GitExportArgs),
     Fetch(GitFetchArgs),
+    FormatPatch(GitFormatPatchArgs),
     Import(GitImportArgs),
     Init(GitInitArgs),
     Push(GitPushArgs),
@@ -85,6 +89,7 @@ pub fn cmd_git(
         GitCommand::Clone(args) => cmd_git_clone(ui, command, args),
         GitCommand::Export(args) => cmd_git_export(ui, command, args),
         GitCommand::Fetch(args) => cmd_git_fetch(ui, command, args),
+        GitCommand::FormatPatch(args) => cmd_git_format_patch(ui, command, args),
         GitCommand::Import(args) => cmd_git_import(ui, command, args),
         GitCommand::Init(args) => cmd_git_init(ui, command, args),
         GitCommand::Push(args) => cmd_git_push(ui, command, args),
@@ -28,6 +28,8 @@ use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitPushError;
+use jj_lib::git::GitPushOptions;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::refs::classify_bookmark_push_action;
@@ -36,6 +38,7 @@ use jj_lib::refs::BookmarkPushUpdate;
 use jj_lib::refs::LocalAndRemoteRef;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SignBehavior;
 use jj_lib::str_util::StringPattern;
@@ -48,11 +51,15 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
 use crate::formatter::Formatter;
+use crate::git_util::upload_lfs_objects;
 use crate::git_util::with_remote_git_callbacks;
+use crate::hooks::run_hook;
+use crate::hooks::Hook;
 use crate::ui::Ui;
 
 /// Push to a Git remote
@@ -60,7 +67,8 @@ use crate::ui::Ui;
 /// By default, pushes tracking bookmarks pointing to
 /// `remote_bookmarks(remote=<remote>)..@`. Use `--bookmark` to push specific
 /// bookmarks. Use `--all` to push all bookmarks. Use `--change` to generate
-/// bookmark names based on the change IDs of specific commits.
+/// bookmark names based on the change IDs of specific commits. Use `--tag` to
+/// push tags.
 ///
 /// Unlike in Git, the remote to push to is not derived from the tracked remote
 /// bookmarks. Use `--remote` to select the remote Git repository by name. There
@@ -77,7 +85,7 @@ use crate::ui::Ui;
 ///     https://jj-vcs.github.io/jj/latest/bookmarks/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions", "tag"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
@@ -166,6 +174,27 @@ pub struct GitPushArgs {
         add = ArgValueCandidates::new(complete::mutable_revisions)
     )]
     change: Vec<RevisionArg>,
+    /// Push this tag, or tags matching a pattern (can be repeated)
+    ///
+    /// Tags that were deleted locally are deleted on the remote. By default,
+    /// the specified name matches exactly. Use `glob:` prefix to select tags
+    /// by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets#string-patterns
+    #[arg(long, value_parser = StringPattern::parse)]
+    tag: Vec<StringPattern>,
+    /// Transmit this string to the server as a push option (can be repeated)
+    ///
+    /// The server passes push options to its hooks. Their meaning depends on
+    /// the server, e.g. `-o ci.skip` skips CI pipelines on GitLab.
+    #[arg(long = "option", short = 'o', value_name = "OPTION")]
+    options: Vec<String>,
+    /// Either update all refs on the remote, or none of them
+    ///
+    /// This requires the `git.subprocess` setting to be enabled.
+    #[arg(long)]
+    atomic: bool,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
@@ -178,6 +207,13 @@ fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
     }
 }
 
+fn make_tag_term(tag_names: &[impl fmt::Display]) -> String {
+    match tag_names {
+        [tag_name] => format!("tag {tag_name}"),
+        tag_names => format!("tags {}", tag_names.iter().join(", ")),
+    }
+}
+
 const DEFAULT_REMOTE: &str = "origin";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -204,6 +240,7 @@ pub fn cmd_git_push(
     let view = tx.repo().view();
     let tx_description;
     let mut bookmark_updates = vec![];
+    let mut tag_updates = vec![];
     if args.all {
         for (bookmark_name, targets) in view.local_remote_bookmarks(&remote) {
             let allow_new = true; // implied by --all
@@ -286,8 +323,21 @@ pub fn cmd_git_push(
             }
         }
 
-        let use_default_revset =
-            args.bookmark.is_empty() && args.change.is_empty() && args.revisions.is_empty();
+        for (tag_name, targets) in find_tags_to_push(view, &args.tag, &remote)? {
+            match classify_tag_update(tag_name, &remote, targets) {
+                Ok(Some(update)) => tag_updates.push((tag_name.to_owned(), update)),
+                Ok(None) => writeln!(
+                    ui.status(),
+                    "Tag {tag_name}@{remote} already matches {tag_name}",
+                )?,
+                Err(reason) => return Err(reason.into()),
+            }
+        }
+
+        let use_default_revset = args.bookmark.is_empty()
+            && args.change.is_empty()
+            && args.revisions.is_empty()
+            && args.tag.is_empty();
         let bookmarks_targeted = find_bookmarks_targeted_by_revisions(
             ui,
             tx.base_workspace_helper(),
@@ -306,18 +356,26 @@ pub fn cmd_git_push(
             }
         }
 
-        tx_description = format!(
-            "push {} to git remote {}",
-            make_bookmark_term(
-                &bookmark_updates
-                    .iter()
-                    .map(|(bookmark, _)| bookmark.as_str())
-                    .collect_vec()
+        let bookmark_names = bookmark_updates
+            .iter()
+            .map(|(bookmark, _)| bookmark.as_str())
+            .collect_vec();
+        let tag_names = tag_updates
+            .iter()
+            .map(|(tag, _)| tag.as_str())
+            .collect_vec();
+        let terms = match (&bookmark_names[..], &tag_names[..]) {
+            (_, []) => make_bookmark_term(&bookmark_names),
+            ([], _) => make_tag_term(&tag_names),
+            _ => format!(
+                "{} and {}",
+                make_bookmark_term(&bookmark_names),
+                make_tag_term(&tag_names)
             ),
-            &remote
-        );
+        };
+        tx_description = format!("push {terms} to git remote {remote}");
     }
-    if bookmark_updates.is_empty() {
+    if bookmark_updates.is_empty() && tag_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
     }
@@ -329,6 +387,9 @@ pub fn cmd_git_push(
     };
     let commits_to_sign =
         validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, args, sign_behavior)?;
+    // Commits are only signed if bookmarks point to them, since tags don't
+    // follow rewritten commits.
+    validate_commits_ready_to_push(ui, &tag_updates, &remote, &tx, args, None)?;
     if !args.dry_run && !commits_to_sign.is_empty() {
         if let Some(sign_behavior) = sign_behavior {
             let num_updated_signatures = commits_to_sign.len();
@@ -356,7 +417,8 @@ pub fn cmd_git_push(
 
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(formatter, "Changes to push to {remote}:")?;
-        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
+        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), "bookmark", &bookmark_updates)?;
+        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), "tag", &tag_updates)?;
     }
 
     if args.dry_run {
@@ -364,17 +426,104 @@ pub fn cmd_git_push(
         return Ok(());
     }
 
+    run_pre_push_hook(&tx, &remote, &bookmark_updates, &tag_updates)?;
+
+    let all_updates = bookmark_updates
+        .iter()
+        .chain(&tag_updates)
+        .cloned()
+        .collect_vec();
+    upload_lfs_objects_before_push(ui, &tx, &remote, &all_updates)?;
+
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
+        tag_updates,
     };
     let git_settings = tx.settings().git_settings()?;
-    with_remote_git_callbacks(ui, |cb| {
-        git::push_branches(tx.repo_mut(), &git_settings, &remote, &targets, cb)
+    let push_options = GitPushOptions {
+        remote_push_options: args.options.clone(),
+        atomic: args.atomic,
+    };
+    let push_stats = with_remote_git_callbacks(ui, |cb| {
+        git::push_branches(
+            tx.repo_mut(),
+            &git_settings,
+            &remote,
+            &targets,
+            &push_options,
+            cb,
+        )
     })?;
-    tx.finish(ui, tx_description)?;
+    if push_stats.all_ok() {
+        tx.finish(ui, tx_description)?;
+    } else {
+        // Record the refs that were accepted by the remote, if any
+        if !push_stats.pushed.is_empty() {
+            tx.finish(ui, tx_description)?;
+        }
+        if !push_stats.rejected.is_empty() {
+            writeln!(
+                ui.warning_default(),
+                "The following references unexpectedly failed:"
+            )?;
+            let mut formatter = ui.stderr_formatter();
+            for (reference, reason) in &push_stats.rejected {
+                if let Some(reason) = reason {
+                    writeln!(formatter, "  {reference} (reason: {reason})")?;
+                } else {
+                    writeln!(formatter, "  {reference}")?;
+                }
+            }
+        }
+        if !push_stats.unexpected_location.is_empty() {
+            return Err(
+                GitPushError::RefInUnexpectedLocation(push_stats.unexpected_location).into(),
+            );
+        }
+        return Err(user_error_with_hint(
+            "Failed to push some bookmarks",
+            "Try checking if you have permission to push to all the bookmarks.",
+        ));
+    }
     Ok(())
 }
 
+/// Runs the `pre-push` hook with one `<ref name> <old commit id> <new commit
+/// id>` line per ref to update. Missing targets are spelled as the root commit
+/// id, which is all zeros like in Git's pre-push hook.
+fn run_pre_push_hook(
+    tx: &WorkspaceCommandTransaction,
+    remote: &str,
+    bookmark_updates: &[(String, BookmarkPushUpdate)],
+    tag_updates: &[(String, BookmarkPushUpdate)],
+) -> Result<(), CommandError> {
+    let zero_id = tx.repo().store().root_commit_id().hex();
+    let format_id = |id: &Option<CommitId>| id.as_ref().map_or(zero_id.clone(), |id| id.hex());
+    let refs = itertools::chain(
+        bookmark_updates
+            .iter()
+            .map(|(name, update)| (format!("refs/heads/{name}"), update)),
+        tag_updates
+            .iter()
+            .map(|(name, update)| (format!("refs/tags/{name}"), update)),
+    );
+    let mut input = String::new();
+    for (ref_name, update) in refs {
+        let old_id = format_id(&update.old_target);
+        let new_id = format_id(&update.new_target);
+        input.push_str(&format!("{ref_name} {old_id} {new_id}\n"));
+    }
+    let workspace_root = tx.base_workspace_helper().workspace_root();
+    let variables = HashMap::from([("remote", remote)]);
+    run_hook(
+        tx.settings(),
+        Hook::PrePush,
+        workspace_root,
+        &variables,
+        input.as_bytes(),
+    )
+}
+
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.).
 ///
@@ -472,6 +621,36 @@ fn validate_commits_ready_to_push(
     Ok(commits_to_sign)
 }
 
+/// Uploads the Git LFS objects referenced by the commits that will be pushed.
+fn upload_lfs_objects_before_push(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    remote: &str,
+    bookmark_updates: &[(String, BookmarkPushUpdate)],
+) -> Result<(), CommandError> {
+    let repo = tx.repo();
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    // Commits reachable from any bookmark of the remote are already there, even
+    // if the bookmark being pushed is new: ::new_heads ~ ::remote_heads
+    let remote_heads = repo
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect_vec();
+    let commits: Vec<Commit> = RevsetExpression::commits(new_heads)
+        .ancestors()
+        .minus(&RevsetExpression::commits(remote_heads).ancestors())
+        .evaluate(repo)?
+        .iter()
+        .commits(repo.store())
+        .try_collect()?;
+    upload_lfs_objects(ui, repo, remote, &commits)
+}
+
 /// Signs commits before pushing.
 ///
 /// Returns the number of commits with rebased descendants and the updated list
@@ -523,6 +702,7 @@ fn sign_commits_before_push(
 fn print_commits_ready_to_push(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
+    ref_kind: &str,
     bookmark_updates: &[(String, BookmarkPushUpdate)],
 ) -> io::Result<()> {
     let to_direction = |old_target: &CommitId, new_target: &CommitId| {
@@ -549,13 +729,13 @@ fn print_commits_ready_to_push(
                 // possibly "Move bookmark ... sideways (X forward, Y back)".
                 let msg = match to_direction(old_target, new_target) {
                     BookmarkMoveDirection::Forward => {
-                        format!("Move forward bookmark {bookmark_name} from {old} to {new}")
+                        format!("Move forward {ref_kind} {bookmark_name} from {old} to {new}")
                     }
                     BookmarkMoveDirection::Backward => {
-                        format!("Move backward bookmark {bookmark_name} from {old} to {new}")
+                        format!("Move backward {ref_kind} {bookmark_name} from {old} to {new}")
                     }
                     BookmarkMoveDirection::Sideways => {
-                        format!("Move sideways bookmark {bookmark_name} from {old} to {new}")
+                        format!("Move sideways {ref_kind} {bookmark_name} from {old} to {new}")
                     }
                 };
                 writeln!(formatter, "  {msg}")?;
@@ -563,19 +743,19 @@ fn print_commits_ready_to_push(
             (Some(old_target), None) => {
                 writeln!(
                     formatter,
-                    "  Delete bookmark {bookmark_name} from {}",
+                    "  Delete {ref_kind} {bookmark_name} from {}",
                     short_commit_hash(old_target)
                 )?;
             }
             (None, Some(new_target)) => {
                 writeln!(
                     formatter,
-                    "  Add bookmark {bookmark_name} to {}",
+                    "  Add {ref_kind} {bookmark_name} to {}",
                     short_commit_hash(new_target)
                 )?;
             }
             (None, None) => {
-                panic!("Not pushing any change to bookmark {bookmark_name}");
+                panic!("Not pushing any change to {ref_kind} {bookmark_name}");
             }
         }
     }
@@ -671,6 +851,27 @@ fn classify_bookmark_update(
     }
 }
 
+fn classify_tag_update(
+    tag_name: &str,
+    remote_name: &str,
+    targets: LocalAndRemoteRef,
+) -> Result<Option<BookmarkPushUpdate>, RejectedBookmarkUpdateReason> {
+    match classify_bookmark_push_action(targets) {
+        BookmarkPushAction::AlreadyMatches => Ok(None),
+        BookmarkPushAction::LocalConflicted => Err(RejectedBookmarkUpdateReason {
+            message: format!("Tag {tag_name} is conflicted"),
+            hint: Some("Use `jj tag set` to fix it up.".to_owned()),
+        }),
+        BookmarkPushAction::RemoteConflicted => Err(RejectedBookmarkUpdateReason {
+            message: format!("Tag {tag_name}@{remote_name} is conflicted"),
+            hint: Some("Run `jj git fetch` to update the conflicted remote tag.".to_owned()),
+        }),
+        // Remote tags are always tracked
+        BookmarkPushAction::RemoteUntracked => Ok(None),
+        BookmarkPushAction::Update(update) => Ok(Some(update)),
+    }
+}
+
 /// Creates or moves bookmarks based on the change IDs.
 fn update_change_bookmarks(
     ui: &Ui,
@@ -806,3 +1007,32 @@ fn find_bookmarks_targeted_by_revisions<'a>(
         .collect_vec();
     Ok(bookmarks_targeted)
 }
+
+fn find_tags_to_push<'a>(
+    view: &'a View,
+    tag_patterns: &[StringPattern],
+    remote_name: &str,
+) -> Result<Vec<(&'a str, LocalAndRemoteRef<'a>)>, CommandError> {
+    let mut matching_tags = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in tag_patterns {
+        let mut matches = view
+            .local_remote_tags(remote_name)
+            .filter(|(name, _)| pattern.matches(name))
+            .peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        matching_tags.extend(matches);
+    }
+    matching_tags.sort_unstable_by_key(|(name, _)| *name);
+    matching_tags.dedup_by_key(|(name, _)| *name);
+    match &unmatched_patterns[..] {
+        [] => Ok(matching_tags),
+        [pattern] if pattern.is_exact() => Err(user_error(format!("No such tag: {pattern}"))),
+        patterns => Err(user_error(format!(
+            "No matching tags for patterns: {}",
+            patterns.iter().join(", ")
+        ))),
+    }
+}
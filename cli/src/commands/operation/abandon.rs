@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::io::Write as _;
 use std::iter;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 
 use crate::cli_util::short_operation_hash;
@@ -37,11 +39,16 @@ use crate::ui::Ui;
 /// To discard recent operations, use `jj op restore <operation ID>` followed
 /// by `jj op abandon <operation ID>..@-`.
 ///
+/// Any [opset] expression can be specified as long as the selected operations
+/// form a contiguous range of the operation history.
+///
 /// The abandoned operations, commits, and other unreachable objects can later
 /// be garbage collected by using `jj util gc` command.
+///
+/// [opset]: https://jj-vcs.github.io/jj/latest/opsets/
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
-    /// The operation or operation range to abandon
+    /// The operations to abandon
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 }
@@ -63,35 +70,50 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation()
-            } else {
-                resolve_op(root_op_str)?
-            };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
-            } else {
-                vec![resolve_op(head_op_str)?]
-            };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation)?;
-            let parent_ops: Vec<_> = op.parents().try_collect()?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
-        };
-
-    if let Some(op) = abandon_head_ops
+    let workspace_env = command.workspace_environment(ui, &workspace)?;
+    let revset_parse_context = workspace_env.revset_parse_context();
+    let opset_parse_context = workspace_env.opset_parse_context(&revset_parse_context);
+    let abandon_ops = op_walk::resolve_ops_at(
+        repo_loader,
+        &current_head_ops,
+        &opset_parse_context,
+        &args.operation,
+    )?;
+    if abandon_ops.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    let abandon_op_ids: HashSet<&OperationId> = abandon_ops.iter().map(|op| op.id()).collect();
+    if abandon_ops.iter().any(|op| op.parent_ids().is_empty()) {
+        return Err(user_error("Cannot abandon the root operation"));
+    }
+    // The abandoned operations must form a contiguous range, so the
+    // descendants can be reparented onto the single operation below it.
+    let outside_parent_ids: Vec<&OperationId> = abandon_ops
         .iter()
-        .find(|op| current_head_ops.contains(op))
-    {
+        .flat_map(|op| op.parent_ids())
+        .filter(|id| !abandon_op_ids.contains(id))
+        .unique()
+        .collect();
+    let abandon_root_op = match &*outside_parent_ids {
+        [id] => repo_loader.load_operation(id)?,
+        _ if abandon_ops.len() == 1 => {
+            return Err(user_error("Cannot abandon a merge operation"));
+        }
+        _ => return Err(user_error("Cannot abandon non-contiguous operations")),
+    };
+    let inside_parent_ids: HashSet<&OperationId> = abandon_ops
+        .iter()
+        .flat_map(|op| op.parent_ids())
+        .filter(|id| abandon_op_ids.contains(id))
+        .collect();
+    let abandon_head_ops = abandon_ops
+        .iter()
+        .filter(|op| !inside_parent_ids.contains(op.id()))
+        .cloned()
+        .collect_vec();
+
+    if let Some(op) = abandon_ops.iter().find(|op| current_head_ops.contains(op)) {
         let mut err = user_error(format!(
             "Cannot abandon the current operation {}",
             short_operation_hash(op.id())
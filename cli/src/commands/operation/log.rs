@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
@@ -20,7 +22,10 @@ use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
+use jj_lib::graph::GraphNode;
 use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OpStoreResult;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
@@ -50,6 +55,14 @@ use crate::ui::Ui;
 /// to inspect the current state without mutation.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// If no operations are specified, all ancestors of the current operation
+    /// are shown. See [opsets] for the expression syntax.
+    ///
+    /// [opsets]: https://jj-vcs.github.io/jj/latest/opsets/
+    #[arg(long, short = 'r', value_name = "OPSET")]
+    operations: Vec<String>,
     /// Limit number of operations to show
     ///
     /// Applied after operations are reordered topologically, but before being
@@ -152,6 +165,24 @@ fn do_op_log(
             .labeled("node");
     }
 
+    let selected_op_ids: Option<HashSet<OperationId>> = if args.operations.is_empty() {
+        None
+    } else {
+        let revset_parse_context = workspace_env.revset_parse_context();
+        let opset_parse_context = workspace_env.opset_parse_context(&revset_parse_context);
+        let mut op_ids = HashSet::new();
+        for op_str in &args.operations {
+            let ops = op_walk::resolve_ops_at(
+                repo_loader,
+                slice::from_ref(current_op),
+                &opset_parse_context,
+                op_str,
+            )?;
+            op_ids.extend(ops.iter().map(|op| op.id().clone()));
+        }
+        Some(op_ids)
+    };
+
     let diff_formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
     let maybe_show_op_diff = if args.op_diff || !diff_formats.is_empty() {
         let template_text = settings.get_string("templates.commit_summary")?;
@@ -206,18 +237,23 @@ fn do_op_log(
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let iter =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
+    let all_ops = op_walk::walk_ancestors(slice::from_ref(current_op));
+    let iter: Box<dyn Iterator<Item = Result<_, OpStoreError>>> =
+        if let Some(op_ids) = &selected_op_ids {
+            Box::new(filter_op_graph(all_ops, op_ids)?.into_iter().map(Ok))
+        } else {
+            Box::new(all_ops.map(|op| {
+                let op = op?;
+                let ids = op.parent_ids();
+                let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
+                Ok((op, edges))
+            }))
+        };
+    let iter = iter.take(args.limit.unwrap_or(usize::MAX));
 
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            Ok((op, edges))
-        });
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
         } else {
@@ -251,8 +287,8 @@ fn do_op_log(
         } else {
             Box::new(iter)
         };
-        for op in iter {
-            let op = op?;
+        for node in iter {
+            let (op, _edges) = node?;
             with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
             if let Some(show) = &maybe_show_op_diff {
                 show(ui, formatter, &op, &with_content_format)?;
@@ -263,6 +299,39 @@ fn do_op_log(
     Ok(())
 }
 
+/// Builds graph nodes of the selected operations. Edges to unselected parents
+/// are redirected to their nearest selected ancestors.
+fn filter_op_graph(
+    all_ops: impl Iterator<Item = OpStoreResult<Operation>>,
+    op_ids: &HashSet<OperationId>,
+) -> OpStoreResult<Vec<GraphNode<Operation, OperationId>>> {
+    let all_ops: Vec<_> = all_ops.try_collect()?;
+    // Selected ancestors reachable from each unselected operation.
+    let mut nearest_ancestors: HashMap<&OperationId, Vec<OperationId>> = HashMap::new();
+    let mut nodes = Vec::new();
+    // Visit parents before children.
+    for op in all_ops.iter().rev() {
+        let mut edges = vec![];
+        for parent_id in op.parent_ids() {
+            if op_ids.contains(parent_id) {
+                edges.push(GraphEdge::direct(parent_id.clone()));
+            } else {
+                let ancestor_ids = &nearest_ancestors[parent_id];
+                edges.extend(ancestor_ids.iter().cloned().map(GraphEdge::indirect));
+            }
+        }
+        if op_ids.contains(op.id()) {
+            let edges = edges.into_iter().unique_by(|edge| edge.target.clone());
+            nodes.push((op.clone(), edges.collect()));
+        } else {
+            let ancestor_ids = edges.into_iter().map(|edge| edge.target).unique();
+            nearest_ancestors.insert(op.id(), ancestor_ids.collect());
+        }
+    }
+    nodes.reverse();
+    Ok(nodes)
+}
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
@@ -0,0 +1,361 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::Matcher;
+use jj_lib::repo::Repo;
+use jj_lib::rewrite::rebase_to_dest_parent;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::diff_util::DiffFormat;
+use crate::diff_util::DiffFormatArgs;
+use crate::diff_util::DiffRenderer;
+use crate::diff_util::UnifiedDiffOptions;
+use crate::formatter::Formatter;
+use crate::formatter::PlainTextFormatter;
+use crate::ui::Ui;
+
+/// Minimum similarity of the changed lines for two commits with unrelated
+/// change IDs to be considered versions of the same patch.
+const SIMILARITY_THRESHOLD: f64 = 0.5;
+
+/// Maximum number of predecessor generations to walk when looking for the old
+/// version of a commit. Long evolution histories are otherwise slow to search.
+const MAX_PREDECESSOR_DEPTH: usize = 100;
+
+/// Compare two versions of a series of commits
+///
+/// Commits in `--from` are paired with commits in `--to` that have the same
+/// change ID, or that were rewritten from them as recorded in the evolution
+/// log. Remaining commits are paired if they make similar changes.
+///
+/// Each pair is shown as unchanged (`=`) or modified (`!`). For modified
+/// commits, the changes between the two versions are shown like in `jj
+/// interdiff`. Commits only in `--from` are shown as dropped (`-`), and
+/// commits only in `--to` as added (`+`).
+#[derive(clap::Args, Clone, Debug)]
+#[command(mut_arg("ignore_all_space", |a| a.short('w')))]
+#[command(mut_arg("ignore_space_change", |a| a.short('b')))]
+pub(crate) struct RangeDiffArgs {
+    /// The old version of the commits
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    from: RevisionArg,
+    /// The new version of the commits
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    to: RevisionArg,
+    /// Restrict the diff to these paths
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+    #[command(flatten)]
+    format: DiffFormatArgs,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_range_diff(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &RangeDiffArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    // Parents first
+    let evaluate = |revision_arg: &RevisionArg| -> Result<Vec<Commit>, CommandError> {
+        let mut commits: Vec<_> = workspace_command
+            .parse_revset(ui, revision_arg)?
+            .evaluate_to_commits()?
+            .try_collect()?;
+        commits.reverse();
+        Ok(commits)
+    };
+    let old_commits = evaluate(&args.from)?;
+    let new_commits = evaluate(&args.to)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+
+    let git_diff_renderer = DiffRenderer::new(
+        repo,
+        workspace_command.path_converter(),
+        workspace_command.env().conflict_marker_style(),
+        vec![DiffFormat::Git(Box::new(
+            UnifiedDiffOptions::from_settings(workspace_command.settings())?,
+        ))],
+    );
+    let changed_lines = |commit: &Commit| -> Result<Vec<Vec<u8>>, CommandError> {
+        let mut output = vec![];
+        let mut formatter = PlainTextFormatter::new(&mut output);
+        git_diff_renderer.show_patch(ui, &mut formatter, commit, matcher.as_ref(), 80)?;
+        Ok(output
+            .split_inclusive(|b| *b == b'\n')
+            .filter(|line| {
+                (line.starts_with(b"+") && !line.starts_with(b"+++ "))
+                    || (line.starts_with(b"-") && !line.starts_with(b"--- "))
+            })
+            .map(|line| line.to_vec())
+            .collect())
+    };
+    let pairs = pair_commits(&old_commits, &new_commits, changed_lines)?;
+
+    let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let write_symbol = |formatter: &mut dyn Formatter, label: &str, symbol: &str| {
+        formatter.with_label("diff", |formatter| {
+            formatter.with_label(label, |formatter| write!(formatter, "{symbol} "))
+        })
+    };
+    let write_entry = |formatter: &mut dyn Formatter,
+                       label: &str,
+                       symbol: &str,
+                       commit: &Commit|
+     -> Result<(), CommandError> {
+        write_symbol(formatter, label, symbol)?;
+        workspace_command.write_commit_summary(formatter, commit)?;
+        writeln!(formatter)?;
+        Ok(())
+    };
+    let mut old_shown = vec![false; old_commits.len()];
+    for (new_index, new_commit) in new_commits.iter().enumerate() {
+        let Some(old_index) = pairs.new_to_old[new_index] else {
+            write_entry(formatter, "added", "+", new_commit)?;
+            continue;
+        };
+        // Show dropped commits which came before this one in the old series
+        for (index, old_commit) in old_commits.iter().enumerate().take(old_index) {
+            if !old_shown[index] && pairs.old_to_new[index].is_none() {
+                old_shown[index] = true;
+                write_entry(formatter, "removed", "-", old_commit)?;
+            }
+        }
+        old_shown[old_index] = true;
+        let old_commit = &old_commits[old_index];
+        let unchanged = old_commit.description() == new_commit.description()
+            && !has_inter_diff(repo, old_commit, new_commit, matcher.as_ref())?;
+        if unchanged {
+            write_symbol(formatter, "unchanged", "=")?;
+        } else {
+            write_symbol(formatter, "modified", "!")?;
+        }
+        write!(formatter, "{} -> ", short_commit_hash(old_commit.id()))?;
+        workspace_command.write_commit_summary(formatter, new_commit)?;
+        writeln!(formatter)?;
+        if !unchanged {
+            diff_renderer.show_inter_diff(
+                ui,
+                formatter,
+                slice::from_ref(old_commit),
+                new_commit,
+                matcher.as_ref(),
+                ui.term_width(),
+            )?;
+        }
+    }
+    for (index, old_commit) in old_commits.iter().enumerate() {
+        if !old_shown[index] {
+            write_entry(formatter, "removed", "-", old_commit)?;
+        }
+    }
+    Ok(())
+}
+
+/// Pairs of indices into the old and new commits.
+struct CommitPairs {
+    old_to_new: Vec<Option<usize>>,
+    new_to_old: Vec<Option<usize>>,
+}
+
+impl CommitPairs {
+    fn insert(&mut self, old_index: usize, new_index: usize) {
+        self.old_to_new[old_index] = Some(new_index);
+        self.new_to_old[new_index] = Some(old_index);
+    }
+}
+
+/// Pairs old and new versions of commits by change ID, then by walking the
+/// predecessors of the new commits, and finally by the similarity of their
+/// changes.
+fn pair_commits(
+    old_commits: &[Commit],
+    new_commits: &[Commit],
+    mut changed_lines: impl FnMut(&Commit) -> Result<Vec<Vec<u8>>, CommandError>,
+) -> Result<CommitPairs, CommandError> {
+    let mut pairs = CommitPairs {
+        old_to_new: vec![None; old_commits.len()],
+        new_to_old: vec![None; new_commits.len()],
+    };
+
+    for (new_index, new_commit) in new_commits.iter().enumerate() {
+        let old_index = (0..old_commits.len()).find(|&index| {
+            pairs.old_to_new[index].is_none()
+                && old_commits[index].change_id() == new_commit.change_id()
+        });
+        if let Some(old_index) = old_index {
+            pairs.insert(old_index, new_index);
+        }
+    }
+
+    let old_indices_by_id: HashMap<&CommitId, usize> = old_commits
+        .iter()
+        .enumerate()
+        .map(|(index, commit)| (commit.id(), index))
+        .collect();
+    for (new_index, new_commit) in new_commits.iter().enumerate() {
+        if pairs.new_to_old[new_index].is_some() {
+            continue;
+        }
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(Commit, usize)> = new_commit
+            .predecessors()
+            .map_ok(|commit| (commit, 1))
+            .try_collect()?;
+        while let Some((commit, depth)) = queue.pop_front() {
+            if !visited.insert(commit.id().clone()) {
+                continue;
+            }
+            if let Some(&old_index) = old_indices_by_id.get(commit.id()) {
+                if pairs.old_to_new[old_index].is_none() {
+                    pairs.insert(old_index, new_index);
+                    break;
+                }
+            }
+            if depth < MAX_PREDECESSOR_DEPTH {
+                for predecessor in commit.predecessors() {
+                    queue.push_back((predecessor?, depth + 1));
+                }
+            }
+        }
+    }
+
+    let unpaired_old: Vec<_> = (0..old_commits.len())
+        .filter(|&index| pairs.old_to_new[index].is_none())
+        .map(|index| Ok((index, changed_lines(&old_commits[index])?)))
+        .try_collect::<_, _, CommandError>()?;
+    let unpaired_new: Vec<_> = (0..new_commits.len())
+        .filter(|&index| pairs.new_to_old[index].is_none())
+        .map(|index| Ok((index, changed_lines(&new_commits[index])?)))
+        .try_collect::<_, _, CommandError>()?;
+    let mut candidates = vec![];
+    for (old_index, old_lines) in &unpaired_old {
+        for (new_index, new_lines) in &unpaired_new {
+            let score = similarity(old_lines, new_lines);
+            if score >= SIMILARITY_THRESHOLD {
+                candidates.push((score, *old_index, *new_index));
+            }
+        }
+    }
+    // Most similar first, then in order of the new commits
+    candidates.sort_by(|(score1, _, index1), (score2, _, index2)| {
+        score2.total_cmp(score1).then(index1.cmp(index2))
+    });
+    for (_, old_index, new_index) in candidates {
+        if pairs.old_to_new[old_index].is_none() && pairs.new_to_old[new_index].is_none() {
+            pairs.insert(old_index, new_index);
+        }
+    }
+    Ok(pairs)
+}
+
+/// Returns the fraction of lines which are common to both sides.
+fn similarity(lines1: &[Vec<u8>], lines2: &[Vec<u8>]) -> f64 {
+    if lines1.is_empty() && lines2.is_empty() {
+        return 0.0;
+    }
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for line in lines1 {
+        *counts.entry(line).or_default() += 1;
+    }
+    let mut common = 0;
+    for line in lines2 {
+        if let Some(count) = counts.get_mut(line.as_slice()) {
+            if *count > 0 {
+                *count -= 1;
+                common += 1;
+            }
+        }
+    }
+    (2 * common) as f64 / (lines1.len() + lines2.len()) as f64
+}
+
+/// Returns true if `old_commit` and `new_commit` make different changes to
+/// the paths matched by `matcher`.
+fn has_inter_diff(
+    repo: &dyn Repo,
+    old_commit: &Commit,
+    new_commit: &Commit,
+    matcher: &dyn Matcher,
+) -> Result<bool, CommandError> {
+    let old_tree = rebase_to_dest_parent(repo, slice::from_ref(old_commit), new_commit)?;
+    let new_tree = new_commit.tree()?;
+    if old_tree.id() == new_tree.id() {
+        return Ok(false);
+    }
+    Ok(old_tree
+        .diff_stream(&new_tree, matcher)
+        .next()
+        .block_on()
+        .is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity() {
+        let lines = |lines: &[&str]| {
+            lines
+                .iter()
+                .map(|line| line.as_bytes().to_vec())
+                .collect_vec()
+        };
+        assert_eq!(similarity(&[], &[]), 0.0);
+        assert_eq!(similarity(&lines(&["+a\n"]), &[]), 0.0);
+        assert_eq!(similarity(&lines(&["+a\n"]), &lines(&["+a\n"])), 1.0);
+        assert_eq!(
+            similarity(&lines(&["+a\n", "-b\n"]), &lines(&["+a\n", "-c\n"])),
+            0.5
+        );
+        // Repeated lines are only matched once
+        assert_eq!(
+            similarity(&lines(&["+a\n", "+a\n"]), &lines(&["+a\n", "+b\n"])),
+            0.5
+        );
+    }
+}
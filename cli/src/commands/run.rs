@@ -14,19 +14,57 @@
 
 //! This file contains the internal implementation of `run`.
 
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::file_util;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::WorkingCopy as _;
+use rayon::iter::IntoParallelRefIterator as _;
+use rayon::iter::ParallelIterator as _;
+use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::internal_error;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
-/// (**Stub**, does not work yet) Run a command across a set of revisions.
+/// Run a command across a set of revisions
+///
+/// The command is run through the shell once for each selected revision, in a
+/// temporary working copy containing the files of that revision. The temporary
+/// working copies live in the `.jj` directory and are reused between runs, so
+/// the main working copy is left untouched and ignored files like build
+/// outputs are kept around for incremental builds.
 ///
+/// If the command succeeds and changes any files, the revision is rewritten to
+/// contain the changed files. Descendants of rewritten revisions that were not
+/// selected are rebased. Revisions for which the command fails are left
+/// unchanged.
 ///
-/// All recorded state will be persisted in the `.jj` directory, so occasionally
-/// a `jj run --clean` is needed to clean up disk space.
+/// All recorded state, including the exit status of the command for each
+/// revision, will be persisted in the `.jj` directory, so occasionally a
+/// `jj run --clean` is needed to clean up disk space.
 ///
 /// # Example
 ///
@@ -38,7 +76,8 @@ use crate::ui::Ui;
 #[command(verbatim_doc_comment)]
 pub struct RunArgs {
     /// The command to run across all selected revisions.
-    shell_command: String,
+    #[arg(required_unless_present = "clean")]
+    shell_command: Option<String>,
     /// The revisions to change.
     #[arg(long, short, default_value = "@", value_name = "REVSETS")]
     revisions: Vec<RevisionArg>,
@@ -48,23 +87,255 @@ pub struct RunArgs {
     /// How many processes should run in parallel, uses by default all cores.
     #[arg(long, short)]
     jobs: Option<usize>,
+    /// Remove the temporary working copies and recorded exit statuses
+    ///
+    /// If a command is also given, it is run in freshly created working
+    /// copies.
+    #[arg(long)]
+    clean: bool,
+}
+
+/// The outcome of running the command on a single commit.
+struct RunResult {
+    status: ExitStatus,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    /// The tree of the temporary working copy after the command finished.
+    new_tree_id: MergedTreeId,
 }
 
+#[instrument(skip_all)]
 pub fn cmd_run(ui: &mut Ui, command: &CommandHelper, args: &RunArgs) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let _resolved_commits: Vec<_> = workspace_command
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let run_dir = workspace_command.workspace_root().join(".jj").join("run");
+    if args.clean {
+        match fs::remove_dir_all(&run_dir) {
+            Ok(()) => writeln!(ui.status(), "Removed temporary working copies")?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(internal_error_with_message(
+                    "Failed to remove temporary working copies",
+                    err,
+                ));
+            }
+        }
+    }
+    let Some(shell_command) = &args.shell_command else {
+        return Ok(());
+    };
+    let resolved_commits: Vec<_> = workspace_command
         .parse_union_revsets(ui, &args.revisions)?
         .evaluate_to_commits()?
         .try_collect()?;
+    if resolved_commits.is_empty() {
+        writeln!(ui.status(), "No revisions to run on.")?;
+        return Ok(());
+    }
+    workspace_command.check_rewritable(resolved_commits.iter().ids())?;
     // Jobs are resolved in this order:
     // 1. Commandline argument iff > 0.
     // 2. the amount of cores available.
     // 3. a single job, if all of the above fails.
-    let _jobs = match args.jobs {
+    let jobs = match args.jobs {
         Some(0) | None => std::thread::available_parallelism().map(|t| t.into()).ok(),
         Some(jobs) => Some(jobs),
     }
     // Fallback to a single user-visible job.
     .unwrap_or(1usize);
-    Err(user_error("This is a stub, do not use"))
+
+    let repo = workspace_command.repo().clone();
+    let workspace_id = workspace_command.workspace_id().clone();
+    let checkout_options = workspace_command.checkout_options();
+    // New files created by the command are tracked regardless of the
+    // `snapshot.auto-track` setting, since the user asked for them explicitly.
+    let snapshot_options = workspace_command
+        .snapshot_options_with_start_tracking_matcher(&jj_lib::matchers::EverythingMatcher)?;
+    let working_copies_dir = run_dir.join("working_copies");
+    let exit_status_dir = run_dir.join("exit_status");
+    file_util::create_or_reuse_dir(&run_dir)?;
+    file_util::create_or_reuse_dir(&working_copies_dir)?;
+    file_util::create_or_reuse_dir(&exit_status_dir)?;
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(internal_error)?;
+    // Run in topological order, so that slots tend to be reused for nearby
+    // commits, which keeps checkouts and incremental builds cheap.
+    let results: HashMap<CommitId, RunResult> = thread_pool.install(|| {
+        resolved_commits
+            .iter()
+            .rev()
+            .collect_vec()
+            .par_iter()
+            .map(|commit| -> Result<_, CommandError> {
+                // Each thread of the pool owns one temporary working copy.
+                let slot = rayon::current_thread_index().unwrap_or(0);
+                let result = run_in_working_copy(
+                    &repo,
+                    &working_copies_dir.join(slot.to_string()),
+                    commit,
+                    shell_command,
+                    &workspace_id,
+                    &checkout_options,
+                    &snapshot_options,
+                )?;
+                record_exit_status(&exit_status_dir, commit.id(), result.status)?;
+                Ok((commit.id().clone(), result))
+            })
+            .collect::<Result<_, CommandError>>()
+    })?;
+
+    for commit in resolved_commits.iter().rev() {
+        let result = &results[commit.id()];
+        ui.stdout().write_all(&result.stdout)?;
+        ui.stderr().write_all(&result.stderr)?;
+        if !result.status.success() {
+            write!(
+                ui.warning_default(),
+                "Command failed ({}) on ",
+                result.status
+            )?;
+            let mut formatter = ui.stderr_formatter();
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    let num_failed = results
+        .values()
+        .filter(|result| !result.status.success())
+        .count();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut num_rewritten = 0;
+    let mut num_rebased = 0;
+    tx.repo_mut().transform_descendants(
+        resolved_commits.iter().ids().cloned().collect_vec(),
+        |rewriter| {
+            match results.get(rewriter.old_commit().id()) {
+                Some(result) if result.status.success() => {
+                    // The command ran on this commit, so its tree is the result of
+                    // the run. Rebasing would bring in the changes from the
+                    // rewritten parents a second time.
+                    if rewriter.parents_changed()
+                        || result.new_tree_id != *rewriter.old_commit().tree_id()
+                    {
+                        num_rewritten += 1;
+                        rewriter
+                            .reparent()
+                            .set_tree_id(result.new_tree_id.clone())
+                            .write()?;
+                    }
+                }
+                _ => {
+                    if rewriter.parents_changed() {
+                        num_rebased += 1;
+                        rewriter.rebase()?.write()?;
+                    }
+                }
+            }
+            Ok(())
+        },
+    )?;
+    if num_rewritten > 0 || num_rebased > 0 {
+        writeln!(
+            ui.status(),
+            "Rewrote {num_rewritten} commits and rebased {num_rebased} descendant commits"
+        )?;
+    }
+    tx.finish(
+        ui,
+        format!("run command '{shell_command}' on {} commits", results.len()),
+    )?;
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "Command failed on {num_failed} of {} commits",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks out `commit` in the temporary working copy at `slot_dir`, runs the
+/// command in it, and snapshots the result.
+fn run_in_working_copy(
+    repo: &ReadonlyRepo,
+    slot_dir: &Path,
+    commit: &Commit,
+    shell_command: &str,
+    workspace_id: &WorkspaceId,
+    checkout_options: &CheckoutOptions,
+    snapshot_options: &SnapshotOptions,
+) -> Result<RunResult, CommandError> {
+    let store = repo.store();
+    let working_copy_path = slot_dir.join("working_copy");
+    let state_path = slot_dir.join("state");
+    file_util::create_or_reuse_dir(slot_dir)?;
+    file_util::create_or_reuse_dir(&working_copy_path)?;
+    let working_copy = if state_path.join("checkout").exists() {
+        LocalWorkingCopy::load(store.clone(), working_copy_path.clone(), state_path)
+    } else {
+        file_util::create_or_reuse_dir(&state_path)?;
+        LocalWorkingCopy::init(
+            store.clone(),
+            working_copy_path.clone(),
+            state_path,
+            repo.op_id().clone(),
+            workspace_id.clone(),
+        )?
+    };
+    let mut locked_working_copy = working_copy.start_mutation()?;
+    // Files modified by a previous run have already been snapshotted into the
+    // working copy state, so this only updates the files that differ.
+    locked_working_copy
+        .check_out(commit, checkout_options)
+        .map_err(|err| internal_error_with_message("Failed to check out commit", err))?;
+
+    let output = shell_command_in(shell_command)
+        .current_dir(&working_copy_path)
+        .env("JJ_RUN_COMMIT_ID", commit.id().hex())
+        .env("JJ_RUN_CHANGE_ID", commit.change_id().hex())
+        .output()
+        .map_err(|err| user_error(format!("Failed to run '{shell_command}': {err}")))?;
+
+    // Snapshot even if the command failed so the working copy state stays in
+    // sync with the files on disk.
+    let (new_tree_id, _stats) = locked_working_copy.snapshot(snapshot_options)?;
+    locked_working_copy.finish(repo.op_id().clone())?;
+    Ok(RunResult {
+        status: output.status,
+        stdout: output.stdout,
+        stderr: output.stderr,
+        new_tree_id,
+    })
+}
+
+#[cfg(unix)]
+fn shell_command_in(shell_command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(shell_command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell_command_in(shell_command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(shell_command);
+    cmd
+}
+
+/// Writes the exit status of the command for `commit_id` so it can be
+/// inspected after the run.
+fn record_exit_status(
+    exit_status_dir: &Path,
+    commit_id: &CommitId,
+    status: ExitStatus,
+) -> Result<(), CommandError> {
+    let path: PathBuf = exit_status_dir.join(commit_id.hex());
+    let content = match status.code() {
+        Some(code) => code.to_string(),
+        None => status.to_string(),
+    };
+    fs::write(&path, content + "\n")?;
+    Ok(())
 }
@@ -0,0 +1,159 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::signing::SignBehavior;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Cryptographically sign a revision
+///
+/// Existing signatures are replaced by new ones made with the configured
+/// signing backend. Descendants of the signed revisions are rebased.
+///
+/// This command requires configuring a [commit signing] backend.
+///
+/// [commit signing]:
+///     https://jj-vcs.github.io/jj/latest/config/#commit-signing
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct SignArgs {
+    /// What revision(s) to sign
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The key used for signing
+    ///
+    /// Defaults to the `signing.key` setting.
+    #[arg(long)]
+    key: Option<String>,
+}
+
+pub(crate) fn cmd_sign(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SignArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if !workspace_command.repo().store().signer().can_sign() {
+        return Err(user_error_with_hint(
+            "No signing backend configured",
+            "For configuring a signing backend, see \
+             https://jj-vcs.github.io/jj/latest/config/#commit-signing",
+        ));
+    }
+    let to_sign: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    workspace_command.check_rewritable(to_sign.iter().ids())?;
+
+    let mut tx = workspace_command.start_transaction();
+    let (signed_commits, num_rebased) =
+        rewrite_signatures(&mut tx, &to_sign, SignBehavior::Force, args.key.as_deref())?;
+    print_rewritten_commits(ui, &tx, "Signed", &signed_commits, num_rebased)?;
+    tx.finish(ui, transaction_description("sign", &signed_commits))?;
+    Ok(())
+}
+
+/// Rewrites `commits` with the given signing behavior and rebases their
+/// descendants. Returns the rewritten commits and the number of rebased
+/// descendants.
+pub(crate) fn rewrite_signatures(
+    tx: &mut WorkspaceCommandTransaction,
+    commits: &[Commit],
+    sign_behavior: SignBehavior,
+    key: Option<&str>,
+) -> Result<(Vec<Commit>, usize), CommandError> {
+    let commit_ids: HashSet<_> = commits.iter().ids().cloned().collect();
+    let mut rewritten_commits = vec![];
+    let mut num_rebased = 0;
+    tx.repo_mut().transform_descendants(
+        commits.iter().ids().cloned().collect_vec(),
+        |rewriter| {
+            if commit_ids.contains(rewriter.old_commit().id()) {
+                let mut commit_builder = rewriter.reparent().set_sign_behavior(sign_behavior);
+                if let Some(key) = key {
+                    commit_builder = commit_builder.set_sign_key(Some(key.to_owned()));
+                }
+                rewritten_commits.push(commit_builder.write()?);
+            } else {
+                rewriter.reparent().write()?;
+                num_rebased += 1;
+            }
+            Ok(())
+        },
+    )?;
+    Ok((rewritten_commits, num_rebased))
+}
+
+pub(crate) fn print_rewritten_commits(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    action: &str,
+    commits: &[Commit],
+    num_rebased: usize,
+) -> Result<(), CommandError> {
+    let Some(mut formatter) = ui.status_formatter() else {
+        return Ok(());
+    };
+    match commits {
+        [] => {
+            writeln!(formatter, "Nothing changed.")?;
+        }
+        [commit] => {
+            write!(formatter, "{action} 1 commit: ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+        commits => {
+            let template = tx.commit_summary_template();
+            writeln!(formatter, "{action} {} commits:", commits.len())?;
+            for commit in commits {
+                write!(formatter, "  ")?;
+                template.format(commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+        }
+    }
+    if num_rebased > 0 {
+        writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+    }
+    Ok(())
+}
+
+pub(crate) fn transaction_description(verb: &str, commits: &[Commit]) -> String {
+    match commits {
+        [] => format!("{verb} no commits"),
+        [commit] => format!("{verb} commit {}", commit.id().hex()),
+        [first, rest @ ..] => format!("{verb} commit {} and {} more", first.id().hex(), rest.len()),
+    }
+}
@@ -13,16 +13,13 @@
 // limitations under the License.
 
 use std::fmt::Write as _;
-use std::path::Path;
 
-use itertools::Itertools;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
+use super::parse_sparse_patterns;
 use super::update_sparse_patterns_with;
 use crate::cli_util::CommandHelper;
-use crate::command_error::internal_error;
-use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::description_util::TextEditor;
 use crate::ui::Ui;
@@ -39,8 +36,8 @@ pub fn cmd_sparse_edit(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let editor = workspace_command.text_editor()?;
-    update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
-        let mut new_patterns = edit_sparse(&editor, old_patterns)?;
+    update_sparse_patterns_with(ui, &mut workspace_command, |ui, old_patterns| {
+        let mut new_patterns = edit_sparse(ui, &editor, old_patterns)?;
         new_patterns.sort_unstable();
         new_patterns.dedup();
         Ok(new_patterns)
@@ -48,35 +45,23 @@ pub fn cmd_sparse_edit(
 }
 
 fn edit_sparse(
+    ui: &Ui,
     editor: &TextEditor,
-    sparse: &[RepoPathBuf],
-) -> Result<Vec<RepoPathBuf>, CommandError> {
+    sparse: &[SparsePattern],
+) -> Result<Vec<SparsePattern>, CommandError> {
     let mut content = String::new();
-    for sparse_path in sparse {
-        // Invalid path shouldn't block editing. Edited paths will be validated.
-        let workspace_relative_sparse_path = sparse_path.to_fs_path_unchecked(Path::new(""));
-        let path_string = workspace_relative_sparse_path.to_str().ok_or_else(|| {
-            internal_error(format!(
-                "Stored sparse path is not valid utf-8: {}",
-                workspace_relative_sparse_path.display()
-            ))
-        })?;
-        writeln!(&mut content, "{path_string}").unwrap();
+    for pattern in sparse {
+        writeln!(&mut content, "{pattern}").unwrap();
     }
 
     let content = editor
         .edit_str(content, Some(".jjsparse"))
         .map_err(|err| err.with_name("sparse patterns"))?;
 
-    content
+    let lines = content
         .lines()
         .filter(|line| !line.starts_with("JJ:"))
         .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            RepoPathBuf::from_relative_path(line).map_err(|err| {
-                user_error_with_message(format!("Failed to parse sparse pattern: {line}"), err)
-            })
-        })
-        .try_collect()
+        .filter(|line| !line.is_empty());
+    parse_sparse_patterns(ui, lines)
 }
@@ -13,7 +13,6 @@
 // limitations under the License.
 
 use std::io::Write as _;
-use std::path::Path;
 
 use tracing::instrument;
 
@@ -26,6 +25,8 @@ use crate::ui::Ui;
 /// By default, a newly cloned or initialized repo will have have a pattern
 /// matching all files from the repo root. That pattern is rendered as `.` (a
 /// single period).
+///
+/// Patterns are listed as fileset expressions of workspace-relative paths.
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseListArgs {}
 
@@ -36,12 +37,8 @@ pub fn cmd_sparse_list(
     _args: &SparseListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    for path in workspace_command.working_copy().sparse_patterns()? {
-        writeln!(
-            ui.stdout(),
-            "{}",
-            path.to_fs_path_unchecked(Path::new("")).display()
-        )?;
+    for pattern in workspace_command.working_copy().sparse_patterns()? {
+        writeln!(ui.stdout(), "{pattern}")?;
     }
     Ok(())
 }
@@ -16,15 +16,20 @@ mod edit;
 mod list;
 mod reset;
 mod set;
+mod r#use;
 
 use clap::Subcommand;
-use jj_lib::repo_path::RepoPathBuf;
+use itertools::Itertools as _;
+use jj_lib::fileset::FilesetDiagnostics;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use self::edit::cmd_sparse_edit;
 use self::edit::SparseEditArgs;
 use self::list::cmd_sparse_list;
 use self::list::SparseListArgs;
+use self::r#use::cmd_sparse_use;
+use self::r#use::SparseUseArgs;
 use self::reset::cmd_sparse_reset;
 use self::reset::SparseResetArgs;
 use self::set::cmd_sparse_set;
@@ -33,6 +38,8 @@ use crate::cli_util::print_checkout_stats;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::internal_error_with_message;
+use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -44,6 +51,7 @@ pub(crate) enum SparseCommand {
     List(SparseListArgs),
     Reset(SparseResetArgs),
     Set(SparseSetArgs),
+    Use(SparseUseArgs),
 }
 
 #[instrument(skip_all)]
@@ -57,13 +65,33 @@ pub(crate) fn cmd_sparse(
         SparseCommand::List(args) => cmd_sparse_list(ui, command, args),
         SparseCommand::Reset(args) => cmd_sparse_reset(ui, command, args),
         SparseCommand::Set(args) => cmd_sparse_set(ui, command, args),
+        SparseCommand::Use(args) => cmd_sparse_use(ui, command, args),
     }
 }
 
+/// Parses sparse patterns, which are fileset expressions of workspace-relative
+/// paths.
+fn parse_sparse_patterns<'a>(
+    ui: &Ui,
+    texts: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<SparsePattern>, CommandError> {
+    let mut diagnostics = FilesetDiagnostics::new();
+    let patterns = texts
+        .into_iter()
+        .map(|text| {
+            SparsePattern::parse(&mut diagnostics, text).map_err(|err| {
+                user_error_with_message(format!("Failed to parse sparse pattern: {text}"), err)
+            })
+        })
+        .try_collect()?;
+    print_parse_diagnostics(ui, "In sparse pattern", &diagnostics)?;
+    Ok(patterns)
+}
+
 fn update_sparse_patterns_with(
     ui: &mut Ui,
     workspace_command: &mut WorkspaceCommandHelper,
-    f: impl FnOnce(&mut Ui, &[RepoPathBuf]) -> Result<Vec<RepoPathBuf>, CommandError>,
+    f: impl FnOnce(&mut Ui, &[SparsePattern]) -> Result<Vec<SparsePattern>, CommandError>,
 ) -> Result<(), CommandError> {
     let checkout_options = workspace_command.checkout_options();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
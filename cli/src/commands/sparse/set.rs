@@ -15,9 +15,9 @@
 use std::collections::HashSet;
 
 use itertools::Itertools as _;
-use jj_lib::repo_path::RepoPathBuf;
 use tracing::instrument;
 
+use super::parse_sparse_patterns;
 use super::update_sparse_patterns_with;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -28,23 +28,23 @@ use crate::ui::Ui;
 /// For example, if all you need is the `README.md` and the `lib/`
 /// directory, use `jj sparse set --clear --add README.md --add lib`.
 /// If you no longer need the `lib` directory, use `jj sparse set --remove lib`.
+///
+/// Patterns are [fileset expressions] of workspace-relative paths. A pattern
+/// matching a directory includes all the files in it. For example,
+/// `--add 'glob:services/*/api'` includes the `api` directory of every
+/// service. Negated patterns such as `--add '~docs'` exclude the paths from
+/// the ones included by the other patterns.
+///
+/// [fileset expressions]:
+///     https://jj-vcs.github.io/jj/latest/filesets/
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseSetArgs {
     /// Patterns to add to the working copy
-    #[arg(
-        long,
-        value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
-    )]
-    add: Vec<RepoPathBuf>,
+    #[arg(long, value_hint = clap::ValueHint::AnyPath)]
+    add: Vec<String>,
     /// Patterns to remove from the working copy
-    #[arg(
-        long,
-        conflicts_with = "clear",
-        value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
-    )]
-    remove: Vec<RepoPathBuf>,
+    #[arg(long, conflicts_with = "clear", value_hint = clap::ValueHint::AnyPath)]
+    remove: Vec<String>,
     /// Include no files in the working copy (combine with --add)
     #[arg(long)]
     clear: bool,
@@ -57,17 +57,17 @@ pub fn cmd_sparse_set(
     args: &SparseSetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let added_patterns = parse_sparse_patterns(ui, args.add.iter().map(String::as_str))?;
+    let removed_patterns = parse_sparse_patterns(ui, args.remove.iter().map(String::as_str))?;
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
         let mut new_patterns = HashSet::new();
         if !args.clear {
             new_patterns.extend(old_patterns.iter().cloned());
-            for path in &args.remove {
-                new_patterns.remove(path);
+            for pattern in &removed_patterns {
+                new_patterns.remove(pattern);
             }
         }
-        for path in &args.add {
-            new_patterns.insert(path.to_owned());
-        }
+        new_patterns.extend(added_patterns);
         Ok(new_patterns.into_iter().sorted_unstable().collect())
     })
 }
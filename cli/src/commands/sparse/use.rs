@@ -0,0 +1,112 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+use std::path::Path;
+
+use jj_lib::backend::TreeValue;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
+use tracing::instrument;
+
+use super::parse_sparse_patterns;
+use super::update_sparse_patterns_with;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Directory of the sparse profiles in the repo
+const SPARSE_PROFILES_DIR: &str = ".jj-sparse";
+
+/// Set the patterns from a sparse profile checked into the repo
+///
+/// Sparse profiles are TOML files in the `.jj-sparse/` directory of the
+/// working-copy commit. For example, `jj sparse use backend` sets the patterns
+/// of the current workspace from `.jj-sparse/backend.toml`, which may contain
+/// `patterns = ["root-file:README.md", "glob:services/*/api", "~docs"]`.
+///
+/// Each workspace can use a different profile. The patterns aren't updated
+/// when the profile changes, so run `jj sparse use` again to apply the
+/// changes.
+#[derive(clap::Args, Clone, Debug)]
+pub struct SparseUseArgs {
+    /// Name of the profile, without the `.toml` extension
+    profile: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SparseProfile {
+    patterns: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_sparse_use(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SparseUseArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let profile_path = Path::new(SPARSE_PROFILES_DIR).join(format!("{}.toml", args.profile));
+    let profile_repo_path = RepoPathBuf::from_relative_path(&profile_path).map_err(|err| {
+        user_error_with_message(
+            format!("Invalid sparse profile name: {}", args.profile),
+            err,
+        )
+    })?;
+    let wc_commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?;
+    let wc_commit = workspace_command.repo().store().get_commit(wc_commit_id)?;
+    let tree = wc_commit.tree()?;
+    let mut content = String::new();
+    match tree.path_value(&profile_repo_path)?.into_resolved() {
+        Ok(Some(TreeValue::File { id, .. })) => {
+            let store = workspace_command.repo().store();
+            store
+                .read_file(&profile_repo_path, &id)?
+                .read_to_string(&mut content)
+                .map_err(|err| {
+                    user_error_with_message(
+                        format!("Failed to read sparse profile {}", profile_path.display()),
+                        err,
+                    )
+                })?;
+        }
+        Ok(None) => {
+            return Err(user_error(format!(
+                "No such sparse profile: {}",
+                profile_path.display()
+            )));
+        }
+        _ => {
+            return Err(user_error(format!(
+                "Sparse profile {} is not a file",
+                profile_path.display()
+            )));
+        }
+    }
+    let profile: SparseProfile = toml_edit::de::from_str(&content).map_err(|err| {
+        user_error_with_message(
+            format!("Failed to parse sparse profile {}", profile_path.display()),
+            err,
+        )
+    })?;
+    let new_patterns = parse_sparse_patterns(ui, profile.patterns.iter().map(String::as_str))?;
+    update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| {
+        Ok(new_patterns)
+    })
+}
@@ -24,6 +24,7 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::ui::Ui;
@@ -151,6 +152,7 @@ The remainder will be in the second commit.
             &temp_commit,
         )?;
         let description = edit_description(&text_editor, &template)?;
+        let description = add_trailers(ui, &tx, &temp_commit, &description)?;
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
     };
@@ -190,7 +192,8 @@ The remainder will be in the second commit.
                 "Enter a description for the second commit.",
                 &temp_commit,
             )?;
-            edit_description(&text_editor, &template)?
+            let description = edit_description(&text_editor, &template)?;
+            add_trailers(ui, &tx, &temp_commit, &description)?
         };
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
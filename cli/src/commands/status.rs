@@ -14,18 +14,28 @@
 
 use std::io;
 
+use futures::executor::block_on_stream;
 use itertools::Itertools;
+use jj_lib::backend::TreeValue;
 use jj_lib::copies::CopyRecords;
+use jj_lib::matchers::Matcher;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::submodule_store::SubmoduleStore;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::diff_util::get_copy_records;
 use crate::diff_util::DiffFormat;
+use crate::formatter::Formatter;
 use crate::ui::Ui;
 
 /// Show high-level repo status
@@ -92,6 +102,14 @@ pub(crate) fn cmd_status(
                     &copy_records,
                     width,
                 )?;
+                print_submodule_changes(
+                    formatter,
+                    repo.submodule_store().as_ref(),
+                    &parent_tree,
+                    &tree,
+                    &matcher,
+                    workspace_command.path_converter(),
+                )?;
             }
 
             if wc_has_untracked {
@@ -211,3 +229,56 @@ pub(crate) fn cmd_status(
 
     Ok(())
 }
+
+/// Prints the commits the changed submodules were moved between, along with
+/// the summary of the new commit if it has been fetched.
+fn print_submodule_changes(
+    formatter: &mut dyn Formatter,
+    submodule_store: &dyn SubmoduleStore,
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    path_converter: &RepoPathUiConverter,
+) -> Result<(), CommandError> {
+    let submodule_id = |value: &MergedTreeValue| match value.as_resolved() {
+        Some(Some(TreeValue::GitSubmodule(id))) => Some(id.clone()),
+        _ => None,
+    };
+    let mut changes = vec![];
+    for TreeDiffEntry { path, values } in block_on_stream(from_tree.diff_stream(to_tree, matcher)) {
+        let (before, after) = values?;
+        let (old_id, new_id) = (submodule_id(&before), submodule_id(&after));
+        if old_id.is_some() || new_id.is_some() {
+            changes.push((path, old_id, new_id));
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(formatter, "Submodule changes:")?;
+    for (path, old_id, new_id) in changes {
+        let ui_path = path_converter.format_file_path(&path);
+        write!(formatter, "{ui_path}: ")?;
+        match (&old_id, &new_id) {
+            (Some(old_id), Some(new_id)) => write!(
+                formatter,
+                "{} -> {}",
+                short_commit_hash(old_id),
+                short_commit_hash(new_id)
+            )?,
+            (None, Some(new_id)) => write!(formatter, "added at {}", short_commit_hash(new_id))?,
+            (Some(old_id), None) => {
+                write!(formatter, "removed (was at {})", short_commit_hash(old_id))?;
+            }
+            (None, None) => unreachable!(),
+        }
+        // The summary is only informational, so lookup errors aren't fatal.
+        if let Some(Ok(Some(commit))) = new_id.map(|id| submodule_store.get_commit(&id)) {
+            let summary = commit.description.lines().next().unwrap_or_default();
+            write!(formatter, " {summary}")?;
+        }
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
@@ -12,10 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap::builder::NonEmptyStringValueParser;
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+#[cfg(feature = "git")]
+use jj_lib::git;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
 use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+#[cfg(feature = "git")]
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::RefName;
@@ -25,13 +38,89 @@ use crate::ui::Ui;
 /// Manage tags.
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum TagCommand {
+    #[command(visible_alias("c"))]
+    Create(TagCreateArgs),
+    #[command(visible_alias("d"))]
+    Delete(TagDeleteArgs),
     #[command(visible_alias("l"))]
     List(TagListArgs),
+    #[command(visible_alias("s"))]
+    Set(TagSetArgs),
+}
+
+/// Create a new tag
+///
+/// Tags are exported to the underlying Git repo as `refs/tags/<name>`, and can
+/// be pushed with `jj git push --tag`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagCreateArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: Option<RevisionArg>,
+
+    /// Create an annotated tag with the given message
+    ///
+    /// Without this option, a lightweight tag is created.
+    #[arg(long, short, value_name = "MESSAGE")]
+    message: Option<String>,
+
+    /// The tags to create
+    #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
+    names: Vec<String>,
+}
+
+/// Delete existing tags
+///
+/// The deletion is exported to the underlying Git repo, and propagated to
+/// remotes with `jj git push --tag`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagDeleteArgs {
+    /// The tags to delete
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select tags by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(required = true, value_parser = StringPattern::parse)]
+    names: Vec<StringPattern>,
 }
 
 /// List tags.
 #[derive(clap::Args, Clone, Debug)]
 pub struct TagListArgs {
+    /// Show all tracking and non-tracking remote tags including the ones whose
+    /// targets are synchronized with the local tags
+    #[arg(long, short, alias = "all")]
+    pub all_remotes: bool,
+    /// Show all tracking and non-tracking remote tags belonging to this remote
+    ///
+    /// Can be combined with `--tracked` or `--conflicted` to filter the tags
+    /// shown (can be repeated.)
+    ///
+    /// By default, the specified remote name matches exactly. Use `glob:`
+    /// prefix to select remotes by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(
+        long = "remote",
+        value_name = "REMOTE",
+        conflicts_with_all = ["all_remotes"],
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    pub remotes: Option<Vec<StringPattern>>,
+    /// Show remote tracked tags only. Omits local Git-tracking tags by default
+    #[arg(long, short, conflicts_with_all = ["all_remotes"])]
+    pub tracked: bool,
+    /// Show conflicted tags only
+    #[arg(long, short, conflicts_with_all = ["all_remotes"])]
+    pub conflicted: bool,
     /// Show tags whose local name matches
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
@@ -55,14 +144,122 @@ pub struct TagListArgs {
     template: Option<String>,
 }
 
+/// Create or update a tag to point to a certain commit
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagSetArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: Option<RevisionArg>,
+
+    /// Create an annotated tag with the given message
+    ///
+    /// Without this option, the tag is updated as a lightweight tag.
+    #[arg(long, short, value_name = "MESSAGE")]
+    message: Option<String>,
+
+    /// The tags to update
+    #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
+    names: Vec<String>,
+}
+
 pub fn cmd_tag(
     ui: &mut Ui,
     command: &CommandHelper,
     subcommand: &TagCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        TagCommand::Create(args) => cmd_tag_create(ui, command, args),
+        TagCommand::Delete(args) => cmd_tag_delete(ui, command, args),
         TagCommand::List(args) => cmd_tag_list(ui, command, args),
+        TagCommand::Set(args) => cmd_tag_set(ui, command, args),
+    }
+}
+
+fn cmd_tag_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagCreateArgs,
+) -> Result<(), CommandError> {
+    let tag_names = &args.names;
+    check_tag_names(tag_names)?;
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let view = workspace_command.repo().view();
+    for name in tag_names {
+        if view.get_tag(name).is_present() {
+            return Err(user_error_with_hint(
+                format!("Tag already exists: {name}"),
+                "Use `jj tag set` to update it.",
+            ));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    set_tags(&mut tx, tag_names, &target_commit, args.message.as_deref())?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Created {} tags pointing to ", tag_names.len())?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+    if tag_names.len() > 1 && args.revision.is_none() {
+        writeln!(ui.hint_default(), "Use -r to specify the target revision.")?;
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "create tag {names} pointing to commit {id}",
+            names = tag_names.join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
+
+fn cmd_tag_delete(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagDeleteArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let mut matched_tags = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in &args.names {
+        let mut matches = repo.view().tags_matching(pattern).peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        matched_tags.extend(matches.map(|(name, _)| name));
     }
+    match &unmatched_patterns[..] {
+        [] => {}
+        [pattern] if pattern.is_exact() => {
+            return Err(user_error(format!("No such tag: {pattern}")));
+        }
+        patterns => {
+            return Err(user_error(format!(
+                "No matching tags for patterns: {}",
+                patterns.iter().join(", ")
+            )));
+        }
+    }
+    matched_tags.sort_unstable();
+    matched_tags.dedup();
+
+    let mut tx = workspace_command.start_transaction();
+    for name in &matched_tags {
+        tx.repo_mut().set_tag_target(name, RefTarget::absent());
+    }
+    writeln!(ui.status(), "Deleted {} tags.", matched_tags.len())?;
+    tx.finish(ui, format!("delete tag {}", matched_tags.iter().join(", ")))?;
+    Ok(())
 }
 
 fn cmd_tag_list(
@@ -88,13 +285,149 @@ fn cmd_tag_list(
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
 
-    for (name, target) in view.tags() {
-        if !args.names.is_empty() && !args.names.iter().any(|pattern| pattern.matches(name)) {
-            continue;
+    let tags_to_list = view.tags_with_remotes().filter(|(name, target)| {
+        (args.names.is_empty() || args.names.iter().any(|pattern| pattern.matches(name)))
+            && (!args.conflicted || target.local_target.has_conflict())
+    });
+    for (name, tag_target) in tags_to_list {
+        let local_target = tag_target.local_target;
+        let remote_refs = tag_target.remote_refs;
+        let (mut tracking_remote_refs, untracked_remote_refs) = remote_refs
+            .iter()
+            .copied()
+            .filter(|&(remote_name, _)| {
+                args.remotes.as_ref().map_or(true, |patterns| {
+                    patterns.iter().any(|pattern| pattern.matches(remote_name))
+                })
+            })
+            .partition::<Vec<_>, _>(|&(_, remote_ref)| remote_ref.is_tracking());
+
+        if args.tracked {
+            tracking_remote_refs.retain(|&(remote, _)| !jj_lib::git::is_special_git_remote(remote));
+        } else if !args.all_remotes && args.remotes.is_none() {
+            tracking_remote_refs.retain(|&(_, remote_ref)| remote_ref.target != *local_target);
+        }
+
+        let include_local_only = !args.tracked && args.remotes.is_none();
+        if include_local_only && local_target.is_present() || !tracking_remote_refs.is_empty() {
+            let ref_name = RefName::local(
+                name,
+                local_target.clone(),
+                remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
+            );
+            template.format(&ref_name, formatter.as_mut())?;
+        }
+
+        for &(remote, remote_ref) in &tracking_remote_refs {
+            let ref_name = RefName::remote(name, remote, remote_ref.clone(), local_target);
+            template.format(&ref_name, formatter.as_mut())?;
+        }
+
+        if !args.tracked && (args.all_remotes || args.remotes.is_some()) {
+            for &(remote, remote_ref) in &untracked_remote_refs {
+                let ref_name = RefName::remote_only(name, remote, remote_ref.target.clone());
+                template.format(&ref_name, formatter.as_mut())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_tag_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagSetArgs,
+) -> Result<(), CommandError> {
+    let tag_names = &args.names;
+    check_tag_names(tag_names)?;
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let view = workspace_command.repo().view();
+    let mut new_tag_count = 0;
+    let mut moved_tag_count = 0;
+    for name in tag_names {
+        let old_target = view.get_tag(name);
+        if old_target.is_absent() {
+            new_tag_count += 1;
+        } else if old_target.as_normal() != Some(target_commit.id()) {
+            moved_tag_count += 1;
         }
-        let ref_name = RefName::local_only(name, target.clone());
-        template.format(&ref_name, formatter.as_mut())?;
     }
 
+    let mut tx = workspace_command.start_transaction();
+    set_tags(&mut tx, tag_names, &target_commit, args.message.as_deref())?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if new_tag_count > 0 {
+            write!(formatter, "Created {new_tag_count} tags pointing to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+        if moved_tag_count > 0 {
+            write!(formatter, "Moved {moved_tag_count} tags to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    if tag_names.len() > 1 && args.revision.is_none() {
+        writeln!(ui.hint_default(), "Use -r to specify the target revision.")?;
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "point tag {names} to commit {id}",
+            names = tag_names.join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
+
+/// Checks that the tags can be exported to Git as `refs/tags/<name>`.
+fn check_tag_names(tag_names: &[String]) -> Result<(), CommandError> {
+    #[cfg(feature = "git")]
+    for name in tag_names {
+        gix::validate::reference::name_partial(name.as_str().into())
+            .map_err(|err| user_error_with_message(format!("Invalid tag name: {name}"), err))?;
+    }
+    #[cfg(not(feature = "git"))]
+    let _ = tag_names;
+    Ok(())
+}
+
+/// Points the tags to `target_commit`, creating annotated tags if a `message`
+/// is given.
+fn set_tags(
+    tx: &mut WorkspaceCommandTransaction,
+    tag_names: &[String],
+    target_commit: &Commit,
+    message: Option<&str>,
+) -> Result<(), CommandError> {
+    for name in tag_names {
+        if let Some(message) = message {
+            #[cfg(feature = "git")]
+            {
+                let tagger = tx.settings().signature();
+                git::create_annotated_tag(
+                    tx.repo_mut(),
+                    name,
+                    target_commit.id(),
+                    message,
+                    &tagger,
+                )?;
+            }
+            #[cfg(not(feature = "git"))]
+            {
+                let _ = message;
+                return Err(user_error("Annotated tags require Git support"));
+            }
+        } else {
+            tx.repo_mut()
+                .set_tag_target(name, RefTarget::normal(target_commit.id().clone()));
+        }
+    }
     Ok(())
 }
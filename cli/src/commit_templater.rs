@@ -61,6 +61,8 @@ use jj_lib::signing::SignError;
 use jj_lib::signing::SignResult;
 use jj_lib::signing::Verification;
 use jj_lib::store::Store;
+use jj_lib::trailer::parse_description_trailers;
+use jj_lib::trailer::Trailer;
 use once_cell::unsync::OnceCell;
 use pollster::FutureExt as _;
 
@@ -89,6 +91,7 @@ use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
+use crate::templater::ToJson;
 use crate::text_util;
 
 pub trait CommitTemplateLanguageExtension {
@@ -301,6 +304,23 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::Trailer(property) => {
+                let table = &self.build_fn_table.trailer_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
+            CommitTemplatePropertyKind::TrailerList(property) => {
+                // TODO: migrate to table?
+                template_builder::build_formattable_list_method(
+                    self,
+                    diagnostics,
+                    build_ctx,
+                    property,
+                    function,
+                    Self::wrap_trailer,
+                    Self::wrap_trailer_list,
+                )
+            }
             CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
                 let type_name = "CryptographicSignature";
                 let table = &self.build_fn_table.cryptographic_signature_methods;
@@ -421,6 +441,18 @@ impl<'repo> CommitTemplateLanguage<'repo> {
         CommitTemplatePropertyKind::TreeEntry(Box::new(property))
     }
 
+    pub fn wrap_trailer(
+        property: impl TemplateProperty<Output = Trailer> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::Trailer(Box::new(property))
+    }
+
+    pub fn wrap_trailer_list(
+        property: impl TemplateProperty<Output = Vec<Trailer>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::TrailerList(Box::new(property))
+    }
+
     fn wrap_cryptographic_signature_opt(
         property: impl TemplateProperty<Output = Option<CryptographicSignature>> + 'repo,
     ) -> CommitTemplatePropertyKind<'repo> {
@@ -444,6 +476,8 @@ pub enum CommitTemplatePropertyKind<'repo> {
     TreeDiffEntry(Box<dyn TemplateProperty<Output = TreeDiffEntry> + 'repo>),
     TreeDiffEntryList(Box<dyn TemplateProperty<Output = Vec<TreeDiffEntry>> + 'repo>),
     TreeEntry(Box<dyn TemplateProperty<Output = TreeEntry> + 'repo>),
+    Trailer(Box<dyn TemplateProperty<Output = Trailer> + 'repo>),
+    TrailerList(Box<dyn TemplateProperty<Output = Vec<Trailer>> + 'repo>),
     CryptographicSignatureOpt(
         Box<dyn TemplateProperty<Output = Option<CryptographicSignature>> + 'repo>,
     ),
@@ -467,6 +501,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::TreeDiffEntry(_) => "TreeDiffEntry",
             CommitTemplatePropertyKind::TreeDiffEntryList(_) => "List<TreeDiffEntry>",
             CommitTemplatePropertyKind::TreeEntry(_) => "TreeEntry",
+            CommitTemplatePropertyKind::Trailer(_) => "Trailer",
+            CommitTemplatePropertyKind::TrailerList(_) => "List<Trailer>",
             CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => {
                 "Option<CryptographicSignature>"
             }
@@ -504,6 +540,10 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
                 Some(Box::new(property.map(|l| !l.is_empty())))
             }
             CommitTemplatePropertyKind::TreeEntry(_) => None,
+            CommitTemplatePropertyKind::Trailer(_) => None,
+            CommitTemplatePropertyKind::TrailerList(property) => {
+                Some(Box::new(property.map(|l| !l.is_empty())))
+            }
             CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
                 Some(Box::new(property.map(|sig| sig.is_some())))
             }
@@ -548,10 +588,39 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::TreeDiffEntry(_) => None,
             CommitTemplatePropertyKind::TreeDiffEntryList(_) => None,
             CommitTemplatePropertyKind::TreeEntry(_) => None,
+            CommitTemplatePropertyKind::Trailer(property) => Some(property.into_template()),
+            CommitTemplatePropertyKind::TrailerList(property) => Some(property.into_template()),
             CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => None,
         }
     }
 
+    fn try_into_json(
+        self,
+    ) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'repo>> {
+        match self {
+            CommitTemplatePropertyKind::Core(property) => property.try_into_json(),
+            CommitTemplatePropertyKind::Commit(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefName(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefNameOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefNameList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RepoPath(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RepoPathOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitOrChangeId(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::ShortestIdPrefix(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiff(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiffEntry(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiffEntryList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeEntry(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::Trailer(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TrailerList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
+                Some(property.into_json())
+            }
+        }
+    }
+
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'repo>> {
         match (self, other) {
             (CommitTemplatePropertyKind::Core(lhs), CommitTemplatePropertyKind::Core(rhs)) => {
@@ -572,6 +641,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::TreeDiffEntry(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiffEntryList(_), _) => None,
             (CommitTemplatePropertyKind::TreeEntry(_), _) => None,
+            (CommitTemplatePropertyKind::Trailer(_), _) => None,
+            (CommitTemplatePropertyKind::TrailerList(_), _) => None,
             (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
@@ -599,6 +670,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::TreeDiffEntry(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiffEntryList(_), _) => None,
             (CommitTemplatePropertyKind::TreeEntry(_), _) => None,
+            (CommitTemplatePropertyKind::Trailer(_), _) => None,
+            (CommitTemplatePropertyKind::TrailerList(_), _) => None,
             (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
@@ -619,6 +692,7 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub tree_diff_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiff>,
     pub tree_diff_entry_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>,
     pub tree_entry_methods: CommitTemplateBuildMethodFnMap<'repo, TreeEntry>,
+    pub trailer_methods: CommitTemplateBuildMethodFnMap<'repo, Trailer>,
     pub cryptographic_signature_methods:
         CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
 }
@@ -626,8 +700,10 @@ pub struct CommitTemplateBuildFnTable<'repo> {
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
     /// Creates new symbol table containing the builtin methods.
     fn builtin() -> Self {
+        let mut core = CoreTemplateBuildFnTable::builtin();
+        merge_fn_map(&mut core.string_methods, builtin_commit_string_methods());
         CommitTemplateBuildFnTable {
-            core: CoreTemplateBuildFnTable::builtin(),
+            core,
             commit_methods: builtin_commit_methods(),
             ref_name_methods: builtin_ref_name_methods(),
             repo_path_methods: builtin_repo_path_methods(),
@@ -636,6 +712,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             tree_diff_methods: builtin_tree_diff_methods(),
             tree_diff_entry_methods: builtin_tree_diff_entry_methods(),
             tree_entry_methods: builtin_tree_entry_methods(),
+            trailer_methods: builtin_trailer_methods(),
             cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
         }
     }
@@ -651,6 +728,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             tree_diff_methods: HashMap::new(),
             tree_diff_entry_methods: HashMap::new(),
             tree_entry_methods: HashMap::new(),
+            trailer_methods: HashMap::new(),
             cryptographic_signature_methods: HashMap::new(),
         }
     }
@@ -666,6 +744,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             tree_diff_methods,
             tree_diff_entry_methods,
             tree_entry_methods,
+            trailer_methods,
             cryptographic_signature_methods,
         } = extension;
 
@@ -684,6 +763,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
         merge_fn_map(&mut self.tree_diff_methods, tree_diff_methods);
         merge_fn_map(&mut self.tree_diff_entry_methods, tree_diff_entry_methods);
         merge_fn_map(&mut self.tree_entry_methods, tree_entry_methods);
+        merge_fn_map(&mut self.trailer_methods, trailer_methods);
         merge_fn_map(
             &mut self.cryptographic_signature_methods,
             cryptographic_signature_methods,
@@ -732,6 +812,19 @@ impl<'repo> CommitKeywordCache<'repo> {
     }
 }
 
+impl ToJson for Commit {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "commit_id": self.id().hex(),
+            "parents": self.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+            "change_id": self.change_id().reverse_hex(),
+            "description": self.description(),
+            "author": self.author().to_json()?,
+            "committer": self.committer().to_json()?,
+        }))
+    }
+}
+
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1299,6 +1392,32 @@ impl Template for Vec<Rc<RefName>> {
     }
 }
 
+impl ToJson for Rc<RefName> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let mut value = serde_json::json!({
+            "name": self.name,
+            "target": ref_target_to_json(&self.target),
+        });
+        if let Some(remote) = &self.remote {
+            value["remote"] = remote.as_str().into();
+        }
+        if let Some(tracking) = &self.tracking_ref {
+            value["tracking_target"] = ref_target_to_json(&tracking.target);
+        }
+        Ok(value)
+    }
+}
+
+/// Serializes the terms of the (possibly conflicted) ref target. Absent terms
+/// are mapped to null.
+fn ref_target_to_json(target: &RefTarget) -> serde_json::Value {
+    target
+        .as_merge()
+        .iter()
+        .map(|id| id.as_ref().map(|id| id.hex()))
+        .collect()
+}
+
 fn builtin_ref_name_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Rc<RefName>> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1470,6 +1589,12 @@ impl Template for RepoPathBuf {
     }
 }
 
+impl ToJson for RepoPathBuf {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.as_internal_file_string().into())
+    }
+}
+
 fn builtin_repo_path_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, RepoPathBuf> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1540,6 +1665,12 @@ impl Template for CommitOrChangeId {
     }
 }
 
+impl ToJson for CommitOrChangeId {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.hex().into())
+    }
+}
+
 fn builtin_commit_or_change_id_methods<'repo>(
 ) -> CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
@@ -1631,6 +1762,15 @@ impl Template for ShortestIdPrefix {
     }
 }
 
+impl ToJson for ShortestIdPrefix {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "prefix": self.prefix,
+            "rest": self.rest,
+        }))
+    }
+}
+
 impl ShortestIdPrefix {
     fn to_upper(&self) -> Self {
         Self {
@@ -1737,6 +1877,12 @@ impl TreeDiff {
     }
 }
 
+impl ToJson for TreeDiff {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.collect_entries().block_on()?.to_json()
+    }
+}
+
 /// Tree diff to be rendered by predefined function `F`.
 struct TreeDiffFormatted<F> {
     diff: TreeDiff,
@@ -1792,16 +1938,19 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     TemplateParseError::expression(message, function.name_span).with_source(err)
                 })?;
             let conflict_marker_style = language.conflict_marker_style;
+            let submodule_store = language.repo.submodule_store().clone();
             let template = (self_property, context_property)
                 .map(move |(diff, context)| {
                     let mut options = options.clone();
                     if let Some(context) = context {
                         options.context = context;
                     }
+                    let submodule_store = submodule_store.clone();
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_color_words_diff(
                             formatter,
                             store,
+                            submodule_store.as_ref(),
                             tree_diff,
                             path_converter,
                             &options,
@@ -1947,6 +2096,25 @@ impl TreeDiffEntry {
     }
 }
 
+impl ToJson for TreeDiffEntry {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "path": self.path.target.to_json()?,
+            "status": self.status_label(),
+            "source": if self.source_value.is_absent() {
+                serde_json::Value::Null
+            } else {
+                self.clone().into_source_entry().to_json()?
+            },
+            "target": if self.target_value.is_absent() {
+                serde_json::Value::Null
+            } else {
+                self.clone().into_target_entry().to_json()?
+            },
+        }))
+    }
+}
+
 fn builtin_tree_diff_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>
 {
     type L<'repo> = CommitTemplateLanguage<'repo>;
@@ -1996,6 +2164,17 @@ pub struct TreeEntry {
     pub value: MergedTreeValue,
 }
 
+impl ToJson for TreeEntry {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "path": self.path.to_json()?,
+            "conflict": !self.value.is_resolved(),
+            "file_type": describe_file_type(&self.value),
+            "executable": is_executable_file(&self.value).unwrap_or_default(),
+        }))
+    }
+}
+
 fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeEntry> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -2054,6 +2233,68 @@ fn is_executable_file(value: &MergedTreeValue) -> Option<bool> {
     executable.resolve_trivial().copied()
 }
 
+/// String methods that need types of the commit template language.
+fn builtin_commit_string_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, String> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<String>::new();
+    map.insert(
+        "trailers",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|s| parse_description_trailers(&s));
+            Ok(L::wrap_trailer_list(out_property))
+        },
+    );
+    map
+}
+
+impl Template for Trailer {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter, "{}: {}", self.key, self.value)
+    }
+}
+
+impl Template for Vec<Trailer> {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        templater::format_joined(formatter, self, "\n")
+    }
+}
+
+impl ToJson for Trailer {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "key": self.key,
+            "value": self.value,
+        }))
+    }
+}
+
+fn builtin_trailer_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Trailer> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<Trailer>::new();
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|trailer| trailer.key);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "value",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|trailer| trailer.value);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
+
 #[derive(Debug)]
 pub struct CryptographicSignature {
     commit: Commit,
@@ -2088,6 +2329,21 @@ impl CryptographicSignature {
     }
 }
 
+impl ToJson for CryptographicSignature {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let status = match self.status() {
+            Ok(status) => status.to_string(),
+            Err(SignError::InvalidSignatureFormat) => "invalid".to_string(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(serde_json::json!({
+            "status": status,
+            "key": self.key()?,
+            "display": self.display()?,
+        }))
+    }
+}
+
 pub fn builtin_cryptographic_signature_methods<'repo>(
 ) -> CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
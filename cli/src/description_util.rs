@@ -14,13 +14,18 @@ use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::file_util::IoResultExt as _;
 use jj_lib::file_util::PathError;
 use jj_lib::settings::UserSettings;
+use jj_lib::trailer::parse_description_trailers;
+use jj_lib::trailer::parse_trailers;
+use jj_lib::trailer::Trailer;
 use thiserror::Error;
 
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::config::CommandNameAndArgs;
 use crate::formatter::PlainTextFormatter;
@@ -361,6 +366,55 @@ pub fn description_template(
     Ok(output.into_string_lossy())
 }
 
+/// Appends the trailers rendered by the `templates.commit_trailers` template
+/// for the `commit` to its new `description`.
+///
+/// Trailers that the description already has aren't added again. Empty
+/// descriptions are left unchanged.
+pub fn add_trailers(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commit: &Commit,
+    description: &str,
+) -> Result<String, CommandError> {
+    let template_key = "templates.commit_trailers";
+    let Some(template_text) = tx.settings().get_string(template_key).optional()? else {
+        return Ok(description.to_owned());
+    };
+    if description.trim().is_empty() {
+        return Ok(description.to_owned());
+    }
+    let template = tx.parse_commit_template(ui, &template_text)?;
+    let mut output = Vec::new();
+    template
+        .format(commit, &mut PlainTextFormatter::new(&mut output))
+        .expect("write() to vec backed formatter should never fail");
+    let new_trailers = parse_trailers(&output.into_string_lossy()).map_err(|err| {
+        user_error_with_message(format!("Invalid output of {template_key} template"), err)
+    })?;
+    let old_trailers = parse_description_trailers(description);
+    let new_trailers = new_trailers
+        .into_iter()
+        .filter(|trailer| !old_trailers.contains(trailer))
+        .collect_vec();
+    Ok(append_trailers(description, &new_trailers))
+}
+
+/// Appends the `trailers` to the trailer block of the `description`, starting
+/// a new paragraph if there's no trailer block yet.
+pub fn append_trailers(description: &str, trailers: &[Trailer]) -> String {
+    if trailers.is_empty() {
+        return description.to_owned();
+    }
+    let has_trailers = !parse_description_trailers(description).is_empty();
+    let mut description = description.trim_end().to_owned();
+    description.push_str(if has_trailers { "\n" } else { "\n\n" });
+    for trailer in trailers {
+        description.push_str(&format!("{}: {}\n", trailer.key, trailer.value));
+    }
+    description
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexmap;
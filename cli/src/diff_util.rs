@@ -14,6 +14,7 @@
 
 use std::borrow::Borrow;
 use std::cmp::max;
+use std::fmt::Write as _;
 use std::io;
 use std::mem;
 use std::ops::Range;
@@ -64,6 +65,7 @@ use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
+use jj_lib::submodule_store::SubmoduleStore;
 use pollster::FutureExt;
 use thiserror::Error;
 use tracing::instrument;
@@ -380,6 +382,7 @@ impl<'a> DiffRenderer<'a> {
                     show_color_words_diff(
                         formatter,
                         store,
+                        self.repo.submodule_store().as_ref(),
                         tree_diff,
                         path_converter,
                         options,
@@ -921,6 +924,31 @@ fn diff_content(
     }
 }
 
+/// Like [`diff_content()`], but describes the commit a submodule is checked
+/// out at if the submodule store knows about it.
+fn color_words_diff_content(
+    path: &RepoPath,
+    value: MaterializedTreeValue,
+    submodule_store: &dyn SubmoduleStore,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> io::Result<FileContent> {
+    match value {
+        MaterializedTreeValue::GitSubmodule(id) => {
+            let mut contents = format!("Git submodule checked out at {id}");
+            // The summary is only informational, so lookup errors aren't fatal.
+            if let Ok(Some(commit)) = submodule_store.get_commit(&id) {
+                let summary = commit.description.lines().next().unwrap_or_default();
+                write!(contents, "\n{summary}").unwrap();
+            }
+            Ok(FileContent {
+                is_binary: false,
+                contents: contents.into_bytes(),
+            })
+        }
+        value => diff_content(path, value, conflict_marker_style),
+    }
+}
+
 fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     match value {
         MaterializedTreeValue::Absent => {
@@ -945,6 +973,7 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
 pub fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
+    submodule_store: &dyn SubmoduleStore,
     tree_diff: BoxStream<CopiesTreeDiffEntry>,
     path_converter: &RepoPathUiConverter,
     options: &ColorWordsDiffOptions,
@@ -984,7 +1013,12 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Added {description} {right_ui_path}:"
                 )?;
-                let right_content = diff_content(right_path, right_value, conflict_marker_style)?;
+                let right_content = color_words_diff_content(
+                    right_path,
+                    right_value,
+                    submodule_store,
+                    conflict_marker_style,
+                )?;
                 if right_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
                 } else if right_content.is_binary {
@@ -1046,8 +1080,18 @@ pub fn show_color_words_diff(
                         )
                     }
                 };
-                let left_content = diff_content(left_path, left_value, conflict_marker_style)?;
-                let right_content = diff_content(right_path, right_value, conflict_marker_style)?;
+                let left_content = color_words_diff_content(
+                    left_path,
+                    left_value,
+                    submodule_store,
+                    conflict_marker_style,
+                )?;
+                let right_content = color_words_diff_content(
+                    right_path,
+                    right_value,
+                    submodule_store,
+                    conflict_marker_style,
+                )?;
                 if left_path == right_path {
                     writeln!(
                         formatter.labeled("header"),
@@ -1075,7 +1119,12 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Removed {description} {right_ui_path}:"
                 )?;
-                let left_content = diff_content(left_path, left_value, conflict_marker_style)?;
+                let left_content = color_words_diff_content(
+                    left_path,
+                    left_value,
+                    submodule_store,
+                    conflict_marker_style,
+                )?;
                 if left_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
                 } else if left_content.is_binary {
@@ -1210,10 +1259,13 @@ fn git_diff_part(
             };
         }
         MaterializedTreeValue::GitSubmodule(id) => {
-            // TODO: What should we actually do here?
-            mode = "040000";
+            // Same as Git, which diffs the commit ids as text.
+            mode = "160000";
             hash = id.hex();
-            content = FileContent::empty();
+            content = FileContent {
+                is_binary: false,
+                contents: format!("Subproject commit {hash}\n").into_bytes(),
+            };
         }
         MaterializedTreeValue::FileConflict {
             id: _,
@@ -14,6 +14,7 @@
 
 //! Git utilities shared by various commands.
 
+use std::collections::BTreeMap;
 use std::error;
 use std::io;
 use std::io::Read;
@@ -29,17 +30,27 @@ use std::time::Instant;
 use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use itertools::Itertools;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
 use jj_lib::fmt_util::binary_prefix;
 use jj_lib::git;
 use jj_lib::git::FailedRefExport;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::RefName;
+use jj_lib::git::SubmoduleConfig;
+use jj_lib::git_lfs;
+use jj_lib::git_lfs::LfsObjectStore;
+use jj_lib::git_lfs::LfsPointer;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::store::Store;
+use jj_lib::submodule_store::SubmoduleStoreError;
 use jj_lib::workspace::Workspace;
 use unicode_width::UnicodeWidthStr;
 
@@ -48,6 +59,7 @@ use crate::command_error::cli_error;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::formatter::Formatter;
+use crate::ui::format_error_with_sources;
 use crate::ui::ProgressOutput;
 use crate::ui::Ui;
 
@@ -505,6 +517,11 @@ impl RefStatus {
                 },
             ),
             RefName::Tag(tag) => (tag.clone(), RefKind::Tag, TrackingStatus::NotApplicable),
+            RefName::RemoteTag { tag, remote } => (
+                format!("{tag}@{remote}"),
+                RefKind::Tag,
+                TrackingStatus::NotApplicable,
+            ),
             RefName::LocalBranch(branch) => {
                 (branch.clone(), RefKind::Branch, TrackingStatus::Tracked)
             }
@@ -606,6 +623,232 @@ export or their "parent" bookmarks."#,
     Ok(())
 }
 
+/// Reads the submodule configuration from `.gitmodules` in `tree`. Returns an
+/// empty map if there's no such file.
+pub fn read_gitmodules(
+    store: &Store,
+    tree: &MergedTree,
+) -> Result<BTreeMap<String, SubmoduleConfig>, CommandError> {
+    let gitmodules_path = RepoPath::from_internal_string(".gitmodules");
+    let mut gitmodules_file = match tree.path_value(gitmodules_path)?.into_resolved() {
+        Ok(Some(TreeValue::File { id, .. })) => store.read_file(gitmodules_path, &id)?,
+        _ => return Ok(BTreeMap::new()),
+    };
+    Ok(git::parse_gitmodules(&mut gitmodules_file)?)
+}
+
+/// Resolves a submodule URL relative to the URL of the "origin" remote, or to
+/// the workspace if there's no such remote, the same way Git does.
+fn resolve_submodule_url(store: &Store, workspace_root: &Path, url: &str) -> String {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return url.to_owned();
+    }
+    let remote_url = get_git_repo(store).ok().and_then(|git_repo| {
+        let remote = git_repo.find_remote("origin").ok()?;
+        remote.url().map(|url| url.trim_end_matches('/').to_owned())
+    });
+    let Some(mut base) = remote_url else {
+        return workspace_root.join(url).to_string_lossy().into_owned();
+    };
+    let mut relative = url;
+    loop {
+        if let Some(rest) = relative.strip_prefix("./") {
+            relative = rest;
+        } else if let Some(rest) = relative.strip_prefix("../") {
+            relative = rest;
+            // scp-like URLs such as "host:path" have no slash before the path.
+            let len = base.rfind(['/', ':']).unwrap_or(0);
+            base.truncate(len);
+        } else {
+            break;
+        }
+    }
+    format!("{base}/{relative}")
+}
+
+/// Checks out the commits the submodules are pinned to in `new_tree`, fetching
+/// the submodule repositories as needed.
+///
+/// Submodules which can't be updated are reported as warnings since the rest of
+/// the working copy has already been updated at this point.
+pub fn update_submodules(
+    ui: &Ui,
+    repo: &ReadonlyRepo,
+    workspace_root: &Path,
+    old_tree: Option<&MergedTree>,
+    new_tree: &MergedTree,
+) -> Result<(), CommandError> {
+    let submodule_store = repo.submodule_store();
+    for (name, submodule) in read_gitmodules(repo.store(), new_tree)? {
+        let Some((path, disk_path)) = RepoPathBuf::from_relative_path(&submodule.path)
+            .ok()
+            .and_then(|path| {
+                let disk_path = path.to_fs_path(workspace_root).ok()?;
+                Some((path, disk_path))
+            })
+        else {
+            writeln!(
+                ui.warning_default(),
+                "Ignoring submodule {name} with invalid path {}",
+                submodule.path
+            )?;
+            continue;
+        };
+        let Ok(Some(TreeValue::GitSubmodule(id))) = new_tree.path_value(&path)?.into_resolved()
+        else {
+            continue;
+        };
+        let old_id = match old_tree {
+            Some(tree) => match tree.path_value(&path)?.into_resolved() {
+                Ok(Some(TreeValue::GitSubmodule(id))) => Some(id),
+                _ => None,
+            },
+            None => None,
+        };
+        // Leave submodules which are already checked out alone, so local
+        // changes in them are preserved.
+        let is_empty_dir = || {
+            disk_path
+                .read_dir()
+                .map_or(true, |mut entries| entries.next().is_none())
+        };
+        if old_id.as_ref() == Some(&id) && !is_empty_dir() {
+            continue;
+        }
+        let url = resolve_submodule_url(repo.store(), workspace_root, &submodule.url);
+        let check_out = || submodule_store.check_out(&name, old_id.as_ref(), &id, &disk_path);
+        let result = match check_out() {
+            Err(
+                SubmoduleStoreError::NotFetched(_) | SubmoduleStoreError::CommitNotFound { .. },
+            ) => {
+                writeln!(ui.status(), "Fetching submodule {name} from {url}")?;
+                with_remote_git_callbacks(ui, |callbacks| {
+                    submodule_store.fetch(&name, &url, callbacks)
+                })
+                .and_then(|()| check_out())
+            }
+            result => result,
+        };
+        if let Err(err) = result {
+            writeln!(
+                ui.warning_default(),
+                "Failed to update submodule {name}: {err}"
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Downloads the Git LFS objects referenced by the remote bookmarks of `remote`
+/// which were updated between `old_repo` and `new_repo`.
+///
+/// Failures are reported as warnings since the fetched refs are still usable.
+/// Files whose objects are missing are checked out as pointer files.
+pub fn download_lfs_objects(
+    ui: &Ui,
+    old_repo: &dyn Repo,
+    new_repo: &dyn Repo,
+    remote: &str,
+) -> Result<(), CommandError> {
+    let store = new_repo.store();
+    let Some(lfs_objects) = LfsObjectStore::for_store(store) else {
+        return Ok(());
+    };
+    let empty_tree = store.get_root_tree(&store.empty_merged_tree_id())?;
+    let mut pointers = vec![];
+    for (name, remote_ref) in new_repo.view().remote_bookmarks(remote) {
+        let old_target = &old_repo.view().get_remote_bookmark(name, remote).target;
+        if *old_target == remote_ref.target {
+            continue;
+        }
+        let old_tree = match old_target.as_normal() {
+            Some(id) => store.get_commit(id)?.tree()?,
+            None => empty_tree.clone(),
+        };
+        for id in remote_ref.target.added_ids() {
+            let new_tree = store.get_commit(id)?.tree()?;
+            pointers.extend(git_lfs::changed_lfs_pointers(&old_tree, &new_tree)?);
+        }
+    }
+    let pointers = pointers
+        .into_iter()
+        .filter(|pointer| !lfs_objects.contains(pointer))
+        .sorted()
+        .dedup()
+        .collect_vec();
+    if pointers.is_empty() {
+        return Ok(());
+    }
+    let git_settings = new_repo.base_repo().settings().git_settings()?;
+    let git_repo = get_git_repo(store)?;
+    let Some(client) = git_lfs::lfs_client_for_remote(&git_repo, remote, &git_settings)? else {
+        writeln!(
+            ui.warning_default(),
+            "Not downloading {} Git LFS objects since remote {remote} has no Git LFS server",
+            pointers.len()
+        )?;
+        return Ok(());
+    };
+    writeln!(
+        ui.status(),
+        "Downloading {} Git LFS objects from {}",
+        pointers.len(),
+        client.url()
+    )?;
+    if let Err(err) = client.download(&lfs_objects, &pointers) {
+        writeln!(
+            ui.warning_default(),
+            "Failed to download Git LFS objects: {}",
+            format_error_with_sources(&err)
+        )?;
+    }
+    Ok(())
+}
+
+/// Uploads the Git LFS objects referenced by the `commits` to the Git LFS
+/// server of `remote`. Objects which the server already has aren't uploaded.
+pub fn upload_lfs_objects(
+    ui: &Ui,
+    repo: &dyn Repo,
+    remote: &str,
+    commits: &[Commit],
+) -> Result<(), CommandError> {
+    let store = repo.store();
+    let Some(lfs_objects) = LfsObjectStore::for_store(store) else {
+        return Ok(());
+    };
+    let mut pointers: Vec<LfsPointer> = vec![];
+    for commit in commits {
+        let parent_tree = commit.parent_tree(repo)?;
+        pointers.extend(git_lfs::changed_lfs_pointers(
+            &parent_tree,
+            &commit.tree()?,
+        )?);
+    }
+    let pointers = pointers.into_iter().sorted().dedup().collect_vec();
+    if pointers.is_empty() {
+        return Ok(());
+    }
+    let git_settings = repo.base_repo().settings().git_settings()?;
+    let git_repo = get_git_repo(store)?;
+    let Some(client) = git_lfs::lfs_client_for_remote(&git_repo, remote, &git_settings)? else {
+        writeln!(
+            ui.warning_default(),
+            "Not uploading {} Git LFS objects since remote {remote} has no Git LFS server",
+            pointers.len()
+        )?;
+        return Ok(());
+    };
+    writeln!(
+        ui.status(),
+        "Uploading {} Git LFS objects to {}",
+        pointers.len(),
+        client.url()
+    )?;
+    client.upload(&lfs_objects, &pointers)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::MAIN_SEPARATOR;
@@ -0,0 +1,151 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-configured commands run at certain points of jj commands.
+//!
+//! A hook is configured as `hooks.<name>` and runs as a subprocess in the
+//! workspace root. It receives the details of the operation through `$name`
+//! variables in its arguments and on stdin. If it exits with a non-zero
+//! status, the command is aborted before the operation is committed, except
+//! for the `post-snapshot` hook which only runs after the fact.
+
+use std::collections::HashMap;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::settings::UserSettings;
+use thiserror::Error;
+
+use crate::command_error::CommandError;
+use crate::config::CommandNameAndArgs;
+
+/// Point at which a hook can be run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Hook {
+    /// Run by `jj git push` before anything is pushed. Receives `$remote`, and
+    /// one `<ref name> <old commit id> <new commit id>` line per updated ref on
+    /// stdin.
+    PrePush,
+    /// Run by `jj describe` and `jj commit` for each new description.
+    /// Receives `$change_id` and `$commit_id` of the described commit, and the
+    /// description on stdin.
+    CommitMsg,
+    /// Run when the working copy was snapshotted with changes, after the
+    /// snapshot is recorded. Receives `$change_id`, `$commit_id` and
+    /// `$tree_id` of the new working-copy commit.
+    PostSnapshot,
+}
+
+impl Hook {
+    /// Name of the hook, as used in the `hooks` config table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PrePush => "pre-push",
+            Hook::CommitMsg => "commit-msg",
+            Hook::PostSnapshot => "post-snapshot",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Failed to run {hook} hook '{name}'")]
+    FailedToRun {
+        hook: &'static str,
+        name: String,
+        source: io::Error,
+    },
+    #[error("The {hook} hook exited with {status}")]
+    ExitStatus {
+        hook: &'static str,
+        status: ExitStatus,
+    },
+}
+
+/// Runs the `hook` if it's configured, and fails if it exits with a non-zero
+/// status.
+///
+/// The hook's stdout is redirected to stderr so that it doesn't get mixed
+/// with the output of the command.
+pub fn run_hook(
+    settings: &UserSettings,
+    hook: Hook,
+    cwd: &Path,
+    variables: &HashMap<&str, &str>,
+    input: &[u8],
+) -> Result<(), CommandError> {
+    let Some(command) = settings
+        .get::<CommandNameAndArgs>(["hooks", hook.name()])
+        .optional()?
+    else {
+        return Ok(());
+    };
+    let mut cmd = command.to_command_with_variables(variables);
+    cmd.current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(io::stderr());
+    tracing::info!(?cmd, hook = hook.name(), "running hook");
+    let failed_to_run = |source| HookError::FailedToRun {
+        hook: hook.name(),
+        name: command.split_name().into_owned(),
+        source,
+    };
+    let mut child = cmd.spawn().map_err(failed_to_run)?;
+    let mut stdin = child.stdin.take().unwrap();
+    let status = std::thread::scope(|s| {
+        s.spawn(move || {
+            // The hook may not read its input at all.
+            stdin.write_all(input).ok();
+        });
+        child.wait()
+    })
+    .map_err(failed_to_run)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(HookError::ExitStatus {
+            hook: hook.name(),
+            status,
+        }
+        .into())
+    }
+}
+
+/// Runs the `commit-msg` hook for the new `description` of `commit`.
+pub fn run_commit_msg_hook(
+    settings: &UserSettings,
+    cwd: &Path,
+    commit: &Commit,
+    description: &str,
+) -> Result<(), CommandError> {
+    let change_id = commit.change_id().reverse_hex();
+    let commit_id = commit.id().hex();
+    let variables = HashMap::from([
+        ("change_id", change_id.as_str()),
+        ("commit_id", commit_id.as_str()),
+    ]);
+    run_hook(
+        settings,
+        Hook::CommitMsg,
+        cwd,
+        &variables,
+        description.as_bytes(),
+    )
+}
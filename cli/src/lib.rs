@@ -31,14 +31,31 @@ pub mod git_util;
 /// A stub module that provides a no-op implementation of some of the functions
 /// in the `git` module.
 pub mod git_util {
+    use std::path::Path;
+
+    use jj_lib::merged_tree::MergedTree;
     use jj_lib::repo::ReadonlyRepo;
     use jj_lib::workspace::Workspace;
 
+    use crate::command_error::CommandError;
+    use crate::ui::Ui;
+
     pub fn is_colocated_git_workspace(_workspace: &Workspace, _repo: &ReadonlyRepo) -> bool {
         false
     }
+
+    pub fn update_submodules(
+        _ui: &Ui,
+        _repo: &ReadonlyRepo,
+        _workspace_root: &Path,
+        _old_tree: Option<&MergedTree>,
+        _new_tree: &MergedTree,
+    ) -> Result<(), CommandError> {
+        Ok(())
+    }
 }
 pub mod graphlog;
+pub mod hooks;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
@@ -24,6 +24,7 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigValue;
 use jj_lib::dsl_util::AliasExpandError as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::str_util::StringPattern;
 use jj_lib::time_util::DatePattern;
 use serde::de::IntoDeserializer as _;
 use serde::Deserialize;
@@ -180,6 +181,9 @@ pub trait IntoTemplateProperty<'a> {
     fn try_into_plain_text(self) -> Option<Box<dyn TemplateProperty<Output = String> + 'a>>;
     fn try_into_template(self) -> Option<Box<dyn Template + 'a>>;
 
+    /// Transforms into a property that will evaluate to a JSON value.
+    fn try_into_json(self) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>>;
+
     /// Transforms into a property that will evaluate to `self == other`.
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>>;
 
@@ -301,6 +305,25 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
         }
     }
 
+    fn try_into_json(self) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+        match self {
+            CoreTemplatePropertyKind::String(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::StringList(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Boolean(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Integer(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::IntegerOpt(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::ConfigValue(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Signature(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Email(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::SizeHint(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Timestamp(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::TimestampRange(property) => Some(property.into_json()),
+            // Formatted templates have no structure to be serialized.
+            CoreTemplatePropertyKind::Template(_) => None,
+            CoreTemplatePropertyKind::ListTemplate(_) => None,
+        }
+    }
+
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>> {
         match (self, other) {
             (CoreTemplatePropertyKind::String(lhs), CoreTemplatePropertyKind::String(rhs)) => {
@@ -624,6 +647,12 @@ impl<'a, P: IntoTemplateProperty<'a>> Expression<P> {
         }
     }
 
+    pub fn try_into_json(
+        self,
+    ) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+        self.property.try_into_json()
+    }
+
     pub fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>> {
         self.property.try_into_eq(other.property)
     }
@@ -879,6 +908,48 @@ fn builtin_string_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
             Ok(L::wrap_string_list(out_property))
         },
     );
+    map.insert(
+        "split",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [separator_node] = function.expect_exact_arguments()?;
+            let separator_property =
+                expect_plain_text_expression(language, diagnostics, build_ctx, separator_node)?;
+            let out_property = (self_property, separator_property)
+                .map(|(s, separator)| s.split(&separator).map(|s| s.to_owned()).collect());
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    map.insert(
+        "match",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            let [pattern_node] = function.expect_exact_arguments()?;
+            let (regex, _) = expect_regex_pattern_literal(pattern_node)?;
+            let out_property = self_property
+                .map(move |s| regex.find_iter(&s).map(|m| m.as_str().to_owned()).collect());
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    map.insert(
+        "replace",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [pattern_node, replacement_node] = function.expect_exact_arguments()?;
+            let (regex, is_regex) = expect_regex_pattern_literal(pattern_node)?;
+            let replacement_property =
+                expect_plain_text_expression(language, diagnostics, build_ctx, replacement_node)?;
+            let out_property =
+                (self_property, replacement_property).map(move |(s, replacement)| {
+                    // Only regex patterns have groups to be referenced by `$`.
+                    if is_regex {
+                        regex.replace_all(&s, replacement.as_str()).into_owned()
+                    } else {
+                        regex
+                            .replace_all(&s, regex::NoExpand(&replacement))
+                            .into_owned()
+                    }
+                });
+            Ok(L::wrap_string(out_property))
+        },
+    );
     map.insert(
         "upper",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -898,6 +969,30 @@ fn builtin_string_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     map
 }
 
+/// Parses the given string literal as a string pattern, and converts it to a
+/// regex. The pattern is a substring match if no kind is specified.
+///
+/// Also returns whether the pattern was specified as a regex.
+fn expect_regex_pattern_literal(
+    node: &ExpressionNode,
+) -> TemplateParseResult<(regex::Regex, bool)> {
+    template_parser::expect_string_literal_with(node, |src, span| {
+        let pattern = if let Some((kind, pat)) = src.split_once(':') {
+            StringPattern::from_str_kind(pat, kind)
+        } else {
+            Ok(StringPattern::substring(src))
+        }
+        .map_err(|err| {
+            TemplateParseError::expression("Invalid string pattern", span).with_source(err)
+        })?;
+        let is_regex = matches!(pattern, StringPattern::Regex(_));
+        let regex = pattern.to_regex().ok_or_else(|| {
+            TemplateParseError::expression("Glob pattern is not supported by this method", span)
+        })?;
+        Ok((regex, is_regex))
+    })
+}
+
 /// Clamps and aligns the given index `i` to char boundary.
 ///
 /// Negative index counts from the end. If the index isn't at a char boundary,
@@ -1575,6 +1670,12 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
         });
         Ok(L::wrap_template(Box::new(template)))
     });
+    map.insert("json", |language, diagnostics, build_ctx, function| {
+        let [value_node] = function.expect_exact_arguments()?;
+        let value = expect_json_expression(language, diagnostics, build_ctx, value_node)?;
+        let out_property = value.map(|value| value.to_string());
+        Ok(L::wrap_string(out_property))
+    });
     map.insert("config", |language, _diagnostics, _build_ctx, function| {
         // Dynamic lookup can be implemented if needed. The name is literal
         // string for now so the error can be reported early.
@@ -1863,6 +1964,22 @@ pub fn expect_template_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
     )
 }
 
+pub fn expect_json_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    node: &ExpressionNode,
+) -> TemplateParseResult<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+    expect_expression_of_type(
+        language,
+        diagnostics,
+        build_ctx,
+        node,
+        "Serializable",
+        |expression| expression.try_into_json(),
+    )
+}
+
 fn expect_expression_of_type<'a, L: TemplateLanguage<'a> + ?Sized, T>(
     language: &L,
     diagnostics: &mut TemplateDiagnostics,
@@ -2622,6 +2739,76 @@ mod tests {
         // ranges with end > start are empty
         insta::assert_snapshot!(env.render_ok(r#""abcdef".substr(4, 2)"#), @"");
         insta::assert_snapshot!(env.render_ok(r#""abcdef".substr(-2, -4)"#), @"");
+
+        insta::assert_snapshot!(env.render_ok(r#""".split(",")"#), @"");
+        insta::assert_snapshot!(env.render_ok(r#""a,b,,c".split(",").join("|")"#), @"a|b||c");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a, b".split(", ").map(|s| "<" ++ s ++ ">")"#), @"<a> <b>");
+
+        insta::assert_snapshot!(
+            env.render_ok(r#""fix PROJ-12 and PROJ-3".match('regex:[A-Z]+-\d+')"#), @"PROJ-12 PROJ-3");
+        insta::assert_snapshot!(
+            env.render_ok(r#""foo".match('regex:\d+').len()"#), @"0");
+        insta::assert_snapshot!(env.render_ok(r#""a.b.c".match(".").join("")"#), @"..");
+        insta::assert_snapshot!(env.render_ok(r#""aAa".match("substring-i:a").len()"#), @"3");
+        insta::assert_snapshot!(
+            env.render_ok(
+                r#""PROJ-1 PROJ-22".match('regex:PROJ-\d+').filter(|s| s.len() > 6)"#),
+            @"PROJ-22");
+
+        insta::assert_snapshot!(
+            env.render_ok(r#""fix PROJ-12".replace('regex:([A-Z]+)-(\d+)', "$2@$1")"#),
+            @"fix 12@PROJ");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a-b".replace('regex:(?<x>\w)', "[${x}]")"#),
+            @"[a]-[b]");
+        insta::assert_snapshot!(env.render_ok(r#""a.b.c".replace(".", "/")"#), @"a/b/c");
+        insta::assert_snapshot!(env.render_ok(r#""5 EUR".replace("EUR", "$1")"#), @"5 $1");
+        insta::assert_snapshot!(
+            env.render_ok(r#""cost".replace("exact:cost", "${x} $$")"#),
+            @"${x} $$");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a1".replace('regex:\d', "$$")"#),
+            @"a$");
+        insta::assert_snapshot!(
+            env.render_ok(r#""fix bug".replace("exact:fix bug", description)"#),
+            @"description 1");
+        insta::assert_snapshot!(
+            env.render_ok(r#""foo".replace("o", bad_string)"#),
+            @"<Error: Bad>");
+
+        insta::assert_snapshot!(env.parse_err(r#""foo".match("unknown:o")"#), @r#"
+         --> 1:13
+          |
+        1 | "foo".match("unknown:o")
+          |             ^---------^
+          |
+          = Invalid string pattern
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#""foo".match('regex:(')"#), @r#"
+         --> 1:13
+          |
+        1 | "foo".match('regex:(')
+          |             ^-------^
+          |
+          = Invalid string pattern
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#""foo".replace("glob:*", "")"#), @r#"
+         --> 1:15
+          |
+        1 | "foo".replace("glob:*", "")
+          |               ^------^
+          |
+          = Glob pattern is not supported by this method
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#""foo".match(description)"#), @r#"
+         --> 1:13
+          |
+        1 | "foo".match(description)
+          |             ^---------^
+          |
+          = Expected string literal
+        "#);
     }
 
     #[test]
@@ -3209,4 +3396,64 @@ mod tests {
             env.render_ok(r#"surround(lt, gt, if(empty_content, "not empty", ""))"#),
             @"");
     }
+
+    #[test]
+    fn test_json_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("description", || {
+            L::wrap_string(Literal("multiline\n\"quoted\"\n".to_owned()))
+        });
+        env.add_keyword("none_i64", || L::wrap_integer_opt(Literal(None)));
+        env.add_keyword("string_list", || {
+            L::wrap_string_list(Literal(vec!["foo".to_owned(), "bar".to_owned()]))
+        });
+        env.add_keyword("config_table", || {
+            let value: ConfigValue = "{ a = 1, b = [true, 'x'] }".parse().unwrap();
+            L::wrap_config_value(Literal(value))
+        });
+        env.add_keyword("author", || {
+            L::wrap_signature(Literal(new_signature("Test User", "test.user@example.com")))
+        });
+        env.add_keyword("size_hint", || L::wrap_size_hint(Literal((5, None))));
+        env.add_keyword("t0", || {
+            L::wrap_timestamp(Literal(new_timestamp(1_500, 540)))
+        });
+        env.add_keyword("bad_string", || L::wrap_string(new_error_property("Bad")));
+
+        insta::assert_snapshot!(
+            env.render_ok("json(description)"),
+            @r#""multiline\n\"quoted\"\n""#);
+        insta::assert_snapshot!(env.render_ok("json(42)"), @"42");
+        insta::assert_snapshot!(env.render_ok("json(none_i64)"), @"null");
+        insta::assert_snapshot!(env.render_ok("json(true)"), @"true");
+        insta::assert_snapshot!(env.render_ok("json(string_list)"), @r#"["foo","bar"]"#);
+        insta::assert_snapshot!(env.render_ok("json(config_table)"), @r#"{"a":1,"b":[true,"x"]}"#);
+        insta::assert_snapshot!(
+            env.render_ok("json(author)"),
+            @r#"{"email":"test.user@example.com","name":"Test User","timestamp":"1970-01-01T00:00:00+00:00"}"#);
+        insta::assert_snapshot!(
+            env.render_ok("json(author.email())"),
+            @r#""test.user@example.com""#);
+        insta::assert_snapshot!(env.render_ok("json(size_hint)"), @"[5,null]");
+        insta::assert_snapshot!(env.render_ok("json(t0)"), @r#""1970-01-01T09:00:01.500+09:00""#);
+        insta::assert_snapshot!(env.render_ok("json(bad_string)"), @"<Error: Bad>");
+
+        // Formatted templates can't be serialized
+        insta::assert_snapshot!(env.parse_err(r#"json(label("error", "a"))"#), @r#"
+         --> 1:6
+          |
+        1 | json(label("error", "a"))
+          |      ^-----------------^
+          |
+          = Expected expression of type `Serializable`, but actual type is `Template`
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"json(string_list.map(|s| s))"#), @r"
+         --> 1:6
+          |
+        1 | json(string_list.map(|s| s))
+          |      ^--------------------^
+          |
+          = Expected expression of type `Serializable`, but actual type is `ListTemplate`
+        ");
+    }
 }
@@ -187,6 +187,109 @@ impl Template for i64 {
     }
 }
 
+/// Value that can be serialized by the `json()` template function.
+pub trait ToJson {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError>;
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        <T as ToJson>::to_json(self)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.as_ref()
+            .map_or(Ok(serde_json::Value::Null), |t| t.to_json())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.iter().map(|t| t.to_json()).collect()
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok((*self).into())
+    }
+}
+
+impl ToJson for i64 {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok((*self).into())
+    }
+}
+
+impl ToJson for ConfigValue {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let value = match self {
+            ConfigValue::String(v) => v.value().as_str().into(),
+            ConfigValue::Integer(v) => (*v.value()).into(),
+            // Non-finite numbers are mapped to null.
+            ConfigValue::Float(v) => (*v.value()).into(),
+            ConfigValue::Boolean(v) => (*v.value()).into(),
+            ConfigValue::Datetime(v) => v.value().to_string().into(),
+            ConfigValue::Array(array) => array
+                .iter()
+                .map(|v| v.to_json())
+                .collect::<Result<_, _>>()?,
+            ConfigValue::InlineTable(table) => table
+                .iter()
+                .map(|(k, v)| Ok((k.to_owned(), v.to_json()?)))
+                .collect::<Result<serde_json::Map<_, _>, TemplatePropertyError>>()?
+                .into(),
+        };
+        Ok(value)
+    }
+}
+
+impl ToJson for Signature {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "name": self.name,
+            "email": self.email,
+            "timestamp": self.timestamp.to_json()?,
+        }))
+    }
+}
+
+impl ToJson for Email {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.0.to_json()
+    }
+}
+
+impl ToJson for SizeHint {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let (lower, upper) = *self;
+        Ok(serde_json::json!([lower, upper]))
+    }
+}
+
+impl ToJson for Timestamp {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(time_util::format_rfc3339_timestamp(self)?.into())
+    }
+}
+
+impl ToJson for TimestampRange {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "start": self.start.to_json()?,
+            "end": self.end.to_json()?,
+        }))
+    }
+}
+
 pub struct LabelTemplate<T, L> {
     content: T,
     labels: L,
@@ -421,6 +524,15 @@ pub trait TemplatePropertyExt: TemplateProperty {
     {
         Box::new(FormattablePropertyTemplate::new(self))
     }
+
+    /// Converts this property into a property of JSON value.
+    fn into_json<'a>(self) -> Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>
+    where
+        Self: Sized + 'a,
+        Self::Output: ToJson,
+    {
+        Box::new(self.and_then(|value| value.to_json()))
+    }
 }
 
 impl<P: TemplateProperty + ?Sized> TemplatePropertyExt for P {}
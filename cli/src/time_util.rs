@@ -84,6 +84,19 @@ pub fn format_absolute_timestamp_with(
     Ok(datetime.format_with_items(format.items.iter()).to_string())
 }
 
+/// Formats the timestamp in RFC 3339 format with its original time zone.
+pub fn format_rfc3339_timestamp(timestamp: &Timestamp) -> Result<String, TimestampOutOfRange> {
+    let datetime = datetime_from_timestamp(timestamp)?;
+    Ok(datetime.to_rfc3339())
+}
+
+/// Formats the timestamp in RFC 2822 format with its original time zone, as
+/// used in email headers.
+pub fn format_rfc2822_timestamp(timestamp: &Timestamp) -> Result<String, TimestampOutOfRange> {
+    let datetime = datetime_from_timestamp(timestamp)?;
+    Ok(datetime.to_rfc2822())
+}
+
 pub fn format_duration(
     from: &Timestamp,
     to: &Timestamp,
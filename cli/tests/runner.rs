@@ -13,7 +13,9 @@ mod test_absorb_command;
 mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
+mod test_apply_command;
 mod test_backout_command;
+mod test_bisect_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
 mod test_commit_command;
@@ -35,11 +37,14 @@ mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
 mod test_generate_md_cli_help;
+mod test_gerrit_upload;
 mod test_git_clone;
 mod test_git_colocated;
 mod test_git_fetch;
+mod test_git_format_patch;
 mod test_git_import_export;
 mod test_git_init;
+mod test_git_lfs;
 mod test_git_private_commits;
 mod test_git_push;
 mod test_git_remotes;
@@ -47,6 +52,7 @@ mod test_git_submodule;
 mod test_gitignores;
 mod test_global_opts;
 mod test_help_command;
+mod test_hooks;
 mod test_immutable_commits;
 mod test_init_command;
 mod test_interdiff_command;
@@ -55,14 +61,17 @@ mod test_new_command;
 mod test_next_prev_commands;
 mod test_operations;
 mod test_parallelize_command;
+mod test_range_diff_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
 mod test_restore_command;
 mod test_revset_output;
 mod test_root;
+mod test_run_command;
 mod test_shell_completion;
 mod test_show_command;
+mod test_sign_unsign_commands;
 mod test_simplify_parents_command;
 mod test_sparse_command;
 mod test_split_command;
@@ -0,0 +1,265 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
+    let template = r#"
+    separate(" ",
+      change_id.short(),
+      author.name(),
+      author.timestamp(),
+      if(conflict, "conflict"),
+      description.first_line(),
+    ) ++ "\n"
+    "#;
+    test_env.jj_cmd_success(cwd, &["log", "-T", template])
+}
+
+#[test]
+fn test_apply_format_patch_output() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file\n\nWith a body."]);
+    std::fs::write(repo_path.join("file"), "a\nc\n").unwrap();
+    std::fs::write(repo_path.join("other"), "other\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "update file"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "original", "-r@-"]);
+    let mbox = test_env.jj_cmd_success(&repo_path, &["git", "format-patch", "-r::@- & ~root()"]);
+
+    // Apply the series on top of a new commit authored by someone else
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "root()",
+            "--config=user.name=Someone Else",
+            "--config=debug.commit-timestamp=2020-01-01T00:00:00+00:00",
+        ],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["apply"], &mbox);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Applied yqosqzyt 350cddde add file
+    Applied spxsnpux f35e75f2 update file
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    ○  spxsnpuxtvxq Test User 2001-02-03 04:05:09.000 +07:00 update file
+    ○  yqosqzytrlsw Test User 2001-02-03 04:05:08.000 +07:00 add file
+    @  royxmykxtrkr Someone Else 2020-01-01 00:00:00.000 +00:00
+    │ ○  rlvkpnrzqnoo Test User 2001-02-03 04:05:09.000 +07:00 update file
+    │ ○  qpvuntsmwlqt Test User 2001-02-03 04:05:08.000 +07:00 add file
+    ├─╯
+    ◆  zzzzzzzzzzzz 1970-01-01 00:00:00.000 +00:00
+    ");
+
+    // The new commits have the same content as the original ones
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from", "original", "--to", "heads(@::)"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_apply_plain_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\nb\nc\n").unwrap();
+    std::fs::write(
+        repo_path.join("change.diff"),
+        indoc::indoc! {"
+            diff --git a/file b/file
+            --- a/file
+            +++ b/file
+            @@ -2,1 +2,1 @@
+            -b
+            +B
+            diff --git a/new b/new
+            new file mode 100755
+            --- /dev/null
+            +++ b/new
+            @@ -0,0 +1 @@
+            +new
+        "},
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+
+    // Patches without metadata get an empty description and the current user
+    // as author
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["apply", "change.diff", "-d", "@-"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Applied kkmpptxz 9ad479e8 (no description set)");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-n1", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @"apply 1 patch");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvkpnrzqnoo Test User 2001-02-03 04:05:08.000 +07:00
+    │ ○  kkmpptxzrspx Test User 2001-02-03 04:05:09.000 +07:00
+    ├─╯
+    ○  qpvuntsmwlqt Test User 2001-02-03 04:05:08.000 +07:00 base
+    ◆  zzzzzzzzzzzz 1970-01-01 00:00:00.000 +00:00
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r", "children(@-) & ~@"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file b/file
+    index de980441c3..7be73ce3c1 100644
+    --- a/file
+    +++ b/file
+    @@ -1,3 +1,3 @@
+     a
+    -b
+    +B
+     c
+    diff --git a/new b/new
+    new file mode 100755
+    index 0000000000..3e757656cf
+    --- /dev/null
+    +++ b/new
+    @@ -0,0 +1,1 @@
+    +new
+    ");
+
+    // Invalid patches are rejected
+    std::fs::write(repo_path.join("bad.diff"), "--- a/file\n+++ b/file\n@@ x\n").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["apply", "bad.diff"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse patch
+    Caused by: Invalid hunk header at line 3
+    ");
+    std::fs::write(repo_path.join("empty.diff"), "").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["apply", "empty.diff"]);
+    insta::assert_snapshot!(stderr, @"Error: No patches found in input");
+    std::fs::write(repo_path.join("text.diff"), "not a patch\n").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["apply", "text.diff"]);
+    insta::assert_snapshot!(stderr, @"Error: No patches found in input");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["apply", "missing.diff"]);
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Error: Failed to read $TEST_ENV/repo/missing.diff
+    Caused by: No such file or directory (os error 2)
+    ");
+}
+
+#[test]
+fn test_apply_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\nx\nc\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+
+    // The hunk doesn't apply, so the file is left conflicted
+    let patch = indoc::indoc! {"
+        From: Patch Author <patch.author@example.com>
+        Date: Mon, 1 Jan 2024 12:00:00 +0000
+        Subject: [PATCH] change b
+
+        --- a/file
+        +++ b/file
+        @@ -1,3 +1,3 @@
+         a
+        -b
+        +B
+         c
+    "};
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["apply", "-"], patch);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Applied kkmpptxz 997c1bc5 (conflict) change b
+    Warning: Patch did not apply cleanly to file
+    New conflicts appeared in these commits:
+      kkmpptxz 997c1bc5 (conflict) change b
+    To resolve the conflicts, start by updating to it:
+      jj new kkmpptxz
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    ×  kkmpptxzrspx Patch Author 2024-01-01 12:00:00.000 +00:00 conflict change b
+    @  rlvkpnrzqnoo Test User 2001-02-03 04:05:08.000 +07:00
+    ○  qpvuntsmwlqt Test User 2001-02-03 04:05:08.000 +07:00 base
+    ◆  zzzzzzzzzzzz 1970-01-01 00:00:00.000 +00:00
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "show", "-r", "description('change b')", "file"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    a
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -b
+    +x
+    +++++++ Contents of side #2
+    B
+    >>>>>>> Conflict 1 of 1 ends
+    c
+    ");
+}
+
+#[test]
+fn test_apply_conflict_materialized() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "1\n2\n3\nx\n5\n6\n7\n8\n9\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+
+    // The second hunk doesn't apply. The conflict is between the whole file
+    // with the first hunk applied and the whole file with the second hunk put
+    // in its expected position.
+    let patch = indoc::indoc! {"
+        --- a/file
+        +++ b/file
+        @@ -1,2 +1,2 @@
+        -1
+        +one
+         2
+        @@ -3,3 +3,3 @@
+         3
+        -4
+        +four
+         5
+    "};
+    test_env.jj_cmd_stdin_ok(&repo_path, &["apply", "-"], patch);
+    test_env.jj_cmd_ok(&repo_path, &["new", "conflicts()"]);
+    let content = std::fs::read_to_string(repo_path.join("file")).unwrap();
+    insta::assert_snapshot!(content, @r"
+    one
+    2
+    3
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -4
+    +x
+    +++++++ Contents of side #2
+    four
+    >>>>>>> Conflict 1 of 1 ends
+    5
+    6
+    7
+    8
+    9
+    ");
+}
@@ -0,0 +1,211 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"change_id.short() ++ " " ++ description.first_line()"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+/// Creates commits "1" to "8", where "6" introduces the text "bad" to a file.
+fn init_history(test_env: &TestEnvironment) -> PathBuf {
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    for i in 1..=8 {
+        let content = if i >= 6 { "bad\n" } else { "good\n" };
+        std::fs::write(repo_path.join("file"), content).unwrap();
+        std::fs::write(repo_path.join("counter"), format!("{i}\n")).unwrap();
+        test_env.jj_cmd_ok(&repo_path, &["commit", "-m", &i.to_string()]);
+    }
+    repo_path
+}
+
+#[test]
+fn test_bisect_manual() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_history(&test_env);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bisect",
+            "start",
+            "--bad=description(exact:'8\n')",
+            "--good=description(exact:'1\n')",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now evaluating: mzvwutvl 87c18720 5
+    6 revisions remain, roughly 3 steps left
+    Working copy now at: znkkpsqq 1871ec91 (empty) (no description set)
+    Parent commit      : mzvwutvl 87c18720 5
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now evaluating: yqosqzyt 6c2fc548 7
+    2 revisions remain, roughly 2 steps left
+    Working copy now at: kpqxywon 4320d737 (empty) (no description set)
+    Parent commit      : yqosqzyt 6c2fc548 7
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "bad"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now evaluating: royxmykx d352f3c7 6
+    1 revisions remain, roughly 1 steps left
+    Working copy now at: kmkuslsw 908ae9c8 (empty) (no description set)
+    Parent commit      : royxmykx d352f3c7 6
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+
+    // Undoing restores the previous bisection state.
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "skip"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now evaluating: royxmykx d352f3c7 6
+    1 revisions remain, roughly 1 steps left
+    Working copy now at: lylxulpl 49729b51 (empty) (no description set)
+    Parent commit      : royxmykx d352f3c7 6
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "bad"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"The first bad revision is: royxmykx d352f3c7 6");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No revision is being evaluated
+    Hint: Specify the revisions to mark.
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "reset"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  lylxulplsnyw
+    │ ○  vruxwmqvtpmx 8
+    │ ○  yqosqzytrlsw 7
+    ├─╯
+    ○  royxmykxtrkr 6
+    ○  mzvwutvlkqwt 5
+    ○  zsuskulnrvyr 4
+    ○  kkmpptxzrspx 3
+    ○  rlvkpnrzqnoo 2
+    ○  qpvuntsmwlqt 1
+    ◆  zzzzzzzzzzzz
+    ");
+}
+
+#[test]
+fn test_bisect_errors() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_history(&test_env);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "start"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No revisions to start the bisection from
+    Hint: Use --bad and --good to mark revisions.
+    ");
+
+    // Only a good revision is known so far.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "start", "--good=@-"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Hint: Mark a revision that has the change as bad to continue.");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No revision is being evaluated
+    Hint: Specify the revisions to mark.
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "start", "--bad=@"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: A bisection is already in progress
+    Hint: Use `jj bisect reset` to end it first.
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "bad", "@--"]);
+    insta::assert_snapshot!(stderr, @"Error: Good commit 3be9150039c423c8c9582ff2312f01884a3a02c3 is a descendant of bad commit 6c2fc548e5f099eb567b90cc3c84ea791b5d48b0");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bisect_run() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_history(&test_env);
+
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start", "--bad=@-"]);
+    // Revision "4" can't be evaluated.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bisect",
+            "run",
+            "sh",
+            "-c",
+            "grep -q 4 counter && exit 125; grep -q good file",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    The revision is skipped (command exited with exit status: 125)
+    Now evaluating: mzvwutvl 87c18720 5
+    6 revisions remain, roughly 3 steps left
+    Working copy now at: kpqxywon 427ac952 (empty) (no description set)
+    Parent commit      : mzvwutvl 87c18720 5
+    Added 0 files, modified 1 files, removed 0 files
+    The revision is good (command exited with exit status: 0)
+    Now evaluating: yqosqzyt 6c2fc548 7
+    2 revisions remain, roughly 2 steps left
+    Working copy now at: vzqnnsmr 4320d737 (empty) (no description set)
+    Parent commit      : yqosqzyt 6c2fc548 7
+    Added 0 files, modified 2 files, removed 0 files
+    The revision is bad (command exited with exit status: 1)
+    Now evaluating: royxmykx d352f3c7 6
+    1 revisions remain, roughly 1 steps left
+    Working copy now at: rlvoymqp f3d605b2 (empty) (no description set)
+    Parent commit      : royxmykx d352f3c7 6
+    Added 0 files, modified 1 files, removed 0 files
+    The revision is bad (command exited with exit status: 1)
+    The first bad revision is: royxmykx d352f3c7 6
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  rlvoymqptspt
+    │ ○  vruxwmqvtpmx 8
+    │ ○  yqosqzytrlsw 7
+    ├─╯
+    ○  royxmykxtrkr 6
+    ○  mzvwutvlkqwt 5
+    ○  zsuskulnrvyr 4
+    ○  kkmpptxzrspx 3
+    ○  rlvkpnrzqnoo 2
+    ○  qpvuntsmwlqt 1
+    ◆  zzzzzzzzzzzz
+    ");
+
+    // Commands killed by a signal abort the run.
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "reset"]);
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start", "--bad=@-"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "run", "sh", "-c", "kill -9 $$"]);
+    insta::assert_snapshot!(stderr, @"Error: Command failed (signal: 9 (SIGKILL)), aborting the bisection run");
+}
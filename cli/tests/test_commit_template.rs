@@ -1514,3 +1514,105 @@ fn test_signature_templates() {
     ~
     "#);
 }
+
+#[test]
+fn test_log_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first \"quoted\"\n\nbody"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "c\n").unwrap();
+
+    // One object per line
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r::", "-T", r#"json(self) ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    {"author":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:11+07:00"},"change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","commit_id":"d07856102acfd49ebeef2cf68085a9e893d5f32a","committer":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:11+07:00"},"description":"","parents":["757462a4c558b8d66c7a4d15cdce6ee599650a39"]}
+    {"author":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:08+07:00"},"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","commit_id":"757462a4c558b8d66c7a4d15cdce6ee599650a39","committer":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:08+07:00"},"description":"first \"quoted\"\n\nbody\n","parents":["0000000000000000000000000000000000000000"]}
+    {"author":{"email":"","name":"","timestamp":"1970-01-01T00:00:00+00:00"},"change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","commit_id":"0000000000000000000000000000000000000000","committer":{"email":"","name":"","timestamp":"1970-01-01T00:00:00+00:00"},"description":"","parents":[]}
+    "#);
+
+    let template = r#"separate("\n", json(bookmarks), json(diff), json(parents)) ++ "\n""#;
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@-", "-T", template]);
+    insta::assert_snapshot!(stdout, @r#"
+    [{"name":"main","target":["757462a4c558b8d66c7a4d15cdce6ee599650a39"]}]
+    [{"path":"file1","source":null,"status":"added","target":{"conflict":false,"executable":false,"file_type":"file","path":"file1"}}]
+    [{"author":{"email":"","name":"","timestamp":"1970-01-01T00:00:00+00:00"},"change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","commit_id":"0000000000000000000000000000000000000000","committer":{"email":"","name":"","timestamp":"1970-01-01T00:00:00+00:00"},"description":"","parents":[]}]
+    "#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r@", "-T", "json(diff.files())"],
+    );
+    insta::assert_snapshot!(stdout, @r#"[{"path":"file1","source":{"conflict":false,"executable":false,"file_type":"file","path":"file1"},"status":"modified","target":{"conflict":false,"executable":false,"file_type":"file","path":"file1"}},{"path":"file2","source":null,"status":"added","target":{"conflict":false,"executable":false,"file_type":"file","path":"file2"}}]"#);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["bookmark", "list", "-T", r#"json(self) ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r#"{"name":"main","target":["757462a4c558b8d66c7a4d15cdce6ee599650a39"]}"#);
+
+    // Absent sides of added and deleted files are null
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+    std::fs::write(repo_path.join("file3"), "d\n").unwrap();
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r@", "-T", "json(diff.files())"],
+    );
+    insta::assert_snapshot!(stdout, @r#"[{"path":"file2","source":{"conflict":false,"executable":false,"file_type":"file","path":"file2"},"status":"removed","target":null},{"path":"file3","source":null,"status":"added","target":{"conflict":false,"executable":false,"file_type":"file","path":"file3"}}]"#);
+}
+
+#[test]
+fn test_trailer_templates() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let description = indoc! {"
+        subject
+
+        Signed-off-by: Test User <test.user@example.com>
+        Change-Id: I1234
+          continued
+    "};
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", description]);
+
+    let template = indoc! {r#"
+        description.trailers().map(|t| t.key() ++ "=" ++ t.value()).join("\n") ++ "\n"
+    "#};
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    Signed-off-by=Test User <test.user@example.com>
+    Change-Id=I1234 continued
+    ");
+
+    let template = r#"description.trailers() ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    Signed-off-by: Test User <test.user@example.com>
+    Change-Id: I1234 continued
+    ");
+
+    let template = r#"json(description.trailers()) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r#"
+    [{"key":"Signed-off-by","value":"Test User <test.user@example.com>"},{"key":"Change-Id","value":"I1234 continued"}]
+    "#);
+
+    // The subject is never a trailer
+    let template = r#"if(description.trailers(), "some", "none") ++ "\n""#;
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-r@-", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @"none");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "Key: value"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @"none");
+}
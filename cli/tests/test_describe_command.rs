@@ -849,7 +849,93 @@ fn test_edit_cannot_be_used_with_no_edit() {
     ");
 }
 
+#[test]
+fn test_describe_add_trailers() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"templates.commit_trailers = '''
+        "Change-Id: I6a6a6964" ++ change_id.normal_hex() ++ "\n" ++
+        "Signed-off-by: " ++ committer.name() ++ " <" ++ committer.email() ++ ">\n"
+        '''"#,
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    let stdout = get_description(&test_env, &repo_path, "@");
+    insta::assert_snapshot!(stdout, @r"
+    first
+
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+
+    // Existing trailers are kept, and only missing ones are added
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "second\n\nSigned-off-by: Test User <test.user@example.com>\nReviewed-by: Someone",
+        ],
+    );
+    let stdout = get_description(&test_env, &repo_path, "@");
+    insta::assert_snapshot!(stdout, @r"
+    second
+
+    Signed-off-by: Test User <test.user@example.com>
+    Reviewed-by: Someone
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ");
+
+    // Describing again doesn't add duplicate trailers
+    let stdout = get_description(&test_env, &repo_path, "@");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", &stdout]);
+    let stdout = get_description(&test_env, &repo_path, "@");
+    insta::assert_snapshot!(stdout, @r"
+    second
+
+    Signed-off-by: Test User <test.user@example.com>
+    Reviewed-by: Someone
+    Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ");
+
+    // Empty descriptions are left alone
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", ""]);
+    insta::assert_snapshot!(get_description(&test_env, &repo_path, "@-"), @"");
+
+    // jj commit adds trailers too
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "third"]);
+    insta::assert_snapshot!(get_description(&test_env, &repo_path, "@-"), @r"
+    third
+
+    Change-Id: I6a6a69641b76972398e6b49e8e0701307e57d55a
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+}
+
+#[test]
+fn test_describe_add_trailers_invalid_template_output() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"templates.commit_trailers = '"not a trailer"'"#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "first"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid output of templates.commit_trailers template
+    Caused by: Invalid trailer line: not a trailer
+    ");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = r#"commit_id.short() ++ " " ++ description"#;
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
 }
+
+fn get_description(test_env: &TestEnvironment, repo_path: &Path, rev: &str) -> String {
+    test_env.jj_cmd_success(
+        repo_path,
+        &["log", "--no-graph", "-r", rev, "-T", "description"],
+    )
+}
@@ -80,6 +80,17 @@ fn test_annotate_merge() {
     zsuskuln test.use 2001-02-03 08:05:11    2: new text from new commit 1
     royxmykx test.use 2001-02-03 08:05:13    3: new text from new commit 2
     ");
+
+    // Lines from the second parent are attributed to the merge commit
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "annotate", "--first-parent", "file.txt"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: line1
+    zsuskuln test.use 2001-02-03 08:05:11    2: new text from new commit 1
+    vruxwmqv test.use 2001-02-03 08:05:15    3: new text from new commit 2
+    ");
 }
 
 #[test]
@@ -0,0 +1,209 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+fn set_up() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    let remote_path = test_env.env_root().join("remote.git");
+    git2::Repository::init_bare(&remote_path).unwrap();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "local"]);
+    let workspace_root = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            remote_path.to_str().unwrap(),
+        ],
+    );
+    (test_env, workspace_root)
+}
+
+/// Lists the refs of the remote with the subjects of the commits they point
+/// to, and their Change-Id trailers.
+fn get_remote_refs(test_env: &TestEnvironment) -> String {
+    let git_repo = git2::Repository::open(test_env.env_root().join("remote.git")).unwrap();
+    let mut refs = git_repo
+        .references()
+        .unwrap()
+        .map(|reference| {
+            let reference = reference.unwrap();
+            let commit = reference.peel_to_commit().unwrap();
+            let message = commit.message().unwrap();
+            let change_id = message
+                .lines()
+                .find_map(|line| line.strip_prefix("Change-Id: "))
+                .unwrap_or("<none>");
+            format!(
+                "{} {} {change_id}\n",
+                reference.name().unwrap(),
+                message.lines().next().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    refs.sort();
+    refs.concat()
+}
+
+fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
+    let template = r#"commit_id.short() ++ " " ++ description"#;
+    test_env.jj_cmd_success(cwd, &["log", "-T", template])
+}
+
+#[test]
+fn test_gerrit_upload() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=second"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["gerrit", "upload", "-b=main"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Added Change-Id trailers to 2 commits
+    Changes to upload to origin:
+      Push 7e506b88c8f3 to refs/for/main
+    Working copy now at: zsuskuln 7e506b88 (empty) second
+    Parent commit      : qpvuntsm 89ecb754 (empty) first
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r"
+    @  7e506b88c8f3 second
+    │
+    │  Change-Id: I6a6a69640757f5ec8418b4f0983d29e77f9c624a
+    ○  89ecb754e378 first
+    │
+    │  Change-Id: I6a6a69649a45c67d3e96a7e5007c110ede34dec5
+    ◆  000000000000
+    ");
+    insta::assert_snapshot!(get_remote_refs(&test_env), @"refs/for/main second I6a6a69640757f5ec8418b4f0983d29e77f9c624a");
+
+    // Existing Change-Id trailers are kept
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "new",
+            "-m=third\n\nChange-Id: I0123456789abcdef0123456789abcdef01234567",
+        ],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["gerrit", "upload", "-b=main", "--topic=feature"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to upload to origin:
+      Push 0a87c386fc74 to refs/for/main%topic=feature
+    ");
+    insta::assert_snapshot!(get_remote_refs(&test_env), @r"
+    refs/for/main second I6a6a69640757f5ec8418b4f0983d29e77f9c624a
+    refs/for/main%topic=feature third I0123456789abcdef0123456789abcdef01234567
+    ");
+}
+
+#[test]
+fn test_gerrit_upload_push_options() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config("gerrit.default-remote-branch = 'main'");
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m=first"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "gerrit",
+            "upload",
+            "--topic=feature",
+            "--reviewer=a@example.com",
+            "--reviewer=b@example.com",
+            "--wip",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Added Change-Id trailers to 1 commits
+    Changes to upload to origin:
+      Push 3959d514bfce to refs/for/main%topic=feature,r=a@example.com,r=b@example.com,wip
+    Working copy now at: qpvuntsm 3959d514 (empty) first
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    ");
+    insta::assert_snapshot!(get_remote_refs(&test_env), @"refs/for/main%topic=feature,r=a@example.com,r=b@example.com,wip first I6a6a69649a45c67d3e96a7e5007c110ede34dec5");
+}
+
+#[test]
+fn test_gerrit_upload_dry_run() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m=first"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["gerrit", "upload", "-b=main", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Would add Change-Id trailers to 1 commits
+    Changes to upload to origin:
+      Push 3959d514bfce to refs/for/main
+    Dry-run requested, not pushing.
+    ");
+    // Nothing was rewritten or pushed
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_root), @r"
+    @  ef6b9b66c057 first
+    ◆  000000000000
+    ");
+    insta::assert_snapshot!(get_remote_refs(&test_env), @"");
+}
+
+#[test]
+fn test_gerrit_upload_errors() {
+    let (test_env, workspace_root) = set_up();
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["gerrit", "upload"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No remote branch to upload to
+    Hint: Use --remote-branch or set `gerrit.default-remote-branch`
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["gerrit", "upload", "-b=main"]);
+    insta::assert_snapshot!(stderr, @"Error: Won't upload commit 230dd059e1b0 since it has no description");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["gerrit", "upload", "-b=main", "-r=root()"],
+    );
+    insta::assert_snapshot!(stderr, @"No mutable revisions to upload.");
+
+    // Values containing push option separators are rejected
+    let stderr = test_env.jj_cmd_cli_error(
+        &workspace_root,
+        &["gerrit", "upload", "-b=main", "--topic=a,b"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'a,b' for '--topic <TOPIC>': Value must not contain `,` or `%`
+
+    For more information, try '--help'.
+    ");
+    let stderr = test_env.jj_cmd_cli_error(
+        &workspace_root,
+        &["gerrit", "upload", "-b=main", "--reviewer=100%"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value '100%' for '--reviewer <REVIEWER>': Value must not contain `,` or `%`
+
+    For more information, try '--help'.
+    ");
+}
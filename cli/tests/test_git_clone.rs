@@ -964,6 +964,121 @@ fn test_git_clone_with_depth_subprocess() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_git_clone_with_filter() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.subprocess = true");
+    let clone_path = test_env.env_root().join("clone");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(&git_repo_path).unwrap();
+    git_repo
+        .config()
+        .unwrap()
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut parents = vec![];
+    let mut blob_oids = vec![];
+    for contents in ["old content", "new content"] {
+        let blob_oid = git_repo.blob(contents.as_bytes()).unwrap();
+        let mut tree_builder = git_repo.treebuilder(None).unwrap();
+        tree_builder
+            .insert("file", blob_oid, git2::FileMode::Blob.into())
+            .unwrap();
+        let tree = git_repo.find_tree(tree_builder.write().unwrap()).unwrap();
+        let parent_refs = parents.iter().collect::<Vec<_>>();
+        let commit_oid = git_repo
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                contents,
+                &tree,
+                &parent_refs,
+            )
+            .unwrap();
+        parents = vec![git_repo.find_commit(commit_oid).unwrap()];
+        blob_oids.push(blob_oid);
+    }
+    git_repo.set_head("refs/heads/main").unwrap();
+    let source_url = format!(
+        "file://{}",
+        git_repo_path.to_str().unwrap().replace('\\', "/")
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", "--filter=blob:none", &source_url, "clone"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] untracked
+    Setting the revset alias `trunk()` to `main@origin`
+    Working copy now at: sqpuoqvx 409d3512 (empty) (no description set)
+    Parent commit      : owoypzsx 83d6fe0b main | new content
+    Added 1 files, modified 0 files, removed 0 files
+    "#);
+
+    // Only the contents of the checked out file were fetched
+    insta::assert_snapshot!(
+        std::fs::read_to_string(clone_path.join("file")).unwrap(),
+        @"new content");
+    let clone_git_repo = git2::Repository::open(clone_path.join(".jj/repo/store/git")).unwrap();
+    let odb = clone_git_repo.odb().unwrap();
+    assert!(!odb.exists(blob_oids[0]));
+    assert!(odb.exists(blob_oids[1]));
+
+    // Missing contents can't be fetched while the remote is unavailable
+    let moved_git_repo_path = test_env.env_root().join("moved-source");
+    std::fs::rename(&git_repo_path, &moved_git_repo_path).unwrap();
+    let stderr = test_env.jj_cmd_failure(&clone_path, &["file", "show", "-r", "main-", "file"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to fetch missing object 3bb96bea4d7b0f18dea35ae463221f41ef495298 of type file
+    Caused by: Could not find repository at '$TEST_ENV/source'
+    Hint: The repository is a partial clone. Check your network connection to the remote.
+    ");
+
+    // The missing contents are fetched on demand
+    std::fs::rename(&moved_git_repo_path, &git_repo_path).unwrap();
+    let stdout = test_env.jj_cmd_success(&clone_path, &["file", "show", "-r", "main-", "file"]);
+    insta::assert_snapshot!(stdout, @"old content");
+    let odb = clone_git_repo.odb().unwrap();
+    assert!(odb.exists(blob_oids[0]));
+
+    // Trees and commits aren't fetched on demand
+    let stderr = test_env.jj_cmd_cli_error(
+        test_env.env_root(),
+        &["git", "clone", "--filter=tree:0", &source_url, "clone2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'tree:0' for '--filter <FILTER>': Only `blob:` filters are supported
+
+    For more information, try '--help'.
+    ");
+    assert!(!test_env.env_root().join("clone2").exists());
+}
+
+#[test]
+fn test_git_clone_with_filter_git2() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+
+    // libgit2 doesn't support partial clones
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["git", "clone", "--filter=blob:none", "source", "clone"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Partial clones require running git as a subprocess
+    Hint: Set `git.subprocess = true` to use --filter.
+    ");
+    assert!(!test_env.env_root().join("clone").exists());
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_git_clone_invalid_immutable_heads(subprocess: bool) {
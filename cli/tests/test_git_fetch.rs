@@ -1900,3 +1900,79 @@ fn test_git_fetch_preserve_commits_across_repos(subprocess: bool) {
     ");
     }
 }
+
+/// Creates a remote Git repo with a linear history of `count` commits on the
+/// `main` bookmark
+fn init_git_remote_with_history(test_env: &TestEnvironment, count: usize) {
+    let git_repo_path = test_env.env_root().join("origin");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    let signature = git2_signature();
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    let mut parent = None;
+    for i in 1..=count {
+        let parents = parent.iter().collect::<Vec<_>>();
+        let oid = git_repo
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                &format!("commit {i}"),
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        parent = Some(git_repo.find_commit(oid).unwrap());
+    }
+}
+
+#[test]
+fn test_git_fetch_shallow() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.subprocess = true");
+    init_git_remote_with_history(&test_env, 4);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["git", "remote", "add", "origin", "../origin"]);
+    let template = r#"description.first_line() ++ " " ++ bookmarks"#;
+    let get_history =
+        || test_env.jj_cmd_success(&repo_path, &["log", "-T", template, "-r", "::main@origin"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--depth", "1"]);
+    insta::assert_snapshot!(get_history(), @r"
+    ◆  commit 4 main@origin
+    ◆
+    ");
+
+    // The boundary commit gets its parents back
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--deepen", "2"]);
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_history(), @r"
+    ◆  commit 4 main@origin
+    ◆  commit 3
+    ◆  commit 2
+    ◆
+    ");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--unshallow"]);
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_history(), @r"
+    ◆  commit 4 main@origin
+    ◆  commit 3
+    ◆  commit 2
+    ◆  commit 1
+    ◆
+    ");
+}
+
+#[test]
+fn test_git_fetch_deepen_git2() {
+    let test_env = TestEnvironment::default();
+    init_git_remote_with_history(&test_env, 2);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["git", "remote", "add", "origin", "../origin"]);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["git", "fetch", "--deepen", "1"]);
+    insta::assert_snapshot!(stderr, @"Error: Deepening shallow history is not supported without git.subprocess");
+}
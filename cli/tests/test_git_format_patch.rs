@@ -0,0 +1,222 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_git_format_patch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file\n\nWith a body."]);
+    std::fs::write(repo_path.join("file"), "a\nc\n").unwrap();
+    std::fs::write(repo_path.join("other"), "other\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "update file"]);
+
+    // A single patch isn't numbered
+    let stdout = test_env.jj_cmd_success(&repo_path, &["git", "format-patch", "-r@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    From ac994761910494a5a7de9fd0045c99d3073a8cd3 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [PATCH] update file
+
+    ---
+     file  | 2 +-
+     other | 1 +
+     2 files changed, 2 insertions(+), 1 deletion(-)
+
+    diff --git a/file b/file
+    index 422c2b7ab3..0f7bc76605 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +c
+    diff --git a/other b/other
+    new file mode 100644
+    index 0000000000..e45c9c2666
+    --- /dev/null
+    +++ b/other
+    @@ -0,0 +1,1 @@
+    +other
+    ");
+
+    // Multiple patches are numbered, from parents to children
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "git",
+            "format-patch",
+            "-r::@- & ~root()",
+            "--subject-prefix=PATCH v2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    From bb0b7ce100dba59f9c74947176b522ea88f5098f Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:08 +0700
+    Subject: [PATCH v2 1/2] add file
+
+    With a body.
+    ---
+     file | 2 ++
+     1 file changed, 2 insertions(+), 0 deletions(-)
+
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..422c2b7ab3
+    --- /dev/null
+    +++ b/file
+    @@ -0,0 +1,2 @@
+    +a
+    +b
+
+    From ac994761910494a5a7de9fd0045c99d3073a8cd3 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [PATCH v2 2/2] update file
+
+    ---
+     file  | 2 +-
+     other | 1 +
+     2 files changed, 2 insertions(+), 1 deletion(-)
+
+    diff --git a/file b/file
+    index 422c2b7ab3..0f7bc76605 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +c
+    diff --git a/other b/other
+    new file mode 100644
+    index 0000000000..e45c9c2666
+    --- /dev/null
+    +++ b/other
+    @@ -0,0 +1,1 @@
+    +other
+    ");
+}
+
+#[test]
+fn test_git_format_patch_output_directory() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "cli: update `file` (again)"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "git",
+            "format-patch",
+            "-r::@- & ~root()",
+            "--cover-letter",
+            "-o",
+            "patches",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    patches/0000-cover-letter.patch
+    patches/0001-add-file.patch
+    patches/0002-cli-update-file-again.patch
+    ");
+    let cover_letter =
+        std::fs::read_to_string(repo_path.join("patches/0000-cover-letter.patch")).unwrap();
+    insta::assert_snapshot!(cover_letter, @r"
+    From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:10 +0700
+    Subject: [PATCH 0/2] *** SUBJECT HERE ***
+
+    *** BLURB HERE ***
+
+    Test User (2):
+      add file
+      cli: update `file` (again)
+
+     file | 1 +
+     1 file changed, 1 insertion(+), 0 deletions(-)
+    ");
+    assert!(repo_path.join("patches/0001-add-file.patch").exists());
+
+    // Merge commits are skipped
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "@--", "-m", "merge"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["git", "format-patch"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Skipping merge commit fc9b17ac53fb
+    Error: No revisions to export
+    ");
+}
+
+#[test]
+fn test_git_format_patch_cover_letter_non_linear() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("base"), "base\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("file1"), "1\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "@--"]);
+    std::fs::write(repo_path.join("file2"), "2\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "add file2"]);
+
+    // The diffstat covers both branches, but not their common parent
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "git",
+            "format-patch",
+            "-r",
+            "description(file)",
+            "--cover-letter",
+            "-o",
+            "patches",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    patches/0000-cover-letter.patch
+    patches/0001-add-file1.patch
+    patches/0002-add-file2.patch
+    ");
+    let cover_letter =
+        std::fs::read_to_string(repo_path.join("patches/0000-cover-letter.patch")).unwrap();
+    insta::assert_snapshot!(cover_letter, @r"
+    From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:12 +0700
+    Subject: [PATCH 0/2] *** SUBJECT HERE ***
+
+    *** BLURB HERE ***
+
+    Test User (2):
+      add file1
+      add file2
+
+     file1 | 1 +
+     file2 | 1 +
+     2 files changed, 2 insertions(+), 0 deletions(-)
+    ");
+}
@@ -0,0 +1,174 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use testutils::test_lfs_server::TestLfsServer;
+
+use crate::common::TestEnvironment;
+
+/// Creates a workspace with an "origin" remote pointing to the bare repo
+/// `origin`, optionally using `lfs_url` as its LFS server.
+fn init_workspace(test_env: &TestEnvironment, name: &str, lfs_url: Option<&str>) {
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", name]);
+    let workspace_root = test_env.env_root().join(name);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "remote", "add", "origin", "../origin"],
+    );
+    if let Some(lfs_url) = lfs_url {
+        let git_repo = git2::Repository::open(workspace_root.join(".jj/repo/store/git")).unwrap();
+        git_repo
+            .config()
+            .unwrap()
+            .set_str("remote.origin.lfsurl", lfs_url)
+            .unwrap();
+    }
+}
+
+fn read_file(workspace_root: &Path, name: &str) -> String {
+    std::fs::read_to_string(workspace_root.join(name)).unwrap()
+}
+
+#[test]
+fn test_git_lfs_push_fetch() {
+    let test_env = TestEnvironment::default();
+    let server = TestLfsServer::start();
+    let normalize = |output: String| output.replace(server.url(), "$LFS_SERVER");
+    git2::Repository::init_bare(test_env.env_root().join("origin")).unwrap();
+
+    init_workspace(&test_env, "repo1", Some(server.url()));
+    let repo1_root = test_env.env_root().join("repo1");
+    std::fs::write(repo1_root.join(".gitattributes"), "*.bin filter=lfs\n").unwrap();
+    std::fs::write(repo1_root.join("large.bin"), "large content\n").unwrap();
+    std::fs::write(repo1_root.join("small.txt"), "small content\n").unwrap();
+    test_env.jj_cmd_ok(&repo1_root, &["commit", "-m", "add files"]);
+    test_env.jj_cmd_ok(&repo1_root, &["bookmark", "create", "main", "-r", "@-"]);
+
+    // The file is stored as a pointer
+    let stdout = test_env.jj_cmd_success(&repo1_root, &["file", "show", "-r", "main", "large.bin"]);
+    insta::assert_snapshot!(stdout, @r"
+    version https://git-lfs.github.com/spec/v1
+    oid sha256:ca27f3a1fa46b52b9b770c0f09ec44d48b0f6a1e6b0c8955b8ac1976b6d6c3cb
+    size 14
+    ");
+
+    // Objects are uploaded before pushing
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo1_root, &["git", "push", "--allow-new", "-b", "main"]);
+    insta::assert_snapshot!(normalize(stderr), @r"
+    Changes to push to origin:
+      Add bookmark main to 2d13da87457a
+    Uploading 1 Git LFS objects to $LFS_SERVER
+    ");
+
+    // Objects are downloaded when fetching, and written on checkout
+    init_workspace(&test_env, "repo2", Some(server.url()));
+    let repo2_root = test_env.env_root().join("repo2");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo2_root, &["git", "fetch"]);
+    insta::assert_snapshot!(normalize(stderr), @r"
+    bookmark: main@origin [new] untracked
+    Downloading 1 Git LFS objects from $LFS_SERVER
+    ");
+    test_env.jj_cmd_ok(&repo2_root, &["new", "main@origin"]);
+    assert_eq!(read_file(&repo2_root, "large.bin"), "large content\n");
+    assert_eq!(read_file(&repo2_root, "small.txt"), "small content\n");
+    let stdout = test_env.jj_cmd_success(&repo2_root, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : znkkpsqq 0aa50406 (empty) (no description set)
+    Parent commit: kvmxmrsp 2d13da87 main@origin | add files
+    ");
+
+    // Without an LFS server, files are checked out as pointers
+    init_workspace(&test_env, "repo3", None);
+    let repo3_root = test_env.env_root().join("repo3");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo3_root, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: main@origin [new] untracked
+    Warning: Not downloading 1 Git LFS objects since remote origin has no Git LFS server
+    ");
+    test_env.jj_cmd_ok(&repo3_root, &["new", "main@origin"]);
+    assert!(read_file(&repo3_root, "large.bin").starts_with("version https://git-lfs"));
+    let stdout = test_env.jj_cmd_success(&repo3_root, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_git_lfs_push_new_bookmark() {
+    let test_env = TestEnvironment::default();
+    let server = TestLfsServer::start();
+    let normalize = |output: String| output.replace(server.url(), "$LFS_SERVER");
+    git2::Repository::init_bare(test_env.env_root().join("origin")).unwrap();
+    init_workspace(&test_env, "repo", Some(server.url()));
+    let workspace_root = test_env.env_root().join("repo");
+    std::fs::write(workspace_root.join(".gitattributes"), "*.bin filter=lfs\n").unwrap();
+    std::fs::write(workspace_root.join("a.bin"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", "add a"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "main", "-r", "@-"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--allow-new", "-b", "main"],
+    );
+
+    // Objects reachable from other bookmarks on the remote aren't uploaded again
+    std::fs::write(workspace_root.join("b.bin"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", "add b"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "create", "feature", "-r", "@-"],
+    );
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--allow-new", "-b", "feature"],
+    );
+    insta::assert_snapshot!(normalize(stderr), @r"
+    Changes to push to origin:
+      Add bookmark feature to 6e247d93b3cd
+    Uploading 1 Git LFS objects to $LFS_SERVER
+    ");
+}
+
+#[test]
+fn test_git_lfs_push_missing_object() {
+    let test_env = TestEnvironment::default();
+    let server = TestLfsServer::start();
+    git2::Repository::init_bare(test_env.env_root().join("origin")).unwrap();
+    init_workspace(&test_env, "repo", Some(server.url()));
+    let workspace_root = test_env.env_root().join("repo");
+
+    // A pointer file whose object isn't available locally
+    std::fs::write(workspace_root.join(".gitattributes"), "*.bin filter=lfs\n").unwrap();
+    std::fs::write(
+        workspace_root.join("large.bin"),
+        format!(
+            "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize 1\n",
+            "0".repeat(64)
+        ),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", "add pointer"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "main", "-r", "@-"]);
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "--allow-new", "-b", "main"],
+    );
+    insta::assert_snapshot!(stderr.replace(server.url(), "$LFS_SERVER"), @r"
+    Changes to push to origin:
+      Add bookmark main to 4ba1201052fa
+    Uploading 1 Git LFS objects to $LFS_SERVER
+    Error: Git LFS operation failed
+    Caused by: Git LFS object 0000000000000000000000000000000000000000000000000000000000000000 is not available locally
+    ");
+}
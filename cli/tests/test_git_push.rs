@@ -1817,6 +1817,79 @@ fn test_git_push_to_remote_named_git(subprocess: bool) {
     }
 }
 
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_git_push_tags(subprocess: bool) {
+    let (test_env, workspace_root) = set_up();
+    if subprocess {
+        test_env.add_config("git.subprocess = true");
+    }
+    let origin_git_repo =
+        git2::Repository::open(test_env.env_root().join("origin/.jj/repo/store/git")).unwrap();
+    let origin_tag_target = |name: &str| {
+        origin_git_repo
+            .find_reference(&format!("refs/tags/{name}"))
+            .ok()
+            .map(|r| r.peel_to_commit().unwrap().summary().unwrap().to_owned())
+    };
+
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "create", "-rbookmark1", "v1"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["tag", "create", "-rbookmark2", "-m=release", "v2"],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--tag=v3"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v3");
+    }
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=glob:v*"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(stdout, @"");
+    }
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add tag v1 to d13ecdbda2a2
+      Add tag v2 to 8476341eb395
+    ");
+    }
+    assert_eq!(origin_tag_target("v1").as_deref(), Some("description 1"));
+    assert_eq!(origin_tag_target("v2").as_deref(), Some("description 2"));
+    assert!(origin_git_repo
+        .find_reference("refs/tags/v2")
+        .unwrap()
+        .peel_to_tag()
+        .is_ok());
+
+    // Pushing again is a no-op
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=v1"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(stderr, @r"
+    Tag v1@origin already matches v1
+    Nothing changed.
+    ");
+    }
+
+    // Move and delete tags
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "set", "-rbookmark2", "v1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "delete", "v2"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--tag=v1", "--tag=v2", "--dry-run"],
+    );
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move sideways tag v1 from d13ecdbda2a2 to 8476341eb395
+      Delete tag v2 from 8476341eb395
+    Dry-run requested, not pushing.
+    ");
+    }
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=v1", "--tag=v2"]);
+    assert_eq!(origin_tag_target("v1").as_deref(), Some("description 2"));
+    assert_eq!(origin_tag_target("v2"), None);
+}
+
 #[test]
 fn test_git_push_sign_on_push() {
     let (test_env, workspace_root) = set_up();
@@ -1957,7 +2030,158 @@ fn test_git_push_sign_on_push() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+// libgit2 doesn't run hooks when pushing to a local path
+#[cfg(unix)]
+#[test]
+fn test_git_push_options() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config("git.subprocess = true");
+    let origin_git_repo_path = test_env.env_root().join("origin/.jj/repo/store/git");
+    let origin_git_repo = git2::Repository::open(&origin_git_repo_path).unwrap();
+    origin_git_repo
+        .config()
+        .unwrap()
+        .set_bool("receive.advertisePushOptions", true)
+        .unwrap();
+    let options_path = test_env.env_root().join("push-options");
+    set_up_origin_hook(
+        &test_env,
+        "pre-receive",
+        &format!(
+            "env | grep '^GIT_PUSH_OPTION_[0-9]' | sort > {}\n",
+            options_path.display()
+        ),
+    );
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "-o", "ci.skip", "--option=key=value"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to dd96a393f44e
+    ");
+    let options = std::fs::read_to_string(&options_path).unwrap();
+    insta::assert_snapshot!(options, @r"
+    GIT_PUSH_OPTION_0=ci.skip
+    GIT_PUSH_OPTION_1=key=value
+    ");
+}
+
+// libgit2 doesn't run hooks when pushing to a local path
+#[cfg(unix)]
+#[test]
+fn test_git_push_rejected_by_remote() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config("git.subprocess = true");
+    // The update hook runs once per ref and can reject them individually
+    set_up_origin_hook(&test_env, "update", r#"test "$1" != refs/heads/bookmark2"#);
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "set", "bookmark2", "-r@", "--allow-backwards"],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to dd96a393f44e
+      Move sideways bookmark bookmark2 from 8476341eb395 to dd96a393f44e
+    remote: error: hook declined to update refs/heads/bookmark2                
+    Warning: The following references unexpectedly failed:
+      refs/heads/bookmark2 (reason: hook declined)
+    Error: Failed to push some bookmarks
+    Hint: Try checking if you have permission to push to all the bookmarks.
+    ");
+    // Only the accepted bookmark was updated
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: vruxwmqv dd96a393 (empty) new
+      @origin: vruxwmqv dd96a393 (empty) new
+    bookmark2: vruxwmqv dd96a393 (empty) new
+      @origin (ahead by 1 commits, behind by 2 commits): rlzusymt 8476341e (empty) description 2
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_push_atomic() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config("git.subprocess = true");
+    set_up_origin_hook(&test_env, "update", r#"test "$1" != refs/heads/bookmark2"#);
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "set", "bookmark2", "-r@", "--allow-backwards"],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all", "--atomic"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to dd96a393f44e
+      Move sideways bookmark bookmark2 from 8476341eb395 to dd96a393f44e
+    remote: error: hook declined to update refs/heads/bookmark2                
+    Warning: The following references unexpectedly failed:
+      refs/heads/bookmark1 (reason: atomic push failure)
+      refs/heads/bookmark2 (reason: hook declined)
+    Error: Failed to push some bookmarks
+    Hint: Try checking if you have permission to push to all the bookmarks.
+    ");
+    // Neither bookmark was updated
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: vruxwmqv dd96a393 (empty) new
+      @origin (behind by 1 commits): xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: vruxwmqv dd96a393 (empty) new
+      @origin (ahead by 1 commits, behind by 2 commits): rlzusymt 8476341e (empty) description 2
+    ");
+
+    // Without the rejection, all bookmarks are pushed
+    set_up_origin_hook(&test_env, "update", "true");
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--all", "--atomic"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to dd96a393f44e
+      Move sideways bookmark bookmark2 from 8476341eb395 to dd96a393f44e
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: vruxwmqv dd96a393 (empty) new
+      @origin: vruxwmqv dd96a393 (empty) new
+    bookmark2: vruxwmqv dd96a393 (empty) new
+      @origin: vruxwmqv dd96a393 (empty) new
+    ");
+}
+
+#[test]
+fn test_git_push_atomic_without_subprocess() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--atomic"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to dd96a393f44e
+    Error: Atomic pushes are not supported without git.subprocess
+    ");
+}
+
 fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     // --quiet to suppress deleted bookmarks hint
     test_env.jj_cmd_success(repo_path, &["bookmark", "list", "--all-remotes", "--quiet"])
 }
+
+/// Installs a hook named `name` running `script` in the origin repo.
+#[cfg(unix)]
+fn set_up_origin_hook(test_env: &TestEnvironment, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let origin_git_repo_path = test_env.env_root().join("origin/.jj/repo/store/git");
+    let hooks_path = origin_git_repo_path.join("hooks");
+    std::fs::create_dir_all(&hooks_path).unwrap();
+    let hook_path = hooks_path.join(name);
+    std::fs::write(&hook_path, format!("#!/bin/sh\n{script}")).unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
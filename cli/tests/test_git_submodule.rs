@@ -12,8 +12,218 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+
 use crate::common::TestEnvironment;
 
+fn git_signature() -> git2::Signature<'static> {
+    git2::Signature::new(
+        "Someone",
+        "someone@example.org",
+        &git2::Time::new(1234567890, 60),
+    )
+    .unwrap()
+}
+
+/// Commits `tree` to `refs/heads/main` on top of its current target.
+fn git_commit(git_repo: &git2::Repository, message: &str, tree_id: git2::Oid) -> git2::Oid {
+    let tree = git_repo.find_tree(tree_id).unwrap();
+    let parent = git_repo
+        .find_reference("refs/heads/main")
+        .and_then(|reference| reference.peel_to_commit())
+        .ok();
+    let signature = git_signature();
+    git_repo
+        .commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )
+        .unwrap()
+}
+
+fn git_commit_files(
+    git_repo: &git2::Repository,
+    message: &str,
+    files: &[(&str, &str)],
+) -> git2::Oid {
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    for (name, contents) in files {
+        let blob_id = git_repo.blob(contents.as_bytes()).unwrap();
+        tree_builder.insert(name, blob_id, 0o100644).unwrap();
+    }
+    git_commit(git_repo, message, tree_builder.write().unwrap())
+}
+
+/// Commits a `.gitmodules` file and the submodule `sub` pinned to
+/// `sub_commit`. The submodule URL is relative to the workspace.
+fn git_commit_submodule(
+    git_repo: &git2::Repository,
+    message: &str,
+    sub_commit: git2::Oid,
+) -> git2::Oid {
+    let gitmodules = "[submodule \"sub\"]\n\tpath = sub\n\turl = ../sub\n";
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let blob_id = git_repo.blob(gitmodules.as_bytes()).unwrap();
+    tree_builder
+        .insert(".gitmodules", blob_id, 0o100644)
+        .unwrap();
+    tree_builder.insert("sub", sub_commit, 0o160000).unwrap();
+    git_commit(git_repo, message, tree_builder.write().unwrap())
+}
+
+fn read_dir_names(path: &Path) -> Vec<String> {
+    let mut names: Vec<_> = std::fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_gitsubmodule_check_out() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.update-submodules = true");
+    let sub_path = test_env.env_root().join("sub");
+    let sub_repo = git2::Repository::init(&sub_path).unwrap();
+    let sub_commit1 = git_commit_files(
+        &sub_repo,
+        "Initial submodule commit\n",
+        &[("a", "a1\n"), ("b", "b1\n")],
+    );
+    let sub_commit2 = git_commit_files(
+        &sub_repo,
+        "Update submodule\n",
+        &[("a", "a2\n"), ("c", "c2\n")],
+    );
+    let super_repo = git2::Repository::init_bare(test_env.env_root().join("super")).unwrap();
+    git_commit_submodule(&super_repo, "Add submodule\n", sub_commit1);
+    git_commit_submodule(&super_repo, "Bump submodule\n", sub_commit2);
+
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "init", "--git-repo=super", "repo"],
+    );
+    let workspace_root = test_env.env_root().join("repo");
+
+    // The submodule is fetched the first time it's checked out.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "main-"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: rlvkpnrz 33720dee (empty) (no description set)
+    Parent commit      : uqnnokzl 1c13fdad Add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    Fetching submodule sub from $TEST_ENV/repo/../sub
+    ");
+    assert_eq!(read_dir_names(&workspace_root.join("sub")), ["a", "b"]);
+
+    // Files removed from the submodule are deleted.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "main"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: kkmpptxz 4b2a8b5a (empty) (no description set)
+    Parent commit      : szrmrqtn f6876a56 main | Bump submodule
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    assert_eq!(read_dir_names(&workspace_root.join("sub")), ["a", "c"]);
+    assert_eq!(
+        std::fs::read_to_string(workspace_root.join("sub").join("a")).unwrap(),
+        "a2\n"
+    );
+
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["diff", "-r", "main"]);
+    insta::assert_snapshot!(stdout, @r"
+    Git submodule became Git submodule at sub:
+       1    1: Git submodule checked out at 816c4b8cda9e2baf7b8e53cbe14952b8b3b5531cd8e4f32b951cb390b610bcc6cb59fc24167dbbf2
+       2    2: InitialUpdate submodule commit
+    ");
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["diff", "-r", "main", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/sub b/sub
+    index 816c4b8cda..d8e4f32b95 160000
+    --- a/sub
+    +++ b/sub
+    @@ -1,1 +1,1 @@
+    -Subproject commit 816c4b8cda9e2baf7b8e53cbe14952b8b3b5531c
+    +Subproject commit d8e4f32b951cb390b610bcc6cb59fc24167dbbf2
+    ");
+
+    // Snapshotting leaves the submodule alone.
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    Working copy : kkmpptxz 4b2a8b5a (empty) (no description set)
+    Parent commit: szrmrqtn f6876a56 main | Bump submodule
+    ");
+    test_env.jj_cmd_ok(&workspace_root, &["edit", "main"]);
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    Working copy changes:
+    M sub
+    Submodule changes:
+    sub: 816c4b8cda9e -> d8e4f32b951c Update submodule
+    Working copy : szrmrqtn f6876a56 main | Bump submodule
+    Parent commit: uqnnokzl 1c13fdad Add submodule
+    ");
+
+    // Changes made inside the submodule aren't overwritten.
+    std::fs::write(workspace_root.join("sub").join("a"), "modified\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "main-"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: yostqsxw c7f0ea6b (empty) (no description set)
+    Parent commit      : uqnnokzl 1c13fdad Add submodule
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: Failed to update submodule sub: Submodule sub has uncommitted changes
+    ");
+    assert_eq!(
+        std::fs::read_to_string(workspace_root.join("sub").join("a")).unwrap(),
+        "modified\n"
+    );
+}
+
+#[test]
+fn test_gitsubmodule_fetch_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.update-submodules = true");
+    let sub_path = test_env.env_root().join("sub");
+    let sub_repo = git2::Repository::init(&sub_path).unwrap();
+    let sub_commit = git_commit_files(&sub_repo, "Submodule commit\n", &[("a", "a\n")]);
+    let super_repo = git2::Repository::init_bare(test_env.env_root().join("super")).unwrap();
+    git_commit_submodule(&super_repo, "Add submodule\n", sub_commit);
+    std::fs::remove_dir_all(&sub_path).unwrap();
+
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "init", "--git-repo=super", "repo"],
+    );
+    let workspace_root = test_env.env_root().join("repo");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "main"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: rlvkpnrz 1c31e007 (empty) (no description set)
+    Parent commit      : ywxlolow d4b231ec main | Add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    Fetching submodule sub from $TEST_ENV/repo/../sub
+    Warning: Failed to update submodule sub: unsupported URL protocol; class=Net (12)
+    ");
+
+    // Updating submodules can be turned off.
+    test_env.add_config("git.update-submodules = false");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "root()"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: kkmpptxz fcdbbd73 (empty) (no description set)
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 2 files
+    ");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["new", "main"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: zsuskuln 0edd5851 (empty) (no description set)
+    Parent commit      : ywxlolow d4b231ec main | Add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    ");
+}
+
 #[test]
 fn test_gitsubmodule_print_gitmodules() {
     let test_env = TestEnvironment::default();
@@ -0,0 +1,273 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+/// Configures `hook` to run `fake-formatter` with `args`.
+fn set_hook(test_env: &TestEnvironment, hook: &str, args: &[&str]) {
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(formatter_path.is_file());
+    let command = toml_edit::Value::from_iter(
+        [formatter_path.to_str().unwrap()]
+            .iter()
+            .chain(args)
+            .copied(),
+    );
+    test_env.add_config(format!("hooks.{hook} = {command}"));
+}
+
+fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
+    test_env.jj_cmd_success(cwd, &["log", "-T", r#"description ++ "\n""#])
+}
+
+#[test]
+fn test_commit_msg_hook() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let input_path = test_env.env_root().join("input");
+    set_hook(
+        &test_env,
+        "commit-msg",
+        &[
+            "--tee",
+            input_path.to_str().unwrap(),
+            "--stderr",
+            "checking $change_id\n",
+        ],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    checking qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    first
+    Working copy now at: qpvuntsm fa15625b (empty) first
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(&input_path).unwrap(), @"first");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "second"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    checking qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    second
+    Working copy now at: kkmpptxz a01e3947 (empty) (no description set)
+    Parent commit      : qpvuntsm 53aecb7c (empty) second
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(&input_path).unwrap(), @r"
+    first
+    second
+    ");
+
+    // The hook isn't run if the description doesn't change
+    std::fs::remove_file(&input_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-r@-", "-m", "second"]);
+    assert!(!input_path.exists());
+}
+
+#[test]
+fn test_commit_msg_hook_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    set_hook(
+        &test_env,
+        "commit-msg",
+        &["--stderr", "description is too short\n", "--fail"],
+    );
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "wip"]);
+    insta::assert_snapshot!(stderr, @r"
+    description is too short
+    wip
+    Error: The commit-msg hook exited with exit status: 1
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["commit", "-m", "wip"]);
+    insta::assert_snapshot!(stderr, @r"
+    description is too short
+    wip
+    Error: The commit-msg hook exited with exit status: 1
+    ");
+
+    // Nothing was recorded
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @
+    ◆
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T", r#"description ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @"add workspace 'default'");
+}
+
+#[test]
+fn test_commit_msg_hook_missing_program() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"hooks.commit-msg = ["this-hook-does-not-exist"]"#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "wip"]);
+    insta::assert_snapshot!(stderr.lines().next().unwrap(), @"Error: Failed to run commit-msg hook 'this-hook-does-not-exist'");
+}
+
+#[test]
+fn test_post_snapshot_hook() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    set_hook(
+        &test_env,
+        "post-snapshot",
+        &["--stdout", "snapshotted $commit_id with tree $tree_id\n"],
+    );
+
+    // Nothing to snapshot
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"snapshotted 81b46e2c7fdcd28ca37744d426a20c7305dcc450 with tree 5bfa85fe39384c975a4d1558a6811e2b47492603");
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-r@", "--no-graph", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @"81b46e2c7fdcd28ca37744d426a20c7305dcc450");
+}
+
+#[test]
+fn test_post_snapshot_hook_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    set_hook(&test_env, "post-snapshot", &["--fail"]);
+
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"Warning: The post-snapshot hook exited with exit status: 1");
+
+    // The snapshot was recorded anyway
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--ignore-working-copy",
+            "-T",
+            r#"description ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    snapshot working copy
+    add workspace 'default'
+    ");
+    // Commands which snapshot nothing don't run the hook
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"");
+}
+
+fn set_up_remote(test_env: &TestEnvironment) -> std::path::PathBuf {
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "origin"]);
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(&origin_path, &["describe", "-m=description 1"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "bookmark1"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+    let origin_git_repo_path = origin_path.join(".jj/repo/store/git");
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "clone",
+            "--config=git.auto-local-bookmark=true",
+            origin_git_repo_path.to_str().unwrap(),
+            "local",
+        ],
+    );
+    test_env.env_root().join("local")
+}
+
+#[test]
+fn test_pre_push_hook() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = set_up_remote(&test_env);
+    let input_path = test_env.env_root().join("input");
+    set_hook(
+        &test_env,
+        "pre-push",
+        &[
+            "--tee",
+            input_path.to_str().unwrap(),
+            "--stderr",
+            "pushing to $remote\n",
+        ],
+    );
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "bookmark2", "-r@"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 03644fbdcbf1
+      Add bookmark bookmark2 to 03644fbdcbf1
+    pushing to origin
+    refs/heads/bookmark1 d13ecdbda2a2e7471a29db2bed6f08ca28f91264 03644fbdcbf181c66d8901fc20d02cb1b50a2db0
+    refs/heads/bookmark2 0000000000000000000000000000000000000000 03644fbdcbf181c66d8901fc20d02cb1b50a2db0
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(&input_path).unwrap(), @r"
+    refs/heads/bookmark1 d13ecdbda2a2e7471a29db2bed6f08ca28f91264 03644fbdcbf181c66d8901fc20d02cb1b50a2db0
+    refs/heads/bookmark2 0000000000000000000000000000000000000000 03644fbdcbf181c66d8901fc20d02cb1b50a2db0
+    ");
+
+    // Deleting a bookmark
+    std::fs::remove_file(&input_path).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "delete", "bookmark2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(std::fs::read_to_string(&input_path).unwrap(), @"refs/heads/bookmark2 03644fbdcbf181c66d8901fc20d02cb1b50a2db0 0000000000000000000000000000000000000000");
+
+    // The hook isn't run on dry runs
+    std::fs::remove_file(&input_path).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m=changed"]);
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--all", "--dry-run"]);
+    assert!(!input_path.exists());
+}
+
+#[test]
+fn test_pre_push_hook_failure() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = set_up_remote(&test_env);
+    set_hook(&test_env, "pre-push", &["--fail"]);
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-m=new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1", "-r@"]);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 03644fbdcbf1
+    refs/heads/bookmark1 d13ecdbda2a2e7471a29db2bed6f08ca28f91264 03644fbdcbf181c66d8901fc20d02cb1b50a2db0
+    Error: The pre-push hook exited with exit status: 1
+    ");
+
+    // Nothing was pushed
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["bookmark", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r"
+    bookmark1: royxmykx 03644fbd (empty) new
+      @origin (behind by 1 commits): xtvrqkyv d13ecdbd (empty) description 1
+    ");
+}
@@ -812,6 +812,70 @@ fn test_log_reversed() {
     "###);
 }
 
+#[test]
+fn test_log_sorted() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "a"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "-r",
+            "sort(~root(), description)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    a
+    b
+    c
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "--limit=2",
+            "-r",
+            "sort(~root(), description, reverse)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    c
+    b
+    ");
+
+    // The graph is still rendered in topological order
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "sort(~root(), description)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  a
+    ○  c
+    ○  b
+    │
+    ~
+    ");
+}
+
 #[test]
 fn test_log_filtered_by_path() {
     let test_env = TestEnvironment::default();
@@ -341,6 +341,34 @@ fn test_op_log_template() {
     "#);
 }
 
+#[test]
+fn test_op_log_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T", r#"json(self) ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    {"description":"add workspace 'default'","hostname":"host.example.com","id":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","is_snapshot":false,"parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"tags":{},"time":{"end":"2001-02-03T04:05:07+07:00","start":"2001-02-03T04:05:07+07:00"},"username":"test-username"}
+    {"description":"","hostname":"","id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","is_snapshot":false,"parents":[],"tags":{},"time":{"end":"1970-01-01T00:00:00+00:00","start":"1970-01-01T00:00:00+00:00"},"username":""}
+    "#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "-n1",
+            "-T",
+            "json(id) ++ json(time.start())",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#""eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1""2001-02-03T04:05:07+07:00""#);
+}
+
 #[test]
 fn test_op_log_builtin_templates() {
     let test_env = TestEnvironment::default();
@@ -526,6 +554,103 @@ fn test_op_log_configurable() {
     assert!(stdout.contains("my-username@my-hostname"));
 }
 
+#[test]
+fn test_op_log_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        [opset-aliases]
+        'commits' = 'description(glob:"commit *")'
+        'by(x)' = 'user(x) ~ root()'
+        "#,
+    );
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "description 2"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 3"]);
+    let op_log = |args: &[&str]| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &[&["op", "log", "-Tdescription ++ \"\\n\""], args].concat(),
+        )
+    };
+
+    // Unselected operations are elided from the graph
+    insta::assert_snapshot!(op_log(&["-rcommits"]), @r"
+    @  commit 72e26e6948f515aaa2a029bc6176ef54a8f92146
+    ○  commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ");
+    insta::assert_snapshot!(op_log(&["-r", "@ | @--"]), @r"
+    @  commit 72e26e6948f515aaa2a029bc6176ef54a8f92146
+    ○  commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ");
+    insta::assert_snapshot!(op_log(&["-r", "@", "-r", "root()"]), @r"
+    @  commit 72e26e6948f515aaa2a029bc6176ef54a8f92146
+    ○
+    ");
+    insta::assert_snapshot!(op_log(&["-r", "..@-", "--no-graph", "--reversed"]), @r"
+    add workspace 'default'
+    commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    describe commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    ");
+    insta::assert_snapshot!(op_log(&["-r", "latest(commits, 1)"]), @"@  commit 72e26e6948f515aaa2a029bc6176ef54a8f92146");
+    insta::assert_snapshot!(op_log(&["-r", "by(glob:'test-username@*') ~ commits"]), @r"
+    ○  describe commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    ○  add workspace 'default'
+    ");
+    insta::assert_snapshot!(op_log(&["-r", r#"tags(args=glob:"*describe*")"#]), @"○  describe commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e");
+    insta::assert_snapshot!(op_log(&["-r", r#"touched("description(2)")"#]), @r"
+    @  commit 72e26e6948f515aaa2a029bc6176ef54a8f92146
+    ○  describe commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
+    ");
+    insta::assert_snapshot!(op_log(&["-r", "none()"]), @"");
+
+    // Opsets can also be used with --at-op
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "-Tdescription",
+            "--at-op=latest(commits ~ @)",
+            "-n1",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"@  commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "-r", "commits ~"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse opset: Syntax error
+    Caused by:  --> 1:10
+      |
+    1 | commits ~
+      |          ^---
+      |
+      = expected `::`, `..`, `~`, or <primary>
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "-r", "bar()"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse opset: Function `bar` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | bar()
+      | ^-^
+      |
+      = Function `bar` doesn't exist
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--at-op", "touched('@')"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse opset: Cannot resolve revset expression without repository
+    Caused by:  --> 1:9
+      |
+    1 | touched('@')
+      |         ^-^
+      |
+      = Cannot resolve revset expression without repository
+    ");
+}
+
 #[test]
 fn test_op_abandon_ancestors() {
     let test_env = TestEnvironment::default();
@@ -627,6 +752,44 @@ fn test_op_abandon_ancestors() {
     "###);
 }
 
+#[test]
+fn test_op_abandon_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "description 2"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 3"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "description 4"]);
+
+    // Non-contiguous operations can't be abandoned at once.
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["op", "abandon", r#"description(glob:"commit *")"#],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Cannot abandon non-contiguous operations");
+
+    // Nothing to abandon.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "abandon", "none()"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "abandon", "::@-"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot abandon the root operation");
+
+    // Abandon contiguous operations selected by a filter.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "abandon", r#"..@- & description(glob:"commit *")::"#],
+    );
+    insta::assert_snapshot!(stderr, @"Abandoned 3 operations and reparented 1 descendant operations.");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "-Tdescription ++ \"\\n\""]);
+    insta::assert_snapshot!(stdout, @r"
+    @  describe commit 35d67ffb34bc885dde054a62e7ac941d20989b86
+    ○  add workspace 'default'
+    ○
+    ");
+}
+
 #[test]
 fn test_op_abandon_without_updating_working_copy() {
     let test_env = TestEnvironment::default();
@@ -0,0 +1,141 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use itertools::Itertools as _;
+
+use crate::common::TestEnvironment;
+
+fn create_commit(test_env: &TestEnvironment, repo_path: &Path, name: &str, content: &str) {
+    std::fs::write(repo_path.join(name), content).unwrap();
+    test_env.jj_cmd_ok(repo_path, &["commit", "-m", name]);
+}
+
+/// Returns a revset matching the current commits in `revset`, even after they
+/// get rewritten.
+fn commit_ids_revset(test_env: &TestEnvironment, repo_path: &Path, revset: &str) -> String {
+    let stdout = test_env.jj_cmd_success(
+        repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            revset,
+            "-T",
+            r#"commit_id ++ "\n""#,
+        ],
+    );
+    stdout.lines().join("|")
+}
+
+#[test]
+fn test_range_diff_rewritten_stack() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", "a\n");
+    create_commit(&test_env, &repo_path, "b", "b\n");
+    create_commit(&test_env, &repo_path, "c", "c\n");
+    create_commit(&test_env, &repo_path, "d", "d\n");
+    let old_revset = commit_ids_revset(&test_env, &repo_path, "root()..@-");
+
+    // Modify "b", drop "c", and add "e"
+    test_env.jj_cmd_ok(&repo_path, &["edit", "description(b)"]);
+    std::fs::write(repo_path.join("b"), "b\nmore b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "description(c)"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(d)"]);
+    create_commit(&test_env, &repo_path, "e", "e\n");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["range-diff", "--from", &old_revset, "--to", "root()..@-"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    = af42592fcaac -> qpvuntsm af42592f a
+    ! 44cc221a2461 -> rlvkpnrz 1397111f b
+    Modified regular file b:
+       1    1: b
+            2: more b
+    - kkmpptxz hidden 4f7d8e73 c
+    = 3af9db125cc1 -> zsuskuln a2c9cb74 d
+    + yostqsxw fc52a763 e
+    "#);
+
+    // Paths can be restricted
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "range-diff",
+            "--from",
+            &old_revset,
+            "--to",
+            "root()..@-",
+            "--summary",
+            "a",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    = af42592fcaac -> qpvuntsm af42592f a
+    = 44cc221a2461 -> rlvkpnrz 1397111f b
+    - kkmpptxz hidden 4f7d8e73 c
+    = 3af9db125cc1 -> zsuskuln a2c9cb74 d
+    + yostqsxw fc52a763 e
+    "#);
+}
+
+#[test]
+fn test_range_diff_similar_commits() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", "a\n");
+    create_commit(&test_env, &repo_path, "b", "b1\nb2\nb3\n");
+    create_commit(&test_env, &repo_path, "c", "c\n");
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "old", "-r@-"]);
+
+    // Duplicated commits have unrelated change IDs, so they're paired by
+    // similarity
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    create_commit(&test_env, &repo_path, "a", "a\n");
+    create_commit(&test_env, &repo_path, "b", "b1\nb2\nb4\n");
+    create_commit(&test_env, &repo_path, "c", "something else\n");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "range-diff",
+            "--from",
+            "root()..old",
+            "--to",
+            "root()..@-",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    = af42592fcaac -> royxmykx 7f189129 a
+    ! 2326ae43fe3e -> yqosqzyt 4d3ff601 b
+    diff --git a/b b/b
+    index 5b27bfa541..e0a619c93f 100644
+    --- a/b
+    +++ b/b
+    @@ -1,3 +1,3 @@
+     b1
+     b2
+    -b3
+    +b4
+    + vruxwmqv 84f87c9e c
+    - kkmpptxz 0005cf8f old | c
+    "#);
+}
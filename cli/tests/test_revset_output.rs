@@ -346,6 +346,27 @@ fn test_parse_warning() {
       |
       = Multi-argument patterns syntax is deprecated; separate them with |
     "###);
+
+    // Ordering of sort() is discarded unless it's at the top level or in
+    // latest()
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "present(sort(none(), description)) & latest(sort(none(), author_date))",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: In revset expression
+     --> 1:9
+      |
+    1 | present(sort(none(), description)) & latest(sort(none(), author_date))
+      |         ^--^
+      |
+      = Ordering of sort() is discarded when combined with other operators
+    ");
 }
 
 #[test]
@@ -0,0 +1,176 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The tests use `sh` syntax for the commands.
+#![cfg(unix)]
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"change_id.short() ++ " " ++ description.first_line()"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+fn init_stack(test_env: &TestEnvironment) -> std::path::PathBuf {
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "a"]);
+    std::fs::write(repo_path.join("file"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "b"]);
+    std::fs::write(repo_path.join("file"), "a\nb\nc\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "c"]);
+    repo_path
+}
+
+#[test]
+fn test_run_read_only() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_stack(&test_env);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["run", "-j1", "-r", "::@ ~ root()", "tail -n1 file"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    a
+    b
+    c
+    ");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    // The main working copy is untouched.
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx c
+    ○  rlvkpnrzqnoo b
+    ○  qpvuntsmwlqt a
+    ◆  zzzzzzzzzzzz
+    ");
+    // A single temporary working copy is reused for all commits.
+    let working_copies_dir = repo_path.join(".jj").join("run").join("working_copies");
+    assert_eq!(std::fs::read_dir(&working_copies_dir).unwrap().count(), 1);
+    let exit_status_dir = repo_path.join(".jj").join("run").join("exit_status");
+    assert_eq!(std::fs::read_dir(&exit_status_dir).unwrap().count(), 3);
+
+    // Clean up the temporary working copies and exit statuses.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["run", "--clean"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Removed temporary working copies");
+    assert!(!repo_path.join(".jj").join("run").exists());
+
+    // Nothing to clean up.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["run", "--clean"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_run_rewrites_changed_files() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_stack(&test_env);
+
+    // Only "b" is modified. Its descendant "c" is rebased on top.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["run", "-r", "description(b)", "echo new > added"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rewrote 1 commits and rebased 1 descendant commits
+    Working copy now at: kkmpptxz 34eb655d c
+    Parent commit      : rlvkpnrz 49a39957 b
+    Added 1 files, modified 0 files, removed 0 files
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-r", "@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    A added
+    M file
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "@", "added"]);
+    insta::assert_snapshot!(stdout, @"new");
+
+    // Running on every commit rewrites them independently.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["run", "-j2", "-r", "::@ ~ root()", "sed -i 's/a/x/' file"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Rewrote 3 commits and rebased 0 descendant commits
+    Working copy now at: kkmpptxz 48325b40 c
+    Parent commit      : rlvkpnrz 72431bad b
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "@", "file"]);
+    insta::assert_snapshot!(stdout, @r"
+    x
+    b
+    c
+    ");
+}
+
+#[test]
+fn test_run_failure() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_stack(&test_env);
+
+    // Changes made by a failing command are discarded.
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "run",
+            "-j1",
+            "-r",
+            "::@ ~ root()",
+            "echo changed >> file; grep -q b file",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Command failed (exit status: 1) on qpvuntsm b5eb8ca5 a
+    Rewrote 2 commits and rebased 0 descendant commits
+    Working copy now at: kkmpptxz 91b152bf c
+    Parent commit      : rlvkpnrz a93119ea b
+    Added 0 files, modified 1 files, removed 0 files
+    Error: Command failed on 1 of 3 commits
+    ");
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "description(a)",
+            "-T",
+            "commit_id",
+        ],
+    );
+    let exit_status = std::fs::read_to_string(
+        repo_path
+            .join(".jj")
+            .join("run")
+            .join("exit_status")
+            .join(commit_id),
+    )
+    .unwrap();
+    insta::assert_snapshot!(exit_status, @"1");
+}
+
+#[test]
+fn test_run_immutable() {
+    let test_env = TestEnvironment::default();
+    let repo_path = init_stack(&test_env);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["run", "-r", "root()", "true"]);
+    insta::assert_snapshot!(stderr, @"Error: The root commit 000000000000 is immutable");
+}
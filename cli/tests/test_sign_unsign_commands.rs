@@ -0,0 +1,164 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+const LOG_TEMPLATE: &str = r#"
+separate(" ",
+  change_id.short(),
+  description.first_line(),
+  if(signature,
+    "[" ++ signature.status() ++ " " ++ signature.key() ++ "]",
+    "[unsigned]",
+  ),
+) ++ "\n"
+"#;
+
+#[test]
+fn test_sign() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    signing.backend = "test"
+    signing.key = "impeccable"
+    "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "one"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "two"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "three"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "description(two)"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Signed 1 commit: rlvkpnrz 247706bf (empty) two
+    Rebased 2 descendant commits
+    Working copy now at: zsuskuln bd840e20 (empty) (no description set)
+    Parent commit      : kkmpptxz 85f9c99b (empty) three
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(stdout, @r#"
+    @  zsuskulnrvyr [unsigned]
+    ○  kkmpptxzrspx three [unsigned]
+    ○  rlvkpnrzqnoo two [good impeccable]
+    ○  qpvuntsmwlqt one [unsigned]
+    ◆  zzzzzzzzzzzz [unsigned]
+    "#);
+
+    // Existing signatures are replaced, with the key given on the command line
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["sign", "-r", "::@- ~ root()", "--key", "other"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Signed 3 commits:
+      qpvuntsm 03ccb3e6 (empty) one
+      rlvkpnrz 4c302d98 (empty) two
+      kkmpptxz 1dc70feb (empty) three
+    Rebased 1 descendant commits
+    Working copy now at: zsuskuln 55949206 (empty) (no description set)
+    Parent commit      : kkmpptxz 1dc70feb (empty) three
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(stdout, @r#"
+    @  zsuskulnrvyr [unsigned]
+    ○  kkmpptxzrspx three [good other]
+    ○  rlvkpnrzqnoo two [good other]
+    ○  qpvuntsmwlqt one [good other]
+    ◆  zzzzzzzzzzzz [unsigned]
+    "#);
+
+    // Immutable commits cannot be signed
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign", "-r", "root()"]);
+    insta::assert_snapshot!(stderr, @"Error: The root commit 000000000000 is immutable");
+}
+
+#[test]
+fn test_sign_without_backend() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: No signing backend configured
+    Hint: For configuring a signing backend, see https://jj-vcs.github.io/jj/latest/config/#commit-signing
+    "#);
+}
+
+#[test]
+fn test_unsign() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    signing.backend = "test"
+    signing.key = "impeccable"
+    signing.sign-all = true
+    "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "one"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "two"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "three"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(stdout, @r#"
+    @  zsuskulnrvyr [good impeccable]
+    ○  kkmpptxzrspx three [good impeccable]
+    ○  rlvkpnrzqnoo two [good impeccable]
+    ○  qpvuntsmwlqt one [good impeccable]
+    ◆  zzzzzzzzzzzz [unsigned]
+    "#);
+
+    // Keep the descendants signed when they're rebased
+    test_env.add_config("signing.sign-all = false");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "description(one)"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Unsigned 1 commit: qpvuntsm 55caf99f (empty) one
+    Rebased 3 descendant commits
+    Working copy now at: zsuskuln c4671e91 (empty) (no description set)
+    Parent commit      : kkmpptxz fa0c2f1f (empty) three
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(stdout, @r#"
+    @  zsuskulnrvyr [good impeccable]
+    ○  kkmpptxzrspx three [good impeccable]
+    ○  rlvkpnrzqnoo two [good impeccable]
+    ○  qpvuntsmwlqt one [unsigned]
+    ◆  zzzzzzzzzzzz [unsigned]
+    "#);
+
+    // Unsigned commits are skipped
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "::@ ~ root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Unsigned 3 commits:
+      rlvkpnrz f08eefaa (empty) two
+      kkmpptxz aeaafd31 (empty) three
+      zsuskuln 65f96a25 (empty) (no description set)
+    Working copy now at: zsuskuln 65f96a25 (empty) (no description set)
+    Parent commit      : kkmpptxz aeaafd31 (empty) three
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(stdout, @r#"
+    @  zsuskulnrvyr [unsigned]
+    ○  kkmpptxzrspx three [unsigned]
+    ○  rlvkpnrzqnoo two [unsigned]
+    ○  qpvuntsmwlqt one [unsigned]
+    ◆  zzzzzzzzzzzz [unsigned]
+    "#);
+}
@@ -62,12 +62,19 @@ fn test_sparse_manage_patterns() {
     std::fs::create_dir(&sub_dir).unwrap();
 
     // Not a workspace-relative path
-    let stderr = test_env.jj_cmd_cli_error(&sub_dir, &["sparse", "set", "--add=../file2"]);
-    insta::assert_snapshot!(stderr, @r###"
-    error: invalid value '../file2' for '--add <ADD>': Invalid component ".." in repo-relative path "../file2"
-
-    For more information, try '--help'.
-    "###);
+    let stderr = test_env.jj_cmd_failure(&sub_dir, &["sparse", "set", "--add=../file2"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse sparse pattern: ../file2
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | ../file2
+      | ^------^
+      |
+      = Invalid file pattern
+    2: Path "../file2" is not in the repo "."
+    3: Invalid component ".." in repo-relative path "../file2"
+    "#);
 
     // Can `--add` a few files
     let (stdout, stderr) = test_env.jj_cmd_ok(
@@ -175,6 +182,196 @@ fn test_sparse_manage_patterns() {
     "###);
 }
 
+#[test]
+fn test_sparse_fileset_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    for dir in [
+        "docs",
+        "services/foo/api",
+        "services/foo/impl",
+        "services/bar/api",
+    ] {
+        std::fs::create_dir_all(repo_path.join(dir)).unwrap();
+        std::fs::write(repo_path.join(dir).join("file"), "contents").unwrap();
+    }
+    std::fs::write(repo_path.join("README.md"), "contents").unwrap();
+
+    // Can exclude a directory with a negated pattern
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sparse", "set", "--add", "~docs"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Added 0 files, modified 0 files, removed 1 files");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    .
+    ~docs
+    ");
+    assert!(!repo_path.join("docs").exists());
+    assert!(repo_path.join("README.md").exists());
+
+    // Can use globs
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "sparse",
+            "set",
+            "--clear",
+            "--add",
+            "glob:services/*/api",
+            "--add",
+            "root-file:README.md",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Added 0 files, modified 0 files, removed 1 files");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    glob:services/*/api
+    root-file:README.md
+    ");
+    assert!(repo_path.join("README.md").exists());
+    assert!(repo_path.join("services/foo/api/file").exists());
+    assert!(repo_path.join("services/bar/api/file").exists());
+    assert!(!repo_path.join("services/foo/impl").exists());
+
+    // Can remove a pattern by its text
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["sparse", "set", "--remove", "root-file:README.md"],
+    );
+    insta::assert_snapshot!(stderr, @"Added 0 files, modified 0 files, removed 1 files");
+    assert!(!repo_path.join("README.md").exists());
+
+    // Invalid pattern
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "set", "--add", "glob:["]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse sparse pattern: glob:[
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | glob:[
+      | ^----^
+      |
+      = Invalid file pattern
+    2: Pattern syntax error near position 0: invalid range pattern
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "set", "--add", "foo |"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse sparse pattern: foo |
+    Caused by:  --> 1:6
+      |
+    1 | foo |
+      |      ^---
+      |
+      = expected `~` or <primary>
+    ");
+}
+
+#[test]
+fn test_sparse_use_profile() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    for dir in ["backend", "frontend", "docs", ".jj-sparse"] {
+        std::fs::create_dir(repo_path.join(dir)).unwrap();
+    }
+    std::fs::write(repo_path.join("backend/file"), "contents").unwrap();
+    std::fs::write(repo_path.join("frontend/file"), "contents").unwrap();
+    std::fs::write(repo_path.join("docs/file"), "contents").unwrap();
+    std::fs::write(
+        repo_path.join(".jj-sparse/backend.toml"),
+        "patterns = ['.jj-sparse', 'backend']\n",
+    )
+    .unwrap();
+    std::fs::write(
+        repo_path.join(".jj-sparse/no-docs.toml"),
+        "patterns = ['.', '~docs']\n",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join(".jj-sparse/invalid.toml"), "pattern = []\n").unwrap();
+    std::fs::write(
+        repo_path.join(".jj-sparse/bad-pattern.toml"),
+        "patterns = ['glob:[']\n",
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add files"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sparse", "use", "backend"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Added 0 files, modified 0 files, removed 2 files");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    .jj-sparse
+    backend
+    ");
+    assert!(repo_path.join("backend/file").exists());
+    assert!(!repo_path.join("frontend").exists());
+    assert!(!repo_path.join("docs").exists());
+
+    // Only the paths whose membership changed are updated
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sparse", "use", "no-docs"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Added 1 files, modified 0 files, removed 0 files");
+    assert!(repo_path.join("frontend/file").exists());
+    assert!(!repo_path.join("docs").exists());
+
+    // Other workspaces can use a different profile
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "workspace",
+            "add",
+            "--sparse-patterns=empty",
+            "../secondary",
+        ],
+    );
+    let secondary_path = test_env.env_root().join("secondary");
+    test_env.jj_cmd_ok(&secondary_path, &["sparse", "use", "backend"]);
+    let stdout = test_env.jj_cmd_success(&secondary_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    .jj-sparse
+    backend
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r"
+    .
+    ~docs
+    ");
+
+    // Errors
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "use", "missing"]);
+    insta::assert_snapshot!(stderr, @"Error: No such sparse profile: .jj-sparse/missing.toml");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "use", "invalid"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse sparse profile .jj-sparse/invalid.toml
+    Caused by: TOML parse error at line 1, column 1
+      |
+    1 | pattern = []
+      | ^^^^^^^
+    unknown field `pattern`, expected `patterns`
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "use", "bad-pattern"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to parse sparse pattern: glob:[
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | glob:[
+      | ^----^
+      |
+      = Invalid file pattern
+    2: Pattern syntax error near position 0: invalid range pattern
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "use", "../backend"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Invalid sparse profile name: ../backend
+    Caused by: Invalid component ".." in repo-relative path ".jj-sparse/../backend.toml"
+    "#);
+}
+
 #[test]
 fn test_sparse_editor_avoids_unc() {
     use std::path::PathBuf;
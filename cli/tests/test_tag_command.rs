@@ -117,3 +117,168 @@ fn test_tag_list() {
     added_targets: commit2
     "###);
 }
+
+#[test]
+fn test_tag_list_remotes() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "origin"]);
+    let origin_path = test_env.env_root().join("origin");
+    test_env.jj_cmd_ok(&origin_path, &["describe", "-mcommit1"]);
+    test_env.jj_cmd_ok(&origin_path, &["tag", "create", "-r@", "tag1", "tag3"]);
+    test_env.jj_cmd_ok(&origin_path, &["new", "-mcommit2"]);
+    test_env.jj_cmd_ok(&origin_path, &["tag", "create", "-r@", "tag2"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+    let origin_git_repo_path = origin_path.join(".jj/repo/store/git");
+
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "clone",
+            origin_git_repo_path.to_str().unwrap(),
+            "local",
+        ],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["tag", "set", "-rtag1", "tag2"]);
+    test_env.jj_cmd_ok(&local_path, &["tag", "delete", "tag1"]);
+
+    // Synchronized remote tags are omitted by default
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["tag", "list"]), @r"
+    tag1 (deleted)
+      @origin: wqtqxxvn caf975d0 (empty) commit1
+    tag2: wqtqxxvn caf975d0 (empty) commit1
+      @origin (ahead by 2 commits): roukrsvw 139b0cdc (empty) commit2
+    tag3: wqtqxxvn caf975d0 (empty) commit1
+    ");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["tag", "list", "--all-remotes"]), @r"
+    tag1 (deleted)
+      @origin: wqtqxxvn caf975d0 (empty) commit1
+    tag2: wqtqxxvn caf975d0 (empty) commit1
+      @origin (ahead by 2 commits): roukrsvw 139b0cdc (empty) commit2
+    tag3: wqtqxxvn caf975d0 (empty) commit1
+      @origin: wqtqxxvn caf975d0 (empty) commit1
+    ");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["tag", "list", "--remote=origin", "tag2"]), @r"
+    tag2: wqtqxxvn caf975d0 (empty) commit1
+      @origin (ahead by 2 commits): roukrsvw 139b0cdc (empty) commit2
+    ");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["tag", "list", "--tracked"]), @r"
+    tag1 (deleted)
+      @origin: wqtqxxvn caf975d0 (empty) commit1
+    tag2: wqtqxxvn caf975d0 (empty) commit1
+      @origin (ahead by 2 commits): roukrsvw 139b0cdc (empty) commit2
+    tag3: wqtqxxvn caf975d0 (empty) commit1
+      @origin: wqtqxxvn caf975d0 (empty) commit1
+    ");
+}
+
+#[test]
+fn test_tag_create_set_delete() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-mcommit1"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-mcommit2"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-r@--", "v1"]);
+    insta::assert_snapshot!(stderr, @"Created 1 tags pointing to qpvuntsm caf975d0 (empty) commit1");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "v1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Tag already exists: v1
+    Hint: Use `jj tag set` to update it.
+    ");
+
+    // Tags are exported to Git
+    let git_tag_target = |name: &str| {
+        git_repo
+            .find_reference(&format!("refs/tags/{name}"))
+            .ok()
+            .map(|r| r.peel_to_commit().unwrap().summary().unwrap().to_owned())
+    };
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    assert_eq!(git_tag_target("v1").as_deref(), Some("commit1"));
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "set", "-r@-", "v1", "v2"]);
+    insta::assert_snapshot!(stderr, @r"
+    Created 1 tags pointing to rlvkpnrz d0a19ea4 (empty) commit2
+    Moved 1 tags to rlvkpnrz d0a19ea4 (empty) commit2
+    ");
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    assert_eq!(git_tag_target("v1").as_deref(), Some("commit2"));
+    assert_eq!(git_tag_target("v2").as_deref(), Some("commit2"));
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1: rlvkpnrz d0a19ea4 (empty) commit2
+    v2: rlvkpnrz d0a19ea4 (empty) commit2
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "delete", "v3"]);
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v3");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "glob:v*"]);
+    insta::assert_snapshot!(stderr, @"Deleted 2 tags.");
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    assert_eq!(git_tag_target("v1"), None);
+    assert_eq!(git_tag_target("v2"), None);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"");
+
+    // Tags which can't be exported to Git are rejected
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "v1..v2"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid tag name: v1..v2
+    Caused by:
+    1: A reference must be a valid tag name as well
+    2: A ref must not contain '..' as it may be mistaken for a range
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "set", "v1", "v2.lock"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid tag name: v2.lock
+    Caused by:
+    1: A reference must be a valid tag name as well
+    2: A ref must not end with '.lock'
+    ");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"");
+}
+
+#[test]
+fn test_tag_create_annotated() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-mcommit1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["tag", "create", "-r@-", "-m", "Release 1.0", "v1.0"],
+    );
+    // The Git tag is created on export
+    assert!(git_repo.find_reference("refs/tags/v1.0").is_err());
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let git_tag = git_repo
+        .find_reference("refs/tags/v1.0")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(git_tag.message(), Some("Release 1.0\n"));
+    assert_eq!(git_tag.tagger().unwrap().name(), Some("Test User"));
+    assert_eq!(
+        git_tag
+            .target()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .summary(),
+        Some("commit1")
+    );
+
+    // The tag object is kept on subsequent export
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let git_ref = git_repo.find_reference("refs/tags/v1.0").unwrap();
+    assert_eq!(git_ref.target(), Some(git_tag.id()));
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"v1.0: qpvuntsm caf975d0 (empty) commit1");
+}
@@ -197,6 +197,35 @@ fn test_templater_upper_lower() {
       render(r#""Hello".upper() ++ "Hello".lower()"#), @"HELLOhello");
 }
 
+#[test]
+fn test_templater_string_pattern_methods() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "Fix crash\n\nFixes PROJ-1234, refs PROJ-99",
+        ],
+    );
+    let render = |template| get_template_output(&test_env, &repo_path, "@", template);
+
+    insta::assert_snapshot!(
+        render(r#"description.match('regex:[A-Z]+-\d+').map(|id| "https://issues/" ++ id).join("\n")"#),
+        @r"
+    https://issues/PROJ-1234
+    https://issues/PROJ-99
+    ");
+    insta::assert_snapshot!(
+        render(r#"description.first_line().replace('regex:(\w+) (\w+)', "$2 $1")"#),
+        @"crash Fix");
+    insta::assert_snapshot!(
+        render(r#"description.split(", ").map(|s| s.len()).join(",")"#),
+        @"26,13");
+}
+
 #[test]
 fn test_templater_alias() {
     let test_env = TestEnvironment::default();
@@ -161,7 +161,24 @@ pub fn get_annotation_for_file(
     file_path: &RepoPath,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
     let source = Source::load(starting_commit, file_path)?;
-    compute_file_annotation(repo, starting_commit.id(), domain, file_path, source)
+    compute_file_annotation(repo, starting_commit.id(), domain, file_path, source, false)
+}
+
+/// Get line by line annotations for a specific file path in the repo, by
+/// following the first parents only.
+///
+/// Lines introduced in side branches are attributed to the merge commits on the
+/// first-parent chain of the `starting_commit`.
+///
+/// See [`get_annotation_for_file()`] for the other arguments.
+pub fn get_first_parent_annotation_for_file(
+    repo: &dyn Repo,
+    starting_commit: &Commit,
+    domain: &Rc<ResolvedRevsetExpression>,
+    file_path: &RepoPath,
+) -> Result<FileAnnotation, RevsetEvaluationError> {
+    let source = Source::load(starting_commit, file_path)?;
+    compute_file_annotation(repo, starting_commit.id(), domain, file_path, source, true)
 }
 
 /// Get line by line annotations for a specific file path starting with the
@@ -179,7 +196,7 @@ pub fn get_annotation_with_file_content(
     starting_text: impl Into<Vec<u8>>,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
     let source = Source::new(BString::new(starting_text.into()));
-    compute_file_annotation(repo, starting_commit_id, domain, file_path, source)
+    compute_file_annotation(repo, starting_commit_id, domain, file_path, source, false)
 }
 
 fn compute_file_annotation(
@@ -188,10 +205,15 @@ fn compute_file_annotation(
     domain: &Rc<ResolvedRevsetExpression>,
     file_path: &RepoPath,
     mut source: Source,
+    first_parent: bool,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
     source.fill_line_map();
     let text = source.text.clone();
-    let line_map = process_commits(repo, starting_commit_id, source, domain, file_path)?;
+    let line_map = if first_parent {
+        process_first_parent_commits(repo, starting_commit_id, source, domain, file_path)?
+    } else {
+        process_commits(repo, starting_commit_id, source, domain, file_path)?
+    };
     Ok(FileAnnotation { line_map, text })
 }
 
@@ -237,6 +259,54 @@ fn process_commits(
     Ok(original_line_map)
 }
 
+/// Like [`process_commits()`], but walks the first-parent chain from the
+/// starting commit. Each commit is compared with the nearest first-parent
+/// ancestor that may have changed the file.
+fn process_first_parent_commits(
+    repo: &dyn Repo,
+    starting_commit_id: &CommitId,
+    starting_source: Source,
+    domain: &Rc<ResolvedRevsetExpression>,
+    file_name: &RepoPath,
+) -> Result<OriginalLineMap, RevsetEvaluationError> {
+    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
+    // Merge commits are included because the file may differ from the first
+    // parent even if the merge doesn't change it relative to the merged parents.
+    let candidates = RevsetExpression::filter(predicate).union(&RevsetExpression::filter(
+        RevsetFilterPredicate::ParentCount(2..u32::MAX),
+    ));
+    let ancestors = RevsetExpression::commit(starting_commit_id.clone()).first_ancestors();
+    let revset = RevsetExpression::commit(starting_commit_id.clone())
+        .union(&domain.intersection(&ancestors).intersection(&candidates))
+        .evaluate(repo)?;
+
+    let mut original_line_map = vec![None; starting_source.line_map.len()];
+    let mut commit_source_map = HashMap::from([(starting_commit_id.clone(), starting_source)]);
+
+    // The first-parent ancestors form a linear chain.
+    let mut commit_ids = revset.iter().peekable();
+    while let Some(commit_id) = commit_ids.next() {
+        let commit_id = commit_id?;
+        let edges = match commit_ids.peek() {
+            Some(Ok(parent_id)) => vec![GraphEdge::indirect(parent_id.clone())],
+            Some(Err(_)) | None => vec![],
+        };
+        process_commit(
+            repo,
+            file_name,
+            &mut original_line_map,
+            &mut commit_source_map,
+            &commit_id,
+            &edges,
+        )?;
+        if commit_source_map.is_empty() {
+            // No more lines to propagate to ancestors.
+            break;
+        }
+    }
+    Ok(original_line_map)
+}
+
 /// For a given commit, for each parent, we compare the version in the parent
 /// tree with the current version, updating the mappings for any lines in
 /// common. If the parent doesn't have the file, we skip it.
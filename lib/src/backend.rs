@@ -228,6 +228,14 @@ pub enum BackendError {
         hash: String,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    /// The object is missing locally and couldn't be fetched from the remote
+    /// it's expected to be available from.
+    #[error("Failed to fetch missing object {hash} of type {object_type}")]
+    FetchObject {
+        object_type: String,
+        hash: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     #[error("Error when reading object {hash} of type {object_type}")]
     ReadObject {
         object_type: String,
@@ -0,0 +1,259 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binary search for the first commit that introduced a change.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::CommitId;
+use crate::graph::GraphEdge;
+use crate::graph::GraphEdgeType;
+use crate::op_store::BisectState;
+use crate::repo::Repo;
+use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+
+/// Error while computing the next step of a bisection.
+#[derive(Debug, Error)]
+pub enum BisectionError {
+    /// No commit has been marked as bad yet.
+    #[error("No bad commit has been marked")]
+    NoBadCommit,
+    /// A commit marked as good is a descendant of a commit marked as bad.
+    #[error("Good commit {good} is a descendant of bad commit {bad}")]
+    GoodDescendantOfBad {
+        /// The commit marked as good.
+        good: CommitId,
+        /// The commit marked as bad.
+        bad: CommitId,
+    },
+    /// Error while evaluating the range of candidates.
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+}
+
+/// The outcome of a bisection step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BisectionStep {
+    /// The given commit should be tested next.
+    Evaluate {
+        /// The commit to test.
+        commit_id: CommitId,
+        /// The number of commits that may still be the first bad commit,
+        /// excluding skipped commits.
+        remaining: usize,
+    },
+    /// The first bad commit was found.
+    Found(CommitId),
+    /// The first bad commit is one of the given commits, but the remaining
+    /// candidates were skipped.
+    Indeterminate(Vec<CommitId>),
+    /// None of the commits in the range were bad, which means the marks are
+    /// inconsistent, for example if there are multiple unrelated bad commits.
+    NotFound,
+}
+
+/// Narrows down the commits that may have introduced a change, based on the
+/// commits marked in a [`BisectState`].
+///
+/// The candidates are the commits that are ancestors of every bad commit but
+/// not ancestors of any good commit. The root commit is always considered
+/// good.
+pub struct Bisector<'repo> {
+    repo: &'repo dyn Repo,
+    state: BisectState,
+}
+
+impl<'repo> Bisector<'repo> {
+    /// Creates a bisector for the commits marked in `state`.
+    pub fn new(repo: &'repo dyn Repo, state: BisectState) -> Self {
+        Bisector { repo, state }
+    }
+
+    /// Returns the marked commits.
+    pub fn state(&self) -> &BisectState {
+        &self.state
+    }
+
+    /// Marks the given commits as good, replacing any previous marks.
+    pub fn mark_good(&mut self, ids: impl IntoIterator<Item = CommitId>) {
+        for id in ids {
+            let BisectState {
+                good_ids,
+                bad_ids,
+                skipped_ids,
+            } = &mut self.state;
+            move_id(id, good_ids, [bad_ids, skipped_ids]);
+        }
+    }
+
+    /// Marks the given commits as bad, replacing any previous marks.
+    pub fn mark_bad(&mut self, ids: impl IntoIterator<Item = CommitId>) {
+        for id in ids {
+            let BisectState {
+                good_ids,
+                bad_ids,
+                skipped_ids,
+            } = &mut self.state;
+            move_id(id, bad_ids, [good_ids, skipped_ids]);
+        }
+    }
+
+    /// Marks the given commits as skipped, replacing any previous marks.
+    pub fn mark_skipped(&mut self, ids: impl IntoIterator<Item = CommitId>) {
+        for id in ids {
+            let BisectState {
+                good_ids,
+                bad_ids,
+                skipped_ids,
+            } = &mut self.state;
+            move_id(id, skipped_ids, [good_ids, bad_ids]);
+        }
+    }
+
+    /// Returns the range of commits which may be the first bad commit,
+    /// including the lowest bad commit and skipped commits.
+    pub fn range_expression(&self) -> Result<Rc<ResolvedRevsetExpression>, BisectionError> {
+        let bad_ancestors = self
+            .state
+            .bad_ids
+            .iter()
+            .map(|id| RevsetExpression::commit(id.clone()).ancestors())
+            .reduce(|acc, expr| acc.intersection(&expr))
+            .ok_or(BisectionError::NoBadCommit)?;
+        let good_ancestors = RevsetExpression::commits(self.state.good_ids.clone())
+            .union(&RevsetExpression::root())
+            .ancestors();
+        Ok(bad_ancestors.minus(&good_ancestors))
+    }
+
+    /// Computes which commit to test next.
+    ///
+    /// The next commit is chosen so that the range is split as evenly as
+    /// possible, whichever way the commit is marked.
+    pub fn next_step(&self) -> Result<BisectionStep, BisectionError> {
+        let index = self.repo.index();
+        for (good, bad) in self
+            .state
+            .good_ids
+            .iter()
+            .cartesian_product(&self.state.bad_ids)
+        {
+            if index.is_ancestor(bad, good) {
+                return Err(BisectionError::GoodDescendantOfBad {
+                    good: good.clone(),
+                    bad: bad.clone(),
+                });
+            }
+        }
+
+        // The range is closed under ancestry except for ancestors of good
+        // commits, so all edges within it are direct.
+        let nodes: Vec<_> = self
+            .range_expression()?
+            .evaluate(self.repo)?
+            .iter_graph()
+            .try_collect()?;
+        if nodes.is_empty() {
+            return Ok(BisectionStep::NotFound);
+        }
+        let bad_ids: HashSet<_> = self.state.bad_ids.iter().collect();
+        let skipped_ids: HashSet<_> = self.state.skipped_ids.iter().collect();
+        let candidates = nodes
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| !bad_ids.contains(id) && !skipped_ids.contains(id))
+            .collect_vec();
+        if candidates.is_empty() {
+            return match nodes.as_slice() {
+                [(id, _)] => Ok(BisectionStep::Found(id.clone())),
+                _ => Ok(BisectionStep::Indeterminate(
+                    nodes.into_iter().map(|(id, _)| id).collect(),
+                )),
+            };
+        }
+
+        let total = nodes.len();
+        let ancestor_counts = if nodes.iter().all(|(_, edges)| {
+            edges
+                .iter()
+                .filter(|edge| edge.edge_type == GraphEdgeType::Direct)
+                .count()
+                <= 1
+        }) {
+            count_linear_ancestors(&nodes)
+        } else {
+            // Counting ancestors of merges requires deduplication. This is
+            // quadratic, but ranges with merges tend to be small after the
+            // first few steps.
+            candidates
+                .iter()
+                .map(|&candidate| {
+                    let count = nodes
+                        .iter()
+                        .filter(|(id, _)| index.is_ancestor(id, candidate))
+                        .count();
+                    (candidate.clone(), count)
+                })
+                .collect()
+        };
+        // Prefer the commit which splits the range into halves. Ties are
+        // broken by the topological order for stable results.
+        let best = candidates
+            .iter()
+            .rev()
+            .max_by_key(|&&id| {
+                let ancestors = ancestor_counts[id];
+                ancestors.min(total - ancestors)
+            })
+            .unwrap();
+        Ok(BisectionStep::Evaluate {
+            commit_id: (*best).clone(),
+            remaining: candidates.len(),
+        })
+    }
+}
+
+/// Counts ancestors within the range (including the commit itself) for a
+/// range without merges.
+fn count_linear_ancestors(
+    nodes: &[(CommitId, Vec<GraphEdge<CommitId>>)],
+) -> HashMap<CommitId, usize> {
+    let mut counts: HashMap<CommitId, usize> = HashMap::new();
+    // Parents are emitted after children, so visit them in reverse.
+    for (id, edges) in nodes.iter().rev() {
+        let parent_count = edges
+            .iter()
+            .filter(|edge| edge.edge_type == GraphEdgeType::Direct)
+            .map(|edge| counts[&edge.target])
+            .sum::<usize>();
+        counts.insert(id.clone(), parent_count + 1);
+    }
+    counts
+}
+
+fn move_id(id: CommitId, dest: &mut Vec<CommitId>, others: [&mut Vec<CommitId>; 2]) {
+    for other in others {
+        other.retain(|other_id| *other_id != id);
+    }
+    if !dest.contains(&id) {
+        dest.push(id);
+    }
+}
@@ -90,6 +90,12 @@ pub(super) trait IndexSegment: Send + Sync {
     fn num_parents(&self, local_pos: LocalPosition) -> u32;
 
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec;
+
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec;
+
+    /// Returns the local positions of the entries which have the commit at
+    /// `pos` as predecessor. The positions are sorted in ascending order.
+    fn successor_positions(&self, pos: IndexPosition) -> SmallLocalPositionsVec;
 }
 
 pub(super) type DynIndexSegment = dyn IndexSegment;
@@ -191,6 +197,25 @@ impl CompositeIndex {
             .unwrap()
     }
 
+    /// Returns the positions of the indexed successors of the commit at `pos`
+    /// in descending order.
+    pub fn successor_positions(&self, pos: IndexPosition) -> SmallIndexPositionsVec {
+        // Successors are indexed after their predecessors, so segments
+        // preceding the predecessor can be skipped.
+        self.ancestor_index_segments()
+            .take_while(|segment| {
+                segment.num_parent_commits() + segment.num_local_commits() > pos.0
+            })
+            .flat_map(|segment| {
+                segment.successor_positions(pos).into_iter().rev().map(
+                    |LocalPosition(local_pos)| {
+                        IndexPosition(local_pos + segment.num_parent_commits())
+                    },
+                )
+            })
+            .collect()
+    }
+
     pub fn entry_by_id(&self, commit_id: &CommitId) -> Option<IndexEntry<'_>> {
         self.ancestor_index_segments().find_map(|segment| {
             let local_pos = segment.commit_id_to_pos(commit_id)?;
@@ -120,6 +120,18 @@ impl<'a> IndexEntry<'a> {
             .into_iter()
             .map(move |pos| composite.entry_by_pos(pos))
     }
+
+    /// Returns the positions of the indexed predecessors.
+    pub fn predecessor_positions(&self) -> SmallIndexPositionsVec {
+        self.source.predecessor_positions(self.local_pos)
+    }
+
+    pub fn predecessors(&self) -> impl ExactSizeIterator<Item = IndexEntry<'a>> {
+        let composite = CompositeIndex::new(self.source);
+        self.predecessor_positions()
+            .into_iter()
+            .map(move |pos| composite.entry_by_pos(pos))
+    }
 }
 
 /// Wrapper to sort `IndexPosition` by its generation number.
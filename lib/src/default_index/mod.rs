@@ -112,7 +112,7 @@ mod tests {
         let mut mutable_segment = MutableIndexSegment::full(3, 16);
         let id_0 = CommitId::from_hex("000000");
         let change_id0 = new_change_id();
-        mutable_segment.add_commit_data(id_0.clone(), change_id0.clone(), &[]);
+        mutable_segment.add_commit_data(id_0.clone(), change_id0.clone(), &[], &[]);
         let index_segment: Box<DynIndexSegment> = if on_disk {
             let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
             Box::new(Arc::try_unwrap(saved_index).unwrap())
@@ -178,9 +178,9 @@ mod tests {
         // TODO: Remove the exception after https://github.com/rust-lang/rust-clippy/issues/10577
         // is fixed or file a new bug.
         let change_id2 = change_id1.clone();
-        mutable_segment.add_commit_data(id_0.clone(), change_id0, &[]);
-        mutable_segment.add_commit_data(id_1.clone(), change_id1.clone(), &[id_0.clone()]);
-        mutable_segment.add_commit_data(id_2.clone(), change_id2.clone(), &[id_0.clone()]);
+        mutable_segment.add_commit_data(id_0.clone(), change_id0, &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), change_id1.clone(), &[id_0.clone()], &[]);
+        mutable_segment.add_commit_data(id_2.clone(), change_id2.clone(), &[id_0.clone()], &[]);
 
         // If testing incremental indexing, write the first three commits to one file
         // now and build the remainder as another segment on top.
@@ -195,9 +195,14 @@ mod tests {
         let change_id4 = new_change_id();
         let id_5 = CommitId::from_hex("555555");
         let change_id5 = change_id3.clone();
-        mutable_segment.add_commit_data(id_3.clone(), change_id3.clone(), &[id_2.clone()]);
-        mutable_segment.add_commit_data(id_4.clone(), change_id4, &[id_1.clone()]);
-        mutable_segment.add_commit_data(id_5.clone(), change_id5, &[id_4.clone(), id_2.clone()]);
+        mutable_segment.add_commit_data(id_3.clone(), change_id3.clone(), &[id_2.clone()], &[]);
+        mutable_segment.add_commit_data(id_4.clone(), change_id4, &[id_1.clone()], &[]);
+        mutable_segment.add_commit_data(
+            id_5.clone(),
+            change_id5,
+            &[id_4.clone(), id_2.clone()],
+            &[],
+        );
         let index_segment: Box<DynIndexSegment> = if on_disk {
             let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
             Box::new(Arc::try_unwrap(saved_index).unwrap())
@@ -299,16 +304,17 @@ mod tests {
         let id_4 = CommitId::from_hex("444444");
         let id_5 = CommitId::from_hex("555555");
         let id_6 = CommitId::from_hex("666666");
-        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
-        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[id_0.clone()]);
-        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[id_0.clone()]);
-        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[id_0.clone()]);
-        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[id_0]);
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()], &[]);
+        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[id_0.clone()], &[]);
+        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[id_0.clone()], &[]);
+        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[id_0.clone()], &[]);
+        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[id_0], &[]);
         mutable_segment.add_commit_data(
             id_6.clone(),
             new_change_id(),
             &[id_1, id_2, id_3, id_4, id_5],
+            &[],
         );
         let index_segment: Box<DynIndexSegment> = if on_disk {
             let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
@@ -342,6 +348,69 @@ mod tests {
         assert_eq!(entry_6.generation_number(), 2);
     }
 
+    #[test_case(false, false; "full in memory")]
+    #[test_case(false, true; "full on disk")]
+    #[test_case(true, false; "incremental in memory")]
+    #[test_case(true, true; "incremental on disk")]
+    fn index_commits_with_predecessors(incremental: bool, on_disk: bool) {
+        let temp_dir = testutils::new_temp_dir();
+        let mut new_change_id = change_id_generator();
+        let mut mutable_segment = MutableIndexSegment::full(3, 16);
+        // 0 is rewritten into 1, and 1 is rewritten into 2 and 3. The other
+        // predecessor of 3 isn't indexed.
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_missing = CommitId::from_hex("ffffff");
+        let change_id = new_change_id();
+        mutable_segment.add_commit_data(id_0.clone(), change_id.clone(), &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), change_id.clone(), &[], &[id_0.clone()]);
+
+        if incremental {
+            let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
+            mutable_segment = MutableIndexSegment::incremental(initial_file);
+        }
+
+        mutable_segment.add_commit_data(id_2.clone(), change_id.clone(), &[], &[id_1.clone()]);
+        mutable_segment.add_commit_data(id_3.clone(), change_id, &[], &[id_1.clone(), id_missing]);
+        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[], &[]);
+        let index_segment: Box<DynIndexSegment> = if on_disk {
+            let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+            Box::new(Arc::try_unwrap(saved_index).unwrap())
+        } else {
+            Box::new(mutable_segment)
+        };
+        let index = CompositeIndex::new(index_segment.as_ref());
+
+        let predecessor_positions =
+            |id: &CommitId| index.entry_by_id(id).unwrap().predecessor_positions();
+        assert_eq!(predecessor_positions(&id_0), SmallIndexPositionsVec::new());
+        assert_eq!(
+            predecessor_positions(&id_1),
+            smallvec_inline![IndexPosition(0)]
+        );
+        assert_eq!(
+            predecessor_positions(&id_2),
+            smallvec_inline![IndexPosition(1)]
+        );
+        assert_eq!(
+            predecessor_positions(&id_3),
+            smallvec_inline![IndexPosition(1)]
+        );
+        assert_eq!(predecessor_positions(&id_4), SmallIndexPositionsVec::new());
+        assert_eq!(
+            index
+                .entry_by_id(&id_3)
+                .unwrap()
+                .predecessors()
+                .map(|entry| entry.commit_id())
+                .collect_vec(),
+            vec![id_1]
+        );
+    }
+
     #[test]
     fn resolve_commit_id_prefix() {
         let temp_dir = testutils::new_temp_dir();
@@ -352,9 +421,9 @@ mod tests {
         let id_0 = CommitId::from_hex("000000");
         let id_1 = CommitId::from_hex("009999");
         let id_2 = CommitId::from_hex("055488");
-        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[], &[]);
 
         // Write the first three commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
@@ -363,9 +432,9 @@ mod tests {
         let id_3 = CommitId::from_hex("055444");
         let id_4 = CommitId::from_hex("055555");
         let id_5 = CommitId::from_hex("033333");
-        mutable_segment.add_commit_data(id_3, new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_4, new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_5, new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_3, new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_4, new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_5, new_change_id(), &[], &[]);
 
         let index = mutable_segment.as_composite();
 
@@ -424,9 +493,9 @@ mod tests {
         let id_0 = CommitId::from_hex("000001");
         let id_1 = CommitId::from_hex("009999");
         let id_2 = CommitId::from_hex("055488");
-        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[], &[]);
 
         // Write the first three commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
@@ -435,9 +504,9 @@ mod tests {
         let id_3 = CommitId::from_hex("055444");
         let id_4 = CommitId::from_hex("055555");
         let id_5 = CommitId::from_hex("033333");
-        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[], &[]);
 
         // Local lookup in readonly index, commit_id exists.
         assert_eq!(
@@ -552,9 +621,9 @@ mod tests {
         let id_0 = CommitId::from_hex("000001");
         let id_1 = CommitId::from_hex("009999");
         let id_2 = CommitId::from_hex("055488");
-        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[], &[]);
 
         // Write the first three commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
@@ -563,9 +632,9 @@ mod tests {
         let id_3 = CommitId::from_hex("055444");
         let id_4 = CommitId::from_hex("055555");
         let id_5 = CommitId::from_hex("033333");
-        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[]);
-        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_4.clone(), new_change_id(), &[], &[]);
+        mutable_segment.add_commit_data(id_5.clone(), new_change_id(), &[], &[]);
 
         let index = mutable_segment.as_composite();
 
@@ -616,22 +685,22 @@ mod tests {
 
         // Create some commits with different various common prefixes.
         let mut mutable_segment = MutableIndexSegment::full(16, 4);
-        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
 
         // Write these commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
         mutable_segment = MutableIndexSegment::incremental(initial_file.clone());
 
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[], &[]);
 
         // Local lookup in readonly index with the full hex digits
         assert_eq!(
@@ -780,22 +849,22 @@ mod tests {
 
         // Create some commits with different various common prefixes.
         let mut mutable_segment = MutableIndexSegment::full(16, 4);
-        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
 
         // Write these commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
         mutable_segment = MutableIndexSegment::incremental(initial_file.clone());
 
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[], &[]);
 
         // Local lookup in readonly index, change_id exists.
         assert_eq!(
@@ -926,22 +995,22 @@ mod tests {
 
         // Create some commits with different various common prefixes.
         let mut mutable_segment = MutableIndexSegment::full(16, 4);
-        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_0.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_2.clone(), &[], &[]);
 
         // Write these commits to one file and build the remainder on top.
         let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
         mutable_segment = MutableIndexSegment::incremental(initial_file.clone());
 
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[]);
-        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_3.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_4.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_1.clone(), &[], &[]);
+        mutable_segment.add_commit_data(new_commit_id(), id_5.clone(), &[], &[]);
 
         let index = mutable_segment.as_composite();
 
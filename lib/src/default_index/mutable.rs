@@ -67,6 +67,7 @@ struct MutableGraphEntry {
     change_id: ChangeId,
     generation_number: u32,
     parent_positions: SmallIndexPositionsVec,
+    predecessor_positions: SmallIndexPositionsVec,
 }
 
 pub(super) struct MutableIndexSegment {
@@ -77,6 +78,7 @@ pub(super) struct MutableIndexSegment {
     graph: Vec<MutableGraphEntry>,
     commit_lookup: BTreeMap<CommitId, LocalPosition>,
     change_lookup: BTreeMap<ChangeId, SmallLocalPositionsVec>,
+    successor_lookup: BTreeMap<IndexPosition, SmallLocalPositionsVec>,
 }
 
 impl MutableIndexSegment {
@@ -89,6 +91,7 @@ impl MutableIndexSegment {
             graph: vec![],
             commit_lookup: BTreeMap::new(),
             change_lookup: BTreeMap::new(),
+            successor_lookup: BTreeMap::new(),
         }
     }
 
@@ -104,6 +107,7 @@ impl MutableIndexSegment {
             graph: vec![],
             commit_lookup: BTreeMap::new(),
             change_lookup: BTreeMap::new(),
+            successor_lookup: BTreeMap::new(),
         }
     }
 
@@ -116,6 +120,7 @@ impl MutableIndexSegment {
             commit.id().clone(),
             commit.change_id().clone(),
             commit.parent_ids(),
+            commit.predecessor_ids(),
         );
     }
 
@@ -124,6 +129,7 @@ impl MutableIndexSegment {
         commit_id: CommitId,
         change_id: ChangeId,
         parent_ids: &[CommitId],
+        predecessor_ids: &[CommitId],
     ) {
         if self.as_composite().has_id(&commit_id) {
             return;
@@ -133,6 +139,7 @@ impl MutableIndexSegment {
             change_id,
             generation_number: 0,
             parent_positions: SmallVec::new(),
+            predecessor_positions: SmallVec::new(),
         };
         for parent_id in parent_ids {
             let parent_entry = self
@@ -145,6 +152,12 @@ impl MutableIndexSegment {
             );
             entry.parent_positions.push(parent_entry.position());
         }
+        // Predecessors are usually indexed before their successors, but they
+        // may be missing if the commit was fetched from elsewhere.
+        entry.predecessor_positions = predecessor_ids
+            .iter()
+            .filter_map(|id| self.as_composite().commit_id_to_pos(id))
+            .collect();
         let local_pos = LocalPosition(u32::try_from(self.graph.len()).unwrap());
         self.commit_lookup
             .insert(entry.commit_id.clone(), local_pos);
@@ -153,6 +166,13 @@ impl MutableIndexSegment {
             // positions are inherently sorted
             .and_modify(|positions| positions.push(local_pos))
             .or_insert(smallvec![local_pos]);
+        for &predecessor_pos in &entry.predecessor_positions {
+            self.successor_lookup
+                .entry(predecessor_pos)
+                // positions are inherently sorted
+                .and_modify(|positions| positions.push(local_pos))
+                .or_insert(smallvec![local_pos]);
+        }
         self.graph.push(entry);
     }
 
@@ -161,7 +181,16 @@ impl MutableIndexSegment {
         for pos in other_segment.num_parent_commits()..other.num_commits() {
             let entry = other.entry_by_pos(IndexPosition(pos));
             let parent_ids = entry.parents().map(|entry| entry.commit_id()).collect_vec();
-            self.add_commit_data(entry.commit_id(), entry.change_id(), &parent_ids);
+            let predecessor_ids = entry
+                .predecessors()
+                .map(|entry| entry.commit_id())
+                .collect_vec();
+            self.add_commit_data(
+                entry.commit_id(),
+                entry.change_id(),
+                &parent_ids,
+                &predecessor_ids,
+            );
         }
     }
 
@@ -227,6 +256,12 @@ impl MutableIndexSegment {
         buf.extend(0_u32.to_le_bytes());
         let change_overflow_offset = buf.len();
         buf.extend(0_u32.to_le_bytes());
+        let num_predecessors = self
+            .graph
+            .iter()
+            .map(|entry| entry.predecessor_positions.len())
+            .sum::<usize>();
+        buf.extend(u32::try_from(num_predecessors).unwrap().to_le_bytes());
 
         // Positions of change ids in the sorted table
         let change_id_pos_map: HashMap<&ChangeId, u32> = self
@@ -311,6 +346,24 @@ impl MutableIndexSegment {
         for LocalPosition(pos) in change_overflow {
             buf.extend(pos.to_le_bytes());
         }
+
+        let mut predecessor_end_pos = 0_u32;
+        for entry in &self.graph {
+            predecessor_end_pos += u32::try_from(entry.predecessor_positions.len()).unwrap();
+            buf.extend(predecessor_end_pos.to_le_bytes());
+        }
+        for entry in &self.graph {
+            for IndexPosition(pos) in &entry.predecessor_positions {
+                buf.extend(pos.to_le_bytes());
+            }
+        }
+
+        for (IndexPosition(predecessor_pos), positions) in &self.successor_lookup {
+            for LocalPosition(pos) in positions {
+                buf.extend(predecessor_pos.to_le_bytes());
+                buf.extend(pos.to_le_bytes());
+            }
+        }
     }
 
     /// If the MutableIndex has more than half the commits of its parent
@@ -452,6 +505,16 @@ impl IndexSegment for MutableIndexSegment {
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
         self.graph[local_pos.0 as usize].parent_positions.clone()
     }
+
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
+        self.graph[local_pos.0 as usize]
+            .predecessor_positions
+            .clone()
+    }
+
+    fn successor_positions(&self, pos: IndexPosition) -> SmallLocalPositionsVec {
+        self.successor_lookup.get(&pos).cloned().unwrap_or_default()
+    }
 }
 
 /// In-memory mutable records for the on-disk commit index backend.
@@ -475,7 +538,8 @@ impl DefaultMutableIndex {
         change_id: ChangeId,
         parent_ids: &[CommitId],
     ) {
-        self.0.add_commit_data(commit_id, change_id, parent_ids);
+        self.0
+            .add_commit_data(commit_id, change_id, parent_ids, &[]);
     }
 
     pub(super) fn squash_and_save_in(self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
@@ -103,7 +103,7 @@ impl ReadonlyIndexLoadError {
 }
 
 /// Current format version of the index segment file.
-pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 6;
+pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 7;
 
 /// If set, the value is stored in the overflow table.
 pub(crate) const OVERFLOW_FLAG: u32 = 0x8000_0000;
@@ -185,6 +185,7 @@ impl CommitGraphEntry<'_> {
 /// u32: number of local change ids
 /// u32: number of overflow parent entries
 /// u32: number of overflow change id positions
+/// u32: number of predecessor entries
 /// for each entry, in some topological order with parents first:
 ///   u32: generation number
 ///   if number of parents <= 2:
@@ -210,8 +211,17 @@ impl CommitGraphEntry<'_> {
 ///   u32: global index position
 /// for each overflow change id entry:
 ///   u32: local position in the graph entries table
+/// for each entry, in the same order as the graph entries:
+///   u32: end position of its predecessors in the predecessors table
+/// for each predecessor entry:
+///   u32: global index position
+/// for each predecessor entry, sorted by predecessor position:
+///   u32: global index position of the predecessor
+///   u32: local position of the successor in the graph entries table
 /// ```
 ///
+/// Predecessors which weren't indexed before the entry are omitted.
+///
 /// Note that u32 fields are 4-byte aligned so long as the parent file name
 /// (which is hexadecimal hash) and commit/change ids aren't of exotic length.
 // TODO: replace the table by a trie so we don't have to repeat the full commit
@@ -233,6 +243,10 @@ pub(super) struct ReadonlyIndexSegment {
     change_pos_table_base: usize,
     parent_overflow_base: usize,
     change_overflow_base: usize,
+    predecessor_end_base: usize,
+    predecessor_base: usize,
+    successor_lookup_base: usize,
+    num_predecessor_entries: u32,
     data: Vec<u8>,
 }
 
@@ -328,6 +342,7 @@ impl ReadonlyIndexSegment {
         let num_local_change_ids = read_u32(file)?;
         let num_parent_overflow_entries = read_u32(file)?;
         let num_change_overflow_entries = read_u32(file)?;
+        let num_predecessor_entries = read_u32(file)?;
         let mut data = vec![];
         file.read_to_end(&mut data).map_err(from_io_err)?;
 
@@ -338,6 +353,9 @@ impl ReadonlyIndexSegment {
         let change_pos_table_size = (num_local_change_ids as usize) * 4;
         let parent_overflow_size = (num_parent_overflow_entries as usize) * 4;
         let change_overflow_size = (num_change_overflow_entries as usize) * 4;
+        let predecessor_end_size = (num_local_commits as usize) * 4;
+        let predecessor_size = (num_predecessor_entries as usize) * 4;
+        let successor_lookup_size = (num_predecessor_entries as usize) * 8;
 
         let graph_base = 0;
         let commit_lookup_base = graph_base + graph_size;
@@ -345,7 +363,10 @@ impl ReadonlyIndexSegment {
         let change_pos_table_base = change_id_table_base + change_id_table_size;
         let parent_overflow_base = change_pos_table_base + change_pos_table_size;
         let change_overflow_base = parent_overflow_base + parent_overflow_size;
-        let expected_size = change_overflow_base + change_overflow_size;
+        let predecessor_end_base = change_overflow_base + change_overflow_size;
+        let predecessor_base = predecessor_end_base + predecessor_end_size;
+        let successor_lookup_base = predecessor_base + predecessor_size;
+        let expected_size = successor_lookup_base + successor_lookup_size;
 
         if data.len() != expected_size {
             return Err(ReadonlyIndexLoadError::invalid_data(
@@ -368,6 +389,10 @@ impl ReadonlyIndexSegment {
             change_pos_table_base,
             parent_overflow_base,
             change_overflow_base,
+            predecessor_end_base,
+            predecessor_base,
+            successor_lookup_base,
+            num_predecessor_entries,
             data,
         }))
     }
@@ -432,13 +457,31 @@ impl ReadonlyIndexSegment {
 
     /// Scans graph entry positions stored in the overflow change ids table.
     fn overflow_changes_from(&self, overflow_pos: u32) -> impl Iterator<Item = LocalPosition> + '_ {
-        let table = &self.data[self.change_overflow_base..];
+        let table = &self.data[self.change_overflow_base..self.predecessor_end_base];
         let offset = (overflow_pos as usize) * 4;
         table[offset..]
             .chunks_exact(4)
             .map(|chunk| LocalPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
     }
 
+    /// Returns the end position of the predecessors of the entry in the
+    /// predecessors table.
+    fn predecessor_end_pos(&self, local_pos: LocalPosition) -> u32 {
+        let table = &self.data[self.predecessor_end_base..self.predecessor_base];
+        let offset = (local_pos.0 as usize) * 4;
+        u32::from_le_bytes(table[offset..][..4].try_into().unwrap())
+    }
+
+    /// Returns the `(predecessor, successor)` positions stored in the successor
+    /// lookup table.
+    fn successor_lookup_entry(&self, lookup_pos: u32) -> (IndexPosition, LocalPosition) {
+        let table = &self.data[self.successor_lookup_base..];
+        let offset = (lookup_pos as usize) * 8;
+        let predecessor_pos = u32::from_le_bytes(table[offset..][..4].try_into().unwrap());
+        let pos = u32::from_le_bytes(table[offset + 4..][..4].try_into().unwrap());
+        (IndexPosition(predecessor_pos), LocalPosition(pos))
+    }
+
     /// Binary searches commit id by `prefix`. Returns the lookup position.
     fn commit_id_byte_prefix_to_lookup_pos(&self, prefix: &[u8]) -> PositionLookupResult {
         binary_search_pos_by(self.num_local_commits, |pos| {
@@ -583,6 +626,35 @@ impl IndexSegment for ReadonlyIndexSegment {
             self.overflow_parents(overflow_pos, num_parents)
         }
     }
+
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
+        let start_pos = match local_pos.0.checked_sub(1) {
+            Some(prev_pos) => self.predecessor_end_pos(LocalPosition(prev_pos)),
+            None => 0,
+        };
+        let end_pos = self.predecessor_end_pos(local_pos);
+        let table = &self.data[self.predecessor_base..self.successor_lookup_base];
+        table[(start_pos as usize) * 4..(end_pos as usize) * 4]
+            .chunks_exact(4)
+            .map(|chunk| IndexPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
+            .collect()
+    }
+
+    fn successor_positions(&self, pos: IndexPosition) -> SmallLocalPositionsVec {
+        // Equal entries are ordered after the predecessor so the search finds
+        // the first of them.
+        let lookup_pos = binary_search_pos_by(self.num_predecessor_entries, |lookup_pos| {
+            let (predecessor_pos, _) = self.successor_lookup_entry(lookup_pos);
+            predecessor_pos.cmp(&pos).then(Ordering::Greater)
+        })
+        .result
+        .unwrap_err();
+        (lookup_pos..self.num_predecessor_entries)
+            .map(|lookup_pos| self.successor_lookup_entry(lookup_pos))
+            .take_while(|&(predecessor_pos, _)| predecessor_pos == pos)
+            .map(|(_, local_pos)| local_pos)
+            .collect()
+    }
 }
 
 /// Commit index backend which stores data on local disk.
@@ -453,6 +453,23 @@ impl<'a> RevWalkBuilder<'a> {
         }
     }
 
+    /// Walks ancestors by following the first parents only, within the
+    /// `generation_range`.
+    ///
+    /// A generation number counts from the heads. The unwanted roots are also
+    /// walked by following the first parents.
+    pub fn first_ancestors_filtered_by_generation(
+        self,
+        generation_range: Range<u32>,
+    ) -> RevWalkFirstAncestors<'a> {
+        let RevWalkBorrowedIndexIter { index, walk } =
+            self.ancestors_filtered_by_generation(generation_range);
+        RevWalkBorrowedIndexIter {
+            index,
+            walk: RevWalkFirstParentImpl { walk },
+        }
+    }
+
     /// Walks ancestors until all of the reachable roots in `root_positions` get
     /// visited.
     ///
@@ -642,6 +659,35 @@ impl<I: RevWalkIndex + ?Sized> RevWalk<I> for RevWalkGenerationRangeImpl<I::Posi
     }
 }
 
+pub(super) type RevWalkFirstAncestors<'a> =
+    RevWalkBorrowedIndexIter<'a, CompositeIndex, RevWalkFirstParentImpl>;
+
+#[derive(Clone)]
+#[must_use]
+pub(super) struct RevWalkFirstParentImpl {
+    walk: RevWalkGenerationRangeImpl<IndexPosition>,
+}
+
+impl RevWalk<CompositeIndex> for RevWalkFirstParentImpl {
+    type Item = IndexPosition;
+
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
+        self.walk.next(&RevWalkFirstParentIndex(index))
+    }
+}
+
+/// Index adapter that only exposes the first parent of each entry.
+struct RevWalkFirstParentIndex<'a>(&'a CompositeIndex);
+
+impl RevWalkIndex for RevWalkFirstParentIndex<'_> {
+    type Position = IndexPosition;
+    type AdjacentPositions = Option<IndexPosition>;
+
+    fn adjacent_positions(&self, pos: Self::Position) -> Self::AdjacentPositions {
+        self.0.entry_by_pos(pos).parent_positions().first().copied()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 struct RevWalkItemGenerationRange {
     start: u32,
@@ -1099,6 +1145,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_walk_first_ancestors_filtered_by_generation() {
+        let mut new_change_id = change_id_generator();
+        let mut index = DefaultMutableIndex::full(3, 16);
+        // 6
+        // |
+        // 5
+        // |\
+        // 4 3
+        // | |
+        // 2 |
+        // |/
+        // 1
+        // |
+        // 0
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        let id_6 = CommitId::from_hex("666666");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_2.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_3.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_4.clone(), new_change_id(), &[id_2.clone()]);
+        index.add_commit_data(id_5.clone(), new_change_id(), &[id_4.clone(), id_3.clone()]);
+        index.add_commit_data(id_6.clone(), new_change_id(), &[id_5.clone()]);
+
+        let walk_commit_ids = |wanted: &[CommitId], unwanted: &[CommitId], range: Range<u32>| {
+            let index = index.as_composite();
+            RevWalkBuilder::new(index)
+                .wanted_heads(to_positions_vec(index, wanted))
+                .unwanted_roots(to_positions_vec(index, unwanted))
+                .first_ancestors_filtered_by_generation(range)
+                .map(|pos| index.entry_by_pos(pos).commit_id())
+                .collect_vec()
+        };
+
+        // The second parent of the merge isn't walked
+        assert_eq!(
+            walk_commit_ids(&[&id_6].map(Clone::clone), &[], 0..u32::MAX),
+            [&id_6, &id_5, &id_4, &id_2, &id_1, &id_0].map(Clone::clone)
+        );
+        assert_eq!(
+            walk_commit_ids(&[&id_6].map(Clone::clone), &[], 2..4),
+            [&id_4, &id_2].map(Clone::clone)
+        );
+
+        // The side branch is walked if it is specified as a head
+        assert_eq!(
+            walk_commit_ids(&[&id_5, &id_3].map(Clone::clone), &[], 0..2),
+            [&id_5, &id_4, &id_3, &id_1].map(Clone::clone)
+        );
+
+        // Ancestors of unwanted commits are not walked
+        assert_eq!(
+            walk_commit_ids(
+                &[&id_6].map(Clone::clone),
+                &[&id_2].map(Clone::clone),
+                0..u32::MAX
+            ),
+            [&id_6, &id_5, &id_4].map(Clone::clone)
+        );
+    }
+
     #[test]
     #[allow(clippy::redundant_clone)] // allow id_n.clone()
     fn test_walk_ancestors_filtered_by_generation_range_merging() {
@@ -61,10 +61,13 @@ use crate::revset::Revset;
 use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
+use crate::revset::RevsetSortKey;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
+use crate::signing::Verification;
 use crate::store::Store;
 use crate::str_util::StringPattern;
+use crate::trailer::parse_description_trailers;
 use crate::union_find;
 
 type BoxedPredicateFn<'a> =
@@ -119,12 +122,23 @@ impl<T: InternalRevset + ?Sized> InternalRevset for Box<T> {
 
 pub struct RevsetImpl<I> {
     inner: Box<dyn InternalRevset>,
+    /// Positions in iteration order if the expression specifies an ordering
+    /// other than the index order.
+    sorted_positions: Option<Arc<[IndexPosition]>>,
     index: I,
 }
 
 impl<I: AsCompositeIndex + Clone> RevsetImpl<I> {
-    fn new(inner: Box<dyn InternalRevset>, index: I) -> Self {
-        Self { inner, index }
+    fn new(
+        inner: Box<dyn InternalRevset>,
+        sorted_positions: Option<Arc<[IndexPosition]>>,
+        index: I,
+    ) -> Self {
+        Self {
+            inner,
+            sorted_positions,
+            index,
+        }
     }
 
     fn positions(&self) -> impl Iterator<Item = Result<IndexPosition, RevsetEvaluationError>> + '_ {
@@ -156,6 +170,13 @@ impl<I: AsCompositeIndex + Clone> Revset for RevsetImpl<I> {
         Self: 'a,
     {
         let index = self.index.clone();
+        if let Some(positions) = &self.sorted_positions {
+            let positions = positions.clone();
+            return Box::new(
+                (0..positions.len())
+                    .map(move |i| Ok(index.as_composite().entry_by_pos(positions[i]).commit_id())),
+            );
+        }
         let mut walk = self
             .inner
             .positions()
@@ -170,6 +191,13 @@ impl<I: AsCompositeIndex + Clone> Revset for RevsetImpl<I> {
         Self: 'a,
     {
         let index = self.index.clone();
+        if let Some(positions) = &self.sorted_positions {
+            let positions = positions.clone();
+            return Box::new((0..positions.len()).map(move |i| {
+                let entry = index.as_composite().entry_by_pos(positions[i]);
+                Ok((entry.commit_id(), entry.change_id()))
+            }));
+        }
         let mut walk = self.inner.positions().map(|index, pos| {
             let entry = index.entry_by_pos(pos?);
             Ok((entry.commit_id(), entry.change_id()))
@@ -771,7 +799,40 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
         index: index.as_composite(),
     };
     let internal_revset = context.evaluate(expression)?;
-    Ok(RevsetImpl::new(internal_revset, index))
+    let sort_keys = collect_sort_keys(expression);
+    let sorted_positions = if sort_keys.is_empty() {
+        None
+    } else {
+        Some(
+            context
+                .sort_positions(&*internal_revset, &sort_keys)?
+                .into(),
+        )
+    };
+    Ok(RevsetImpl::new(internal_revset, sorted_positions, index))
+}
+
+/// Collects sort keys applied at the top level of the expression, most
+/// significant key first.
+///
+/// Ordering is discarded when the set is combined with other operators, except
+/// for `latest()` which preserves the ordering of its candidates.
+fn collect_sort_keys(mut expression: &ResolvedExpression) -> Vec<(RevsetSortKey, bool)> {
+    let mut keys = Vec::new();
+    loop {
+        match expression {
+            ResolvedExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => {
+                keys.push((*key, *reverse));
+                expression = candidates;
+            }
+            ResolvedExpression::Latest { candidates, .. } => expression = candidates,
+            _ => return keys,
+        }
+    }
 }
 
 struct EvaluationContext<'index> {
@@ -815,6 +876,16 @@ impl EvaluationContext<'_> {
                     Ok(Box::new(RevWalkRevset { walk }))
                 }
             }
+            ResolvedExpression::FirstAncestors { heads, generation } => {
+                let head_set = self.evaluate(heads)?;
+                let head_positions = head_set.positions().attach(index);
+                let generation = to_u32_generation_range(generation)?;
+                let walk = RevWalkBuilder::new(index)
+                    .wanted_heads(head_positions.try_collect()?)
+                    .first_ancestors_filtered_by_generation(generation)
+                    .detach();
+                Ok(Box::new(RevWalkRevset { walk }))
+            }
             ResolvedExpression::Range {
                 roots,
                 heads,
@@ -972,7 +1043,61 @@ impl EvaluationContext<'_> {
             }
             ResolvedExpression::Latest { candidates, count } => {
                 let candidate_set = self.evaluate(candidates)?;
-                Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
+                let sort_keys = collect_sort_keys(candidates);
+                if sort_keys.is_empty() {
+                    Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
+                } else {
+                    Ok(Box::new(self.take_greatest_revset(
+                        &*candidate_set,
+                        *count,
+                        &sort_keys,
+                    )?))
+                }
+            }
+            // Ordering is applied by the caller if it's at the top level.
+            ResolvedExpression::Sort { candidates, .. } => self.evaluate(candidates),
+            ResolvedExpression::Predecessors(commits) => {
+                let commit_set = self.evaluate(commits)?;
+                let mut pending: Vec<_> = commit_set.positions().attach(index).try_collect()?;
+                let mut positions = BTreeSet::new();
+                while let Some(pos) = pending.pop() {
+                    for predecessor_pos in index.entry_by_pos(pos).predecessor_positions() {
+                        if positions.insert(predecessor_pos) {
+                            pending.push(predecessor_pos);
+                        }
+                    }
+                }
+                let positions = positions.into_iter().rev().collect();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Successors(commits) => {
+                let commit_set = self.evaluate(commits)?;
+                let mut pending: Vec<_> = commit_set.positions().attach(index).try_collect()?;
+                let mut positions = BTreeSet::new();
+                while let Some(pos) = pending.pop() {
+                    for successor_pos in index.successor_positions(pos) {
+                        if positions.insert(successor_pos) {
+                            pending.push(successor_pos);
+                        }
+                    }
+                }
+                let positions = positions.into_iter().rev().collect();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Divergent(candidates) => {
+                let candidate_set = self.evaluate(candidates)?;
+                let entries: Vec<_> = candidate_set
+                    .positions()
+                    .attach(index)
+                    .map_ok(|pos| (pos, index.entry_by_pos(pos).change_id()))
+                    .try_collect()?;
+                let counts = entries.iter().map(|(_, change_id)| change_id).counts();
+                let positions = entries
+                    .iter()
+                    .filter(|(_, change_id)| counts[change_id] > 1)
+                    .map(|&(pos, _)| pos)
+                    .collect();
+                Ok(Box::new(EagerRevset { positions }))
             }
             ResolvedExpression::Coalesce(expression1, expression2) => {
                 let set1 = self.evaluate(expression1)?;
@@ -1106,6 +1231,72 @@ impl EvaluationContext<'_> {
         positions.sort_unstable_by_key(|&pos| Reverse(pos));
         Ok(EagerRevset { positions })
     }
+
+    /// Takes the `count` commits with the greatest values of the sort `keys`,
+    /// regardless of the sort direction. Ties are resolved in favor of the
+    /// later commits in the index.
+    fn take_greatest_revset(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        count: usize,
+        keys: &[(RevsetSortKey, bool)],
+    ) -> Result<EagerRevset, RevsetEvaluationError> {
+        if count == 0 {
+            return Ok(EagerRevset::empty());
+        }
+        let descending_keys = keys.iter().map(|&(key, _)| (key, true)).collect_vec();
+        let mut positions = self.sort_positions(candidate_set, &descending_keys)?;
+        positions.truncate(count);
+        positions.sort_unstable_by_key(|&pos| Reverse(pos));
+        Ok(EagerRevset { positions })
+    }
+
+    /// Returns positions of the `candidate_set` sorted by the given keys. Ties
+    /// are kept in the index order.
+    fn sort_positions(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        keys: &[(RevsetSortKey, bool)],
+    ) -> Result<Vec<IndexPosition>, RevsetEvaluationError> {
+        let mut items: Vec<(IndexPosition, Commit)> = candidate_set
+            .positions()
+            .attach(self.index)
+            .map(|pos| -> Result<_, RevsetEvaluationError> {
+                let entry = self.index.entry_by_pos(pos?);
+                let commit = self.store.get_commit(&entry.commit_id())?;
+                Ok((entry.position(), commit))
+            })
+            .try_collect()?;
+        let compare_by_key = |key: RevsetSortKey, a: &Commit, b: &Commit| match key {
+            RevsetSortKey::AuthorName => a.author().name.cmp(&b.author().name),
+            RevsetSortKey::AuthorEmail => a.author().email.cmp(&b.author().email),
+            RevsetSortKey::AuthorDate => a
+                .author()
+                .timestamp
+                .timestamp
+                .cmp(&b.author().timestamp.timestamp),
+            RevsetSortKey::CommitterDate => a
+                .committer()
+                .timestamp
+                .timestamp
+                .cmp(&b.committer().timestamp.timestamp),
+            RevsetSortKey::Description => a.description().cmp(b.description()),
+        };
+        items.sort_by(|(_, a), (_, b)| {
+            keys.iter()
+                .map(|&(key, reverse)| {
+                    let ordering = compare_by_key(key, a, b);
+                    if reverse {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Ok(items.into_iter().map(|(pos, _)| pos).collect())
+    }
 }
 
 struct PurePredicateFn<F>(F);
@@ -1247,6 +1438,46 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
+        RevsetFilterPredicate::Signed => box_pure_predicate_fn(move |index, pos| {
+            let entry = index.entry_by_pos(pos);
+            let commit = store.get_commit(&entry.commit_id())?;
+            Ok(commit.is_signed())
+        }),
+        RevsetFilterPredicate::Trailer { key, value } => {
+            let key = key.clone();
+            let value = value.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(parse_description_trailers(commit.description())
+                    .iter()
+                    .any(|trailer| {
+                        trailer.key.eq_ignore_ascii_case(&key) && value.matches(&trailer.value)
+                    }))
+            })
+        }
+        RevsetFilterPredicate::SignedBy(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(verify_signature(&commit).is_some_and(|verification| {
+                    [&verification.key, &verification.display]
+                        .into_iter()
+                        .flatten()
+                        .any(|text| pattern.matches(text))
+                }))
+            })
+        }
+        RevsetFilterPredicate::SignatureStatus(status) => {
+            let status = *status;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(verify_signature(&commit)
+                    .is_some_and(|verification| verification.status == status))
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1258,6 +1489,17 @@ fn build_predicate_fn(
     }
 }
 
+/// Verifies the signature of the commit. Verification results are cached by
+/// the signer, so this is cheap for commits which were already checked.
+///
+/// A signature which the backend fails to verify is reported as `Unknown`
+/// instead of aborting the whole evaluation.
+fn verify_signature(commit: &Commit) -> Option<Verification> {
+    commit
+        .verification()
+        .unwrap_or_else(|_| Some(Verification::unknown()))
+}
+
 fn has_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
@@ -14,14 +14,25 @@
 
 #![allow(missing_docs)]
 
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::backend::CommitId;
+#[cfg(feature = "git")]
+use crate::git;
+#[cfg(feature = "git")]
+use crate::git::RemoteCallbacks;
+#[cfg(feature = "git")]
+use crate::object_id::ObjectId as _;
+use crate::submodule_store::SubmoduleCommit;
 use crate::submodule_store::SubmoduleStore;
+use crate::submodule_store::SubmoduleStoreError;
+use crate::submodule_store::SubmoduleStoreResult;
 
+/// Keeps a bare Git repository per submodule under `<store path>/repos`.
 #[derive(Debug)]
 pub struct DefaultSubmoduleStore {
-    #[allow(dead_code)]
     path: PathBuf,
 }
 
@@ -42,10 +53,202 @@ impl DefaultSubmoduleStore {
     pub fn name() -> &'static str {
         "default"
     }
+
+    fn repo_path(&self, name: &str) -> SubmoduleStoreResult<PathBuf> {
+        // Submodule names come from .gitmodules, which may have been written by
+        // anyone, so don't let them escape the store directory.
+        let relative_path = Path::new(name);
+        let is_valid = !name.is_empty()
+            && relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_valid {
+            return Err(SubmoduleStoreError::InvalidName(name.to_owned()));
+        }
+        Ok(self.path.join("repos").join(relative_path))
+    }
+}
+
+#[cfg(feature = "git")]
+impl DefaultSubmoduleStore {
+    /// Lists the paths of all fetched submodule repositories. Since submodule
+    /// names may contain slashes, this descends into directories until it finds
+    /// a repository.
+    fn repo_paths(&self) -> SubmoduleStoreResult<Vec<PathBuf>> {
+        let mut repo_paths = vec![];
+        let mut dirs_to_visit = vec![self.path.join("repos")];
+        while let Some(dir) = dirs_to_visit.pop() {
+            let entries = match dir.read_dir() {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(to_other_err(err)),
+            };
+            for entry in entries {
+                let path = entry.map_err(to_other_err)?.path();
+                if path.join("HEAD").is_file() {
+                    repo_paths.push(path);
+                } else if path.is_dir() {
+                    dirs_to_visit.push(path);
+                }
+            }
+        }
+        repo_paths.sort();
+        Ok(repo_paths)
+    }
+
+    fn open_repo(&self, name: &str) -> SubmoduleStoreResult<Option<git2::Repository>> {
+        let repo_path = self.repo_path(name)?;
+        if !repo_path.exists() {
+            return Ok(None);
+        }
+        let repo = git2::Repository::open_bare(&repo_path).map_err(to_other_err)?;
+        Ok(Some(repo))
+    }
 }
 
 impl SubmoduleStore for DefaultSubmoduleStore {
     fn name(&self) -> &str {
         Self::name()
     }
+
+    #[cfg(feature = "git")]
+    fn fetch(
+        &self,
+        name: &str,
+        url: &str,
+        callbacks: RemoteCallbacks<'_>,
+    ) -> SubmoduleStoreResult<()> {
+        let repo = match self.open_repo(name)? {
+            Some(repo) => repo,
+            None => {
+                let repo_path = self.repo_path(name)?;
+                std::fs::create_dir_all(&repo_path).map_err(to_other_err)?;
+                git2::Repository::init_bare(&repo_path).map_err(to_other_err)?
+            }
+        };
+        let mut remote = repo.remote_anonymous(url).map_err(to_other_err)?;
+        remote
+            .fetch(
+                &["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"],
+                Some(&mut git::git2_fetch_options(callbacks, None)),
+                None,
+            )
+            .map_err(to_other_err)
+    }
+
+    #[cfg(feature = "git")]
+    fn get_commit(&self, id: &CommitId) -> SubmoduleStoreResult<Option<SubmoduleCommit>> {
+        let Ok(oid) = git2::Oid::from_bytes(id.as_bytes()) else {
+            return Ok(None);
+        };
+        for repo_path in self.repo_paths()? {
+            let repo = git2::Repository::open_bare(&repo_path).map_err(to_other_err)?;
+            let commit = match repo.find_commit(oid) {
+                Ok(commit) => commit,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => continue,
+                Err(err) => return Err(to_other_err(err)),
+            };
+            return Ok(Some(SubmoduleCommit {
+                id: id.clone(),
+                description: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
+            }));
+        }
+        Ok(None)
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn get_commit(&self, _id: &CommitId) -> SubmoduleStoreResult<Option<SubmoduleCommit>> {
+        Ok(None)
+    }
+
+    #[cfg(feature = "git")]
+    fn check_out(
+        &self,
+        name: &str,
+        old_id: Option<&CommitId>,
+        id: &CommitId,
+        destination: &Path,
+    ) -> SubmoduleStoreResult<()> {
+        let repo = self
+            .open_repo(name)?
+            .ok_or_else(|| SubmoduleStoreError::NotFetched(name.to_owned()))?;
+        let find_commit = |id: &CommitId| {
+            git2::Oid::from_bytes(id.as_bytes())
+                .and_then(|oid| repo.find_commit(oid))
+                .map_err(|_| SubmoduleStoreError::CommitNotFound {
+                    name: name.to_owned(),
+                    id: id.clone(),
+                })
+        };
+        let new_commit = find_commit(id)?;
+        let new_tree = new_commit.tree().map_err(to_other_err)?;
+        let is_empty_dir = destination
+            .read_dir()
+            .map_or(true, |mut entries| entries.next().is_none());
+        let old_commit = old_id.and_then(|old_id| find_commit(old_id).ok());
+
+        // Files are overwritten by the checkout below, so refuse to touch the
+        // submodule if its files don't match the old commit.
+        if !is_empty_dir {
+            let Some(old_commit) = &old_commit else {
+                return Err(SubmoduleStoreError::UncommittedChanges(name.to_owned()));
+            };
+            let old_tree = old_commit.tree().map_err(to_other_err)?;
+            repo.set_workdir(destination, false).map_err(to_other_err)?;
+            let diff = repo
+                .diff_tree_to_workdir(Some(&old_tree), None)
+                .map_err(to_other_err)?;
+            if diff.deltas().len() > 0 {
+                return Err(SubmoduleStoreError::UncommittedChanges(name.to_owned()));
+            }
+        }
+        std::fs::create_dir_all(destination).map_err(to_other_err)?;
+
+        // Checking out a tree only adds and updates files, so delete the files
+        // the previous commit had on our own.
+        if let Some(old_commit) = old_commit.filter(|_| !is_empty_dir) {
+            let old_tree = old_commit.tree().map_err(to_other_err)?;
+            let diff = repo
+                .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+                .map_err(to_other_err)?;
+            for delta in diff.deltas() {
+                if delta.status() != git2::Delta::Deleted {
+                    continue;
+                }
+                if let Some(path) = delta.old_file().path() {
+                    match std::fs::remove_file(destination.join(path)) {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(err) => return Err(to_other_err(err)),
+                    }
+                }
+            }
+        }
+
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        checkout_builder
+            .target_dir(destination)
+            .update_index(false)
+            .recreate_missing(true)
+            .force();
+        repo.checkout_tree(new_tree.as_object(), Some(&mut checkout_builder))
+            .map_err(to_other_err)
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn check_out(
+        &self,
+        name: &str,
+        _old_id: Option<&CommitId>,
+        _id: &CommitId,
+        _destination: &Path,
+    ) -> SubmoduleStoreResult<()> {
+        self.repo_path(name)?;
+        Err(SubmoduleStoreError::Unsupported)
+    }
+}
+
+#[cfg(feature = "git")]
+fn to_other_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> SubmoduleStoreError {
+    SubmoduleStoreError::Other(err.into())
 }
@@ -0,0 +1,230 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Line ending conversion between the working copy and the store.
+//!
+//! Text files are stored with LF line endings. Which files are text and which
+//! line endings they get in the working copy is determined by the `text` and
+//! `eol` attributes in `.gitattributes`, falling back to
+//! [`EolConversionMode`] for files without attributes.
+
+use bstr::ByteSlice as _;
+
+use crate::gitattributes::EolAttribute;
+use crate::gitattributes::FileAttributes;
+use crate::gitattributes::TextAttribute;
+
+/// How to convert line endings of files without `text` or `eol` attributes.
+/// Similar to Git's `core.autocrlf`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EolConversionMode {
+    /// Don't convert line endings, like `core.autocrlf = false`.
+    #[default]
+    None,
+    /// Convert CRLF to LF when snapshotting text files, like
+    /// `core.autocrlf = input`.
+    Input,
+    /// Convert CRLF to LF when snapshotting text files, and LF to CRLF when
+    /// checking them out, like `core.autocrlf = true`.
+    InputOutput,
+}
+
+/// Whether a file is text, decided by its attributes and possibly its content.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TextKind {
+    Text,
+    Binary,
+    Detect,
+}
+
+fn text_kind(attributes: &FileAttributes, mode: EolConversionMode) -> TextKind {
+    // The content of LFS files is stored outside of the repo as is.
+    if attributes.lfs {
+        return TextKind::Binary;
+    }
+    match attributes.text {
+        TextAttribute::Set => TextKind::Text,
+        TextAttribute::Unset => TextKind::Binary,
+        TextAttribute::Auto => TextKind::Detect,
+        // Setting eol implies that the file is text.
+        TextAttribute::Unspecified if attributes.eol.is_some() => TextKind::Text,
+        TextAttribute::Unspecified => match mode {
+            EolConversionMode::None => TextKind::Binary,
+            EolConversionMode::Input | EolConversionMode::InputOutput => TextKind::Detect,
+        },
+    }
+}
+
+/// Uses the same heuristic as Git: a file is binary if the first 8000 bytes
+/// contain a null byte.
+fn is_binary(contents: &[u8]) -> bool {
+    contents[..contents.len().min(8000)].contains(&0)
+}
+
+/// Decides how line endings of a file are converted.
+#[derive(Clone, Copy, Debug)]
+pub struct EolConverter {
+    text_kind: TextKind,
+    checkout_eol: EolAttribute,
+}
+
+impl EolConverter {
+    /// Creates a converter for a file with the given attributes.
+    pub fn new(attributes: &FileAttributes, mode: EolConversionMode) -> Self {
+        let checkout_eol = attributes.eol.unwrap_or(match mode {
+            EolConversionMode::InputOutput => EolAttribute::Crlf,
+            EolConversionMode::Input => EolAttribute::Lf,
+            // Git's core.eol defaults to the native line ending.
+            EolConversionMode::None if cfg!(windows) => EolAttribute::Crlf,
+            EolConversionMode::None => EolAttribute::Lf,
+        });
+        EolConverter {
+            text_kind: text_kind(attributes, mode),
+            checkout_eol,
+        }
+    }
+
+    /// Returns whether the content of the file may change when it's
+    /// snapshotted. If not, it can be written to the store as is.
+    pub fn may_convert_on_snapshot(&self) -> bool {
+        self.text_kind != TextKind::Binary
+    }
+
+    /// Returns whether the content of the file may change when it's checked
+    /// out. If not, it can be written to disk as is.
+    pub fn may_convert_on_checkout(&self) -> bool {
+        self.text_kind != TextKind::Binary && self.checkout_eol == EolAttribute::Crlf
+    }
+
+    fn is_text(&self, contents: &[u8]) -> bool {
+        match self.text_kind {
+            TextKind::Text => true,
+            TextKind::Binary => false,
+            TextKind::Detect => !is_binary(contents),
+        }
+    }
+
+    /// Converts the content read from the working copy to what should be
+    /// stored. Returns `None` if the content doesn't change.
+    pub fn convert_for_snapshot(&self, contents: &[u8]) -> Option<Vec<u8>> {
+        if !self.may_convert_on_snapshot() || !contents.contains_str("\r\n") {
+            return None;
+        }
+        self.is_text(contents)
+            .then(|| contents.replace("\r\n", "\n"))
+    }
+
+    /// Converts the stored content to what should be written to the working
+    /// copy. Returns `None` if the content doesn't change.
+    pub fn convert_for_checkout(&self, contents: &[u8]) -> Option<Vec<u8>> {
+        if !self.may_convert_on_checkout() || !self.is_text(contents) {
+            return None;
+        }
+        let mut converted = Vec::with_capacity(contents.len() + contents.len() / 16);
+        let mut previous = None;
+        for &b in contents {
+            // Leave existing CRLF alone.
+            if b == b'\n' && previous != Some(b'\r') {
+                converted.push(b'\r');
+            }
+            converted.push(b);
+            previous = Some(b);
+        }
+        (converted.len() != contents.len()).then_some(converted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converter(
+        text: TextAttribute,
+        eol: Option<EolAttribute>,
+        mode: EolConversionMode,
+    ) -> EolConverter {
+        let attributes = FileAttributes {
+            text,
+            eol,
+            lfs: false,
+        };
+        EolConverter::new(&attributes, mode)
+    }
+
+    #[test]
+    fn test_convert_for_snapshot() {
+        let text = converter(TextAttribute::Set, None, EolConversionMode::None);
+        assert_eq!(
+            text.convert_for_snapshot(b"a\r\nb\nc\r"),
+            Some(b"a\nb\nc\r".to_vec())
+        );
+        assert_eq!(text.convert_for_snapshot(b"a\nb\n"), None);
+        assert_eq!(text.convert_for_snapshot(b"\0\r\n"), Some(b"\0\n".to_vec()));
+
+        let auto = converter(TextAttribute::Auto, None, EolConversionMode::None);
+        assert_eq!(auto.convert_for_snapshot(b"a\r\n"), Some(b"a\n".to_vec()));
+        assert_eq!(auto.convert_for_snapshot(b"\0\r\n"), None);
+
+        let binary = converter(TextAttribute::Unset, None, EolConversionMode::InputOutput);
+        assert!(!binary.may_convert_on_snapshot());
+        assert_eq!(binary.convert_for_snapshot(b"a\r\n"), None);
+    }
+
+    #[test]
+    fn test_convert_for_checkout() {
+        let crlf = converter(
+            TextAttribute::Unspecified,
+            Some(EolAttribute::Crlf),
+            EolConversionMode::None,
+        );
+        assert!(crlf.may_convert_on_checkout());
+        assert_eq!(
+            crlf.convert_for_checkout(b"a\nb\r\nc"),
+            Some(b"a\r\nb\r\nc".to_vec())
+        );
+        assert_eq!(crlf.convert_for_checkout(b"a\r\n"), None);
+
+        let lf = converter(
+            TextAttribute::Set,
+            Some(EolAttribute::Lf),
+            EolConversionMode::InputOutput,
+        );
+        assert!(!lf.may_convert_on_checkout());
+        assert_eq!(lf.convert_for_checkout(b"a\n"), None);
+    }
+
+    #[test]
+    fn test_conversion_mode() {
+        let none = converter(TextAttribute::Unspecified, None, EolConversionMode::None);
+        assert!(!none.may_convert_on_snapshot());
+        assert!(!none.may_convert_on_checkout());
+
+        let input = converter(TextAttribute::Unspecified, None, EolConversionMode::Input);
+        assert_eq!(input.convert_for_snapshot(b"a\r\n"), Some(b"a\n".to_vec()));
+        assert_eq!(input.convert_for_snapshot(b"\0\r\n"), None);
+        assert!(!input.may_convert_on_checkout());
+
+        let input_output = converter(
+            TextAttribute::Unspecified,
+            None,
+            EolConversionMode::InputOutput,
+        );
+        assert_eq!(
+            input_output.convert_for_checkout(b"a\n"),
+            Some(b"a\r\n".to_vec())
+        );
+        assert_eq!(input_output.convert_for_checkout(b"\0\n"), None);
+    }
+}
@@ -33,8 +33,10 @@ use thiserror::Error;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::Signature;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
+use crate::git_backend::signature_to_git;
 use crate::git_backend::GitBackend;
 use crate::git_subprocess::GitSubprocessContext;
 use crate::git_subprocess::GitSubprocessError;
@@ -58,6 +60,12 @@ use crate::view::View;
 
 /// Reserved remote name for the backing Git repo.
 pub const REMOTE_NAME_FOR_LOCAL_GIT_REPO: &str = "git";
+/// Ref namespace where tags fetched from remotes are stored, followed by the
+/// remote name.
+const REMOTE_TAGS_REF_PREFIX: &str = "refs/jj/remote-tags/";
+/// Ref namespace to keep annotated tag objects created by jj until the tags are
+/// exported to `refs/tags`.
+const ANNOTATED_TAGS_REF_PREFIX: &str = "refs/jj/annotated-tags/";
 /// Ref name used as a placeholder to unset HEAD without a commit.
 const UNBORN_ROOT_REF_NAME: &str = "refs/jj/root";
 /// Dummy file to be added to the index to indicate that the user is editing a
@@ -69,6 +77,7 @@ pub enum RefName {
     LocalBranch(String),
     RemoteBranch { branch: String, remote: String },
     Tag(String),
+    RemoteTag { tag: String, remote: String },
 }
 
 impl fmt::Display for RefName {
@@ -77,6 +86,7 @@ impl fmt::Display for RefName {
             RefName::LocalBranch(name) => write!(f, "{name}"),
             RefName::RemoteBranch { branch, remote } => write!(f, "{branch}@{remote}"),
             RefName::Tag(name) => write!(f, "{name}"),
+            RefName::RemoteTag { tag, remote } => write!(f, "{tag}@{remote}"),
         }
     }
 }
@@ -137,11 +147,14 @@ impl RefSpec {
 /// remote it's being pushed to
 pub(crate) struct RefToPush<'a> {
     pub(crate) refspec: &'a RefSpec,
-    pub(crate) expected_location: Option<&'a CommitId>,
+    pub(crate) expected_location: Option<&'a gix::ObjectId>,
 }
 
 impl<'a> RefToPush<'a> {
-    fn new(refspec: &'a RefSpec, expected_locations: &'a HashMap<&str, Option<&CommitId>>) -> Self {
+    fn new(
+        refspec: &'a RefSpec,
+        expected_locations: &'a HashMap<&str, Option<&gix::ObjectId>>,
+    ) -> Self {
         let expected_location = *expected_locations.get(refspec.destination.as_str()).expect(
             "The refspecs and the expected locations were both constructed from the same source \
              of truth. This means the lookup should always work.",
@@ -178,6 +191,13 @@ pub fn parse_git_ref(ref_name: &str) -> Option<RefName> {
                 remote: remote.to_string(),
                 branch: branch.to_string(),
             })
+    } else if let Some(remote_and_tag) = ref_name.strip_prefix(REMOTE_TAGS_REF_PREFIX) {
+        remote_and_tag
+            .split_once('/')
+            .map(|(remote, tag)| RefName::RemoteTag {
+                tag: tag.to_string(),
+                remote: remote.to_string(),
+            })
     } else {
         ref_name
             .strip_prefix("refs/tags/")
@@ -185,6 +205,10 @@ pub fn parse_git_ref(ref_name: &str) -> Option<RefName> {
     }
 }
 
+fn remote_tags_ref_prefix(remote_name: &str) -> String {
+    format!("{REMOTE_TAGS_REF_PREFIX}{remote_name}/")
+}
+
 fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
     match parsed_ref {
         RefName::LocalBranch(branch) => {
@@ -193,13 +217,16 @@ fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
         RefName::RemoteBranch { branch, remote } => (!branch.is_empty() && branch != "HEAD")
             .then(|| format!("refs/remotes/{remote}/{branch}")),
         RefName::Tag(tag) => Some(format!("refs/tags/{tag}")),
+        RefName::RemoteTag { tag, remote } => {
+            Some(format!("{}{tag}", remote_tags_ref_prefix(remote)))
+        }
     }
 }
 
 fn to_remote_branch<'a>(parsed_ref: &'a RefName, remote_name: &str) -> Option<&'a str> {
     match parsed_ref {
         RefName::RemoteBranch { branch, remote } => (remote == remote_name).then_some(branch),
-        RefName::LocalBranch(..) | RefName::Tag(..) => None,
+        RefName::LocalBranch(..) | RefName::Tag(..) | RefName::RemoteTag { .. } => None,
     }
 }
 
@@ -321,6 +348,10 @@ pub struct GitImportStats {
     /// Remote `(ref_name, (old_remote_ref, new_target))`s to be merged in to
     /// the local refs.
     pub changed_remote_refs: BTreeMap<RefName, (RemoteRef, RefTarget)>,
+    /// Existing commits whose parents changed because the boundary of a
+    /// shallow repository moved. The index has to be rebuilt to reflect the
+    /// new parents.
+    pub reparented_commits: Vec<CommitId>,
 }
 
 #[derive(Debug)]
@@ -436,7 +467,14 @@ pub fn import_some_refs(
                 if new_remote_ref.is_tracking() {
                     mut_repo.merge_tag(name, base_target, &new_remote_ref.target);
                 }
-                // TODO: If we add Git-tracking tag, it will be updated here.
+                // Update Git-tracking tag like the other remote tags.
+                mut_repo.set_remote_tag(name, REMOTE_NAME_FOR_LOCAL_GIT_REPO, new_remote_ref);
+            }
+            RefName::RemoteTag { tag, remote } => {
+                if new_remote_ref.is_tracking() {
+                    mut_repo.merge_tag(tag, base_target, &new_remote_ref.target);
+                }
+                mut_repo.set_remote_tag(tag, remote, new_remote_ref);
             }
         }
     }
@@ -450,6 +488,7 @@ pub fn import_some_refs(
     let stats = GitImportStats {
         abandoned_commits,
         changed_remote_refs,
+        reparented_commits: vec![],
     };
     Ok(stats)
 }
@@ -508,8 +547,8 @@ fn diff_refs_to_import(
             git_ref_filter(&ref_name).then_some((full_name.as_ref(), target))
         })
         .collect();
-    // TODO: migrate tags to the remote view, and don't destructure &RemoteRef
-    let mut known_remote_refs: HashMap<RefName, (&RefTarget, RemoteRefState)> = itertools::chain(
+    // TODO: don't destructure &RemoteRef
+    let mut known_remote_refs: HashMap<RefName, (&RefTarget, RemoteRefState)> = itertools::chain!(
         view.all_remote_bookmarks()
             .map(|((branch, remote), remote_ref)| {
                 // TODO: want to abstract local ref as "git" tracking remote, but
@@ -525,11 +564,17 @@ fn diff_refs_to_import(
                 let RemoteRef { target, state } = remote_ref;
                 (ref_name, (target, *state))
             }),
-        // TODO: compare to tags stored in the "git" remote view. Since tags should never
-        // be moved locally in jj, we can consider local tags as merge base.
-        view.tags().iter().map(|(name, target)| {
-            let ref_name = RefName::Tag(name.to_owned());
-            (ref_name, (target, RemoteRefState::Tracking))
+        view.all_remote_tags().map(|((tag, remote), remote_ref)| {
+            let ref_name = if remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                RefName::Tag(tag.to_owned())
+            } else {
+                RefName::RemoteTag {
+                    tag: tag.to_owned(),
+                    remote: remote.to_owned(),
+                }
+            };
+            let RemoteRef { target, state } = remote_ref;
+            (ref_name, (target, *state))
         }),
     )
     .filter(|(ref_name, _)| git_ref_filter(ref_name))
@@ -544,6 +589,7 @@ fn diff_refs_to_import(
             git_references.local_branches()?,
             git_references.remote_branches()?,
             git_references.tags()?,
+            git_references.prefixed(REMOTE_TAGS_REF_PREFIX)?,
         ))
     };
     for git_ref in chain_git_refs_iters().map_err(GitImportError::from_git)? {
@@ -603,8 +649,10 @@ fn diff_refs_to_import(
 
 fn default_remote_ref_state_for(ref_name: &RefName, git_settings: &GitSettings) -> RemoteRefState {
     match ref_name {
-        // LocalBranch means Git-tracking branch
-        RefName::LocalBranch(_) | RefName::Tag(_) => RemoteRefState::Tracking,
+        // LocalBranch means Git-tracking branch. Tags are shared by all remotes.
+        RefName::LocalBranch(_) | RefName::Tag(_) | RefName::RemoteTag { .. } => {
+            RemoteRefState::Tracking
+        }
         RefName::RemoteBranch { .. } => {
             if git_settings.auto_local_bookmark {
                 RemoteRefState::Tracking
@@ -750,17 +798,16 @@ struct RefsToExport {
     failed_branches: HashMap<RefName, FailedRefExportReason>,
 }
 
-/// Export changes to branches made in the Jujutsu repo compared to our last
-/// seen view of the Git repo in `mut_repo.view().git_refs()`. Returns a list of
-/// refs that failed to export.
+/// Export changes to branches and tags made in the Jujutsu repo compared to our
+/// last seen view of the Git repo in `mut_repo.view().git_refs()`. Returns a
+/// list of refs that failed to export.
 ///
 /// We ignore changed branches that are conflicted (were also changed in the Git
 /// repo compared to our last remembered view of the Git repo). These will be
 /// marked conflicted by the next `jj git import`.
 ///
-/// We do not export tags and other refs at the moment, since these aren't
-/// supposed to be modified by JJ. For them, the Git state is considered
-/// authoritative.
+/// We do not export other refs at the moment, since these aren't supposed to
+/// be modified by JJ. For them, the Git state is considered authoritative.
 pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<Vec<FailedRefExport>, GitExportError> {
     export_some_refs(mut_repo, |_| true)
 }
@@ -847,6 +894,11 @@ pub fn export_some_refs(
         REMOTE_NAME_FOR_LOCAL_GIT_REPO,
         |ref_name| git_ref_filter(ref_name) && !failed_branches.contains_key(ref_name),
     );
+    copy_exportable_local_tags_to_remote_view(
+        mut_repo,
+        REMOTE_NAME_FOR_LOCAL_GIT_REPO,
+        |ref_name| git_ref_filter(ref_name) && !failed_branches.contains_key(ref_name),
+    );
 
     let failed_branches = failed_branches
         .into_iter()
@@ -883,17 +935,88 @@ fn copy_exportable_local_branches_to_remote_view(
     }
 }
 
-/// Calculates diff of branches to be exported.
+/// Creates an annotated tag object pointing to the commit, and points the local
+/// tag to the commit.
+///
+/// The Git ref `refs/tags/<name>` isn't updated until the tag is exported, at
+/// which point the ref is pointed to the tag object instead of the commit.
+pub fn create_annotated_tag(
+    mut_repo: &mut MutableRepo,
+    name: &str,
+    commit_id: &CommitId,
+    message: &str,
+    tagger: &Signature,
+) -> Result<(), GitExportError> {
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let message = if message.is_empty() || message.ends_with('\n') {
+        message.to_owned()
+    } else {
+        format!("{message}\n")
+    };
+    let tag = gix::objs::Tag {
+        target: to_git_object_id(commit_id),
+        target_kind: gix::object::Kind::Commit,
+        name: name.into(),
+        tagger: Some(signature_to_git(tagger).to_owned()),
+        message: message.into(),
+        pgp_signature: None,
+    };
+    let tag_id = git_repo
+        .write_object(&tag)
+        .map_err(GitExportError::from_git)?;
+    // Keep the tag object reachable until it gets exported. This isn't a tag
+    // ref, so Git won't see the tag if the transaction is discarded.
+    git_repo
+        .reference(
+            format!("{ANNOTATED_TAGS_REF_PREFIX}{name}"),
+            tag_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "create annotated tag",
+        )
+        .map_err(GitExportError::from_git)?;
+    mut_repo.set_tag_target(name, RefTarget::normal(commit_id.clone()));
+    Ok(())
+}
+
+fn copy_exportable_local_tags_to_remote_view(
+    mut_repo: &mut MutableRepo,
+    remote_name: &str,
+    git_ref_filter: impl Fn(&RefName) -> bool,
+) {
+    let new_local_tags = mut_repo
+        .view()
+        .local_remote_tags(remote_name)
+        .filter_map(|(tag, targets)| {
+            let old_target = &targets.remote_ref.target;
+            let new_target = targets.local_target;
+            (!new_target.has_conflict() && old_target != new_target).then_some((tag, new_target))
+        })
+        .filter(|&(tag, _)| git_ref_filter(&RefName::Tag(tag.to_owned())))
+        .map(|(tag, new_target)| (tag.to_owned(), new_target.clone()))
+        .collect_vec();
+    for (tag, new_target) in new_local_tags {
+        let new_remote_ref = RemoteRef {
+            target: new_target,
+            state: RemoteRefState::Tracking,
+        };
+        mut_repo.set_remote_tag(&tag, remote_name, new_remote_ref);
+    }
+}
+
+/// Calculates diff of branches and tags to be exported.
 fn diff_refs_to_export(
     view: &View,
     root_commit_id: &CommitId,
     git_ref_filter: impl Fn(&RefName) -> bool,
 ) -> RefsToExport {
     // Local targets will be copied to the "git" remote if successfully exported. So
-    // the local branches are considered to be the new "git" remote branches.
-    let mut all_branch_targets: HashMap<RefName, (&RefTarget, &RefTarget)> = itertools::chain(
+    // the local branches and tags are considered to be the new "git" remote refs.
+    let mut all_branch_targets: HashMap<RefName, (&RefTarget, &RefTarget)> = itertools::chain!(
         view.local_bookmarks()
             .map(|(branch, target)| (RefName::LocalBranch(branch.to_owned()), target)),
+        view.tags()
+            .iter()
+            .map(|(tag, target)| (RefName::Tag(tag.to_owned()), target)),
         view.all_remote_bookmarks()
             .filter(|&((_, remote), _)| remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO)
             .map(|((branch, remote), remote_ref)| {
@@ -920,7 +1043,7 @@ fn diff_refs_to_export(
             // 2. `jj op undo`/`restore` in colocated repo
             matches!(
                 ref_name,
-                RefName::LocalBranch(..) | RefName::RemoteBranch { .. }
+                RefName::LocalBranch(..) | RefName::RemoteBranch { .. } | RefName::Tag(..)
             )
         })
         .filter(|(ref_name, _)| git_ref_filter(ref_name));
@@ -980,7 +1103,7 @@ fn delete_git_ref(
     old_oid: &gix::oid,
 ) -> Result<(), FailedRefExportReason> {
     if let Ok(git_ref) = git_repo.find_reference(git_ref_name) {
-        if git_ref.inner.target.try_id() == Some(old_oid) {
+        if peeled_git_ref_id(&git_ref).as_deref() == Some(old_oid) {
             // The branch has not been updated by git, so go ahead and delete it
             git_ref
                 .delete()
@@ -1001,12 +1124,28 @@ fn update_git_ref(
     old_oid: Option<gix::ObjectId>,
     new_oid: gix::ObjectId,
 ) -> Result<(), FailedRefExportReason> {
+    // Tags created by jj or fetched from remotes may be annotated. Point the ref
+    // to the tag object instead of the commit in that case.
+    let tag_name = git_ref_name.strip_prefix("refs/tags/");
+    let annotated_tag_ref = tag_name.and_then(|tag| {
+        git_repo
+            .find_reference(&format!("{ANNOTATED_TAGS_REF_PREFIX}{tag}"))
+            .ok()
+    });
+    let new_target_oid = annotated_tag_ref
+        .as_ref()
+        .and_then(|git_ref| {
+            let object_id = git_ref.inner.target.try_id()?.to_owned();
+            (peeled_git_ref_id(git_ref) == Some(new_oid)).then_some(object_id)
+        })
+        .or_else(|| tag_name.and_then(|tag| find_remote_tag_object_id(git_repo, tag, &new_oid)))
+        .unwrap_or(new_oid);
     match old_oid {
         None => {
             if let Ok(git_repo_ref) = git_repo.find_reference(git_ref_name) {
                 // The branch was added in jj and in git. We're good if and only if git
                 // pointed it to our desired target.
-                if git_repo_ref.inner.target.try_id() != Some(&new_oid) {
+                if peeled_git_ref_id(&git_repo_ref) != Some(new_oid) {
                     return Err(FailedRefExportReason::AddedInJjAddedInGit);
                 }
             } else {
@@ -1014,7 +1153,7 @@ fn update_git_ref(
                 git_repo
                     .reference(
                         git_ref_name,
-                        new_oid,
+                        new_target_oid,
                         gix::refs::transaction::PreviousValue::MustNotExist,
                         "export from jj",
                     )
@@ -1023,16 +1162,23 @@ fn update_git_ref(
         }
         Some(old_oid) => {
             // The branch was modified in jj. We can use gix API for updating under a lock.
+            // Annotated tags are expected to point to the same tag object.
+            let expected_target = match git_repo.find_reference(git_ref_name) {
+                Ok(git_repo_ref) if peeled_git_ref_id(&git_repo_ref) == Some(old_oid) => {
+                    git_repo_ref.inner.target
+                }
+                _ => old_oid.into(),
+            };
             if let Err(err) = git_repo.reference(
                 git_ref_name,
-                new_oid,
-                gix::refs::transaction::PreviousValue::MustExistAndMatch(old_oid.into()),
+                new_target_oid,
+                gix::refs::transaction::PreviousValue::MustExistAndMatch(expected_target),
                 "export from jj",
             ) {
                 // The reference was probably updated in git
                 if let Ok(git_repo_ref) = git_repo.find_reference(git_ref_name) {
                     // We still consider this a success if it was updated to our desired target
-                    if git_repo_ref.inner.target.try_id() != Some(&new_oid) {
+                    if peeled_git_ref_id(&git_repo_ref) != Some(new_oid) {
                         return Err(FailedRefExportReason::FailedToSet(err.into()));
                     }
                 } else {
@@ -1045,9 +1191,48 @@ fn update_git_ref(
             }
         }
     }
+    if let Some(git_ref) = annotated_tag_ref {
+        git_ref
+            .delete()
+            .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?;
+    }
     Ok(())
 }
 
+/// Finds an annotated tag object fetched from any remote as the tag `name`, and
+/// pointing to the commit.
+fn find_remote_tag_object_id(
+    git_repo: &gix::Repository,
+    name: &str,
+    commit_oid: &gix::oid,
+) -> Option<gix::ObjectId> {
+    let git_references = git_repo.references().ok()?;
+    let remote_tag_refs = git_references.prefixed(REMOTE_TAGS_REF_PREFIX).ok()?;
+    remote_tag_refs
+        .filter_map(Result::ok)
+        .filter(|git_ref| {
+            let full_name = git_ref.name().as_bstr();
+            matches!(
+                str::from_utf8(full_name).ok().and_then(parse_git_ref),
+                Some(RefName::RemoteTag { tag, .. }) if tag == name
+            )
+        })
+        .find_map(|git_ref| {
+            let object_id = git_ref.inner.target.try_id()?.to_owned();
+            (object_id != commit_oid && peeled_git_ref_id(&git_ref).as_deref() == Some(commit_oid))
+                .then_some(object_id)
+        })
+}
+
+/// Returns the id of the object the ref points to, peeling annotated tags.
+fn peeled_git_ref_id(git_ref: &gix::Reference) -> Option<gix::ObjectId> {
+    git_ref
+        .clone()
+        .into_fully_peeled_id()
+        .ok()
+        .map(|id| id.detach())
+}
+
 /// Ensures Git HEAD is detached and pointing to the `new_oid`. If `new_oid`
 /// is `None` (meaning absent), dummy placeholder ref will be set.
 fn update_git_head(
@@ -1373,19 +1558,35 @@ pub fn remove_remote(
         }
     })?;
     if remote_name != REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        delete_remote_tag_git_refs(git_repo, remote_name)
+            .map_err(GitRemoteManagementError::InternalGitError)?;
         remove_remote_refs(mut_repo, remote_name);
     }
     Ok(())
 }
 
+/// Deletes the remote tags of the remote from the Git repo. Unlike remote
+/// branches, Git doesn't know they belong to the remote.
+fn delete_remote_tag_git_refs(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+) -> Result<(), git2::Error> {
+    let tags_glob = format!("{}*", remote_tags_ref_prefix(remote_name));
+    for git_ref in git_repo.references_glob(&tags_glob)? {
+        git_ref?.delete()?;
+    }
+    Ok(())
+}
+
 fn remove_remote_refs(mut_repo: &mut MutableRepo, remote_name: &str) {
     mut_repo.remove_remote(remote_name);
     let prefix = format!("refs/remotes/{remote_name}/");
+    let tags_prefix = remote_tags_ref_prefix(remote_name);
     let git_refs_to_delete = mut_repo
         .view()
         .git_refs()
         .keys()
-        .filter(|&r| r.starts_with(&prefix))
+        .filter(|&r| r.starts_with(&prefix) || r.starts_with(&tags_prefix))
         .cloned()
         .collect_vec();
     for git_ref in git_refs_to_delete {
@@ -1414,6 +1615,8 @@ pub fn rename_remote(
             }
         })?;
     if old_remote_name != REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        rename_remote_tag_git_refs(git_repo, old_remote_name, new_remote_name)
+            .map_err(GitRemoteManagementError::InternalGitError)?;
         rename_remote_refs(mut_repo, old_remote_name, new_remote_name);
     }
     Ok(())
@@ -1445,20 +1648,84 @@ pub fn set_remote_url(
     Ok(())
 }
 
+/// Makes the remote the promisor remote of a partial clone.
+///
+/// Subsequent fetches from the remote omit the objects excluded by the
+/// `filter` (e.g. `blob:none`), and the missing objects are fetched from the
+/// remote on demand.
+pub fn set_remote_partial_clone_filter(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    filter: &str,
+) -> Result<(), GitRemoteManagementError> {
+    if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(GitRemoteManagementError::RemoteReservedForLocalGitRepo);
+    }
+    git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitRemoteManagementError::NoSuchRemote(remote_name.to_owned())
+        } else {
+            GitRemoteManagementError::InternalGitError(err)
+        }
+    })?;
+
+    let mut config = git_repo
+        .config()
+        .map_err(GitRemoteManagementError::InternalGitError)?;
+    config
+        .set_bool(&format!("remote.{remote_name}.promisor"), true)
+        .map_err(GitRemoteManagementError::InternalGitError)?;
+    config
+        .set_str(&format!("remote.{remote_name}.partialclonefilter"), filter)
+        .map_err(GitRemoteManagementError::InternalGitError)?;
+    Ok(())
+}
+
+/// Renames the remote tags of the remote in the Git repo. Unlike remote
+/// branches, Git doesn't know they belong to the remote.
+fn rename_remote_tag_git_refs(
+    git_repo: &git2::Repository,
+    old_remote_name: &str,
+    new_remote_name: &str,
+) -> Result<(), git2::Error> {
+    let old_prefix = remote_tags_ref_prefix(old_remote_name);
+    let new_prefix = remote_tags_ref_prefix(new_remote_name);
+    let git_refs: Vec<_> = git_repo
+        .references_glob(&format!("{old_prefix}*"))?
+        .try_collect()?;
+    for mut git_ref in git_refs {
+        let Some(tag) = git_ref
+            .name()
+            .and_then(|name| name.strip_prefix(&old_prefix))
+        else {
+            continue;
+        };
+        let new_name = format!("{new_prefix}{tag}");
+        git_ref.rename(&new_name, false, "rename remote")?;
+    }
+    Ok(())
+}
+
 fn rename_remote_refs(mut_repo: &mut MutableRepo, old_remote_name: &str, new_remote_name: &str) {
     mut_repo.rename_remote(old_remote_name, new_remote_name);
-    let prefix = format!("refs/remotes/{old_remote_name}/");
+    let prefixes = [
+        (
+            format!("refs/remotes/{old_remote_name}/"),
+            format!("refs/remotes/{new_remote_name}/"),
+        ),
+        (
+            remote_tags_ref_prefix(old_remote_name),
+            remote_tags_ref_prefix(new_remote_name),
+        ),
+    ];
     let git_refs = mut_repo
         .view()
         .git_refs()
         .iter()
         .filter_map(|(r, target)| {
-            r.strip_prefix(&prefix).map(|p| {
-                (
-                    r.clone(),
-                    format!("refs/remotes/{new_remote_name}/{p}"),
-                    target.clone(),
-                )
+            prefixes.iter().find_map(|(old_prefix, new_prefix)| {
+                r.strip_prefix(old_prefix)
+                    .map(|p| (r.clone(), format!("{new_prefix}{p}"), target.clone()))
             })
         })
         .collect_vec();
@@ -1480,6 +1747,8 @@ pub enum GitFetchError {
     )]
     InvalidBranchPattern(StringPattern),
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
+    #[error("Deepening shallow history is not supported without git.subprocess")]
+    DeepenUnsupported,
     #[error("Unexpected git error when fetching")]
     InternalGitError(#[from] git2::Error),
     #[error(transparent)]
@@ -1494,11 +1763,28 @@ pub enum GitFetchPrepareError {
     Git2(#[from] git2::Error),
     #[error(transparent)]
     UnexpectedBackend(#[from] UnexpectedGitBackendError),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
 }
 
-fn git2_fetch_options(
+/// How much history `GitFetch::fetch()` downloads.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GitFetchDepth {
+    /// Fetch the full history, or the history up to the current boundary of a
+    /// shallow repository.
+    #[default]
+    Unchanged,
+    /// Fetch at most this many commits from the tip of each branch.
+    Depth(NonZeroU32),
+    /// Fetch this many more commits past the boundary of a shallow repository.
+    Deepen(NonZeroU32),
+    /// Fetch the full history of a shallow repository.
+    Unshallow,
+}
+
+pub(crate) fn git2_fetch_options(
     mut callbacks: RemoteCallbacks<'_>,
-    depth: Option<NonZeroU32>,
+    depth: Option<i32>,
 ) -> git2::FetchOptions<'_> {
     let mut proxy_options = git2::ProxyOptions::new();
     proxy_options.auto();
@@ -1512,8 +1798,9 @@ fn git2_fetch_options(
         callbacks.sideband_progress = None;
     }
     fetch_options.remote_callbacks(callbacks.into_git());
+    fetch_options.download_tags(git2::AutotagOption::None);
     if let Some(depth) = depth {
-        fetch_options.depth(depth.get().try_into().unwrap_or(i32::MAX));
+        fetch_options.depth(depth);
     }
 
     fetch_options
@@ -1530,6 +1817,8 @@ pub struct GitFetch<'a> {
     fetch_impl: GitFetchImpl<'a>,
     git_settings: &'a GitSettings,
     fetched: Vec<FetchedBranches>,
+    /// Boundary commits of the shallow repository before fetching.
+    shallow_commits: HashSet<CommitId>,
 }
 
 impl<'a> GitFetch<'a> {
@@ -1538,11 +1827,13 @@ impl<'a> GitFetch<'a> {
         git_settings: &'a GitSettings,
     ) -> Result<Self, GitFetchPrepareError> {
         let fetch_impl = GitFetchImpl::new(mut_repo.store(), git_settings)?;
+        let shallow_commits = get_git_backend(mut_repo.store())?.shallow_commit_ids()?;
         Ok(GitFetch {
             mut_repo,
             fetch_impl,
             git_settings,
             fetched: vec![],
+            shallow_commits,
         })
     }
 
@@ -1557,7 +1848,7 @@ impl<'a> GitFetch<'a> {
         remote_name: &str,
         branch_names: &[StringPattern],
         callbacks: RemoteCallbacks<'_>,
-        depth: Option<NonZeroU32>,
+        depth: GitFetchDepth,
     ) -> Result<(), GitFetchError> {
         self.fetch_impl
             .fetch(remote_name, branch_names, callbacks, depth)?;
@@ -1578,9 +1869,9 @@ impl<'a> GitFetch<'a> {
         self.fetch_impl.get_default_branch(remote_name, callbacks)
     }
 
-    /// Import the previously fetched remote-tracking branches into the jj repo
-    /// and update jj's local branches. We also import local tags since remote
-    /// tags should have been merged by Git.
+    /// Import the previously fetched remote-tracking branches and tags into the
+    /// jj repo and update jj's local branches and tags. We also import local
+    /// tags since remote tags should have been merged by Git.
     ///
     /// Clears all yet-to-be-imported {branch_names, remote_name} pairs after
     /// the import. If `fetch()` has not been called since the last time
@@ -1588,13 +1879,16 @@ impl<'a> GitFetch<'a> {
     #[tracing::instrument(skip(self))]
     pub fn import_refs(&mut self) -> Result<GitImportStats, GitImportError> {
         tracing::debug!("import_refs");
-        let import_stats =
+        let mut import_stats =
             import_some_refs(
                 self.mut_repo,
                 self.git_settings,
                 |ref_name| match ref_name {
                     RefName::LocalBranch(_) => false,
                     RefName::Tag(_) => true,
+                    RefName::RemoteTag { remote, .. } => {
+                        self.fetched.iter().any(|fetched| fetched.remote == *remote)
+                    }
                     RefName::RemoteBranch { branch, remote } => {
                         self.fetched.iter().any(|fetched| {
                             if fetched.remote != *remote {
@@ -1612,15 +1906,61 @@ impl<'a> GitFetch<'a> {
 
         self.fetched.clear();
 
+        import_stats.reparented_commits = self.import_reparented_commits()?;
         Ok(import_stats)
     }
+
+    /// Imports the ancestors of previously shallow commits, and returns the
+    /// indexed commits whose parents changed because the shallow boundary
+    /// moved.
+    fn import_reparented_commits(&mut self) -> Result<Vec<CommitId>, GitImportError> {
+        let git_backend = get_git_backend(self.mut_repo.store())?;
+        let shallow_commits = git_backend
+            .shallow_commit_ids()
+            .map_err(GitImportError::InternalBackend)?;
+        if shallow_commits == self.shallow_commits {
+            return Ok(vec![]);
+        }
+        let unshallowed_ids = self
+            .shallow_commits
+            .difference(&shallow_commits)
+            .cloned()
+            .collect_vec();
+        // The extra metadata of the newly fetched ancestors isn't imported by
+        // import_refs() because the walk stops at already imported commits.
+        let git_repo = git_backend.git_repo();
+        let mut parent_ids = vec![];
+        for id in &unshallowed_ids {
+            let commit = git_repo
+                .find_commit(gix::ObjectId::from_bytes_or_panic(id.as_bytes()))
+                .map_err(GitImportError::from_git)?;
+            parent_ids.extend(
+                commit
+                    .parent_ids()
+                    .map(|parent_id| CommitId::from_bytes(parent_id.as_bytes())),
+            );
+        }
+        git_backend
+            .import_head_commits(&parent_ids)
+            .map_err(GitImportError::InternalBackend)?;
+
+        let index = self.mut_repo.index();
+        let reparented_commits = unshallowed_ids
+            .into_iter()
+            .chain(shallow_commits.difference(&self.shallow_commits).cloned())
+            .filter(|id| index.has_id(id))
+            .sorted()
+            .collect();
+        self.shallow_commits = shallow_commits;
+        Ok(reparented_commits)
+    }
 }
 
 fn expand_fetch_refspecs(
     remote_name: &str,
     branch_names: &[StringPattern],
 ) -> Result<Vec<RefSpec>, GitFetchError> {
-    branch_names
+    let mut refspecs: Vec<_> = branch_names
         .iter()
         .map(|pattern| {
             pattern
@@ -1638,7 +1978,16 @@ fn expand_fetch_refspecs(
                 })
                 .ok_or_else(|| GitFetchError::InvalidBranchPattern(pattern.clone()))
         })
-        .collect()
+        .try_collect()?;
+    if !refspecs.is_empty() {
+        // Tags are fetched separately per remote so they can be tracked like
+        // remote branches.
+        refspecs.push(RefSpec::forced(
+            "refs/tags/*",
+            format!("{}*", remote_tags_ref_prefix(remote_name)),
+        ));
+    }
+    Ok(refspecs)
 }
 
 enum GitFetchImpl<'a> {
@@ -1670,7 +2019,7 @@ impl<'a> GitFetchImpl<'a> {
         remote_name: &str,
         branch_names: &[StringPattern],
         callbacks: RemoteCallbacks<'_>,
-        depth: Option<NonZeroU32>,
+        depth: GitFetchDepth,
     ) -> Result<(), GitFetchError> {
         match self {
             GitFetchImpl::Git2 { git_repo } => {
@@ -1708,8 +2057,15 @@ fn git2_fetch(
     remote_name: &str,
     branch_names: &[StringPattern],
     callbacks: RemoteCallbacks<'_>,
-    depth: Option<NonZeroU32>,
+    depth: GitFetchDepth,
 ) -> Result<(), GitFetchError> {
+    let depth = match depth {
+        GitFetchDepth::Unchanged => None,
+        GitFetchDepth::Depth(depth) => Some(depth.get().try_into().unwrap_or(i32::MAX)),
+        GitFetchDepth::Deepen(_) => return Err(GitFetchError::DeepenUnsupported),
+        // GIT_FETCH_DEPTH_UNSHALLOW
+        GitFetchDepth::Unshallow => Some(i32::MAX),
+    };
     let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
         if is_remote_not_found_err(&err) {
             GitFetchError::NoSuchRemote(remote_name.to_string())
@@ -1737,7 +2093,8 @@ fn git2_fetch(
     remote.update_tips(
         None,
         git2::RemoteUpdateFlags::empty(),
-        git2::AutotagOption::Unspecified,
+        // Tags are fetched as remote tags by the refspecs.
+        git2::AutotagOption::None,
         None,
     )?;
     tracing::debug!("remote.disconnect");
@@ -1789,7 +2146,7 @@ fn subprocess_fetch(
     remote_name: &str,
     branch_names: &[StringPattern],
     mut callbacks: RemoteCallbacks<'_>,
-    depth: Option<NonZeroU32>,
+    depth: GitFetchDepth,
 ) -> Result<(), GitFetchError> {
     // check the remote exists
     if git_repo.try_find_remote(remote_name).is_none() {
@@ -1852,8 +2209,8 @@ pub enum GitPushError {
     RemoteReservedForLocalGitRepo,
     #[error("Refs in unexpected location: {0:?}")]
     RefInUnexpectedLocation(Vec<String>),
-    #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
-    RefUpdateRejected(Vec<String>),
+    #[error("Atomic pushes are not supported without git.subprocess")]
+    AtomicPushUnsupported,
     // TODO: I'm sure there are other errors possible, such as transport-level errors,
     // and errors caused by the remote rejecting the push.
     #[error("Unexpected git error when pushing")]
@@ -1867,6 +2224,35 @@ pub enum GitPushError {
 #[derive(Clone, Debug)]
 pub struct GitBranchPushTargets {
     pub branch_updates: Vec<(String, BookmarkPushUpdate)>,
+    pub tag_updates: Vec<(String, BookmarkPushUpdate)>,
+}
+
+/// Options for pushing to a Git remote.
+#[derive(Clone, Debug, Default)]
+pub struct GitPushOptions {
+    /// Values passed to the remote as `git push --push-option`. Their meaning
+    /// is up to the remote.
+    pub remote_push_options: Vec<String>,
+    /// Whether the remote should update either all refs or none of them.
+    pub atomic: bool,
+}
+
+/// Refs that the remote accepted or rejected in a push.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GitPushStats {
+    /// Refs that were updated on the remote, or that were already up to date.
+    pub pushed: Vec<String>,
+    /// Refs that the remote rejected, with the reason if it gave one.
+    pub rejected: Vec<(String, Option<String>)>,
+    /// Refs that weren't pushed because they unexpectedly moved on the remote.
+    pub unexpected_location: Vec<String>,
+}
+
+impl GitPushStats {
+    /// Whether all refs were pushed.
+    pub fn all_ok(&self) -> bool {
+        self.rejected.is_empty() && self.unexpected_location.is_empty()
+    }
 }
 
 pub struct GitRefUpdate {
@@ -1875,33 +2261,70 @@ pub struct GitRefUpdate {
     /// exist on the remote
     ///
     /// This is sourced from the local remote-tracking branch.
-    pub expected_current_target: Option<CommitId>,
-    pub new_target: Option<CommitId>,
+    ///
+    /// Since Git tags may point to annotated tag objects, this isn't
+    /// necessarily a commit id.
+    pub expected_current_target: Option<gix::ObjectId>,
+    pub new_target: Option<gix::ObjectId>,
 }
 
-/// Pushes the specified branches and updates the repo view accordingly.
+/// Pushes the specified branches and tags and updates the repo view
+/// accordingly.
+///
+/// If the remote rejects some refs, the view is only updated for the refs that
+/// were pushed, and the rejected refs are reported in the returned stats.
 pub fn push_branches(
     mut_repo: &mut MutableRepo,
     git_settings: &GitSettings,
     remote_name: &str,
     targets: &GitBranchPushTargets,
+    options: &GitPushOptions,
     callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
-    let ref_updates = targets
-        .branch_updates
-        .iter()
-        .map(|(branch_name, update)| GitRefUpdate {
-            qualified_name: format!("refs/heads/{branch_name}"),
-            expected_current_target: update.old_target.clone(),
-            new_target: update.new_target.clone(),
-        })
-        .collect_vec();
-    push_updates(mut_repo, git_settings, remote_name, &ref_updates, callbacks)?;
+) -> Result<GitPushStats, GitPushError> {
+    let git_repo = get_git_backend(mut_repo.store())?.open_git_repo()?;
+    let branch_ref_updates =
+        targets
+            .branch_updates
+            .iter()
+            .map(|(branch_name, update)| GitRefUpdate {
+                qualified_name: format!("refs/heads/{branch_name}"),
+                expected_current_target: update.old_target.as_ref().map(to_git_object_id),
+                new_target: update.new_target.as_ref().map(to_git_object_id),
+            });
+    // Annotated tags are pushed as tag objects, and the remote refs point to
+    // the tag objects.
+    let tags_prefix = remote_tags_ref_prefix(remote_name);
+    let tag_ref_updates = targets.tag_updates.iter().map(|(tag_name, update)| {
+        let remote_ref_name = format!("{tags_prefix}{tag_name}");
+        let local_ref_name = format!("refs/tags/{tag_name}");
+        // The annotated tag may not have been exported yet.
+        let annotated_ref_name = format!("{ANNOTATED_TAGS_REF_PREFIX}{tag_name}");
+        GitRefUpdate {
+            qualified_name: local_ref_name.clone(),
+            expected_current_target: update
+                .old_target
+                .as_ref()
+                .map(|id| git_tag_object_id(&git_repo, &[&remote_ref_name], id)),
+            new_target: update.new_target.as_ref().map(|id| {
+                git_tag_object_id(&git_repo, &[&local_ref_name, &annotated_ref_name], id)
+            }),
+        }
+    });
+    let ref_updates = branch_ref_updates.chain(tag_ref_updates).collect_vec();
+    let push_stats = push_updates(
+        mut_repo,
+        git_settings,
+        remote_name,
+        &ref_updates,
+        options,
+        callbacks,
+    )?;
+    let pushed_refs: HashSet<&str> = push_stats.pushed.iter().map(String::as_str).collect();
 
-    // TODO: add support for partially pushed refs? we could update the view
-    // excluding rejected refs, but the transaction would be aborted anyway
-    // if we returned an Err.
     for (branch_name, update) in &targets.branch_updates {
+        if !pushed_refs.contains(format!("refs/heads/{branch_name}").as_str()) {
+            continue;
+        }
         let git_ref_name = format!("refs/remotes/{remote_name}/{branch_name}");
         let new_remote_ref = RemoteRef {
             target: RefTarget::resolved(update.new_target.clone()),
@@ -1910,8 +2333,58 @@ pub fn push_branches(
         mut_repo.set_git_ref_target(&git_ref_name, new_remote_ref.target.clone());
         mut_repo.set_remote_bookmark(branch_name, remote_name, new_remote_ref);
     }
+    // Unlike remote-tracking branches, Git doesn't update the remote tags.
+    let pushed_tag_updates = targets.tag_updates.iter().zip(
+        ref_updates[targets.branch_updates.len()..]
+            .iter()
+            .map(|update| &update.new_target),
+    );
+    for ((tag_name, update), pushed_id) in pushed_tag_updates {
+        if !pushed_refs.contains(format!("refs/tags/{tag_name}").as_str()) {
+            continue;
+        }
+        let git_ref_name = format!("{tags_prefix}{tag_name}");
+        if let Some(id) = pushed_id {
+            let oid = git2::Oid::from_bytes(id.as_bytes())?;
+            git_repo.reference(&git_ref_name, oid, true, "push from jj")?;
+        } else if let Ok(mut git_ref) = git_repo.find_reference(&git_ref_name) {
+            git_ref.delete()?;
+        }
+        let new_remote_ref = RemoteRef {
+            target: RefTarget::resolved(update.new_target.clone()),
+            state: RemoteRefState::Tracking,
+        };
+        mut_repo.set_git_ref_target(&git_ref_name, new_remote_ref.target.clone());
+        mut_repo.set_remote_tag(tag_name, remote_name, new_remote_ref);
+    }
 
-    Ok(())
+    Ok(push_stats)
+}
+
+fn to_git_object_id(commit_id: &CommitId) -> gix::ObjectId {
+    gix::ObjectId::from_bytes_or_panic(commit_id.as_bytes())
+}
+
+/// Returns the id of the annotated tag object the first of `git_ref_names`
+/// points to if the tag points to `commit_id`. Otherwise returns the id of the
+/// commit.
+fn git_tag_object_id(
+    git_repo: &git2::Repository,
+    git_ref_names: &[&str],
+    commit_id: &CommitId,
+) -> gix::ObjectId {
+    git_ref_names
+        .iter()
+        .find_map(|git_ref_name| {
+            let git_ref = git_repo.find_reference(git_ref_name).ok()?;
+            match (git_ref.target(), git_ref.peel_to_commit()) {
+                (Some(object_id), Ok(commit)) if commit.id().as_bytes() == commit_id.as_bytes() => {
+                    Some(gix::ObjectId::from_bytes_or_panic(object_id.as_bytes()))
+                }
+                _ => None,
+            }
+        })
+        .unwrap_or_else(|| to_git_object_id(commit_id))
 }
 
 /// Pushes the specified Git refs without updating the repo view.
@@ -1920,8 +2393,9 @@ pub fn push_updates(
     git_settings: &GitSettings,
     remote_name: &str,
     updates: &[GitRefUpdate],
+    options: &GitPushOptions,
     callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+) -> Result<GitPushStats, GitPushError> {
     if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
         return Err(GitPushError::RemoteReservedForLocalGitRepo);
     }
@@ -1937,7 +2411,10 @@ pub fn push_updates(
             // We always force-push. We use the push_negotiation callback in
             // `push_refs` to check that the refs did not unexpectedly move on
             // the remote.
-            refspecs.push(RefSpec::forced(new_target.hex(), &update.qualified_name));
+            refspecs.push(RefSpec::forced(
+                new_target.to_hex().to_string(),
+                &update.qualified_name,
+            ));
         } else {
             // Prefixing this with `+` to force-push or not should make no
             // difference. The push negotiation happens regardless, and wouldn't
@@ -1959,9 +2436,13 @@ pub fn push_updates(
             remote_name,
             &qualified_remote_refs_expected_locations,
             &refspecs,
+            options,
             callbacks,
         )
     } else {
+        if options.atomic {
+            return Err(GitPushError::AtomicPushUnsupported);
+        }
         let git_repo = git_backend.open_git_repo()?;
         let refspecs: Vec<String> = refspecs.iter().map(RefSpec::to_git_format).collect();
         git2_push_refs(
@@ -1970,6 +2451,7 @@ pub fn push_updates(
             remote_name,
             &qualified_remote_refs_expected_locations,
             &refspecs,
+            &options.remote_push_options,
             callbacks,
         )
     }
@@ -1979,10 +2461,11 @@ fn git2_push_refs(
     repo: &dyn Repo,
     git_repo: &git2::Repository,
     remote_name: &str,
-    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
+    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&gix::ObjectId>>,
     refspecs: &[String],
+    remote_push_options: &[String],
     callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+) -> Result<GitPushStats, GitPushError> {
     let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
         if is_remote_not_found_err(&err) {
             GitPushError::NoSuchRemote(remote_name.to_string())
@@ -1995,11 +2478,14 @@ fn git2_push_refs(
         .copied()
         .collect();
     let mut failed_push_negotiations = vec![];
+    let mut push_stats = GitPushStats::default();
     let push_result = {
         let mut push_options = git2::PushOptions::new();
         let mut proxy_options = git2::ProxyOptions::new();
         proxy_options.auto();
         push_options.proxy_options(proxy_options);
+        let remote_push_options = remote_push_options.iter().map(String::as_str).collect_vec();
+        push_options.remote_push_options(&remote_push_options);
         let mut callbacks = callbacks.into_git();
         callbacks.push_negotiation(|updates| {
             for update in updates {
@@ -2009,10 +2495,11 @@ fn git2_push_refs(
                 let expected_remote_location = *qualified_remote_refs_expected_locations
                     .get(dst_refname)
                     .expect("Push is trying to move a ref it wasn't asked to move");
-                let oid_to_maybe_commitid =
-                    |oid: git2::Oid| (!oid.is_zero()).then(|| CommitId::from_bytes(oid.as_bytes()));
-                let actual_remote_location = oid_to_maybe_commitid(update.src());
-                let local_location = oid_to_maybe_commitid(update.dst());
+                let oid_to_maybe_object_id = |oid: git2::Oid| {
+                    (!oid.is_zero()).then(|| gix::ObjectId::from_bytes_or_panic(oid.as_bytes()))
+                };
+                let actual_remote_location = oid_to_maybe_object_id(update.src());
+                let local_location = oid_to_maybe_object_id(update.dst());
 
                 match allow_push(
                     repo.index(),
@@ -2062,8 +2549,13 @@ fn git2_push_refs(
         });
         callbacks.push_update_reference(|refname, status| {
             // The status is Some if the ref update was rejected
-            if status.is_none() {
-                remaining_remote_refs.remove(refname);
+            remaining_remote_refs.remove(refname);
+            if let Some(reason) = status {
+                push_stats
+                    .rejected
+                    .push((refname.to_owned(), Some(reason.to_owned())));
+            } else {
+                push_stats.pushed.push(refname.to_owned());
             }
             Ok(())
         });
@@ -2084,20 +2576,14 @@ fn git2_push_refs(
         ))
     } else {
         push_result?;
-        if remaining_remote_refs.is_empty() {
-            Ok(())
-        } else {
-            // TODO: this is probably dead code right now
-            // The only way this would happen is if a push fails from some
-            // other reason other than a lease failing (which our tests don't cover)
-            Err(GitPushError::RefUpdateRejected(
-                remaining_remote_refs
-                    .iter()
-                    .sorted()
-                    .map(|name| name.to_string())
-                    .collect(),
-            ))
-        }
+        // Refs the remote didn't report on weren't updated.
+        push_stats.rejected.extend(
+            remaining_remote_refs
+                .iter()
+                .sorted()
+                .map(|name| (name.to_string(), None)),
+        );
+        Ok(push_stats)
     }
 }
 
@@ -2105,10 +2591,11 @@ fn subprocess_push_refs(
     git_repo: &gix::Repository,
     git_ctx: &GitSubprocessContext,
     remote_name: &str,
-    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
+    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&gix::ObjectId>>,
     refspecs: &[RefSpec],
+    options: &GitPushOptions,
     mut callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+) -> Result<GitPushStats, GitPushError> {
     // check the remote exists
     if git_repo.try_find_remote(remote_name).is_none() {
         return Err(GitPushError::NoSuchRemote(remote_name.to_owned()));
@@ -2124,33 +2611,37 @@ fn subprocess_push_refs(
         .map(|full_refspec| RefToPush::new(full_refspec, qualified_remote_refs_expected_locations))
         .collect();
 
-    let (failed_ref_matches, successful_pushes) =
-        git_ctx.spawn_push(remote_name, &refs_to_push, &mut callbacks)?;
-
-    for remote_ref in successful_pushes {
-        remaining_remote_refs.remove(remote_ref.as_str());
-    }
+    let ref_statuses = git_ctx.spawn_push(remote_name, &refs_to_push, options, &mut callbacks)?;
 
-    if !failed_ref_matches.is_empty() {
-        let mut refs_in_unexpected_locations = failed_ref_matches;
-        refs_in_unexpected_locations.sort();
-        Err(GitPushError::RefInUnexpectedLocation(
+    let mut refs_in_unexpected_locations = ref_statuses.lease_failures;
+    refs_in_unexpected_locations.sort();
+    // Unless the push was atomic, the other refs may have been updated, and
+    // the caller has to record them.
+    if !refs_in_unexpected_locations.is_empty() && ref_statuses.pushed.is_empty() {
+        return Err(GitPushError::RefInUnexpectedLocation(
             refs_in_unexpected_locations,
-        ))
-    } else if remaining_remote_refs.is_empty() {
-        Ok(())
-    } else {
-        // TODO: this is probably dead code right now
-        // The only way this would happen is if a push fails from some
-        // other reason other than a lease failing (which our tests don't cover)
-        Err(GitPushError::RefUpdateRejected(
-            remaining_remote_refs
-                .iter()
-                .sorted()
-                .map(|name| name.to_string())
-                .collect(),
-        ))
+        ));
     }
+    for remote_ref in itertools::chain!(
+        &ref_statuses.pushed,
+        ref_statuses.rejected.iter().map(|(name, _)| name),
+        &refs_in_unexpected_locations,
+    ) {
+        remaining_remote_refs.remove(remote_ref.as_str());
+    }
+    let mut push_stats = GitPushStats {
+        pushed: ref_statuses.pushed,
+        rejected: ref_statuses.rejected,
+        unexpected_location: refs_in_unexpected_locations,
+    };
+    // Refs the remote didn't report on weren't updated.
+    push_stats.rejected.extend(
+        remaining_remote_refs
+            .iter()
+            .sorted()
+            .map(|name| (name.to_string(), None)),
+    );
+    Ok(push_stats)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2162,13 +2653,29 @@ enum PushAllowReason {
 
 fn allow_push(
     index: &dyn Index,
-    actual_remote_location: Option<&CommitId>,
-    expected_remote_location: Option<&CommitId>,
-    destination_location: Option<&CommitId>,
+    actual_remote_location: Option<&gix::ObjectId>,
+    expected_remote_location: Option<&gix::ObjectId>,
+    destination_location: Option<&gix::ObjectId>,
 ) -> Result<PushAllowReason, ()> {
     if actual_remote_location == expected_remote_location {
         return Ok(PushAllowReason::NormalMatch);
     }
+    // Annotated tag objects aren't in the index, so tags pointing to them can
+    // only be pushed if the remote ref is where we expect it to be.
+    let to_commit_id =
+        |id: Option<&gix::ObjectId>| id.map(|id| CommitId::from_bytes(id.as_bytes()));
+    let actual_remote_location = to_commit_id(actual_remote_location);
+    let expected_remote_location = to_commit_id(expected_remote_location);
+    let destination_location = to_commit_id(destination_location);
+    if ![&expected_remote_location, &destination_location]
+        .iter()
+        .all(|id| id.as_ref().map_or(true, |id| index.has_id(id)))
+    {
+        return Err(());
+    }
+    let actual_remote_location = actual_remote_location.as_ref();
+    let expected_remote_location = expected_remote_location.as_ref();
+    let destination_location = destination_location.as_ref();
 
     // If the remote ref is in an unexpected location, we still allow some
     // pushes, based on whether `jj git fetch` would result in a conflicted ref.
@@ -23,6 +23,7 @@ use std::fs;
 use std::io;
 use std::io::Cursor;
 use std::io::Read;
+use std::mem;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -31,9 +32,11 @@ use std::str;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
+use std::task::Poll;
 use std::time::SystemTime;
 
 use async_trait::async_trait;
+use futures::future;
 use futures::stream::BoxStream;
 use gix::bstr::BString;
 use gix::objs::CommitRef;
@@ -71,6 +74,7 @@ use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
+use crate::git_subprocess::GitSubprocessContext;
 use crate::index::Index;
 use crate::lock::FileLock;
 use crate::merge::Merge;
@@ -93,6 +97,8 @@ const NO_GC_REF_NAMESPACE: &str = "refs/jj/keep/";
 const CONFLICT_SUFFIX: &str = ".jjconflict";
 
 const JJ_TREES_COMMIT_HEADER: &[u8] = b"jj:trees";
+/// Maximum number of missing objects of a partial clone fetched at once.
+const MISSING_OBJECTS_FETCH_BATCH_SIZE: usize = 64;
 
 #[derive(Debug, Error)]
 pub enum GitBackendInitError {
@@ -159,6 +165,12 @@ pub struct GitBackend {
     empty_tree_id: TreeId,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    git_executable_path: PathBuf,
+    /// Remote to fetch missing objects from if the repo is a partial clone.
+    promisor_remote: Option<String>,
+    /// Missing objects to be fetched by the next batched fetch.
+    pending_missing_objects: Mutex<Vec<gix::ObjectId>>,
+    missing_objects_fetch_lock: Mutex<()>,
 }
 
 impl GitBackend {
@@ -166,8 +178,17 @@ impl GitBackend {
         "git"
     }
 
-    fn new(base_repo: gix::ThreadSafeRepository, extra_metadata_store: TableStore) -> Self {
+    fn new(
+        settings: &UserSettings,
+        base_repo: gix::ThreadSafeRepository,
+        extra_metadata_store: TableStore,
+    ) -> Self {
         let repo = Mutex::new(base_repo.to_thread_local());
+        let git_executable_path = settings
+            .git_settings()
+            .map(|git_settings| git_settings.executable_path)
+            .unwrap_or_else(|_| PathBuf::from("git"));
+        let promisor_remote = find_promisor_remote(&base_repo.to_thread_local());
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
         let empty_tree_id = TreeId::from_hex("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
@@ -179,6 +200,10 @@ impl GitBackend {
             empty_tree_id,
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            git_executable_path,
+            promisor_remote,
+            pending_missing_objects: Mutex::new(vec![]),
+            missing_objects_fetch_lock: Mutex::new(()),
         }
     }
 
@@ -194,7 +219,7 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::InitRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     /// Initializes backend by creating a new Git repo at the specified
@@ -218,7 +243,7 @@ impl GitBackend {
         )
         .map_err(GitBackendInitError::InitRepository)?;
         let git_repo_path = workspace_root.join(".git");
-        Self::init_with_repo(store_path, &git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, &git_repo_path, git_repo)
     }
 
     /// Initializes backend with an existing Git repo at the specified path.
@@ -238,10 +263,11 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::OpenRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     fn init_with_repo(
+        settings: &UserSettings,
         store_path: &Path,
         git_repo_path: &Path,
         git_repo: gix::ThreadSafeRepository,
@@ -271,7 +297,7 @@ impl GitBackend {
                 .map_err(GitBackendInitError::Path)?;
         };
         let extra_metadata_store = TableStore::init(extra_path, HASH_LENGTH);
-        Ok(GitBackend::new(git_repo, extra_metadata_store))
+        Ok(GitBackend::new(settings, git_repo, extra_metadata_store))
     }
 
     pub fn load(
@@ -294,7 +320,7 @@ impl GitBackend {
         )
         .map_err(GitBackendLoadError::OpenRepository)?;
         let extra_metadata_store = TableStore::load(store_path.join("extra"), HASH_LENGTH);
-        Ok(GitBackend::new(repo, extra_metadata_store))
+        Ok(GitBackend::new(settings, repo, extra_metadata_store))
     }
 
     fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
@@ -359,6 +385,20 @@ impl GitBackend {
         Ok(())
     }
 
+    /// Returns the boundary commits of a shallow Git repo, whose parents
+    /// haven't been fetched.
+    pub fn shallow_commit_ids(&self) -> BackendResult<HashSet<CommitId>> {
+        let shallow_commits = self
+            .lock_git_repo()
+            .shallow_commits()
+            .map_err(|err| BackendError::Other(Box::new(err)))?;
+        Ok(shallow_commits
+            .iter()
+            .flat_map(|commits| commits.iter())
+            .map(|id| CommitId::from_bytes(id.as_bytes()))
+            .collect())
+    }
+
     /// Imports the given commits and ancestors from the backing Git repo.
     ///
     /// The `head_ids` may contain commits that have already been imported, but
@@ -409,6 +449,60 @@ impl GitBackend {
         self.save_extra_metadata_table(mut_table, &table_lock)
     }
 
+    /// Returns the remote missing objects are fetched from if the repo is a
+    /// partial clone.
+    pub fn promisor_remote(&self) -> Option<&str> {
+        self.promisor_remote.as_deref()
+    }
+
+    /// Fetches the object from the promisor remote if the repo is a partial
+    /// clone and the object is missing locally.
+    ///
+    /// Objects requested by reads that are polled concurrently are fetched in
+    /// a single batch.
+    async fn fetch_if_missing(&self, id: &impl ObjectId) -> BackendResult<()> {
+        let Some(remote_name) = &self.promisor_remote else {
+            return Ok(());
+        };
+        let git_object_id = validate_git_object_id(id)?;
+        if self.lock_git_repo().has_object(git_object_id) {
+            return Ok(());
+        }
+        self.pending_missing_objects
+            .lock()
+            .unwrap()
+            .push(git_object_id);
+        // Give the other concurrent reads a chance to queue their missing
+        // objects before fetching.
+        yield_now().await;
+        let _fetch_lock = self.missing_objects_fetch_lock.lock().unwrap();
+        let object_ids = mem::take(&mut *self.pending_missing_objects.lock().unwrap());
+        // The objects may have been fetched along with the objects of another
+        // read.
+        if !object_ids.is_empty() {
+            tracing::debug!(count = object_ids.len(), "fetch missing objects");
+            let git_ctx =
+                GitSubprocessContext::new(self.git_repo_path(), &self.git_executable_path);
+            git_ctx
+                .spawn_fetch_objects(remote_name, &object_ids)
+                .map_err(|err| BackendError::FetchObject {
+                    object_type: id.object_type(),
+                    hash: id.hex(),
+                    source: Box::new(err),
+                })?;
+        }
+        // The batch may have been fetched by another read, which failed or
+        // didn't get this object.
+        if !self.lock_git_repo().has_object(git_object_id) {
+            return Err(BackendError::FetchObject {
+                object_type: id.object_type(),
+                hash: id.hex(),
+                source: format!("The object is missing after fetching from {remote_name}").into(),
+            });
+        }
+        Ok(())
+    }
+
     fn read_file_sync(&self, id: &FileId) -> BackendResult<Box<dyn Read>> {
         let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
@@ -494,6 +588,35 @@ fn gix_open_opts_from_settings(settings: &UserSettings) -> gix::open::Options {
         .open_path_as_is(true)
 }
 
+/// Finds the remote that missing objects of a partial clone are fetched from.
+fn find_promisor_remote(repo: &gix::Repository) -> Option<String> {
+    let config = repo.config_snapshot();
+    repo.remote_names()
+        .into_iter()
+        .find(|name| {
+            config
+                .boolean(format!("remote.{name}.promisor").as_str())
+                .unwrap_or(false)
+        })
+        .map(|name| name.to_string())
+}
+
+/// Returns `Pending` once so that the other futures polled concurrently can
+/// make progress.
+async fn yield_now() {
+    let mut yielded = false;
+    future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
 /// Reads the `jj:trees` header from the commit.
 fn root_tree_from_header(git_commit: &CommitRef) -> Result<Option<MergedTreeId>, ()> {
     for (key, value) in &git_commit.extra_headers {
@@ -539,8 +662,8 @@ fn commit_from_git_without_root_parent(
             .collect(),
     );
     // shallow commits don't have parents their parents actually fetched, so we
-    // discard them here
-    // TODO: This causes issues when a shallow repository is deepened/unshallowed
+    // discard them here. When a shallow repository is deepened or unshallowed,
+    // the parents of these commits change, and the index has to be rebuilt.
     let parents = if is_shallow {
         vec![]
     } else {
@@ -628,7 +751,7 @@ fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     }
 }
 
-fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
+pub(crate) fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
     // git does not support empty names or emails
     let name = if !signature.name.is_empty() {
         &signature.name
@@ -942,10 +1065,15 @@ impl Backend for GitBackend {
     }
 
     fn concurrency(&self) -> usize {
-        1
+        if self.promisor_remote.is_some() {
+            MISSING_OBJECTS_FETCH_BATCH_SIZE
+        } else {
+            1
+        }
     }
 
     async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
+        self.fetch_if_missing(id).await?;
         self.read_file_sync(id)
     }
 
@@ -967,6 +1095,7 @@ impl Backend for GitBackend {
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
+        self.fetch_if_missing(id).await?;
         let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
         let mut blob = locked_repo
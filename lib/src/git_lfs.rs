@@ -0,0 +1,667 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Git LFS support: pointer files, the local object store, and a client for
+//! the batch API.
+//!
+//! Files with the `filter=lfs` attribute are stored in the repo as small
+//! pointer files which refer to the actual content by its SHA-256 hash. The
+//! content is kept in `lfs/objects` in the Git repo directory, which is the
+//! same layout as used by `git lfs`.
+
+#![allow(missing_docs)]
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use base64::Engine as _;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest as _;
+use sha2::Sha256;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::git;
+use crate::gitattributes::TreeGitAttributes;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffEntry;
+use crate::settings::GitSettings;
+use crate::store::Store;
+
+const POINTER_VERSION_LINE: &str = "version https://git-lfs.github.com/spec/v1";
+/// Pointer files larger than this are not pointers, as in `git lfs`.
+const MAX_POINTER_SIZE: usize = 1024;
+const MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// Reference to an LFS object, stored in the repo in place of its content.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LfsPointer {
+    /// Hex-encoded SHA-256 hash of the content.
+    pub oid: String,
+    /// Size of the content in bytes.
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Parses the content of a pointer file. Returns `None` if the content
+    /// isn't a valid pointer.
+    pub fn parse(content: &[u8]) -> Option<Self> {
+        if content.len() > MAX_POINTER_SIZE {
+            return None;
+        }
+        let text = std::str::from_utf8(content).ok()?;
+        let mut lines = text.lines();
+        if lines.next()? != POINTER_VERSION_LINE {
+            return None;
+        }
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            let (key, value) = line.split_once(' ')?;
+            match key {
+                "oid" => oid = Some(value.strip_prefix("sha256:")?),
+                "size" => size = Some(value.parse().ok()?),
+                // Unknown keys are allowed by the spec
+                _ => {}
+            }
+        }
+        let oid = oid?;
+        if oid.len() != 64 || !oid.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return None;
+        }
+        Some(LfsPointer {
+            oid: oid.to_owned(),
+            size: size?,
+        })
+    }
+
+    /// Returns the content of the pointer file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut text = String::new();
+        writeln!(text, "{POINTER_VERSION_LINE}").unwrap();
+        writeln!(text, "oid sha256:{}", self.oid).unwrap();
+        writeln!(text, "size {}", self.size).unwrap();
+        text.into_bytes()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LfsError {
+    #[error("Git LFS object {oid} is not available locally")]
+    ObjectNotFound { oid: String },
+    #[error("Git LFS object {oid} doesn't match its pointer")]
+    CorruptObject { oid: String },
+    #[error("Failed to access Git LFS objects")]
+    Io(#[from] io::Error),
+    #[error("Git LFS request to {url} failed")]
+    Http {
+        url: String,
+        source: Box<ureq::Error>,
+    },
+    #[error("Invalid response from Git LFS server")]
+    InvalidResponse(#[source] serde_json::Error),
+    #[error("Git LFS server rejected object {oid}: {message}")]
+    ObjectRejected { oid: String, message: String },
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// Local store of LFS objects.
+#[derive(Clone, Debug)]
+pub struct LfsObjectStore {
+    path: PathBuf,
+}
+
+impl LfsObjectStore {
+    /// Creates an object store at `path`, which is created as needed.
+    pub fn new(path: PathBuf) -> Self {
+        LfsObjectStore { path }
+    }
+
+    /// Returns the object store of the Git repo backing `store`, or `None` if
+    /// `store` isn't backed by Git.
+    pub fn for_store(store: &Store) -> Option<Self> {
+        let git_backend = git::get_git_backend(store).ok()?;
+        let path = git_backend.git_repo_path().join("lfs").join("objects");
+        Some(LfsObjectStore::new(path))
+    }
+
+    fn object_path(&self, oid: &str) -> PathBuf {
+        self.path.join(&oid[0..2]).join(&oid[2..4]).join(oid)
+    }
+
+    /// Returns whether the object referred to by `pointer` is available.
+    pub fn contains(&self, pointer: &LfsPointer) -> bool {
+        self.object_path(&pointer.oid).is_file()
+    }
+
+    /// Opens the object referred to by `pointer`, if available.
+    pub fn open(&self, pointer: &LfsPointer) -> io::Result<Option<File>> {
+        match File::open(self.object_path(&pointer.oid)) {
+            Ok(file) => Ok(Some(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write_temp_file(&self, contents: &mut dyn Read) -> io::Result<(NamedTempFile, LfsPointer)> {
+        fs::create_dir_all(&self.path)?;
+        let mut temp_file = NamedTempFile::new_in(&self.path)?;
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        let mut buf = [0; 64 * 1024];
+        loop {
+            let n = contents.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            temp_file.write_all(&buf[..n])?;
+            size += n as u64;
+        }
+        let oid = hex::encode(hasher.finalize());
+        Ok((temp_file, LfsPointer { oid, size }))
+    }
+
+    fn persist(&self, temp_file: NamedTempFile, pointer: &LfsPointer) -> io::Result<()> {
+        let path = self.object_path(&pointer.oid);
+        fs::create_dir_all(path.parent().unwrap())?;
+        persist_content_addressed_temp_file(temp_file, path)?;
+        Ok(())
+    }
+
+    /// Adds the `contents` to the store and returns the pointer to it.
+    pub fn write(&self, contents: &mut dyn Read) -> io::Result<LfsPointer> {
+        let (temp_file, pointer) = self.write_temp_file(contents)?;
+        if !self.contains(&pointer) {
+            self.persist(temp_file, &pointer)?;
+        }
+        Ok(pointer)
+    }
+
+    /// Adds the `contents` of a working-copy file to the store and returns the
+    /// content of the pointer file. If the `contents` are already a pointer,
+    /// they are returned as is.
+    pub fn clean(&self, contents: &mut dyn Read) -> io::Result<Vec<u8>> {
+        let mut head = vec![];
+        contents
+            .take(MAX_POINTER_SIZE as u64 + 1)
+            .read_to_end(&mut head)?;
+        if LfsPointer::parse(&head).is_some() {
+            return Ok(head);
+        }
+        let pointer = self.write(&mut head.as_slice().chain(contents))?;
+        Ok(pointer.to_bytes())
+    }
+
+    /// Returns the content to write to the working copy for the stored
+    /// `contents`. If the `contents` are a pointer to an available object, the
+    /// object is returned. Otherwise, the `contents` are returned as is.
+    pub fn smudge(&self, mut contents: Box<dyn Read>) -> io::Result<Box<dyn Read>> {
+        let mut head = vec![];
+        (&mut contents)
+            .take(MAX_POINTER_SIZE as u64 + 1)
+            .read_to_end(&mut head)?;
+        if let Some(pointer) = LfsPointer::parse(&head) {
+            if let Some(file) = self.open(&pointer)? {
+                return Ok(Box::new(file));
+            }
+        }
+        Ok(Box::new(io::Cursor::new(head).chain(contents)))
+    }
+
+    /// Adds the `contents` to the store if they match the `pointer`.
+    fn write_verified(
+        &self,
+        pointer: &LfsPointer,
+        contents: &mut dyn Read,
+    ) -> Result<(), LfsError> {
+        let (temp_file, actual_pointer) = self.write_temp_file(contents)?;
+        if actual_pointer != *pointer {
+            return Err(LfsError::CorruptObject {
+                oid: pointer.oid.clone(),
+            });
+        }
+        self.persist(temp_file, pointer)?;
+        Ok(())
+    }
+}
+
+/// Returns the LFS pointers in files that were added or modified between the
+/// two trees. Only files with the `filter=lfs` attribute are considered.
+pub fn changed_lfs_pointers(
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+) -> BackendResult<Vec<LfsPointer>> {
+    let store = to_tree.store();
+    let mut git_attributes = TreeGitAttributes::new(to_tree);
+    let entries: Vec<TreeDiffEntry> = from_tree
+        .diff_stream(to_tree, &EverythingMatcher)
+        .collect()
+        .block_on();
+    let mut pointers = vec![];
+    for TreeDiffEntry { path, values } in entries {
+        let (_before, after) = values?;
+        let Some(Some(TreeValue::File { id, .. })) = after.as_resolved() else {
+            continue;
+        };
+        if !git_attributes.attributes(&path)?.lfs {
+            continue;
+        }
+        let mut content = vec![];
+        store
+            .read_file(&path, id)?
+            .take(MAX_POINTER_SIZE as u64 + 1)
+            .read_to_end(&mut content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        pointers.extend(LfsPointer::parse(&content));
+    }
+    Ok(pointers.into_iter().sorted().dedup().collect())
+}
+
+/// Returns the LFS server URL for the Git remote URL, following the rules used
+/// by `git lfs`. Returns `None` if the URL doesn't use a network protocol.
+pub fn lfs_url_from_remote_url(remote_url: &str) -> Option<String> {
+    let (host, path) = if let Some(rest) = remote_url
+        .strip_prefix("https://")
+        .or_else(|| remote_url.strip_prefix("http://"))
+    {
+        let scheme = &remote_url[..remote_url.len() - rest.len()];
+        let rest = rest.trim_end_matches('/');
+        let rest = rest.strip_suffix(".git").unwrap_or(rest);
+        return Some(format!("{scheme}{rest}.git/info/lfs"));
+    } else if let Some(rest) = remote_url.strip_prefix("ssh://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let host = host.split_once(':').map_or(host, |(host, _port)| host);
+        (host, path)
+    } else if let Some((authority, path)) = remote_url.split_once(':') {
+        // scp-like syntax, e.g. git@github.com:owner/repo.git. A single letter
+        // is a Windows drive letter.
+        if authority.len() == 1 || authority.contains('/') || path.starts_with("//") {
+            return None;
+        }
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        (host, path)
+    } else {
+        return None;
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    Some(format!("https://{host}/{path}.git/info/lfs"))
+}
+
+#[derive(Serialize)]
+struct BatchRequest<'a> {
+    operation: &'a str,
+    transfers: &'a [&'a str],
+    objects: Vec<BatchObject<'a>>,
+}
+
+#[derive(Serialize)]
+struct BatchObject<'a> {
+    oid: &'a str,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    objects: Vec<BatchResponseObject>,
+}
+
+#[derive(Deserialize)]
+struct BatchResponseObject {
+    oid: String,
+    size: u64,
+    #[serde(default)]
+    actions: BatchActions,
+    error: Option<BatchObjectError>,
+}
+
+#[derive(Default, Deserialize)]
+struct BatchActions {
+    download: Option<BatchAction>,
+    upload: Option<BatchAction>,
+    verify: Option<BatchAction>,
+}
+
+#[derive(Deserialize)]
+struct BatchAction {
+    href: String,
+    #[serde(default)]
+    header: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct BatchObjectError {
+    message: String,
+}
+
+fn build_agent(connect: Duration, read: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(connect)
+        .timeout_read(read)
+        .build()
+}
+
+/// Client for the LFS batch API using the "basic" transfer adapter.
+#[derive(Debug)]
+pub struct LfsClient {
+    url: String,
+    authorization: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl LfsClient {
+    /// Creates a client for the LFS server at `url`, which is usually the
+    /// remote URL followed by `.git/info/lfs`.
+    pub fn new(url: impl Into<String>) -> Self {
+        let defaults = GitSettings::default();
+        LfsClient {
+            url: url.into().trim_end_matches('/').to_owned(),
+            authorization: None,
+            agent: build_agent(defaults.lfs_connect_timeout, defaults.lfs_read_timeout),
+        }
+    }
+
+    /// Sets the timeouts for connecting to and reading from the LFS server.
+    pub fn with_timeouts(mut self, connect: Duration, read: Duration) -> Self {
+        self.agent = build_agent(connect, read);
+        self
+    }
+
+    /// Uses HTTP basic authentication for requests to the LFS server.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        self.authorization = Some(format!("Basic {credentials}"));
+        self
+    }
+
+    /// Returns the URL of the LFS server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn batch(
+        &self,
+        operation: &str,
+        pointers: &[LfsPointer],
+    ) -> Result<Vec<BatchResponseObject>, LfsError> {
+        let url = format!("{}/objects/batch", self.url);
+        let request = BatchRequest {
+            operation,
+            transfers: &["basic"],
+            objects: pointers
+                .iter()
+                .map(|pointer| BatchObject {
+                    oid: &pointer.oid,
+                    size: pointer.size,
+                })
+                .collect(),
+        };
+        let mut http_request = self
+            .agent
+            .post(&url)
+            .set("Accept", MEDIA_TYPE)
+            .set("Content-Type", MEDIA_TYPE);
+        if let Some(authorization) = &self.authorization {
+            http_request = http_request.set("Authorization", authorization);
+        }
+        let response = http_request
+            .send_bytes(&serde_json::to_vec(&request).unwrap())
+            .map_err(|err| LfsError::Http {
+                url: url.clone(),
+                source: Box::new(err),
+            })?;
+        let response: BatchResponse =
+            serde_json::from_reader(response.into_reader()).map_err(LfsError::InvalidResponse)?;
+        response
+            .objects
+            .into_iter()
+            .map(|object| match object.error {
+                Some(error) => Err(LfsError::ObjectRejected {
+                    oid: object.oid,
+                    message: error.message,
+                }),
+                None => Ok(object),
+            })
+            .collect()
+    }
+
+    fn action_request(&self, method: &str, action: &BatchAction) -> ureq::Request {
+        let mut request = self.agent.request(method, &action.href);
+        if action.header.is_empty() {
+            // Actions on the LFS server itself usually need the same
+            // credentials. Others are expected to specify their headers.
+            if let Some(authorization) = &self.authorization {
+                if action.href.starts_with(&self.url) {
+                    request = request.set("Authorization", authorization);
+                }
+            }
+        }
+        for (name, value) in &action.header {
+            request = request.set(name, value);
+        }
+        request
+    }
+
+    /// Downloads the objects referred to by `pointers` into `store`.
+    pub fn download(
+        &self,
+        store: &LfsObjectStore,
+        pointers: &[LfsPointer],
+    ) -> Result<(), LfsError> {
+        if pointers.is_empty() {
+            return Ok(());
+        }
+        for object in self.batch("download", pointers)? {
+            let Some(action) = &object.actions.download else {
+                continue;
+            };
+            let response =
+                self.action_request("GET", action)
+                    .call()
+                    .map_err(|err| LfsError::Http {
+                        url: action.href.clone(),
+                        source: Box::new(err),
+                    })?;
+            let pointer = LfsPointer {
+                oid: object.oid,
+                size: object.size,
+            };
+            store.write_verified(&pointer, &mut response.into_reader())?;
+        }
+        Ok(())
+    }
+
+    /// Uploads the objects referred to by `pointers` from `store`. Objects
+    /// that the server already has are skipped.
+    pub fn upload(&self, store: &LfsObjectStore, pointers: &[LfsPointer]) -> Result<(), LfsError> {
+        if pointers.is_empty() {
+            return Ok(());
+        }
+        for object in self.batch("upload", pointers)? {
+            let Some(action) = &object.actions.upload else {
+                continue;
+            };
+            let pointer = LfsPointer {
+                oid: object.oid,
+                size: object.size,
+            };
+            let file = store
+                .open(&pointer)?
+                .ok_or_else(|| LfsError::ObjectNotFound {
+                    oid: pointer.oid.clone(),
+                })?;
+            self.action_request("PUT", action)
+                .set("Content-Type", "application/octet-stream")
+                .set("Content-Length", &pointer.size.to_string())
+                .send(file)
+                .map_err(|err| LfsError::Http {
+                    url: action.href.clone(),
+                    source: Box::new(err),
+                })?;
+            if let Some(action) = &object.actions.verify {
+                let body = BatchObject {
+                    oid: &pointer.oid,
+                    size: pointer.size,
+                };
+                self.action_request("POST", action)
+                    .set("Accept", MEDIA_TYPE)
+                    .set("Content-Type", MEDIA_TYPE)
+                    .send_bytes(&serde_json::to_vec(&body).unwrap())
+                    .map_err(|err| LfsError::Http {
+                        url: action.href.clone(),
+                        source: Box::new(err),
+                    })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creates a client for the LFS server of the Git remote. The server URL is
+/// taken from `remote.<name>.lfsurl` or `lfs.url` in the Git config, or
+/// derived from the remote URL. Credentials are looked up by the configured
+/// Git credential helpers.
+///
+/// Returns `None` if there's no such remote or the remote doesn't use a
+/// network protocol.
+pub fn lfs_client_for_remote(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    git_settings: &GitSettings,
+) -> Result<Option<LfsClient>, git2::Error> {
+    let config = git_repo.config()?;
+    let configured_url = |name: &str| match config.get_string(name) {
+        Ok(url) => Ok(Some(url)),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err),
+    };
+    let url = if let Some(url) = configured_url(&format!("remote.{remote_name}.lfsurl"))? {
+        url
+    } else if let Some(url) = configured_url("lfs.url")? {
+        url
+    } else {
+        let remote = match git_repo.find_remote(remote_name) {
+            Ok(remote) => remote,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        match remote.url().and_then(lfs_url_from_remote_url) {
+            Some(url) => url,
+            None => return Ok(None),
+        }
+    };
+    let client = LfsClient::new(url).with_timeouts(
+        git_settings.lfs_connect_timeout,
+        git_settings.lfs_read_timeout,
+    );
+    let credentials = git2::CredentialHelper::new(client.url())
+        .config(&config)
+        .execute();
+    Ok(Some(match credentials {
+        Some((username, password)) => client.with_credentials(&username, &password),
+        None => client,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_round_trip() {
+        let pointer = LfsPointer {
+            oid: "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393".to_owned(),
+            size: 12345,
+        };
+        let bytes = pointer.to_bytes();
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            "version https://git-lfs.github.com/spec/v1\noid \
+             sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize \
+             12345\n"
+        );
+        assert_eq!(LfsPointer::parse(&bytes), Some(pointer));
+    }
+
+    #[test]
+    fn test_pointer_parse_invalid() {
+        assert_eq!(LfsPointer::parse(b""), None);
+        assert_eq!(LfsPointer::parse(b"hello\n"), None);
+        // Missing size
+        assert_eq!(
+            LfsPointer::parse(
+                b"version https://git-lfs.github.com/spec/v1\noid \
+                  sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n"
+            ),
+            None
+        );
+        // Invalid oid
+        assert_eq!(
+            LfsPointer::parse(
+                b"version https://git-lfs.github.com/spec/v1\noid sha256:../../x\nsize 1\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_lfs_url_from_remote_url() {
+        assert_eq!(
+            lfs_url_from_remote_url("https://example.com/owner/repo.git").as_deref(),
+            Some("https://example.com/owner/repo.git/info/lfs")
+        );
+        assert_eq!(
+            lfs_url_from_remote_url("http://example.com/owner/repo/").as_deref(),
+            Some("http://example.com/owner/repo.git/info/lfs")
+        );
+        assert_eq!(
+            lfs_url_from_remote_url("ssh://git@example.com:22/owner/repo.git").as_deref(),
+            Some("https://example.com/owner/repo.git/info/lfs")
+        );
+        assert_eq!(
+            lfs_url_from_remote_url("git@example.com:owner/repo.git").as_deref(),
+            Some("https://example.com/owner/repo.git/info/lfs")
+        );
+        assert_eq!(lfs_url_from_remote_url("/path/to/repo"), None);
+        assert_eq!(lfs_url_from_remote_url("file:///path/to/repo"), None);
+    }
+}
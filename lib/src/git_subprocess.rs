@@ -15,7 +15,7 @@
 use std::io;
 use std::io::BufReader;
 use std::io::Read;
-use std::num::NonZeroU32;
+use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Child;
@@ -25,8 +25,11 @@ use std::process::Stdio;
 use std::thread;
 
 use bstr::ByteSlice;
+use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::git::GitFetchDepth;
+use crate::git::GitPushOptions;
 use crate::git::Progress;
 use crate::git::RefSpec;
 use crate::git::RefToPush;
@@ -56,6 +59,17 @@ pub enum GitSubprocessError {
     External(String),
 }
 
+/// Status of the refs of a push, as reported by `git push --porcelain`
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct GitPushRefStatuses {
+    /// Refs that were rejected because their lease failed
+    pub lease_failures: Vec<String>,
+    /// Refs that were rejected for another reason, with the reason if any
+    pub rejected: Vec<(String, Option<String>)>,
+    /// Refs that were pushed or already up to date
+    pub pushed: Vec<String>,
+}
+
 /// Context for creating Git subprocesses
 pub(crate) struct GitSubprocessContext<'a> {
     git_dir: PathBuf,
@@ -121,7 +135,7 @@ impl<'a> GitSubprocessContext<'a> {
         remote_name: &str,
         refspecs: &[RefSpec],
         callbacks: &mut RemoteCallbacks<'_>,
-        depth: Option<NonZeroU32>,
+        depth: GitFetchDepth,
     ) -> Result<Option<String>, GitSubprocessError> {
         if refspecs.is_empty() {
             return Ok(None);
@@ -129,12 +143,22 @@ impl<'a> GitSubprocessContext<'a> {
         let mut command = self.create_command();
         command.stdout(Stdio::piped());
         // attempt to prune stale refs with --prune
-        command.args(["fetch", "--prune"]);
+        // tags are fetched by the refspecs
+        command.args(["fetch", "--prune", "--no-tags"]);
         if callbacks.progress.is_some() {
             command.arg("--progress");
         }
-        if let Some(d) = depth {
-            command.arg(format!("--depth={d}"));
+        match depth {
+            GitFetchDepth::Unchanged => {}
+            GitFetchDepth::Depth(d) => {
+                command.arg(format!("--depth={d}"));
+            }
+            GitFetchDepth::Deepen(d) => {
+                command.arg(format!("--deepen={d}"));
+            }
+            GitFetchDepth::Unshallow => {
+                command.arg("--unshallow");
+            }
         }
         command.arg("--").arg(remote_name);
         command.args(refspecs.iter().map(|x| x.to_git_format()));
@@ -144,6 +168,44 @@ impl<'a> GitSubprocessContext<'a> {
         parse_git_fetch_output(output)
     }
 
+    /// Fetch particular objects from the promisor remote of a partial clone
+    ///
+    /// The object ids are passed through stdin so that the number of objects
+    /// fetched at once isn't limited by the command line length.
+    pub(crate) fn spawn_fetch_objects(
+        &self,
+        remote_name: &str,
+        object_ids: &[gix::ObjectId],
+    ) -> Result<(), GitSubprocessError> {
+        if object_ids.is_empty() {
+            return Ok(());
+        }
+        let mut command = self.create_command();
+        command.stdin(Stdio::piped()).stdout(Stdio::null());
+        command.args([
+            "fetch",
+            "--no-tags",
+            "--no-write-fetch-head",
+            "--recurse-submodules=no",
+            "--filter=blob:none",
+            "--stdin",
+            "--",
+            remote_name,
+        ]);
+        let mut child = self.spawn_cmd(command)?;
+        let input = object_ids.iter().map(|id| format!("{id}\n")).join("");
+        // If git exits early, the error is reported from its output below
+        let write_result = child.stdin.take().unwrap().write_all(input.as_bytes());
+        let output = wait_with_output(child)?;
+        if !output.status.success() {
+            if let Some(remote) = parse_no_such_remote(&output.stderr) {
+                return Err(GitSubprocessError::NoSuchRepository(remote));
+            }
+            return Err(external_git_error(&output.stderr));
+        }
+        write_result.map_err(GitSubprocessError::Wait)
+    }
+
     /// Prune particular branches
     pub(crate) fn spawn_branch_prune(
         &self,
@@ -191,18 +253,26 @@ impl<'a> GitSubprocessContext<'a> {
     /// All pushes are forced, using --force-with-lease to perform a test&set
     /// operation on the remote repository
     ///
-    /// Return tuple with
-    ///     1. refs that failed to push
-    ///     2. refs that succeeded to push
+    /// Returns the status of each pushed ref as reported by the remote.
     pub(crate) fn spawn_push(
         &self,
         remote_name: &str,
         references: &[RefToPush],
+        options: &GitPushOptions,
         callbacks: &mut RemoteCallbacks<'_>,
-    ) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
+    ) -> Result<GitPushRefStatuses, GitSubprocessError> {
         let mut command = self.create_command();
         command.stdout(Stdio::piped());
         command.args(["push", "--porcelain"]);
+        if options.atomic {
+            command.arg("--atomic");
+        }
+        command.args(
+            options
+                .remote_push_options
+                .iter()
+                .map(|option| format!("--push-option={option}")),
+        );
         command.args(
             references
                 .iter()
@@ -376,7 +446,7 @@ fn parse_git_remote_show_default_branch(
 // at times the summary is omitted
 //
 // <reason> is a human-readable explanation
-fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
+fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushRefStatuses, GitSubprocessError> {
     if !stdout.starts_with(b"To ") {
         return Err(GitSubprocessError::External(format!(
             "Git push output unfamiliar:\n{}",
@@ -384,8 +454,7 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         )));
     }
 
-    let mut pushed_refs = Vec::new();
-    let mut rejected_refs = Vec::new();
+    let mut statuses = GitPushRefStatuses::default();
     for (idx, line) in stdout
         .lines()
         .skip(1)
@@ -405,7 +474,7 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         let flag = it.next().ok_or_else(create_error)?;
         let reference = it.next().ok_or_else(create_error)?;
         // we capture the remaining elements to ensure the line is well formed
-        let _summary_or_comment = it.next().ok_or_else(create_error)?;
+        let summary = it.next().ok_or_else(create_error)?;
         let _comment_opt = it.next();
         if it.next().is_some() {
             return Err(create_error());
@@ -439,11 +508,21 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
             //  * for a successfully pushed new ref
             //  =  for a ref that was up to date and did not need pushing.
             b"+" | b"-" | b"*" | b"=" | b" " => {
-                pushed_refs.push(reference);
+                statuses.pushed.push(reference);
             }
             // ! for a ref that was rejected or failed to push; and
             b"!" => {
-                rejected_refs.push(reference);
+                // e.g. "[rejected] (stale info)" or
+                // "[remote rejected] (pre-receive hook declined)"
+                let reason = summary
+                    .split_once_str(" (")
+                    .and_then(|(_, reason)| reason.strip_suffix(b")"))
+                    .map(|reason| reason.to_str_lossy().into_owned());
+                if reason.as_deref() == Some("stale info") {
+                    statuses.lease_failures.push(reference);
+                } else {
+                    statuses.rejected.push((reference, reason));
+                }
             }
             unknown => {
                 return Err(GitSubprocessError::External(format!(
@@ -456,13 +535,10 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         }
     }
 
-    Ok((rejected_refs, pushed_refs))
+    Ok(statuses)
 }
 
-// on Ok, return a tuple with
-//  1. list of failed references from test and set
-//  2. list of successful references pushed
-fn parse_git_push_output(output: Output) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
+fn parse_git_push_output(output: Output) -> Result<GitPushRefStatuses, GitSubprocessError> {
     if output.status.success() {
         let ref_pushes = parse_ref_pushes(&output.stdout)?;
         return Ok(ref_pushes);
@@ -472,9 +548,11 @@ fn parse_git_push_output(output: Output) -> Result<(Vec<String>, Vec<String>), G
         return Err(GitSubprocessError::NoSuchRepository(remote));
     }
 
+    // With --atomic, the error message may be preceded by other errors
     if output
         .stderr
-        .starts_with_str("error: failed to push some refs to ")
+        .lines()
+        .any(|line| line.starts_with_str("error: failed to push some refs to "))
     {
         parse_ref_pushes(&output.stdout)
     } else {
@@ -678,7 +756,9 @@ and the repository exists. "###;
 -\tdeadbeef:refs/heads/bookmark3\tdeadbeef\t[new branch]
  \tdeadbeef:refs/heads/bookmark4\tdeadbeef\t[new branch]
 =\tdeadbeef:refs/heads/bookmark5\tdeadbeef\t[new branch]
-!\tdeadbeef:refs/heads/bookmark6\tdeadbeef\t[new branch]
+!\tdeadbeef:refs/heads/bookmark6\t[rejected] (stale info)
+!\tdeadbeef:refs/heads/bookmark7\t[remote rejected] (pre-receive hook declined)
+!\tdeadbeef:refs/heads/bookmark8\t[rejected]
 Done";
     const SAMPLE_OK_STDERR: &[u8] = b"";
 
@@ -751,10 +831,23 @@ Done";
         assert!(parse_ref_pushes(SAMPLE_NO_SUCH_REMOTE_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_REF_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_TRACKING_BRANCH_ERROR).is_err());
-        let (failed, success) = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
-        assert_eq!(failed, vec!["refs/heads/bookmark6".to_string()]);
+        let statuses = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
+        assert_eq!(
+            statuses.lease_failures,
+            vec!["refs/heads/bookmark6".to_string()]
+        );
+        assert_eq!(
+            statuses.rejected,
+            vec![
+                (
+                    "refs/heads/bookmark7".to_string(),
+                    Some("pre-receive hook declined".to_string())
+                ),
+                ("refs/heads/bookmark8".to_string(), None),
+            ]
+        );
         assert_eq!(
-            success,
+            statuses.pushed,
             vec![
                 "refs/heads/bookmark1".to_string(),
                 "refs/heads/bookmark2".to_string(),
@@ -0,0 +1,397 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser for the subset of `.gitattributes` which affects line endings and
+//! Git LFS.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Read as _;
+use std::iter;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ignore::gitignore;
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::merged_tree::MergedTree;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
+
+/// Error while loading `.gitattributes` files.
+#[derive(Debug, Error)]
+pub enum GitAttributesError {
+    /// The file couldn't be read.
+    #[error("Failed to read attributes from file {path}")]
+    ReadFile {
+        /// The path to the file.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+/// State of the `text` attribute.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextAttribute {
+    /// Not specified, or reset by `!text`.
+    #[default]
+    Unspecified,
+    /// `text`: the file is text.
+    Set,
+    /// `-text` or `binary`: the file is never converted.
+    Unset,
+    /// `text=auto`: the file is text unless its content looks binary.
+    Auto,
+}
+
+/// Value of the `eol` attribute.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EolAttribute {
+    /// `eol=lf`
+    Lf,
+    /// `eol=crlf`
+    Crlf,
+}
+
+/// Attributes which apply to a file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FileAttributes {
+    /// The `text` attribute.
+    pub text: TextAttribute,
+    /// The `eol` attribute, if specified.
+    pub eol: Option<EolAttribute>,
+    /// Whether the file is stored in Git LFS (`filter=lfs`).
+    pub lfs: bool,
+}
+
+/// A line in a `.gitattributes` file. `None` means the line doesn't mention
+/// the attribute.
+#[derive(Debug)]
+struct AttributesRule {
+    matcher: gitignore::Gitignore,
+    text: Option<TextAttribute>,
+    eol: Option<Option<EolAttribute>>,
+    lfs: Option<bool>,
+}
+
+/// Models the effective contents of multiple `.gitattributes` files.
+#[derive(Debug)]
+pub struct GitAttributesFile {
+    parent: Option<Arc<GitAttributesFile>>,
+    /// The directory containing the file, with a trailing slash unless it's
+    /// the root.
+    prefix: String,
+    rules: Vec<AttributesRule>,
+}
+
+impl GitAttributesFile {
+    /// Returns an instance without any attributes.
+    pub fn empty() -> Arc<GitAttributesFile> {
+        Arc::new(GitAttributesFile {
+            parent: None,
+            prefix: String::new(),
+            rules: vec![],
+        })
+    }
+
+    /// Concatenates new `.gitattributes` content at the `prefix` directory.
+    ///
+    /// The `prefix` should be a slash-separated path relative to the workspace
+    /// root. Like Git, lines which can't be parsed are ignored.
+    pub fn chain(self: &Arc<GitAttributesFile>, prefix: &str, input: &[u8]) -> Arc<Self> {
+        let rules = input
+            .split(|b| *b == b'\n')
+            .filter_map(|line| parse_rule(prefix, std::str::from_utf8(line).ok()?))
+            .collect_vec();
+        if rules.is_empty() {
+            return self.clone();
+        }
+        let parent = if self.rules.is_empty() {
+            self.parent.clone() // omit the empty root
+        } else {
+            Some(self.clone())
+        };
+        Arc::new(GitAttributesFile {
+            parent,
+            prefix: prefix.to_owned(),
+            rules,
+        })
+    }
+
+    /// Concatenates new `.gitattributes` file at the `prefix` directory.
+    ///
+    /// The `prefix` should be a slash-separated path relative to the workspace
+    /// root.
+    pub fn chain_with_file(
+        self: &Arc<GitAttributesFile>,
+        prefix: &str,
+        file: PathBuf,
+    ) -> Result<Arc<GitAttributesFile>, GitAttributesError> {
+        if file.is_file() {
+            let buf = fs::read(&file).map_err(|err| GitAttributesError::ReadFile {
+                path: file,
+                source: err,
+            })?;
+            Ok(self.chain(prefix, &buf))
+        } else {
+            Ok(self.clone())
+        }
+    }
+
+    /// Returns the attributes of the file at `path`, which should be a
+    /// slash-separated path relative to the workspace root.
+    ///
+    /// Later lines and files in deeper directories take precedence.
+    pub fn attributes(&self, path: &str) -> FileAttributes {
+        let mut text = None;
+        let mut eol = None;
+        let mut lfs = None;
+        let rules = iter::successors(Some(self), |file| file.parent.as_deref())
+            .filter(|file| path.starts_with(&file.prefix))
+            .flat_map(|file| file.rules.iter().rev())
+            .filter(|rule| rule.matcher.matched(path, false).is_ignore());
+        for rule in rules {
+            text = text.or(rule.text);
+            eol = eol.or(rule.eol);
+            lfs = lfs.or(rule.lfs);
+            if text.is_some() && eol.is_some() && lfs.is_some() {
+                break;
+            }
+        }
+        FileAttributes {
+            text: text.unwrap_or_default(),
+            eol: eol.flatten(),
+            lfs: lfs.unwrap_or(false),
+        }
+    }
+}
+
+fn parse_rule(prefix: &str, line: &str) -> Option<AttributesRule> {
+    let mut tokens = line.split_ascii_whitespace();
+    let pattern = tokens.next()?;
+    // Negative patterns are forbidden in .gitattributes.
+    if pattern.starts_with('#') || pattern.starts_with('!') {
+        return None;
+    }
+    let mut text = None;
+    let mut eol = None;
+    let mut lfs = None;
+    for token in tokens {
+        match token {
+            "text" | "crlf" => text = Some(TextAttribute::Set),
+            "-text" | "-crlf" | "binary" => text = Some(TextAttribute::Unset),
+            "text=auto" => text = Some(TextAttribute::Auto),
+            "!text" | "!crlf" => text = Some(TextAttribute::Unspecified),
+            "eol=lf" => eol = Some(Some(EolAttribute::Lf)),
+            "eol=crlf" => eol = Some(Some(EolAttribute::Crlf)),
+            "-eol" | "!eol" => eol = Some(None),
+            "filter=lfs" => lfs = Some(true),
+            "-filter" | "!filter" => lfs = Some(false),
+            _ if token.starts_with("filter=") => lfs = Some(false),
+            _ => {}
+        }
+    }
+    if text.is_none() && eol.is_none() && lfs.is_none() {
+        return None;
+    }
+    let mut builder = gitignore::GitignoreBuilder::new(prefix);
+    builder.add_line(None, pattern).ok()?;
+    let matcher = builder.build().ok()?;
+    Some(AttributesRule {
+        matcher,
+        text,
+        eol,
+        lfs,
+    })
+}
+
+/// Loads `.gitattributes` files from a tree, caching them per directory.
+pub struct TreeGitAttributes<'a> {
+    tree: &'a MergedTree,
+    dirs: HashMap<RepoPathBuf, Arc<GitAttributesFile>>,
+}
+
+impl<'a> TreeGitAttributes<'a> {
+    /// Creates a loader for the given tree.
+    pub fn new(tree: &'a MergedTree) -> Self {
+        TreeGitAttributes {
+            tree,
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Returns the attributes of the file at `path` according to the
+    /// `.gitattributes` files in the tree.
+    pub fn attributes(&mut self, path: &RepoPath) -> BackendResult<FileAttributes> {
+        let dir = path.parent().unwrap_or(RepoPath::root());
+        let git_attributes = self.load_dir(dir)?;
+        Ok(git_attributes.attributes(path.as_internal_file_string()))
+    }
+
+    fn load_dir(&mut self, dir: &RepoPath) -> BackendResult<Arc<GitAttributesFile>> {
+        if let Some(git_attributes) = self.dirs.get(dir) {
+            return Ok(git_attributes.clone());
+        }
+        let parent = match dir.parent() {
+            Some(parent) => self.load_dir(parent)?,
+            None => GitAttributesFile::empty(),
+        };
+        let file_path = dir.join(RepoPathComponent::new(".gitattributes"));
+        // Conflicted .gitattributes files are ignored.
+        let git_attributes = match self.tree.path_value(&file_path)?.into_resolved() {
+            Ok(Some(TreeValue::File { id, .. })) => {
+                let mut content = vec![];
+                self.tree
+                    .store()
+                    .read_file(&file_path, &id)?
+                    .read_to_end(&mut content)
+                    .map_err(|err| BackendError::ReadFile {
+                        path: file_path.clone(),
+                        id: id.clone(),
+                        source: err.into(),
+                    })?;
+                parent.chain(&dir.to_internal_dir_string(), &content)
+            }
+            _ => parent,
+        };
+        self.dirs.insert(dir.to_owned(), git_attributes.clone());
+        Ok(git_attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(input: &[u8], path: &str) -> FileAttributes {
+        GitAttributesFile::empty().chain("", input).attributes(path)
+    }
+
+    #[test]
+    fn test_gitattributes_empty() {
+        assert_eq!(attributes(b"", "foo"), FileAttributes::default());
+        assert_eq!(attributes(b"# text\n", "foo"), FileAttributes::default());
+    }
+
+    #[test]
+    fn test_gitattributes_text() {
+        let input = b"*.txt text\n*.bin binary\n*.auto text=auto\n*.crlf -crlf\n";
+        assert_eq!(attributes(input, "a.txt").text, TextAttribute::Set);
+        assert_eq!(attributes(input, "dir/a.txt").text, TextAttribute::Set);
+        assert_eq!(attributes(input, "a.bin").text, TextAttribute::Unset);
+        assert_eq!(attributes(input, "a.auto").text, TextAttribute::Auto);
+        assert_eq!(attributes(input, "a.crlf").text, TextAttribute::Unset);
+        assert_eq!(attributes(input, "a.rs").text, TextAttribute::Unspecified);
+    }
+
+    #[test]
+    fn test_gitattributes_eol() {
+        let input = b"* eol=lf\r\n*.bat eol=crlf\r\n";
+        assert_eq!(
+            attributes(input, "a.bat"),
+            FileAttributes {
+                text: TextAttribute::Unspecified,
+                eol: Some(EolAttribute::Crlf),
+                lfs: false,
+            }
+        );
+        assert_eq!(attributes(input, "a.sh").eol, Some(EolAttribute::Lf));
+    }
+
+    #[test]
+    fn test_gitattributes_later_lines_take_precedence() {
+        let input = b"* text eol=crlf\n*.png -text\n*.txt !eol\n";
+        assert_eq!(
+            attributes(input, "a.png"),
+            FileAttributes {
+                text: TextAttribute::Unset,
+                eol: Some(EolAttribute::Crlf),
+                lfs: false,
+            }
+        );
+        assert_eq!(
+            attributes(input, "a.txt"),
+            FileAttributes {
+                text: TextAttribute::Set,
+                eol: None,
+                lfs: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_gitattributes_lfs() {
+        let input = b"*.psd filter=lfs diff=lfs merge=lfs -text\n*.c filter=other\n";
+        assert_eq!(
+            attributes(input, "a.psd"),
+            FileAttributes {
+                text: TextAttribute::Unset,
+                eol: None,
+                lfs: true,
+            }
+        );
+        assert!(!attributes(input, "a.c").lfs);
+        assert!(!attributes(input, "a.txt").lfs);
+
+        let input = b"* filter=lfs\n*.txt !filter\n";
+        assert!(attributes(input, "a.bin").lfs);
+        assert!(!attributes(input, "a.txt").lfs);
+    }
+
+    #[test]
+    fn test_gitattributes_chained() {
+        let file = GitAttributesFile::empty()
+            .chain("", b"*.txt text eol=crlf\n")
+            .chain("dir/", b"*.txt -text\n/anchored.md text\n");
+        assert_eq!(
+            file.attributes("a.txt"),
+            FileAttributes {
+                text: TextAttribute::Set,
+                eol: Some(EolAttribute::Crlf),
+                lfs: false,
+            }
+        );
+        assert_eq!(
+            file.attributes("dir/sub/a.txt"),
+            FileAttributes {
+                text: TextAttribute::Unset,
+                eol: Some(EolAttribute::Crlf),
+                lfs: false,
+            }
+        );
+        assert_eq!(file.attributes("dir/anchored.md").text, TextAttribute::Set);
+        assert_eq!(
+            file.attributes("dir/sub/anchored.md").text,
+            TextAttribute::Unspecified
+        );
+        assert_eq!(
+            file.attributes("anchored.md").text,
+            TextAttribute::Unspecified
+        );
+    }
+
+    #[test]
+    fn test_gitattributes_ignores_invalid_lines() {
+        let input = b"!*.txt text\n\xff text\n*.md text\n";
+        assert_eq!(attributes(input, "a.txt").text, TextAttribute::Unspecified);
+        assert_eq!(attributes(input, "a.md").text, TextAttribute::Set);
+    }
+}
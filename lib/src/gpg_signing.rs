@@ -74,6 +74,27 @@ fn parse_gpg_verify_output(
         .ok_or(SignError::InvalidSignatureFormat)
 }
 
+// gpgsm emits the same status lines as gpg, except that it reports a missing
+// certificate as:
+//  [GNUPG:] ERROR verify.findkey <error code>
+// instead of NO_PUBKEY, without telling which certificate was looked up.
+fn parse_gpgsm_verify_output(
+    output: &[u8],
+    allow_expired_keys: bool,
+) -> Result<Verification, SignError> {
+    parse_gpg_verify_output(output, allow_expired_keys).or_else(|err| {
+        let missing_cert = output
+            .split(|&b| b == b'\n')
+            .filter_map(|line| line.strip_prefix(b"[GNUPG:] ERROR "))
+            .any(|line| line.starts_with(b"verify.findkey "));
+        if missing_cert {
+            Ok(Verification::new(SigStatus::Unknown, None, None))
+        } else {
+            Err(err)
+        }
+    })
+}
+
 fn run_sign_command(command: &mut Command, input: &[u8]) -> Result<Vec<u8>, GpgError> {
     tracing::info!(?command, "running GPG signing command");
     let process = command.stderr(Stdio::piped()).spawn()?;
@@ -199,6 +220,82 @@ impl SigningBackend for GpgBackend {
     }
 }
 
+#[derive(Debug)]
+pub struct GpgsmBackend {
+    program: OsString,
+    allow_expired_keys: bool,
+    extra_args: Vec<OsString>,
+}
+
+impl GpgsmBackend {
+    pub fn new(program: OsString, allow_expired_keys: bool) -> Self {
+        Self {
+            program,
+            allow_expired_keys,
+            extra_args: vec![],
+        }
+    }
+
+    /// Primarily intended for testing
+    pub fn with_extra_args(mut self, args: &[OsString]) -> Self {
+        self.extra_args.extend_from_slice(args);
+        self
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let program = settings.get_string("signing.backends.gpgsm.program")?;
+        let allow_expired_keys = settings.get_bool("signing.backends.gpgsm.allow-expired-keys")?;
+        Ok(Self::new(program.into(), allow_expired_keys))
+    }
+
+    fn create_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .args(&self.extra_args);
+        command
+    }
+}
+
+impl SigningBackend for GpgsmBackend {
+    fn name(&self) -> &str {
+        "gpgsm"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN SIGNED MESSAGE-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> Result<Vec<u8>, SignError> {
+        Ok(match key {
+            Some(key) => run_sign_command(self.create_command().args(["-abu", key]), data)?,
+            None => run_sign_command(self.create_command().arg("-ab"), data)?,
+        })
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
+        let mut signature_file = tempfile::Builder::new()
+            .prefix(".jj-gpgsm-sig-tmp-")
+            .tempfile()
+            .map_err(GpgError::Io)?;
+        signature_file.write_all(signature).map_err(GpgError::Io)?;
+        signature_file.flush().map_err(GpgError::Io)?;
+
+        let sig_path = signature_file.into_temp_path();
+
+        let output = run_verify_command(
+            self.create_command()
+                .args(["--status-fd=1", "--verify"])
+                .arg(&sig_path)
+                .arg("-"),
+            data,
+        )?;
+
+        parse_gpgsm_verify_output(&output, self.allow_expired_keys)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +345,34 @@ mod tests {
             Verification::new(SigStatus::Bad, Some("123".into()), Some("456".into()))
         );
     }
+
+    #[test]
+    fn gpgsm_verify_unknown_certificate() {
+        assert_eq!(
+            parse_gpgsm_verify_output(
+                b"[GNUPG:] NEWSIG\n[GNUPG:] ERROR verify.findkey 50331657\n",
+                true
+            )
+            .unwrap(),
+            Verification::new(SigStatus::Unknown, None, None)
+        );
+    }
+
+    #[test]
+    fn gpgsm_verify_good_signature() {
+        assert_eq!(
+            parse_gpgsm_verify_output(b"[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 123 /CN=456\n", true)
+                .unwrap(),
+            Verification::new(SigStatus::Good, Some("123".into()), Some("/CN=456".into()))
+        );
+    }
+
+    #[test]
+    fn gpgsm_verify_invalid_signature_format() {
+        use assert_matches::assert_matches;
+        assert_matches!(
+            parse_gpgsm_verify_output(b"[GNUPG:] NEWSIG\n", true),
+            Err(SignError::InvalidSignatureFormat)
+        );
+    }
 }
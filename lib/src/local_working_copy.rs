@@ -72,13 +72,22 @@ use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
+use crate::eol::EolConversionMode;
+use crate::eol::EolConverter;
 use crate::file_util::check_symlink_support;
 use crate::file_util::try_symlink;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetParseError;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::WatchmanConfig;
+#[cfg(feature = "git")]
+use crate::git_lfs::LfsObjectStore;
+use crate::gitattributes::FileAttributes;
+use crate::gitattributes::GitAttributesFile;
+use crate::gitattributes::TreeGitAttributes;
 use crate::gitignore::GitIgnoreFile;
 use crate::lock::FileLock;
 use crate::matchers::DifferenceMatcher;
@@ -86,7 +95,6 @@ use crate::matchers::EverythingMatcher;
 use crate::matchers::FilesMatcher;
 use crate::matchers::IntersectionMatcher;
 use crate::matchers::Matcher;
-use crate::matchers::PrefixMatcher;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -99,6 +107,8 @@ use crate::op_store::WorkspaceId;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponent;
+use crate::sparse::sparse_matcher;
+use crate::sparse::SparsePattern;
 use crate::store::Store;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
@@ -412,8 +422,7 @@ pub struct TreeState {
     state_path: PathBuf,
     tree_id: MergedTreeId,
     file_states: FileStatesMap,
-    // Currently only path prefixes
-    sparse_patterns: Vec<RepoPathBuf>,
+    sparse_patterns: Vec<SparsePattern>,
     own_mtime: MillisSinceEpoch,
     symlink_support: bool,
 
@@ -502,18 +511,24 @@ fn is_file_state_entries_proto_unique_and_sorted(
 
 fn sparse_patterns_from_proto(
     proto: Option<&crate::protos::working_copy::SparsePatterns>,
-) -> Vec<RepoPathBuf> {
+) -> Result<Vec<SparsePattern>, FilesetParseError> {
     let mut sparse_patterns = vec![];
     if let Some(proto_sparse_patterns) = proto {
+        // Path prefixes were written by older versions
         for prefix in &proto_sparse_patterns.prefixes {
-            sparse_patterns.push(RepoPathBuf::from_internal_string(prefix));
+            let path = RepoPath::from_internal_string(prefix);
+            sparse_patterns.push(SparsePattern::from_path(path));
+        }
+        for text in &proto_sparse_patterns.patterns {
+            let mut diagnostics = FilesetDiagnostics::new();
+            sparse_patterns.push(SparsePattern::parse(&mut diagnostics, text)?);
         }
     } else {
         // For compatibility with old working copies.
         // TODO: Delete this is late 2022 or so.
-        sparse_patterns.push(RepoPathBuf::root());
+        sparse_patterns.push(SparsePattern::everything());
     }
-    sparse_patterns
+    Ok(sparse_patterns)
 }
 
 /// Creates intermediate directories from the `working_copy_path` to the
@@ -730,6 +745,11 @@ pub enum TreeStateError {
         path: PathBuf,
         source: prost::DecodeError,
     },
+    #[error("Parsing sparse patterns of tree state from {path}")]
+    ParseSparsePatterns {
+        path: PathBuf,
+        source: FilesetParseError,
+    },
     #[error("Writing tree state to temporary file {path}")]
     WriteTreeState { path: PathBuf, source: io::Error },
     #[error("Persisting tree state to file {path}")]
@@ -751,12 +771,12 @@ impl TreeState {
         self.file_states.all()
     }
 
-    pub fn sparse_patterns(&self) -> &Vec<RepoPathBuf> {
+    pub fn sparse_patterns(&self) -> &Vec<SparsePattern> {
         &self.sparse_patterns
     }
 
     fn sparse_matcher(&self) -> Box<dyn Matcher> {
-        Box::new(PrefixMatcher::new(&self.sparse_patterns))
+        sparse_matcher(&self.sparse_patterns)
     }
 
     pub fn init(
@@ -777,7 +797,7 @@ impl TreeState {
             state_path,
             tree_id,
             file_states: FileStatesMap::new(),
-            sparse_patterns: vec![RepoPathBuf::root()],
+            sparse_patterns: vec![SparsePattern::everything()],
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
@@ -842,7 +862,13 @@ impl TreeState {
         }
         self.file_states =
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
-        self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        self.sparse_patterns =
+            sparse_patterns_from_proto(proto.sparse_patterns.as_ref()).map_err(|err| {
+                TreeStateError::ParseSparsePatterns {
+                    path: tree_state_path.to_owned(),
+                    source: err,
+                }
+            })?;
         self.watchman_clock = proto.watchman_clock;
         Ok(())
     }
@@ -863,11 +889,19 @@ impl TreeState {
         proto.file_states = self.file_states.data.clone();
         // `FileStatesMap` is guaranteed to be sorted.
         proto.is_file_states_sorted = true;
+        // Plain paths are written as prefixes so older versions can still read
+        // them. Prefixes are loaded before the patterns, so only the leading
+        // paths can be stored that way without changing the order.
         let mut sparse_patterns = crate::protos::working_copy::SparsePatterns::default();
-        for path in &self.sparse_patterns {
+        let mut patterns = self.sparse_patterns.iter().peekable();
+        while let Some(path) = patterns.peek().and_then(|pattern| pattern.as_path()) {
             sparse_patterns
                 .prefixes
                 .push(path.as_internal_file_string().to_owned());
+            patterns.next();
+        }
+        for pattern in patterns {
+            sparse_patterns.patterns.push(pattern.as_str().to_owned());
         }
         proto.sparse_patterns = Some(sparse_patterns);
         proto.watchman_clock = self.watchman_clock.clone();
@@ -956,6 +990,7 @@ impl TreeState {
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            eol_conversion_mode,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
@@ -998,11 +1033,15 @@ impl TreeState {
                 progress,
                 max_new_file_size,
                 conflict_marker_style,
+                eol_conversion_mode,
+                #[cfg(feature = "git")]
+                lfs_objects: LfsObjectStore::for_store(&self.store),
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
                 disk_dir: self.working_copy_path.clone(),
                 git_ignore: base_ignores.clone(),
+                git_attributes: GitAttributesFile::empty(),
                 file_states: self.file_states.all(),
             };
             // Here we use scope as a queue of per-directory jobs.
@@ -1109,6 +1148,7 @@ struct DirectoryToVisit<'a> {
     dir: RepoPathBuf,
     disk_dir: PathBuf,
     git_ignore: Arc<GitIgnoreFile>,
+    git_attributes: Arc<GitAttributesFile>,
     file_states: FileStates<'a>,
 }
 
@@ -1138,6 +1178,9 @@ struct FileSnapshotter<'a> {
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
     conflict_marker_style: ConflictMarkerStyle,
+    eol_conversion_mode: EolConversionMode,
+    #[cfg(feature = "git")]
+    lfs_objects: Option<LfsObjectStore>,
 }
 
 impl FileSnapshotter<'_> {
@@ -1175,11 +1218,16 @@ impl FileSnapshotter<'_> {
             dir,
             disk_dir,
             git_ignore,
+            git_attributes,
             file_states,
         } = directory_to_visit;
 
         let git_ignore = git_ignore
             .chain_with_file(&dir.to_internal_dir_string(), disk_dir.join(".gitignore"))?;
+        let git_attributes = git_attributes.chain_with_file(
+            &dir.to_internal_dir_string(),
+            disk_dir.join(".gitattributes"),
+        )?;
         let dir_entries: Vec<_> = disk_dir
             .read_dir()
             .and_then(|entries| entries.try_collect())
@@ -1193,8 +1241,15 @@ impl FileSnapshotter<'_> {
             // sequential scan should be fast enough.
             .with_min_len(100)
             .filter_map(|entry| {
-                self.process_dir_entry(&dir, &git_ignore, file_states, &entry, scope)
-                    .transpose()
+                self.process_dir_entry(
+                    &dir,
+                    &git_ignore,
+                    &git_attributes,
+                    file_states,
+                    &entry,
+                    scope,
+                )
+                .transpose()
             })
             .map(|item| match item {
                 Ok((PresentDirEntryKind::Dir, name)) => Ok(Either::Left(name)),
@@ -1211,6 +1266,7 @@ impl FileSnapshotter<'_> {
         &'scope self,
         dir: &RepoPath,
         git_ignore: &Arc<GitIgnoreFile>,
+        git_attributes: &Arc<GitAttributesFile>,
         file_states: FileStates<'scope>,
         entry: &DirEntry,
         scope: &rayon::Scope<'scope>,
@@ -1249,6 +1305,7 @@ impl FileSnapshotter<'_> {
                     dir: path,
                     disk_dir: entry.path(),
                     git_ignore: git_ignore.clone(),
+                    git_attributes: git_attributes.clone(),
                     file_states,
                 };
                 self.spawn_ok(scope, |scope| {
@@ -1290,9 +1347,11 @@ impl FileSnapshotter<'_> {
                     self.untracked_paths_tx.send((path, reason)).ok();
                     Ok(None)
                 } else if let Some(new_file_state) = file_state(&metadata) {
+                    let attributes = git_attributes.attributes(path.as_internal_file_string());
                     self.process_present_file(
                         path,
                         &entry.path(),
+                        &attributes,
                         maybe_current_file_state.as_ref(),
                         new_file_state,
                     )?;
@@ -1328,9 +1387,11 @@ impl FileSnapshotter<'_> {
                 }
             };
             if let Some(new_file_state) = metadata.as_ref().and_then(file_state) {
+                let attributes = self.load_git_attributes(tracked_path)?;
                 self.process_present_file(
                     tracked_path.to_owned(),
                     &disk_path,
+                    &attributes,
                     Some(&current_file_state),
                     new_file_state,
                 )?;
@@ -1341,16 +1402,33 @@ impl FileSnapshotter<'_> {
         Ok(())
     }
 
+    /// Loads the attributes of a file from the `.gitattributes` files in its
+    /// ancestor directories. Used for files whose directories aren't visited.
+    fn load_git_attributes(&self, path: &RepoPath) -> Result<FileAttributes, SnapshotError> {
+        let dirs = iter::successors(path.parent(), |dir| dir.parent()).collect_vec();
+        let mut git_attributes = GitAttributesFile::empty();
+        for dir in dirs.into_iter().rev() {
+            let disk_dir = dir.to_fs_path(&self.tree_state.working_copy_path)?;
+            git_attributes = git_attributes.chain_with_file(
+                &dir.to_internal_dir_string(),
+                disk_dir.join(".gitattributes"),
+            )?;
+        }
+        Ok(git_attributes.attributes(path.as_internal_file_string()))
+    }
+
     fn process_present_file(
         &self,
         path: RepoPathBuf,
         disk_path: &Path,
+        attributes: &FileAttributes,
         maybe_current_file_state: Option<&FileState>,
         mut new_file_state: FileState,
     ) -> Result<(), SnapshotError> {
         let update = self.get_updated_tree_value(
             &path,
             disk_path,
+            attributes,
             maybe_current_file_state,
             &new_file_state,
         )?;
@@ -1407,6 +1485,7 @@ impl FileSnapshotter<'_> {
         &self,
         repo_path: &RepoPath,
         disk_path: &Path,
+        attributes: &FileAttributes,
         maybe_current_file_state: Option<&FileState>,
         new_file_state: &FileState,
     ) -> Result<Option<MergedTreeValue>, SnapshotError> {
@@ -1442,6 +1521,7 @@ impl FileSnapshotter<'_> {
                     .write_path_to_store(
                         repo_path,
                         disk_path,
+                        attributes,
                         &current_tree_values,
                         executable,
                         maybe_current_file_state.and_then(|state| state.materialized_conflict_data),
@@ -1471,6 +1551,7 @@ impl FileSnapshotter<'_> {
         &self,
         repo_path: &RepoPath,
         disk_path: &Path,
+        attributes: &FileAttributes,
         current_tree_values: &MergedTreeValue,
         executable: FileExecutableFlag,
         materialized_conflict_data: Option<MaterializedConflictData>,
@@ -1478,7 +1559,9 @@ impl FileSnapshotter<'_> {
         if let Some(current_tree_value) = current_tree_values.as_resolved() {
             #[cfg(unix)]
             let _ = current_tree_value; // use the variable
-            let id = self.write_file_to_store(repo_path, disk_path).await?;
+            let id = self
+                .write_file_to_store(repo_path, disk_path, attributes)
+                .await?;
             // On Windows, we preserve the executable bit from the current tree.
             #[cfg(windows)]
             let executable = {
@@ -1543,12 +1626,42 @@ impl FileSnapshotter<'_> {
         &self,
         path: &RepoPath,
         disk_path: &Path,
+        attributes: &FileAttributes,
     ) -> Result<FileId, SnapshotError> {
         let mut file = File::open(disk_path).map_err(|err| SnapshotError::Other {
             message: format!("Failed to open file {}", disk_path.display()),
             err: err.into(),
         })?;
-        Ok(self.store().write_file(path, &mut file).await?)
+        #[cfg(feature = "git")]
+        if let Some(lfs_objects) = self.lfs_objects.as_ref().filter(|_| attributes.lfs) {
+            let pointer = lfs_objects
+                .clean(&mut file)
+                .map_err(|err| SnapshotError::Other {
+                    message: format!("Failed to store {} in Git LFS", disk_path.display()),
+                    err: err.into(),
+                })?;
+            return Ok(self
+                .store()
+                .write_file(path, &mut pointer.as_slice())
+                .await?);
+        }
+        let eol_converter = EolConverter::new(attributes, self.eol_conversion_mode);
+        if !eol_converter.may_convert_on_snapshot() {
+            return Ok(self.store().write_file(path, &mut file).await?);
+        }
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)
+            .map_err(|err| SnapshotError::Other {
+                message: format!("Failed to read file {}", disk_path.display()),
+                err: err.into(),
+            })?;
+        let contents = eol_converter
+            .convert_for_snapshot(&contents)
+            .unwrap_or(contents);
+        Ok(self
+            .store()
+            .write_file(path, &mut contents.as_slice())
+            .await?)
     }
 
     async fn write_symlink_to_store(
@@ -1685,12 +1798,7 @@ impl TreeState {
             other => CheckoutError::InternalBackendError(other),
         })?;
         let stats = self
-            .update(
-                &old_tree,
-                new_tree,
-                self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
-            )
+            .update(&old_tree, new_tree, self.sparse_matcher().as_ref(), options)
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
@@ -1698,7 +1806,7 @@ impl TreeState {
 
     pub fn set_sparse_patterns(
         &mut self,
-        sparse_patterns: Vec<RepoPathBuf>,
+        sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         let tree = self.current_tree().map_err(|err| match err {
@@ -1707,26 +1815,17 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
-        let old_matcher = PrefixMatcher::new(&self.sparse_patterns);
-        let new_matcher = PrefixMatcher::new(&sparse_patterns);
+        // Only the paths whose membership changed are updated
+        let old_matcher = sparse_matcher(&self.sparse_patterns);
+        let new_matcher = sparse_matcher(&sparse_patterns);
         let added_matcher = DifferenceMatcher::new(&new_matcher, &old_matcher);
         let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
-            .update(
-                &empty_tree,
-                &tree,
-                &added_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&empty_tree, &tree, &added_matcher, options)
             .block_on()?;
         let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&tree, &empty_tree, &removed_matcher, options)
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
         assert_eq!(added_stats.updated_files, 0);
@@ -1747,8 +1846,12 @@ impl TreeState {
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
+        let &CheckoutOptions {
+            conflict_marker_style,
+            eol_conversion_mode,
+        } = options;
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
         let mut stats = CheckoutStats {
@@ -1759,6 +1862,9 @@ impl TreeState {
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
+        let mut git_attributes = TreeGitAttributes::new(new_tree);
+        #[cfg(feature = "git")]
+        let lfs_objects = LfsObjectStore::for_store(&self.store);
         let mut diff_stream = old_tree
             .diff_stream(new_tree, matcher)
             .map(|TreeDiffEntry { path, values }| async {
@@ -1791,7 +1897,6 @@ impl TreeState {
             if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_)))
                 && matches!(after, MaterializedTreeValue::GitSubmodule(_))
             {
-                eprintln!("ignoring git submodule at {path:?}");
                 // Not updating the file state as if there were no diffs. Leave
                 // the state type as FileType::GitSubmodule if it was before.
                 continue;
@@ -1830,7 +1935,41 @@ impl TreeState {
                     executable,
                     mut reader,
                     ..
-                } => self.write_file(&disk_path, &mut reader, executable)?,
+                } => {
+                    let attributes = git_attributes.attributes(&path)?;
+                    #[cfg(feature = "git")]
+                    if let Some(lfs_objects) = lfs_objects.as_ref().filter(|_| attributes.lfs) {
+                        reader =
+                            lfs_objects
+                                .smudge(reader)
+                                .map_err(|err| CheckoutError::Other {
+                                    message: format!(
+                                        "Failed to read Git LFS object for {}",
+                                        path.as_internal_file_string()
+                                    ),
+                                    err: err.into(),
+                                })?;
+                    }
+                    let eol_converter = EolConverter::new(&attributes, eol_conversion_mode);
+                    if eol_converter.may_convert_on_checkout() {
+                        let mut contents = vec![];
+                        reader
+                            .read_to_end(&mut contents)
+                            .map_err(|err| CheckoutError::Other {
+                                message: format!(
+                                    "Failed to read file {}",
+                                    path.as_internal_file_string()
+                                ),
+                                err: err.into(),
+                            })?;
+                        let contents = eol_converter
+                            .convert_for_checkout(&contents)
+                            .unwrap_or(contents);
+                        self.write_file(&disk_path, &mut contents.as_slice(), executable)?
+                    } else {
+                        self.write_file(&disk_path, &mut reader, executable)?
+                    }
+                }
                 MaterializedTreeValue::Symlink { id: _, target } => {
                     if self.symlink_support {
                         self.write_symlink(&disk_path, target)?
@@ -1838,10 +1977,7 @@ impl TreeState {
                         self.write_file(&disk_path, &mut target.as_bytes(), false)?
                     }
                 }
-                MaterializedTreeValue::GitSubmodule(_) => {
-                    eprintln!("ignoring git submodule at {path:?}");
-                    FileState::for_gitsubmodule()
-                }
+                MaterializedTreeValue::GitSubmodule(_) => FileState::for_gitsubmodule(),
                 MaterializedTreeValue::Tree(_) => {
                     panic!("unexpected tree entry in diff at {path:?}");
                 }
@@ -1909,10 +2045,7 @@ impl TreeState {
                         TreeValue::Conflict(_id) => {
                             panic!("unexpected conflict entry in diff at {path:?}");
                         }
-                        TreeValue::GitSubmodule(_id) => {
-                            eprintln!("ignoring git submodule at {path:?}");
-                            FileType::GitSubmodule
-                        }
+                        TreeValue::GitSubmodule(_id) => FileType::GitSubmodule,
                         TreeValue::Tree(_id) => {
                             panic!("unexpected tree entry in diff at {path:?}");
                         }
@@ -1989,7 +2122,7 @@ impl WorkingCopy for LocalWorkingCopy {
         Ok(self.tree_state()?.current_tree_id())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         Ok(self.tree_state()?.sparse_patterns())
     }
 
@@ -2306,13 +2439,13 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         Ok(())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         self.wc.sparse_patterns()
     }
 
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         // TODO: Write a "pending_checkout" file with new sparse patterns so we can
@@ -452,6 +452,52 @@ impl<M1: Matcher, M2: Matcher> Matcher for IntersectionMatcher<M1, M2> {
     }
 }
 
+/// Matches paths that are matched by the input matcher, and paths in the
+/// directories matched by the input matcher.
+///
+/// For example, file glob `dir/*` matches files in `dir` non-recursively, but
+/// this matches files in its subdirectories too.
+#[derive(Clone, Debug)]
+pub struct RecursiveMatcher<M> {
+    input: M,
+}
+
+impl<M: Matcher> RecursiveMatcher<M> {
+    pub fn new(input: M) -> Self {
+        Self { input }
+    }
+
+    fn matches_self_or_ancestor(&self, path: &RepoPath) -> bool {
+        iter::successors(Some(path), |path| path.parent()).any(|path| self.input.matches(path))
+    }
+}
+
+impl<M: Matcher> Matcher for RecursiveMatcher<M> {
+    fn matches(&self, file: &RepoPath) -> bool {
+        self.matches_self_or_ancestor(file)
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        if self.matches_self_or_ancestor(dir) {
+            return Visit::AllRecursively;
+        }
+        match self.input.visit(dir) {
+            Visit::AllRecursively => Visit::AllRecursively,
+            Visit::Nothing => Visit::Nothing,
+            // Matched "files" may be directories, which have to be visited
+            Visit::Specific { dirs, files } => {
+                let dirs = match (dirs, &files) {
+                    (VisitDirs::All, _) | (_, VisitFiles::All) => VisitDirs::All,
+                    (VisitDirs::Set(dirs), VisitFiles::Set(files)) => {
+                        VisitDirs::Set(dirs.iter().chain(files).cloned().collect())
+                    }
+                };
+                Visit::Specific { dirs, files }
+            }
+        }
+    }
+}
+
 /// Tree that maps `RepoPath` to value of type `V`.
 #[derive(Clone, Default, Eq, PartialEq)]
 struct RepoPathTree<V> {
@@ -985,6 +1031,56 @@ mod tests {
         assert_eq!(m.visit(repo_path("bar/foo")), Visit::AllRecursively);
     }
 
+    #[test]
+    fn test_recursivematcher_file_globs() {
+        let m = RecursiveMatcher::new(FileGlobsMatcher::new([(
+            repo_path("dir"),
+            glob::Pattern::new("*/api").unwrap(),
+        )]));
+
+        assert!(!m.matches(repo_path("dir")));
+        assert!(!m.matches(repo_path("dir/foo")));
+        assert!(m.matches(repo_path("dir/foo/api")));
+        assert!(m.matches(repo_path("dir/foo/api/file")));
+        assert!(m.matches(repo_path("dir/foo/api/sub/file")));
+        assert!(!m.matches(repo_path("dir/foo/bar/file")));
+        assert!(!m.matches(repo_path("other/foo/api")));
+
+        assert_eq!(
+            m.visit(RepoPath::root()),
+            Visit::sets(hashset! {RepoPathComponentBuf::from("dir")}, hashset! {})
+        );
+        assert_eq!(
+            m.visit(repo_path("dir/foo")),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::All,
+            }
+        );
+        assert_eq!(m.visit(repo_path("dir/foo/api")), Visit::AllRecursively);
+        assert_eq!(m.visit(repo_path("dir/foo/api/sub")), Visit::AllRecursively);
+        assert_eq!(m.visit(repo_path("other")), Visit::Nothing);
+    }
+
+    #[test]
+    fn test_recursivematcher_files() {
+        let m = RecursiveMatcher::new(FilesMatcher::new([repo_path("dir/foo")]));
+
+        assert!(m.matches(repo_path("dir/foo")));
+        assert!(m.matches(repo_path("dir/foo/file")));
+        assert!(!m.matches(repo_path("dir/bar")));
+
+        // A matched file may be a directory
+        assert_eq!(
+            m.visit(repo_path("dir")),
+            Visit::sets(
+                hashset! {RepoPathComponentBuf::from("foo")},
+                hashset! {RepoPathComponentBuf::from("foo")},
+            )
+        );
+        assert_eq!(m.visit(repo_path("dir/foo")), Visit::AllRecursively);
+    }
+
     #[test]
     fn test_intersectionmatcher_intersecting_roots() {
         let m1 = PrefixMatcher::new([repo_path("foo"), repo_path("bar")]);
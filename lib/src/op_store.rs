@@ -32,6 +32,7 @@ use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
@@ -253,10 +254,10 @@ impl<'a> RefTargetOptionExt for Option<&'a RemoteRef> {
     }
 }
 
-/// Local and remote bookmarks of the same bookmark name.
+/// Local and remote bookmarks (or tags) of the same name.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct BookmarkTarget<'a> {
-    /// The commit the bookmark points to locally.
+    /// The commit the bookmark (or tag) points to locally.
     pub local_target: &'a RefTarget,
     /// `(remote_name, remote_ref)` pairs in lexicographical order.
     pub remote_refs: Vec<(&'a str, &'a RemoteRef)>,
@@ -280,6 +281,8 @@ pub struct View {
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: HashMap<WorkspaceId, CommitId>,
+    /// The state of an in-progress bisection, if any.
+    pub bisect: BisectState,
 }
 
 impl View {
@@ -296,6 +299,7 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: HashMap::new(),
+            bisect: BisectState::default(),
         }
     }
 
@@ -309,19 +313,63 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: HashMap::new(),
+            bisect: BisectState::default(),
+        }
+    }
+}
+
+/// Commits marked while searching for the commit that introduced a change.
+///
+/// The bisection is inactive if no commits are marked.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BisectState {
+    /// Commits known not to have the change.
+    pub good_ids: Vec<CommitId>,
+    /// Commits known to have the change.
+    pub bad_ids: Vec<CommitId>,
+    /// Commits that couldn't be tested.
+    pub skipped_ids: Vec<CommitId>,
+}
+
+impl BisectState {
+    /// Returns true if a bisection is in progress.
+    pub fn is_active(&self) -> bool {
+        !(self.good_ids.is_empty() && self.bad_ids.is_empty() && self.skipped_ids.is_empty())
+    }
+}
+
+// Inactive state doesn't contribute to the hash so that views without
+// bisection keep the same ids as before the field was introduced.
+impl ContentHash for BisectState {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        if self.is_active() {
+            self.good_ids.hash(state);
+            self.bad_ids.hash(state);
+            self.skipped_ids.hash(state);
         }
     }
 }
 
 /// Represents the state of the remote repo.
-#[derive(ContentHash, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct RemoteView {
     // TODO: Do we need to support tombstones for remote bookmarks? For example, if the bookmark
     // has been deleted locally and you pull from a remote, maybe it should make a difference
     // whether the bookmark is known to have existed on the remote. We may not want to resurrect
     // the bookmark if the bookmark's state on the remote was just not known.
     pub bookmarks: BTreeMap<String, RemoteRef>,
-    // TODO: pub tags: BTreeMap<String, RemoteRef>,
+    pub tags: BTreeMap<String, RemoteRef>,
+}
+
+// Tags don't contribute to the hash unless present so that views without
+// remote tags keep the same ids as before the field was introduced.
+impl ContentHash for RemoteView {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        self.bookmarks.hash(state);
+        if !self.tags.is_empty() {
+            self.tags.hash(state);
+        }
+    }
 }
 
 /// Iterates pair of local and remote bookmarks by bookmark name.
@@ -329,34 +377,46 @@ pub(crate) fn merge_join_bookmark_views<'a>(
     local_bookmarks: &'a BTreeMap<String, RefTarget>,
     remote_views: &'a BTreeMap<String, RemoteView>,
 ) -> impl Iterator<Item = (&'a str, BookmarkTarget<'a>)> {
-    let mut local_bookmarks_iter = local_bookmarks
+    merge_join_ref_views(local_bookmarks, flatten_remote_bookmarks(remote_views))
+}
+
+/// Iterates pair of local and remote tags by tag name.
+pub(crate) fn merge_join_tag_views<'a>(
+    local_tags: &'a BTreeMap<String, RefTarget>,
+    remote_views: &'a BTreeMap<String, RemoteView>,
+) -> impl Iterator<Item = (&'a str, BookmarkTarget<'a>)> {
+    merge_join_ref_views(local_tags, flatten_remote_tags(remote_views))
+}
+
+fn merge_join_ref_views<'a>(
+    local_refs: &'a BTreeMap<String, RefTarget>,
+    remote_refs: impl Iterator<Item = ((&'a str, &'a str), &'a RemoteRef)>,
+) -> impl Iterator<Item = (&'a str, BookmarkTarget<'a>)> {
+    let mut local_refs_iter = local_refs
         .iter()
-        .map(|(bookmark_name, target)| (bookmark_name.as_str(), target))
+        .map(|(name, target)| (name.as_str(), target))
         .peekable();
-    let mut remote_bookmarks_iter = flatten_remote_bookmarks(remote_views).peekable();
+    let mut remote_refs_iter = remote_refs.peekable();
 
     iter::from_fn(move || {
-        // Pick earlier bookmark name
-        let (bookmark_name, local_target) = if let Some(&((remote_bookmark_name, _), _)) =
-            remote_bookmarks_iter.peek()
+        // Pick earlier ref name
+        let (name, local_target) = if let Some(&((remote_ref_name, _), _)) = remote_refs_iter.peek()
         {
-            local_bookmarks_iter
-                .next_if(|&(local_bookmark_name, _)| local_bookmark_name <= remote_bookmark_name)
-                .unwrap_or((remote_bookmark_name, RefTarget::absent_ref()))
+            local_refs_iter
+                .next_if(|&(local_ref_name, _)| local_ref_name <= remote_ref_name)
+                .unwrap_or((remote_ref_name, RefTarget::absent_ref()))
         } else {
-            local_bookmarks_iter.next()?
+            local_refs_iter.next()?
         };
-        let remote_refs = remote_bookmarks_iter
-            .peeking_take_while(|&((remote_bookmark_name, _), _)| {
-                remote_bookmark_name == bookmark_name
-            })
+        let remote_refs = remote_refs_iter
+            .peeking_take_while(|&((remote_ref_name, _), _)| remote_ref_name == name)
             .map(|((_, remote_name), remote_ref)| (remote_name, remote_ref))
             .collect();
         let bookmark_target = BookmarkTarget {
             local_target,
             remote_refs,
         };
-        Some((bookmark_name, bookmark_target))
+        Some((name, bookmark_target))
     })
 }
 
@@ -379,6 +439,21 @@ pub(crate) fn flatten_remote_bookmarks(
         .kmerge_by(|(full_name1, _), (full_name2, _)| full_name1 < full_name2)
 }
 
+/// Iterates tag `((name, remote_name), remote_ref)`s in lexicographical order.
+pub(crate) fn flatten_remote_tags(
+    remote_views: &BTreeMap<String, RemoteView>,
+) -> impl Iterator<Item = ((&str, &str), &RemoteRef)> {
+    remote_views
+        .iter()
+        .map(|(remote_name, remote_view)| {
+            remote_view.tags.iter().map(move |(tag_name, remote_ref)| {
+                let full_name = (tag_name.as_str(), remote_name.as_str());
+                (full_name, remote_ref)
+            })
+        })
+        .kmerge_by(|(full_name1, _), (full_name2, _)| full_name1 < full_name2)
+}
+
 /// Represents an operation (transaction) on the repo view, just like how a
 /// Commit object represents an operation on the tree.
 ///
@@ -529,16 +604,19 @@ mod tests {
                     "bookmark1".to_owned() => git_bookmark1_remote_ref.clone(),
                     "bookmark2".to_owned() => git_bookmark2_remote_ref.clone(),
                 },
+                tags: btreemap! {},
             },
             "remote1".to_owned() => RemoteView {
                 bookmarks: btreemap! {
                     "bookmark1".to_owned() => remote1_bookmark1_remote_ref.clone(),
                 },
+                tags: btreemap! {},
             },
             "remote2".to_owned() => RemoteView {
                 bookmarks: btreemap! {
                     "bookmark2".to_owned() => remote2_bookmark2_remote_ref.clone(),
                 },
+                tags: btreemap! {},
             },
         };
         assert_eq!(
@@ -590,6 +668,7 @@ mod tests {
                 bookmarks: btreemap! {
                     "bookmark1".to_owned() => remote1_bookmark1_remote_ref.clone(),
                 },
+                tags: btreemap! {},
             },
         };
         assert_eq!(
@@ -35,9 +35,15 @@ use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::opset;
+use crate::opset::OpsetEvaluationContext;
+use crate::opset::OpsetParseContext;
+use crate::opset::OpsetParseError;
 use crate::repo::ReadonlyRepo;
-use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
+use crate::repo::RepoLoaderError;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetResolutionError;
 
 /// Error that may occur during evaluation of operation set expression.
 #[derive(Debug, Error)]
@@ -54,6 +60,18 @@ pub enum OpsetEvaluationError {
     /// Failed to access operation object.
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
+    /// Failed to parse operation set expression.
+    #[error(transparent)]
+    OpsetParse(#[from] OpsetParseError),
+    /// Failed to load repo at an operation.
+    #[error(transparent)]
+    RepoLoader(#[from] RepoLoaderError),
+    /// Failed to resolve revset expression in an operation set expression.
+    #[error(transparent)]
+    RevsetResolution(#[from] RevsetResolutionError),
+    /// Failed to evaluate revset expression in an operation set expression.
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
 }
 
 /// Error that may occur during parsing and resolution of operation set
@@ -87,8 +105,19 @@ pub enum OpsetResolutionError {
 /// Resolves operation set expression without loading a repo.
 pub fn resolve_op_for_load(
     repo_loader: &RepoLoader,
+    context: &OpsetParseContext,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
+    let ops = resolve_ops_for_load(repo_loader, context, op_str)?;
+    expect_single_op(op_str, ops)
+}
+
+/// Resolves operation set expression to operations without loading a repo.
+pub fn resolve_ops_for_load(
+    repo_loader: &RepoLoader,
+    context: &OpsetParseContext,
+    op_str: &str,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
     let op_store = repo_loader.op_store();
     let op_heads_store = repo_loader.op_heads_store().as_ref();
     let get_current_op = || {
@@ -101,7 +130,14 @@ pub fn resolve_op_for_load(
         })
     };
     let get_head_ops = || get_current_head_ops(op_store, op_heads_store);
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    let expression = opset::parse(op_str, context)?;
+    let context = OpsetEvaluationContext {
+        repo_loader,
+        parse_context: context,
+        get_current_op,
+        get_head_ops,
+    };
+    opset::evaluate(&expression, context)
 }
 
 /// Resolves operation set expression against the loaded repo.
@@ -109,17 +145,36 @@ pub fn resolve_op_for_load(
 /// The "@" symbol will be resolved to the operation the repo was loaded at.
 pub fn resolve_op_with_repo(
     repo: &ReadonlyRepo,
+    context: &OpsetParseContext,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    resolve_op_at(repo.op_store(), slice::from_ref(repo.operation()), op_str)
+    resolve_op_at(
+        repo.loader(),
+        slice::from_ref(repo.operation()),
+        context,
+        op_str,
+    )
 }
 
 /// Resolves operation set expression at the given head operations.
 pub fn resolve_op_at(
-    op_store: &Arc<dyn OpStore>,
+    repo_loader: &RepoLoader,
     head_ops: &[Operation],
+    context: &OpsetParseContext,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
+    let ops = resolve_ops_at(repo_loader, head_ops, context, op_str)?;
+    expect_single_op(op_str, ops)
+}
+
+/// Resolves operation set expression to operations at the given head
+/// operations. The returned operations are sorted in reverse topological order.
+pub fn resolve_ops_at(
+    repo_loader: &RepoLoader,
+    head_ops: &[Operation],
+    context: &OpsetParseContext,
+    op_str: &str,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
     let get_current_op = || match head_ops {
         [head_op] => Ok(head_op.clone()),
         [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()).into()),
@@ -130,43 +185,32 @@ pub fn resolve_op_at(
         .into()),
     };
     let get_head_ops = || Ok(head_ops.to_vec());
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    let expression = opset::parse(op_str, context)?;
+    let context = OpsetEvaluationContext {
+        repo_loader,
+        parse_context: context,
+        get_current_op,
+        get_head_ops,
+    };
+    opset::evaluate(&expression, context)
 }
 
-/// Resolves operation set expression with the given "@" symbol resolution
-/// callbacks.
-fn resolve_single_op(
-    op_store: &Arc<dyn OpStore>,
-    get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
-    get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+fn expect_single_op(
     op_str: &str,
+    mut ops: Vec<Operation>,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let op_symbol = op_str.trim_end_matches(['-', '+']);
-    let op_postfix = &op_str[op_symbol.len()..];
-    let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
-    let mut operation = match op_symbol {
-        "@" => get_current_op(),
-        s => resolve_single_op_from_store(op_store, s),
-    }?;
-    for c in op_postfix.chars() {
-        let mut neighbor_ops = match c {
-            '-' => operation.parents().try_collect()?,
-            '+' => find_child_ops(head_ops.as_ref().unwrap(), operation.id())?,
-            _ => unreachable!(),
-        };
-        operation = match neighbor_ops.len() {
-            0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()))?,
-            1 => neighbor_ops.pop().unwrap(),
-            _ => Err(OpsetResolutionError::MultipleOperations {
-                expr: op_str.to_owned(),
-                candidates: neighbor_ops.iter().map(|op| op.id().clone()).collect(),
-            })?,
-        };
+    match ops.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()).into()),
+        1 => Ok(ops.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: op_str.to_owned(),
+            candidates: ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
     }
-    Ok(operation)
 }
 
-fn resolve_single_op_from_store(
+pub(crate) fn resolve_single_op_from_store(
     op_store: &Arc<dyn OpStore>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
@@ -208,20 +252,6 @@ pub fn get_current_head_ops(
     Ok(head_ops)
 }
 
-/// Looks up children of the `root_op_id` by traversing from the `head_ops`.
-///
-/// This will be slow if the `root_op_id` is far away (or unreachable) from the
-/// `head_ops`.
-fn find_child_ops(
-    head_ops: &[Operation],
-    root_op_id: &OperationId,
-) -> OpStoreResult<Vec<Operation>> {
-    walk_ancestors(head_ops)
-        .take_while(|res| res.as_ref().map_or(true, |op| op.id() != root_op_id))
-        .filter_ok(|op| op.parent_ids().iter().any(|id| id == root_op_id))
-        .try_collect()
-}
-
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct OperationByEndTime(Operation);
 
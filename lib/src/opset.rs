@@ -0,0 +1,714 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation set language.
+//!
+//! Opsets select operations from the operation log in the same way as revsets
+//! select commits. Symbols are resolved to operation IDs (or `@`), and
+//! functions filter operations by their metadata.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use once_cell::unsync::OnceCell;
+
+use crate::backend::CommitId;
+use crate::dsl_util;
+use crate::dsl_util::collect_similar;
+use crate::dsl_util::AliasExpandError as _;
+use crate::dsl_util::InvalidArguments;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::operation::Operation;
+use crate::opset_parser;
+use crate::opset_parser::BinaryOp;
+use crate::opset_parser::ExpressionKind;
+use crate::opset_parser::ExpressionNode;
+use crate::opset_parser::FunctionCallNode;
+pub use crate::opset_parser::OpsetAliasesMap;
+pub use crate::opset_parser::OpsetParseError;
+pub use crate::opset_parser::OpsetParseErrorKind;
+use crate::opset_parser::UnaryOp;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo;
+use crate::repo::RepoLoader;
+use crate::revset;
+use crate::revset::DefaultSymbolResolver;
+use crate::revset::RevsetDiagnostics;
+use crate::revset::RevsetExpression;
+use crate::revset::RevsetParseContext;
+use crate::revset::RevsetResolutionError;
+use crate::revset::UserRevsetExpression;
+use crate::str_util::StringPattern;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Tree of operation set expressions.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// Empty set.
+    None,
+    /// All operations reachable from the head operations.
+    All,
+    /// The root operation.
+    Root,
+    /// The current operation (`@`).
+    Current,
+    /// The head operations.
+    Heads,
+    /// Full or abbreviated operation ID.
+    Id(String),
+    /// `x-`
+    Parents(Rc<Self>),
+    /// `x+`
+    Children(Rc<Self>),
+    /// `::x`
+    Ancestors(Rc<Self>),
+    /// `roots..heads`
+    Range {
+        /// Operations to exclude with their ancestors.
+        roots: Rc<Self>,
+        /// Operations to include with their ancestors.
+        heads: Rc<Self>,
+    },
+    /// `roots::heads`
+    DagRange {
+        /// Operations to include with their descendants.
+        roots: Rc<Self>,
+        /// Operations to include with their ancestors.
+        heads: Rc<Self>,
+    },
+    /// The `count` most recent operations in `candidates`.
+    Latest {
+        /// Operations to select from.
+        candidates: Rc<Self>,
+        /// Maximum number of operations.
+        count: usize,
+    },
+    /// Operations matching the predicate.
+    Filter(OpsetFilterPredicate),
+    /// `~x`
+    NotIn(Rc<Self>),
+    /// `x | y`
+    Union(Rc<Self>, Rc<Self>),
+    /// `x & y`
+    Intersection(Rc<Self>, Rc<Self>),
+    /// `x ~ y`
+    Difference(Rc<Self>, Rc<Self>),
+}
+
+/// Predicate to filter operations by metadata or content.
+#[derive(Clone, Debug)]
+pub enum OpsetFilterPredicate {
+    /// Matches `<username>@<hostname>`.
+    User(StringPattern),
+    /// Matches operation description.
+    Description(StringPattern),
+    /// Pure snapshotting of the working copy.
+    Snapshot,
+    /// Matches operation start time.
+    Time(DatePattern),
+    /// Matches all of the given tag values by key.
+    Tags(Vec<(String, StringPattern)>),
+    /// Operations adding or hiding commits in the revset.
+    Touched(Rc<UserRevsetExpression>),
+}
+
+/// Information needed to parse opset expression.
+#[derive(Clone)]
+pub struct OpsetParseContext<'a> {
+    aliases_map: &'a OpsetAliasesMap,
+    date_pattern_context: DatePatternContext,
+    revset_parse_context: Option<&'a RevsetParseContext<'a>>,
+}
+
+impl<'a> OpsetParseContext<'a> {
+    /// Creates new parse context. Revset expressions in `touched()` can only
+    /// be resolved if the `revset_parse_context` is provided.
+    pub fn new(
+        aliases_map: &'a OpsetAliasesMap,
+        date_pattern_context: DatePatternContext,
+        revset_parse_context: Option<&'a RevsetParseContext<'a>>,
+    ) -> Self {
+        Self {
+            aliases_map,
+            date_pattern_context,
+            revset_parse_context,
+        }
+    }
+
+    /// Aliases to be expanded.
+    pub fn aliases_map(&self) -> &'a OpsetAliasesMap {
+        self.aliases_map
+    }
+}
+
+type OpsetFunction =
+    fn(&FunctionCallNode, &OpsetParseContext) -> Result<Rc<OpsetExpression>, OpsetParseError>;
+
+static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, OpsetFunction>> = Lazy::new(|| {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map: HashMap<&'static str, OpsetFunction> = HashMap::new();
+    map.insert("all", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::All))
+    });
+    map.insert("none", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::None))
+    });
+    map.insert("root", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Root))
+    });
+    map.insert("latest", |function, context| {
+        let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(candidates_arg, context)?;
+        let count = if let Some(count_arg) = count_opt_arg {
+            expect_literal("integer", count_arg)?
+        } else {
+            1
+        };
+        Ok(Rc::new(OpsetExpression::Latest { candidates, count }))
+    });
+    map.insert("user", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OpsetFilterPredicate::User(pattern)))
+    });
+    map.insert("description", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OpsetFilterPredicate::Description(pattern)))
+    });
+    map.insert("snapshot", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(filter(OpsetFilterPredicate::Snapshot))
+    });
+    map.insert("time", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(arg, &context.date_pattern_context)?;
+        Ok(filter(OpsetFilterPredicate::Time(pattern)))
+    });
+    map.insert("tags", |function, _context| {
+        if let Some(arg) = function.args.first() {
+            return Err(InvalidArguments {
+                name: function.name,
+                message: "Expected keyword arguments".to_owned(),
+                span: arg.span,
+            }
+            .into());
+        }
+        let patterns = function
+            .keyword_args
+            .iter()
+            .map(|arg| {
+                Ok::<_, OpsetParseError>((arg.name.to_owned(), expect_string_pattern(&arg.value)?))
+            })
+            .try_collect()?;
+        Ok(filter(OpsetFilterPredicate::Tags(patterns)))
+    });
+    map.insert("touched", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let revset_context = context.revset_parse_context.ok_or_else(|| {
+            OpsetParseError::with_span(OpsetParseErrorKind::RevsetWithoutRepo, arg.span)
+        })?;
+        let expression = opset_parser::expect_string_literal_with(arg, |text, span| {
+            // TODO: propagate warnings
+            let mut diagnostics = RevsetDiagnostics::new();
+            revset::parse(&mut diagnostics, text, revset_context).map_err(|err| {
+                OpsetParseError::expression("In revset expression", span).with_source(err)
+            })
+        })?;
+        Ok(filter(OpsetFilterPredicate::Touched(expression)))
+    });
+    map
+});
+
+fn filter(predicate: OpsetFilterPredicate) -> Rc<OpsetExpression> {
+    Rc::new(OpsetExpression::Filter(predicate))
+}
+
+fn expect_string_pattern(node: &ExpressionNode) -> Result<StringPattern, OpsetParseError> {
+    opset_parser::expect_pattern_with("string pattern", node, |value, kind| match kind {
+        Some(kind) => StringPattern::from_str_kind(value, kind),
+        None => Ok(StringPattern::Substring(value.to_owned())),
+    })
+}
+
+fn expect_date_pattern(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> Result<DatePattern, OpsetParseError> {
+    opset_parser::expect_pattern_with(
+        "date pattern",
+        node,
+        |value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+            match kind {
+                None => Err("Date pattern must specify 'after' or 'before'".into()),
+                Some(kind) => Ok(context.parse_relative(value, kind)?),
+            }
+        },
+    )
+}
+
+fn expect_literal<T: std::str::FromStr>(
+    type_name: &str,
+    node: &ExpressionNode,
+) -> Result<T, OpsetParseError> {
+    opset_parser::expect_string_literal_with(node, |text, span| {
+        text.parse().map_err(|_| {
+            OpsetParseError::expression(format!("Expected expression of type {type_name}"), span)
+        })
+    })
+}
+
+/// Resolves function call by using the builtin function map.
+fn lower_function_call(
+    function: &FunctionCallNode,
+    context: &OpsetParseContext,
+) -> Result<Rc<OpsetExpression>, OpsetParseError> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(function, context)
+    } else {
+        Err(OpsetParseError::with_span(
+            OpsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+/// Transforms the given AST `node` into expression that describes operation
+/// set. Function calls will be resolved at this stage.
+fn lower_expression(
+    node: &ExpressionNode,
+    context: &OpsetParseContext,
+) -> Result<Rc<OpsetExpression>, OpsetParseError> {
+    match &node.kind {
+        ExpressionKind::Identifier(name) => Ok(Rc::new(OpsetExpression::Id((*name).to_owned()))),
+        ExpressionKind::String(name) => Ok(Rc::new(OpsetExpression::Id(name.to_owned()))),
+        ExpressionKind::StringPattern { .. } => Err(OpsetParseError::expression(
+            "String patterns may not be used as operation",
+            node.span,
+        )),
+        ExpressionKind::AtOperation => Ok(Rc::new(OpsetExpression::Current)),
+        ExpressionKind::DagRangeAll => Ok(Rc::new(OpsetExpression::All)),
+        ExpressionKind::RangeAll => Ok(Rc::new(OpsetExpression::Range {
+            roots: Rc::new(OpsetExpression::Root),
+            heads: Rc::new(OpsetExpression::Heads),
+        })),
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = lower_expression(arg_node, context)?;
+            let expression = match op {
+                UnaryOp::Negate => OpsetExpression::NotIn(arg),
+                UnaryOp::DagRangePre => OpsetExpression::Ancestors(arg),
+                UnaryOp::DagRangePost => OpsetExpression::DagRange {
+                    roots: arg,
+                    heads: Rc::new(OpsetExpression::Heads),
+                },
+                UnaryOp::RangePre => OpsetExpression::Range {
+                    roots: Rc::new(OpsetExpression::Root),
+                    heads: arg,
+                },
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Rc::new(OpsetExpression::Heads),
+                },
+                UnaryOp::Parents => OpsetExpression::Parents(arg),
+                UnaryOp::Children => OpsetExpression::Children(arg),
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = lower_expression(lhs_node, context)?;
+            let rhs = lower_expression(rhs_node, context)?;
+            let expression = match op {
+                BinaryOp::Intersection => OpsetExpression::Intersection(lhs, rhs),
+                BinaryOp::Difference => OpsetExpression::Difference(lhs, rhs),
+                BinaryOp::DagRange => OpsetExpression::DagRange {
+                    roots: lhs,
+                    heads: rhs,
+                },
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| lower_expression(node, context))
+                .try_collect()?;
+            let expression = expressions
+                .into_iter()
+                .reduce(|lhs, rhs| Rc::new(OpsetExpression::Union(lhs, rhs)))
+                .unwrap();
+            Ok(expression)
+        }
+        ExpressionKind::FunctionCall(function) => lower_function_call(function, context),
+        ExpressionKind::AliasExpanded(id, subst) => {
+            lower_expression(subst, context).map_err(|e| e.within_alias_expansion(*id, node.span))
+        }
+    }
+}
+
+/// Parses the given `opset_str` and expands aliases.
+pub fn parse(
+    opset_str: &str,
+    context: &OpsetParseContext,
+) -> Result<Rc<OpsetExpression>, OpsetParseError> {
+    let node = opset_parser::parse_program(opset_str)?;
+    let node = dsl_util::expand_aliases(node, context.aliases_map)?;
+    lower_expression(&node, context)
+        .map_err(|err| err.extend_function_candidates(context.aliases_map.function_names()))
+}
+
+/// Callbacks and stores needed to evaluate opset expression.
+pub(crate) struct OpsetEvaluationContext<'a, C, H> {
+    pub repo_loader: &'a RepoLoader,
+    pub parse_context: &'a OpsetParseContext<'a>,
+    pub get_current_op: C,
+    pub get_head_ops: H,
+}
+
+type OpSet = IndexMap<OperationId, Operation>;
+
+struct Evaluator<'a, C, H> {
+    context: OpsetEvaluationContext<'a, C, H>,
+    head_ops: OnceCell<Vec<Operation>>,
+    all_ops: OnceCell<Vec<Operation>>,
+    /// Repos loaded at operations. An operation's repo is also used as the
+    /// parent repo of its children.
+    repos: RefCell<HashMap<OperationId, Arc<ReadonlyRepo>>>,
+}
+
+/// Evaluates the `expression` to operations in reverse topological order.
+pub(crate) fn evaluate<C, H>(
+    expression: &OpsetExpression,
+    context: OpsetEvaluationContext<'_, C, H>,
+) -> Result<Vec<Operation>, OpsetEvaluationError>
+where
+    C: Fn() -> Result<Operation, OpsetEvaluationError>,
+    H: Fn() -> Result<Vec<Operation>, OpsetEvaluationError>,
+{
+    let evaluator = Evaluator {
+        context,
+        head_ops: OnceCell::new(),
+        all_ops: OnceCell::new(),
+        repos: RefCell::new(HashMap::new()),
+    };
+    let set = evaluator.evaluate(expression)?;
+    if set.len() <= 1 {
+        return Ok(set.into_values().collect());
+    }
+    // Operations in the set may not be reachable from the head operations
+    // (e.g. abandoned operations specified by ID), so walk from the set.
+    let heads = set.values().cloned().collect_vec();
+    let mut ops = Vec::with_capacity(set.len());
+    for op in op_walk::walk_ancestors(&heads) {
+        let op = op?;
+        if set.contains_key(op.id()) {
+            ops.push(op);
+            if ops.len() == set.len() {
+                break;
+            }
+        }
+    }
+    Ok(ops)
+}
+
+fn collect_ops(ops: impl IntoIterator<Item = Operation>) -> OpSet {
+    ops.into_iter().map(|op| (op.id().clone(), op)).collect()
+}
+
+impl<C, H> Evaluator<'_, C, H>
+where
+    C: Fn() -> Result<Operation, OpsetEvaluationError>,
+    H: Fn() -> Result<Vec<Operation>, OpsetEvaluationError>,
+{
+    fn head_ops(&self) -> Result<&[Operation], OpsetEvaluationError> {
+        let ops = self
+            .head_ops
+            .get_or_try_init(|| (self.context.get_head_ops)())?;
+        Ok(ops)
+    }
+
+    /// All operations reachable from the head operations.
+    fn all_ops(&self) -> Result<&[Operation], OpsetEvaluationError> {
+        let ops = self
+            .all_ops
+            .get_or_try_init(|| -> Result<_, OpsetEvaluationError> {
+                Ok(op_walk::walk_ancestors(self.head_ops()?).try_collect()?)
+            })?;
+        Ok(ops)
+    }
+
+    fn repo_at(&self, op: &Operation) -> Result<Arc<ReadonlyRepo>, OpsetEvaluationError> {
+        if let Some(repo) = self.repos.borrow().get(op.id()) {
+            return Ok(repo.clone());
+        }
+        let repo = self.context.repo_loader.load_at(op)?;
+        self.repos
+            .borrow_mut()
+            .insert(op.id().clone(), repo.clone());
+        Ok(repo)
+    }
+
+    fn evaluate(&self, expression: &OpsetExpression) -> Result<OpSet, OpsetEvaluationError> {
+        match expression {
+            OpsetExpression::None => Ok(OpSet::new()),
+            OpsetExpression::All => Ok(collect_ops(self.all_ops()?.iter().cloned())),
+            OpsetExpression::Root => Ok(collect_ops([self.context.repo_loader.root_operation()])),
+            OpsetExpression::Current => Ok(collect_ops([(self.context.get_current_op)()?])),
+            OpsetExpression::Heads => Ok(collect_ops(self.head_ops()?.iter().cloned())),
+            OpsetExpression::Id(prefix) => {
+                let op_store = self.context.repo_loader.op_store();
+                let op = op_walk::resolve_single_op_from_store(op_store, prefix)?;
+                Ok(collect_ops([op]))
+            }
+            OpsetExpression::Parents(candidates) => {
+                let mut set = OpSet::new();
+                for op in self.evaluate(candidates)?.values() {
+                    for parent in op.parents() {
+                        let parent = parent?;
+                        set.insert(parent.id().clone(), parent);
+                    }
+                }
+                Ok(set)
+            }
+            OpsetExpression::Children(candidates) => {
+                let parent_ids: HashSet<_> = self.evaluate(candidates)?.into_keys().collect();
+                self.children_of(parent_ids)
+            }
+            OpsetExpression::Ancestors(heads) => {
+                let heads = self.evaluate(heads)?.into_values().collect_vec();
+                Ok(op_walk::walk_ancestors(&heads)
+                    .map_ok(|op| (op.id().clone(), op))
+                    .try_collect()?)
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let roots = self.evaluate(roots)?.into_values().collect_vec();
+                let heads = self.evaluate(heads)?.into_values().collect_vec();
+                let unwanted_ids: HashSet<_> = op_walk::walk_ancestors(&roots)
+                    .map_ok(|op| op.id().clone())
+                    .try_collect()?;
+                Ok(op_walk::walk_ancestors(&heads)
+                    .filter_ok(|op| !unwanted_ids.contains(op.id()))
+                    .map_ok(|op| (op.id().clone(), op))
+                    .try_collect()?)
+            }
+            OpsetExpression::DagRange { roots, heads } => {
+                let root_ids: HashSet<_> = self.evaluate(roots)?.into_keys().collect();
+                let heads = self.evaluate(heads)?.into_values().collect_vec();
+                let ancestors: Vec<_> = op_walk::walk_ancestors(&heads).try_collect()?;
+                let mut reached_ids = HashSet::new();
+                let mut set = OpSet::new();
+                for op in ancestors.into_iter().rev() {
+                    if root_ids.contains(op.id())
+                        || op.parent_ids().iter().any(|id| reached_ids.contains(id))
+                    {
+                        reached_ids.insert(op.id().clone());
+                        set.insert(op.id().clone(), op);
+                    }
+                }
+                set.reverse();
+                Ok(set)
+            }
+            OpsetExpression::Latest { candidates, count } => {
+                let mut ops = self.evaluate(candidates)?.into_values().collect_vec();
+                ops.sort_by(|op1, op2| {
+                    let time1 = &op1.metadata().end_time.timestamp;
+                    let time2 = &op2.metadata().end_time.timestamp;
+                    time2.cmp(time1)
+                });
+                Ok(collect_ops(ops.into_iter().take(*count)))
+            }
+            OpsetExpression::Filter(predicate) => {
+                self.filter_ops(self.all_ops()?.iter().cloned(), predicate)
+            }
+            OpsetExpression::NotIn(complement) => {
+                let complement = self.evaluate(complement)?;
+                Ok(collect_ops(
+                    self.all_ops()?
+                        .iter()
+                        .filter(|op| !complement.contains_key(op.id()))
+                        .cloned(),
+                ))
+            }
+            OpsetExpression::Union(expression1, expression2) => {
+                let mut set = self.evaluate(expression1)?;
+                set.extend(self.evaluate(expression2)?);
+                Ok(set)
+            }
+            OpsetExpression::Intersection(expression1, expression2) => {
+                // Filter the other side to not evaluate expensive predicates
+                // against the whole operation log.
+                match (expression1.as_ref(), expression2.as_ref()) {
+                    (_, OpsetExpression::Filter(predicate)) => {
+                        let set = self.evaluate(expression1)?;
+                        self.filter_ops(set.into_values(), predicate)
+                    }
+                    (OpsetExpression::Filter(predicate), _) => {
+                        let set = self.evaluate(expression2)?;
+                        self.filter_ops(set.into_values(), predicate)
+                    }
+                    _ => {
+                        let mut set = self.evaluate(expression1)?;
+                        let set2 = self.evaluate(expression2)?;
+                        set.retain(|id, _| set2.contains_key(id));
+                        Ok(set)
+                    }
+                }
+            }
+            OpsetExpression::Difference(expression1, expression2) => {
+                let mut set = self.evaluate(expression1)?;
+                let set2 = self.evaluate(expression2)?;
+                set.retain(|id, _| !set2.contains_key(id));
+                Ok(set)
+            }
+        }
+    }
+
+    /// Looks up children of the `parent_ids` by traversing from the head
+    /// operations.
+    fn children_of(&self, parent_ids: HashSet<OperationId>) -> Result<OpSet, OpsetEvaluationError> {
+        let mut remaining_ids = parent_ids.clone();
+        let mut set = OpSet::new();
+        for op in op_walk::walk_ancestors(self.head_ops()?) {
+            if remaining_ids.is_empty() {
+                break;
+            }
+            let op = op?;
+            remaining_ids.remove(op.id());
+            if op.parent_ids().iter().any(|id| parent_ids.contains(id)) {
+                set.insert(op.id().clone(), op);
+            }
+        }
+        Ok(set)
+    }
+
+    fn filter_ops(
+        &self,
+        ops: impl IntoIterator<Item = Operation>,
+        predicate: &OpsetFilterPredicate,
+    ) -> Result<OpSet, OpsetEvaluationError> {
+        let mut set = OpSet::new();
+        for op in ops {
+            if self.matches(&op, predicate)? {
+                set.insert(op.id().clone(), op);
+            }
+        }
+        Ok(set)
+    }
+
+    fn matches(
+        &self,
+        op: &Operation,
+        predicate: &OpsetFilterPredicate,
+    ) -> Result<bool, OpsetEvaluationError> {
+        let metadata = op.metadata();
+        let matched = match predicate {
+            OpsetFilterPredicate::User(pattern) => {
+                pattern.matches(&format!("{}@{}", metadata.username, metadata.hostname))
+            }
+            OpsetFilterPredicate::Description(pattern) => pattern.matches(&metadata.description),
+            OpsetFilterPredicate::Snapshot => metadata.is_snapshot,
+            OpsetFilterPredicate::Time(pattern) => pattern.matches(&metadata.start_time),
+            OpsetFilterPredicate::Tags(patterns) => patterns.iter().all(|(key, pattern)| {
+                metadata
+                    .tags
+                    .get(key)
+                    .is_some_and(|value| pattern.matches(value))
+            }),
+            OpsetFilterPredicate::Touched(expression) => self.touches(op, expression)?,
+        };
+        Ok(matched)
+    }
+
+    /// Whether the operation added or hid commits in the revset `expression`.
+    ///
+    /// The revset is resolved at the operation for added commits, and at the
+    /// parent operation for hidden commits.
+    fn touches(
+        &self,
+        op: &Operation,
+        expression: &Rc<UserRevsetExpression>,
+    ) -> Result<bool, OpsetEvaluationError> {
+        let parent_ops: Vec<_> = op.parents().try_collect()?;
+        let repo = self.repo_at(op)?;
+        let new_heads = repo.view().heads().iter().cloned().collect_vec();
+        let mut old_heads = vec![];
+        for parent_op in &parent_ops {
+            old_heads.extend(parent_op.view()?.heads().iter().cloned());
+        }
+        let added_ids: Vec<_> = RevsetExpression::commits(old_heads.clone())
+            .range(&RevsetExpression::commits(new_heads.clone()))
+            .evaluate(repo.as_ref())?
+            .iter()
+            .try_collect()?;
+        if self.revset_contains_any(repo.as_ref(), expression, added_ids)? {
+            return Ok(true);
+        }
+        let hidden_ids: Vec<_> = RevsetExpression::commits(new_heads)
+            .range(&RevsetExpression::commits(old_heads))
+            .evaluate(repo.as_ref())?
+            .iter()
+            .try_collect()?;
+        if hidden_ids.is_empty() {
+            return Ok(false);
+        }
+        let parent_repo = match parent_ops.as_slice() {
+            [parent_op] => self.repo_at(parent_op)?,
+            _ => repo,
+        };
+        self.revset_contains_any(parent_repo.as_ref(), expression, hidden_ids)
+    }
+
+    fn revset_contains_any(
+        &self,
+        repo: &dyn Repo,
+        expression: &Rc<UserRevsetExpression>,
+        commit_ids: Vec<CommitId>,
+    ) -> Result<bool, OpsetEvaluationError> {
+        if commit_ids.is_empty() {
+            return Ok(false);
+        }
+        let revset_context = self.context.parse_context.revset_parse_context.unwrap();
+        let symbol_resolver = DefaultSymbolResolver::new(repo, revset_context.symbol_resolvers());
+        let expression = match expression.resolve_user_expression(repo, &symbol_resolver) {
+            Ok(expression) => expression,
+            // The symbol may not exist at this operation.
+            Err(RevsetResolutionError::NoSuchRevision { .. }) => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        let revset = expression
+            .intersection(&RevsetExpression::commits(commit_ids))
+            .evaluate(repo)?;
+        let first = revset.iter().next().transpose()?;
+        Ok(first.is_some())
+    }
+}
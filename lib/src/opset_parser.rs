@@ -0,0 +1,760 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![allow(missing_docs)]
+
+use std::collections::HashSet;
+use std::error;
+use std::mem;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use pest::iterators::Pair;
+use pest::iterators::Pairs;
+use pest::pratt_parser::Assoc;
+use pest::pratt_parser::Op;
+use pest::pratt_parser::PrattParser;
+use pest::Parser;
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::dsl_util;
+use crate::dsl_util::collect_similar;
+use crate::dsl_util::AliasDeclaration;
+use crate::dsl_util::AliasDeclarationParser;
+use crate::dsl_util::AliasDefinitionParser;
+use crate::dsl_util::AliasExpandError;
+use crate::dsl_util::AliasExpandableExpression;
+use crate::dsl_util::AliasId;
+use crate::dsl_util::AliasesMap;
+use crate::dsl_util::ExpressionFolder;
+use crate::dsl_util::FoldableExpression;
+use crate::dsl_util::FunctionCallParser;
+use crate::dsl_util::InvalidArguments;
+use crate::dsl_util::StringLiteralParser;
+
+#[derive(Parser)]
+#[grammar = "opset.pest"]
+struct OpsetParser;
+
+const STRING_LITERAL_PARSER: StringLiteralParser<Rule> = StringLiteralParser {
+    content_rule: Rule::string_content,
+    escape_rule: Rule::string_escape,
+};
+const FUNCTION_CALL_PARSER: FunctionCallParser<Rule> = FunctionCallParser {
+    function_name_rule: Rule::function_name,
+    function_arguments_rule: Rule::function_arguments,
+    keyword_argument_rule: Rule::keyword_argument,
+    argument_name_rule: Rule::strict_identifier,
+    argument_value_rule: Rule::expression,
+};
+
+impl Rule {
+    fn to_symbol(self) -> Option<&'static str> {
+        match self {
+            Rule::EOI => None,
+            Rule::whitespace => None,
+            Rule::identifier => None,
+            Rule::strict_identifier => None,
+            Rule::symbol => None,
+            Rule::string_escape => None,
+            Rule::string_content_char => None,
+            Rule::string_content => None,
+            Rule::string_literal => None,
+            Rule::raw_string_content => None,
+            Rule::raw_string_literal => None,
+            Rule::at_op => Some("@"),
+            Rule::pattern_kind_op => Some(":"),
+            Rule::parents_op => Some("-"),
+            Rule::children_op => Some("+"),
+            Rule::dag_range_op
+            | Rule::dag_range_pre_op
+            | Rule::dag_range_post_op
+            | Rule::dag_range_all_op => Some("::"),
+            Rule::range_op => Some(".."),
+            Rule::range_pre_op | Rule::range_post_op | Rule::range_all_op => Some(".."),
+            Rule::range_ops => None,
+            Rule::range_pre_ops => None,
+            Rule::range_post_ops => None,
+            Rule::range_all_ops => None,
+            Rule::negate_op => Some("~"),
+            Rule::union_op => Some("|"),
+            Rule::intersection_op => Some("&"),
+            Rule::difference_op => Some("~"),
+            Rule::infix_op => None,
+            Rule::function => None,
+            Rule::function_name => None,
+            Rule::keyword_argument => None,
+            Rule::argument => None,
+            Rule::function_arguments => None,
+            Rule::formal_parameters => None,
+            Rule::string_pattern => None,
+            Rule::primary => None,
+            Rule::neighbors_expression => None,
+            Rule::range_expression => None,
+            Rule::expression => None,
+            Rule::program => None,
+            Rule::function_alias_declaration => None,
+            Rule::alias_declaration => None,
+        }
+    }
+}
+
+/// Error occurred during opset parsing and function-call resolution.
+#[derive(Debug, Error)]
+#[error("{pest_error}")]
+pub struct OpsetParseError {
+    kind: OpsetParseErrorKind,
+    pest_error: Box<pest::error::Error<Rule>>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// Categories of opset parsing and function-call resolution error.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OpsetParseErrorKind {
+    #[error("Syntax error")]
+    SyntaxError,
+    #[error("Function `{name}` doesn't exist")]
+    NoSuchFunction {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("Function `{name}`: {message}")]
+    InvalidFunctionArguments { name: String, message: String },
+    #[error("Cannot resolve revset expression without repository")]
+    RevsetWithoutRepo,
+    #[error("Redefinition of function parameter")]
+    RedefinedFunctionParameter,
+    #[error("{0}")]
+    Expression(String),
+    #[error("In alias `{0}`")]
+    InAliasExpansion(String),
+    #[error("In function parameter `{0}`")]
+    InParameterExpansion(String),
+    #[error("Alias `{0}` expanded recursively")]
+    RecursiveAlias(String),
+}
+
+impl OpsetParseError {
+    pub(super) fn with_span(kind: OpsetParseErrorKind, span: pest::Span<'_>) -> Self {
+        let message = kind.to_string();
+        let pest_error = Box::new(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        ));
+        OpsetParseError {
+            kind,
+            pest_error,
+            source: None,
+        }
+    }
+
+    pub(super) fn with_source(
+        mut self,
+        source: impl Into<Box<dyn error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Some other expression error.
+    pub(super) fn expression(message: impl Into<String>, span: pest::Span<'_>) -> Self {
+        Self::with_span(OpsetParseErrorKind::Expression(message.into()), span)
+    }
+
+    /// If this is a `NoSuchFunction` error, expands the candidates list with
+    /// the given `other_functions`.
+    pub(super) fn extend_function_candidates<I>(mut self, other_functions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        if let OpsetParseErrorKind::NoSuchFunction { name, candidates } = &mut self.kind {
+            let other_candidates = collect_similar(name, other_functions);
+            *candidates = itertools::merge(mem::take(candidates), other_candidates)
+                .dedup()
+                .collect();
+        }
+        self
+    }
+
+    /// Category of the underlying error.
+    pub fn kind(&self) -> &OpsetParseErrorKind {
+        &self.kind
+    }
+
+    /// Original parsing error which typically occurred in an alias expression.
+    pub fn origin(&self) -> Option<&Self> {
+        self.source.as_ref().and_then(|e| e.downcast_ref())
+    }
+}
+
+impl AliasExpandError for OpsetParseError {
+    fn invalid_arguments(err: InvalidArguments<'_>) -> Self {
+        err.into()
+    }
+
+    fn recursive_expansion(id: AliasId<'_>, span: pest::Span<'_>) -> Self {
+        Self::with_span(OpsetParseErrorKind::RecursiveAlias(id.to_string()), span)
+    }
+
+    fn within_alias_expansion(self, id: AliasId<'_>, span: pest::Span<'_>) -> Self {
+        let kind = match id {
+            AliasId::Symbol(_) | AliasId::Function(..) => {
+                OpsetParseErrorKind::InAliasExpansion(id.to_string())
+            }
+            AliasId::Parameter(_) => OpsetParseErrorKind::InParameterExpansion(id.to_string()),
+        };
+        Self::with_span(kind, span).with_source(self)
+    }
+}
+
+impl From<pest::error::Error<Rule>> for OpsetParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        OpsetParseError {
+            kind: OpsetParseErrorKind::SyntaxError,
+            pest_error: Box::new(rename_rules_in_pest_error(err)),
+            source: None,
+        }
+    }
+}
+
+impl From<InvalidArguments<'_>> for OpsetParseError {
+    fn from(err: InvalidArguments<'_>) -> Self {
+        let kind = OpsetParseErrorKind::InvalidFunctionArguments {
+            name: err.name.to_owned(),
+            message: err.message,
+        };
+        Self::with_span(kind, err.span)
+    }
+}
+
+fn rename_rules_in_pest_error(mut err: pest::error::Error<Rule>) -> pest::error::Error<Rule> {
+    let pest::error::ErrorVariant::ParsingError {
+        positives,
+        negatives,
+    } = &mut err.variant
+    else {
+        return err;
+    };
+
+    // Remove duplicated symbols.
+    let mut known_syms = HashSet::new();
+    positives.retain(|rule| rule.to_symbol().map_or(true, |sym| known_syms.insert(sym)));
+    let mut known_syms = HashSet::new();
+    negatives.retain(|rule| rule.to_symbol().map_or(true, |sym| known_syms.insert(sym)));
+    err.renamed_rules(|rule| {
+        rule.to_symbol()
+            .map(|sym| format!("`{sym}`"))
+            .unwrap_or_else(|| format!("<{rule:?}>"))
+    })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpressionKind<'i> {
+    /// Unquoted symbol.
+    Identifier(&'i str),
+    /// Quoted symbol or string.
+    String(String),
+    /// `<kind>:<value>`
+    StringPattern {
+        kind: &'i str,
+        value: String,
+    },
+    /// `@`
+    AtOperation,
+    /// `::`
+    DagRangeAll,
+    /// `..`
+    RangeAll,
+    Unary(UnaryOp, Box<ExpressionNode<'i>>),
+    Binary(BinaryOp, Box<ExpressionNode<'i>>, Box<ExpressionNode<'i>>),
+    /// `x | y | ..`
+    UnionAll(Vec<ExpressionNode<'i>>),
+    FunctionCall(Box<FunctionCallNode<'i>>),
+    /// Identity node to preserve the span in the source text.
+    AliasExpanded(AliasId<'i>, Box<ExpressionNode<'i>>),
+}
+
+impl<'i> FoldableExpression<'i> for ExpressionKind<'i> {
+    fn fold<F>(self, folder: &mut F, span: pest::Span<'i>) -> Result<Self, F::Error>
+    where
+        F: ExpressionFolder<'i, Self> + ?Sized,
+    {
+        match self {
+            ExpressionKind::Identifier(name) => folder.fold_identifier(name, span),
+            ExpressionKind::String(_)
+            | ExpressionKind::StringPattern { .. }
+            | ExpressionKind::AtOperation
+            | ExpressionKind::DagRangeAll
+            | ExpressionKind::RangeAll => Ok(self),
+            ExpressionKind::Unary(op, arg) => {
+                let arg = Box::new(folder.fold_expression(*arg)?);
+                Ok(ExpressionKind::Unary(op, arg))
+            }
+            ExpressionKind::Binary(op, lhs, rhs) => {
+                let lhs = Box::new(folder.fold_expression(*lhs)?);
+                let rhs = Box::new(folder.fold_expression(*rhs)?);
+                Ok(ExpressionKind::Binary(op, lhs, rhs))
+            }
+            ExpressionKind::UnionAll(nodes) => {
+                let nodes = dsl_util::fold_expression_nodes(folder, nodes)?;
+                Ok(ExpressionKind::UnionAll(nodes))
+            }
+            ExpressionKind::FunctionCall(function) => folder.fold_function_call(function, span),
+            ExpressionKind::AliasExpanded(id, subst) => {
+                let subst = Box::new(folder.fold_expression(*subst)?);
+                Ok(ExpressionKind::AliasExpanded(id, subst))
+            }
+        }
+    }
+}
+
+impl<'i> AliasExpandableExpression<'i> for ExpressionKind<'i> {
+    fn identifier(name: &'i str) -> Self {
+        ExpressionKind::Identifier(name)
+    }
+
+    fn function_call(function: Box<FunctionCallNode<'i>>) -> Self {
+        ExpressionKind::FunctionCall(function)
+    }
+
+    fn alias_expanded(id: AliasId<'i>, subst: Box<ExpressionNode<'i>>) -> Self {
+        ExpressionKind::AliasExpanded(id, subst)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnaryOp {
+    /// `~x`
+    Negate,
+    /// `::x`
+    DagRangePre,
+    /// `x::`
+    DagRangePost,
+    /// `..x`
+    RangePre,
+    /// `x..`
+    RangePost,
+    /// `x-`
+    Parents,
+    /// `x+`
+    Children,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryOp {
+    /// `&`
+    Intersection,
+    /// `~`
+    Difference,
+    /// `::`
+    DagRange,
+    /// `..`
+    Range,
+}
+
+pub type ExpressionNode<'i> = dsl_util::ExpressionNode<'i, ExpressionKind<'i>>;
+pub type FunctionCallNode<'i> = dsl_util::FunctionCallNode<'i, ExpressionKind<'i>>;
+
+fn union_nodes<'i>(lhs: ExpressionNode<'i>, rhs: ExpressionNode<'i>) -> ExpressionNode<'i> {
+    let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+    let expr = match lhs.kind {
+        ExpressionKind::UnionAll(mut nodes) => {
+            nodes.push(rhs);
+            ExpressionKind::UnionAll(nodes)
+        }
+        _ => ExpressionKind::UnionAll(vec![lhs, rhs]),
+    };
+    ExpressionNode::new(expr, span)
+}
+
+pub(super) fn parse_program(opset_str: &str) -> Result<ExpressionNode<'_>, OpsetParseError> {
+    let mut pairs = OpsetParser::parse(Rule::program, opset_str)?;
+    let first = pairs.next().unwrap();
+    parse_expression_node(first.into_inner())
+}
+
+fn parse_expression_node(pairs: Pairs<Rule>) -> Result<ExpressionNode, OpsetParseError> {
+    static PRATT: Lazy<PrattParser<Rule>> = Lazy::new(|| {
+        PrattParser::new()
+            .op(Op::infix(Rule::union_op, Assoc::Left))
+            .op(Op::infix(Rule::intersection_op, Assoc::Left)
+                | Op::infix(Rule::difference_op, Assoc::Left))
+            .op(Op::prefix(Rule::negate_op))
+            // Ranges can't be nested without parentheses. Associativity doesn't matter.
+            .op(Op::infix(Rule::dag_range_op, Assoc::Left) | Op::infix(Rule::range_op, Assoc::Left))
+            .op(Op::prefix(Rule::dag_range_pre_op) | Op::prefix(Rule::range_pre_op))
+            .op(Op::postfix(Rule::dag_range_post_op) | Op::postfix(Rule::range_post_op))
+            // Neighbors
+            .op(Op::postfix(Rule::parents_op) | Op::postfix(Rule::children_op))
+    });
+    PRATT
+        .map_primary(|primary| {
+            let expr = match primary.as_rule() {
+                Rule::primary => return parse_primary_node(primary),
+                Rule::dag_range_all_op => ExpressionKind::DagRangeAll,
+                Rule::range_all_op => ExpressionKind::RangeAll,
+                r => panic!("unexpected primary rule {r:?}"),
+            };
+            Ok(ExpressionNode::new(expr, primary.as_span()))
+        })
+        .map_prefix(|op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::negate_op => UnaryOp::Negate,
+                Rule::dag_range_pre_op => UnaryOp::DagRangePre,
+                Rule::range_pre_op => UnaryOp::RangePre,
+                r => panic!("unexpected prefix operator rule {r:?}"),
+            };
+            let rhs = Box::new(rhs?);
+            let span = op.as_span().start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Unary(op_kind, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_postfix(|lhs, op| {
+            let op_kind = match op.as_rule() {
+                Rule::dag_range_post_op => UnaryOp::DagRangePost,
+                Rule::range_post_op => UnaryOp::RangePost,
+                Rule::parents_op => UnaryOp::Parents,
+                Rule::children_op => UnaryOp::Children,
+                r => panic!("unexpected postfix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let span = lhs.span.start_pos().span(&op.as_span().end_pos());
+            let expr = ExpressionKind::Unary(op_kind, lhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_infix(|lhs, op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::union_op => return Ok(union_nodes(lhs?, rhs?)),
+                Rule::intersection_op => BinaryOp::Intersection,
+                Rule::difference_op => BinaryOp::Difference,
+                Rule::dag_range_op => BinaryOp::DagRange,
+                Rule::range_op => BinaryOp::Range,
+                r => panic!("unexpected infix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let rhs = Box::new(rhs?);
+            let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Binary(op_kind, lhs, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .parse(pairs)
+}
+
+fn parse_primary_node(pair: Pair<Rule>) -> Result<ExpressionNode, OpsetParseError> {
+    let span = pair.as_span();
+    let mut pairs = pair.into_inner();
+    let first = pairs.next().unwrap();
+    let expr = match first.as_rule() {
+        Rule::expression => return parse_expression_node(first.into_inner()),
+        Rule::function => {
+            let function = Box::new(FUNCTION_CALL_PARSER.parse(
+                first,
+                |pair| Ok(pair.as_str()),
+                |pair| parse_expression_node(pair.into_inner()),
+            )?);
+            ExpressionKind::FunctionCall(function)
+        }
+        Rule::string_pattern => {
+            let (lhs, op, rhs) = first.into_inner().collect_tuple().unwrap();
+            assert_eq!(lhs.as_rule(), Rule::strict_identifier);
+            assert_eq!(op.as_rule(), Rule::pattern_kind_op);
+            let kind = lhs.as_str();
+            let value = parse_as_string_literal(rhs);
+            ExpressionKind::StringPattern { kind, value }
+        }
+        Rule::identifier => ExpressionKind::Identifier(first.as_str()),
+        Rule::string_literal | Rule::raw_string_literal => {
+            ExpressionKind::String(parse_as_string_literal(first))
+        }
+        Rule::at_op => ExpressionKind::AtOperation,
+        r => panic!("unexpected opset parse rule: {r:?}"),
+    };
+    Ok(ExpressionNode::new(expr, span))
+}
+
+/// Parses part of compound symbol to string.
+fn parse_as_string_literal(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::identifier => pair.as_str().to_owned(),
+        Rule::string_literal => STRING_LITERAL_PARSER.parse(pair.into_inner()),
+        Rule::raw_string_literal => {
+            let (content,) = pair.into_inner().collect_tuple().unwrap();
+            assert_eq!(content.as_rule(), Rule::raw_string_content);
+            content.as_str().to_owned()
+        }
+        _ => {
+            panic!("unexpected string literal rule: {:?}", pair.as_str());
+        }
+    }
+}
+
+pub type OpsetAliasesMap = AliasesMap<OpsetAliasParser, String>;
+
+#[derive(Clone, Debug, Default)]
+pub struct OpsetAliasParser;
+
+impl AliasDeclarationParser for OpsetAliasParser {
+    type Error = OpsetParseError;
+
+    fn parse_declaration(&self, source: &str) -> Result<AliasDeclaration, Self::Error> {
+        let mut pairs = OpsetParser::parse(Rule::alias_declaration, source)?;
+        let first = pairs.next().unwrap();
+        match first.as_rule() {
+            Rule::strict_identifier => Ok(AliasDeclaration::Symbol(first.as_str().to_owned())),
+            Rule::function_alias_declaration => {
+                let (name_pair, params_pair) = first.into_inner().collect_tuple().unwrap();
+                assert_eq!(name_pair.as_rule(), Rule::function_name);
+                assert_eq!(params_pair.as_rule(), Rule::formal_parameters);
+                let name = name_pair.as_str().to_owned();
+                let params_span = params_pair.as_span();
+                let params = params_pair
+                    .into_inner()
+                    .map(|pair| match pair.as_rule() {
+                        Rule::strict_identifier => pair.as_str().to_owned(),
+                        r => panic!("unexpected formal parameter rule {r:?}"),
+                    })
+                    .collect_vec();
+                if params.iter().all_unique() {
+                    Ok(AliasDeclaration::Function(name, params))
+                } else {
+                    Err(OpsetParseError::with_span(
+                        OpsetParseErrorKind::RedefinedFunctionParameter,
+                        params_span,
+                    ))
+                }
+            }
+            r => panic!("unexpected alias declaration rule {r:?}"),
+        }
+    }
+}
+
+impl AliasDefinitionParser for OpsetAliasParser {
+    type Output<'i> = ExpressionKind<'i>;
+    type Error = OpsetParseError;
+
+    fn parse_definition<'i>(&self, source: &'i str) -> Result<ExpressionNode<'i>, Self::Error> {
+        parse_program(source)
+    }
+}
+
+/// Applies the given function to the `<kind>:<value>` or bare string node.
+/// Alias expansion nodes are unwrapped accordingly.
+pub(super) fn expect_pattern_with<T, E: Into<Box<dyn error::Error + Send + Sync>>>(
+    type_name: &str,
+    node: &ExpressionNode,
+    parse_pattern: impl FnOnce(&str, Option<&str>) -> Result<T, E>,
+) -> Result<T, OpsetParseError> {
+    let wrap_error = |err: E| {
+        OpsetParseError::expression(format!("Invalid {type_name}"), node.span).with_source(err)
+    };
+    expect_expression_with(node, |node| match &node.kind {
+        ExpressionKind::Identifier(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::String(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::StringPattern { kind, value } => {
+            parse_pattern(value, Some(kind)).map_err(wrap_error)
+        }
+        _ => Err(OpsetParseError::expression(
+            format!("Expected expression of {type_name}"),
+            node.span,
+        )),
+    })
+}
+
+/// Applies the given function to the string literal node.
+pub(super) fn expect_string_literal_with<T>(
+    node: &ExpressionNode,
+    f: impl FnOnce(&str, pest::Span<'_>) -> Result<T, OpsetParseError>,
+) -> Result<T, OpsetParseError> {
+    expect_expression_with(node, |node| match &node.kind {
+        ExpressionKind::Identifier(name) => f(name, node.span),
+        ExpressionKind::String(name) => f(name, node.span),
+        _ => Err(OpsetParseError::expression(
+            "Expected string literal",
+            node.span,
+        )),
+    })
+}
+
+/// Applies the give function to the innermost `node` by unwrapping alias
+/// expansion nodes.
+pub(super) fn expect_expression_with<T>(
+    node: &ExpressionNode,
+    f: impl FnOnce(&ExpressionNode) -> Result<T, OpsetParseError>,
+) -> Result<T, OpsetParseError> {
+    if let ExpressionKind::AliasExpanded(id, subst) = &node.kind {
+        expect_expression_with(subst, f).map_err(|e| e.within_alias_expansion(*id, node.span))
+    } else {
+        f(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn parse_into_kind(text: &str) -> Result<ExpressionKind, OpsetParseErrorKind> {
+        parse_program(text)
+            .map(|node| node.kind)
+            .map_err(|err| err.kind)
+    }
+
+    fn with_aliases<'i>(aliases: impl IntoIterator<Item = (&'i str, &'i str)>) -> OpsetAliasesMap {
+        let mut aliases_map = OpsetAliasesMap::new();
+        for (decl, defn) in aliases {
+            aliases_map.insert(decl, defn).unwrap();
+        }
+        aliases_map
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_eq!(
+            parse_into_kind("abc0"),
+            Ok(ExpressionKind::Identifier("abc0"))
+        );
+        assert_eq!(
+            parse_into_kind(r#""abc0""#),
+            Ok(ExpressionKind::String("abc0".to_owned()))
+        );
+        assert_eq!(parse_into_kind("@"), Ok(ExpressionKind::AtOperation));
+        assert_eq!(parse_into_kind("::"), Ok(ExpressionKind::DagRangeAll));
+        assert_eq!(parse_into_kind(".."), Ok(ExpressionKind::RangeAll));
+        assert_eq!(
+            parse_into_kind("exact:foo"),
+            Ok(ExpressionKind::StringPattern {
+                kind: "exact",
+                value: "foo".to_owned()
+            })
+        );
+        assert_eq!(
+            parse_into_kind("abc.def"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+        assert_eq!(parse_into_kind("@@"), Err(OpsetParseErrorKind::SyntaxError));
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_matches!(
+            parse_into_kind("@--"),
+            Ok(ExpressionKind::Unary(UnaryOp::Parents, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc+"),
+            Ok(ExpressionKind::Unary(UnaryOp::Children, _))
+        );
+        assert_matches!(
+            parse_into_kind("::@-"),
+            Ok(ExpressionKind::Unary(UnaryOp::DagRangePre, _))
+        );
+        assert_matches!(
+            parse_into_kind("@-::"),
+            Ok(ExpressionKind::Unary(UnaryOp::DagRangePost, _))
+        );
+        assert_matches!(
+            parse_into_kind("..@"),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePre, _))
+        );
+        assert_matches!(
+            parse_into_kind("@-.."),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePost, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc..def"),
+            Ok(ExpressionKind::Binary(BinaryOp::Range, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc::def"),
+            Ok(ExpressionKind::Binary(BinaryOp::DagRange, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("~abc"),
+            Ok(ExpressionKind::Unary(UnaryOp::Negate, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc & def ~ ghi"),
+            Ok(ExpressionKind::Binary(BinaryOp::Difference, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("abc | def | ghi"),
+            Ok(ExpressionKind::UnionAll(nodes)) if nodes.len() == 3
+        );
+        // Ranges can't be nested
+        assert_eq!(
+            parse_into_kind("abc..def..ghi"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+
+    #[test]
+    fn test_parse_function_call() {
+        assert_matches!(
+            parse_into_kind("user(foo)"),
+            Ok(ExpressionKind::FunctionCall(function))
+                if function.name == "user" && function.args.len() == 1
+        );
+        assert_matches!(
+            parse_into_kind(r#"tags(args=glob:"*rebase*", )"#),
+            Ok(ExpressionKind::FunctionCall(function))
+                if function.keyword_args.len() == 1 && function.keyword_args[0].name == "args"
+        );
+        assert_eq!(
+            parse_into_kind("user(foo"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let aliases_map = with_aliases([
+            ("recent", "@----..@"),
+            ("by(x)", "user(x) & ~snapshot()"),
+            ("rec", "rec"),
+        ]);
+        let node = dsl_util::expand_aliases(parse_program("recent").unwrap(), &aliases_map);
+        assert_matches!(
+            node.unwrap().kind,
+            ExpressionKind::AliasExpanded(AliasId::Symbol("recent"), _)
+        );
+        let node = dsl_util::expand_aliases(parse_program("by(foo)").unwrap(), &aliases_map);
+        assert_matches!(
+            node.unwrap().kind,
+            ExpressionKind::AliasExpanded(AliasId::Function("by", _), _)
+        );
+        let err =
+            dsl_util::expand_aliases(parse_program("rec").unwrap(), &aliases_map).unwrap_err();
+        assert_eq!(
+            err.kind,
+            OpsetParseErrorKind::InAliasExpansion("rec".to_owned())
+        );
+        assert_eq!(
+            err.origin().unwrap().kind,
+            OpsetParseErrorKind::RecursiveAlias("rec".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_alias_declaration() {
+        let mut aliases_map = OpsetAliasesMap::new();
+        assert!(aliases_map.insert("f(x, y)", "x | y").is_ok());
+        assert_eq!(
+            aliases_map.insert("f(x, x)", "x").unwrap_err().kind,
+            OpsetParseErrorKind::RedefinedFunctionParameter
+        );
+    }
+}
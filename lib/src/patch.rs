@@ -0,0 +1,1027 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and applying patches in the unified diff format, optionally wrapped
+//! in email messages as produced by `git format-patch`.
+
+use std::io::Read as _;
+use std::sync::Arc;
+
+use bstr::ByteSlice as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::FileId;
+use crate::backend::Signature;
+use crate::backend::Timestamp;
+use crate::backend::TreeValue;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
+
+/// A patch to a set of files, with the commit metadata from the email headers
+/// if available.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Patch {
+    /// Author parsed from the `From` and `Date` headers.
+    pub author: Option<Signature>,
+    /// Description parsed from the `Subject` header and the message body.
+    pub description: String,
+    /// Changes to individual files.
+    pub files: Vec<FilePatch>,
+}
+
+/// Changes to a single file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FilePatch {
+    /// Path of the file before the change, or `None` if the file is created.
+    pub old_path: Option<RepoPathBuf>,
+    /// Path of the file after the change, or `None` if the file is deleted.
+    pub new_path: Option<RepoPathBuf>,
+    /// Whether the old file is kept when it's renamed.
+    pub is_copy: bool,
+    /// The new executable bit if the patch changes the file mode.
+    pub new_executable: Option<bool>,
+    /// Hex ids of the file contents before and after the change, from the Git
+    /// `index` line. They're usually abbreviated.
+    pub blob_ids: Option<(String, String)>,
+    /// Changed regions of the file.
+    pub hunks: Vec<Hunk>,
+}
+
+/// A region of a file and its replacement.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Hunk {
+    /// 1-based line number of the region in the old file.
+    pub old_start: usize,
+    /// Context and removed lines, including line terminators.
+    pub old_lines: Vec<Vec<u8>>,
+    /// Context and added lines, including line terminators.
+    pub new_lines: Vec<Vec<u8>>,
+}
+
+/// Error that may occur while parsing patches.
+#[derive(Debug, Error)]
+pub enum PatchParseError {
+    /// The input doesn't contain any patch.
+    #[error("No patches found")]
+    Empty,
+    /// A hunk header couldn't be parsed.
+    #[error("Invalid hunk header at line {line}")]
+    InvalidHunkHeader {
+        /// 1-based line number in the input.
+        line: usize,
+    },
+    /// A hunk has fewer lines than its header says.
+    #[error("Truncated hunk at line {line}")]
+    TruncatedHunk {
+        /// 1-based line number in the input.
+        line: usize,
+    },
+    /// A path in the patch isn't a valid repository path.
+    #[error("Invalid path in patch: {0}")]
+    InvalidPath(String),
+    /// The patch contains changes that can't be represented.
+    #[error("Unsupported patch at line {line}: {message}")]
+    Unsupported {
+        /// 1-based line number in the input.
+        line: usize,
+        /// What isn't supported.
+        message: String,
+    },
+}
+
+/// Error that may occur while applying a patch.
+#[derive(Debug, Error)]
+pub enum PatchApplyError {
+    /// Failed to read or write the tree.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// The patched path exists but isn't a regular file.
+    #[error("Cannot apply patch to {path}: not a resolved regular file")]
+    NotAFile {
+        /// Path in internal format.
+        path: String,
+    },
+}
+
+/// Result of applying a patch to a tree.
+#[derive(Clone, Debug)]
+pub struct ApplyPatchResult {
+    /// The new tree.
+    pub tree: MergedTree,
+    /// Paths at which the patch didn't apply cleanly, and which are left
+    /// conflicted in the new tree.
+    pub conflicted_paths: Vec<RepoPathBuf>,
+}
+
+/// Parses a series of patches.
+///
+/// The input can be an mbox file with one email message per patch, a single
+/// email message, or a plain unified diff without any metadata.
+pub fn parse_patches(input: &[u8]) -> Result<Vec<Patch>, PatchParseError> {
+    let lines = input.lines_with_terminator().collect::<Vec<_>>();
+    let is_mbox = lines.first().is_some_and(|line| line.starts_with(b"From "));
+    let is_mail = is_mbox || lines.first().is_some_and(|line| is_header_line(line));
+    let mut patches = vec![];
+    if is_mbox {
+        // Messages start with a "From " line, which is either the first line or
+        // preceded by a blank line.
+        let mut start = 0;
+        for i in 1..lines.len() {
+            if lines[i].starts_with(b"From ") && lines[i - 1].trim().is_empty() {
+                patches.push(parse_message(&lines[start + 1..i], start + 2)?);
+                start = i;
+            }
+        }
+        patches.push(parse_message(&lines[start + 1..], start + 2)?);
+    } else if is_mail {
+        patches.push(parse_message(&lines, 1)?);
+    } else {
+        let files = parse_diff(&lines, 1)?;
+        if !files.is_empty() {
+            patches.push(Patch {
+                author: None,
+                description: String::new(),
+                files,
+            });
+        }
+    }
+    if patches.is_empty() {
+        return Err(PatchParseError::Empty);
+    }
+    Ok(patches)
+}
+
+fn is_header_line(line: &[u8]) -> bool {
+    let Some((name, _)) = line.split_once_str(":") else {
+        return false;
+    };
+    !name.is_empty() && name.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+/// Parses an email message starting at 1-based line number `first_line`.
+fn parse_message(lines: &[&[u8]], first_line: usize) -> Result<Patch, PatchParseError> {
+    let mut headers: Vec<(String, String)> = vec![];
+    let mut body_start = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.to_str_lossy();
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            body_start = i + 1;
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            // Folded header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| decode_header_words(value))
+    };
+
+    let body = &lines[body_start.min(lines.len())..];
+    let diff_start = body
+        .iter()
+        .enumerate()
+        .position(|(i, line)| is_diff_start(body, i, line))
+        .unwrap_or(body.len());
+    // The message ends before the "---" separator which precedes the diffstat.
+    let message_end = body[..diff_start]
+        .iter()
+        .position(|line| line.trim_end() == b"---")
+        .unwrap_or(diff_start);
+    let message = body[..message_end]
+        .concat()
+        .to_str_lossy()
+        .trim()
+        .to_owned();
+    let subject = header("subject")
+        .map(|subject| strip_subject_prefix(&subject).to_owned())
+        .unwrap_or_default();
+    let description = match (subject.is_empty(), message.is_empty()) {
+        (true, true) => String::new(),
+        (false, true) => format!("{subject}\n"),
+        (true, false) => format!("{message}\n"),
+        (false, false) => format!("{subject}\n\n{message}\n"),
+    };
+
+    let author = header("from").and_then(|from| {
+        let (name, email) = parse_address(&from);
+        let timestamp = header("date")
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(&date).ok())
+            .map(Timestamp::from_datetime)?;
+        Some(Signature {
+            name,
+            email,
+            timestamp,
+        })
+    });
+    let files = parse_diff(&body[diff_start..], first_line + body_start + diff_start)?;
+    Ok(Patch {
+        author,
+        description,
+        files,
+    })
+}
+
+fn is_diff_start(lines: &[&[u8]], i: usize, line: &[u8]) -> bool {
+    line.starts_with(b"diff --git ")
+        || (line.starts_with(b"--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| next.starts_with(b"+++ ")))
+}
+
+/// Strips prefixes like `[PATCH v2 1/3]` from the subject.
+fn strip_subject_prefix(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_prefix('[') {
+        let Some((_, rest)) = rest.split_once(']') else {
+            break;
+        };
+        subject = rest.trim_start();
+    }
+    subject
+}
+
+/// Splits `Name <email>` into its parts.
+fn parse_address(address: &str) -> (String, String) {
+    match address.rsplit_once('<') {
+        Some((name, email)) => {
+            let name = name.trim().trim_matches('"');
+            let email = email.trim_end().trim_end_matches('>');
+            (name.to_owned(), email.to_owned())
+        }
+        None => (String::new(), address.trim().to_owned()),
+    }
+}
+
+/// Decodes RFC 2047 encoded words using the "Q" encoding. Other encodings are
+/// left as is.
+fn decode_header_words(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut last_was_encoded = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        let parsed = word[2..].splitn(3, '?').collect::<Vec<_>>();
+        let Some((charset, encoding, text_and_rest)) = (match parsed[..] {
+            [charset, encoding, text_and_rest] => Some((charset, encoding, text_and_rest)),
+            _ => None,
+        }) else {
+            break;
+        };
+        let Some(end) = text_and_rest.find("?=") else {
+            break;
+        };
+        if !charset.eq_ignore_ascii_case("utf-8") || !encoding.eq_ignore_ascii_case("q") {
+            break;
+        }
+        // Whitespace between adjacent encoded words is ignored.
+        if !(last_was_encoded && before.trim().is_empty()) {
+            decoded.push_str(before);
+        }
+        decoded.push_str(&decode_q(&text_and_rest[..end]));
+        rest = &text_and_rest[end + 2..];
+        last_was_encoded = true;
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_q(text: &str) -> String {
+    let mut bytes = vec![];
+    let mut iter = text.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [iter.next().unwrap_or(b'0'), iter.next().unwrap_or(b'0')];
+                let value = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                bytes.push(value.unwrap_or(b'?'));
+            }
+            _ => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Parses the file patches of a unified diff starting at 1-based line number
+/// `first_line`.
+fn parse_diff(lines: &[&[u8]], first_line: usize) -> Result<Vec<FilePatch>, PatchParseError> {
+    let mut files: Vec<FilePatch> = vec![];
+    // Whether the current file patch was started by a "diff --git" line, so
+    // the "---" and "+++" lines belong to it.
+    let mut in_git_header = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line_number = first_line + i;
+        let line = lines[i];
+        let text = line.to_str_lossy();
+        let text = text.trim_end_matches(['\r', '\n']);
+        if let Some(paths) = text.strip_prefix("diff --git ") {
+            let (old_path, new_path) = parse_git_diff_paths(paths)?;
+            files.push(FilePatch {
+                old_path: Some(old_path),
+                new_path: Some(new_path),
+                ..Default::default()
+            });
+            in_git_header = true;
+        } else if text.starts_with("--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| next.starts_with(b"+++ "))
+        {
+            let next = lines[i + 1].to_str_lossy();
+            let old_path = parse_diff_path(&text[4..])?;
+            let new_path = parse_diff_path(next.trim_end_matches(['\r', '\n'])[4..].as_ref())?;
+            if !in_git_header {
+                files.push(FilePatch::default());
+            }
+            let file = files.last_mut().unwrap();
+            file.old_path = old_path;
+            file.new_path = new_path;
+            in_git_header = false;
+            i += 1;
+        } else if text.starts_with("@@ ") {
+            let Some(file) = files.last_mut() else {
+                return Err(PatchParseError::InvalidHunkHeader { line: line_number });
+            };
+            in_git_header = false;
+            let (hunk, num_lines) = parse_hunk(&lines[i..], line_number)?;
+            file.hunks.push(hunk);
+            i += num_lines;
+            continue;
+        } else if in_git_header {
+            let file = files.last_mut().unwrap();
+            parse_git_extended_header(file, text, line_number)?;
+        }
+        i += 1;
+    }
+    Ok(files)
+}
+
+fn parse_git_extended_header(
+    file: &mut FilePatch,
+    text: &str,
+    line_number: usize,
+) -> Result<(), PatchParseError> {
+    let unsupported = |message: &str| PatchParseError::Unsupported {
+        line: line_number,
+        message: message.to_owned(),
+    };
+    if let Some(mode) = text.strip_prefix("new file mode ") {
+        file.old_path = None;
+        file.new_executable = Some(parse_mode(mode).ok_or_else(|| unsupported(text))?);
+    } else if let Some(mode) = text.strip_prefix("deleted file mode ") {
+        parse_mode(mode).ok_or_else(|| unsupported(text))?;
+        file.new_path = None;
+    } else if let Some(mode) = text.strip_prefix("old mode ") {
+        parse_mode(mode).ok_or_else(|| unsupported(text))?;
+    } else if let Some(mode) = text.strip_prefix("new mode ") {
+        file.new_executable = Some(parse_mode(mode).ok_or_else(|| unsupported(text))?);
+    } else if let Some(path) = text.strip_prefix("rename from ") {
+        file.old_path = Some(parse_repo_path(path)?);
+    } else if let Some(path) = text.strip_prefix("rename to ") {
+        file.new_path = Some(parse_repo_path(path)?);
+    } else if let Some(path) = text.strip_prefix("copy from ") {
+        file.old_path = Some(parse_repo_path(path)?);
+        file.is_copy = true;
+    } else if let Some(path) = text.strip_prefix("copy to ") {
+        file.new_path = Some(parse_repo_path(path)?);
+        file.is_copy = true;
+    } else if let Some(ids) = text.strip_prefix("index ") {
+        let ids = ids.split_once(' ').map_or(ids, |(ids, _mode)| ids);
+        file.blob_ids = ids
+            .split_once("..")
+            .map(|(old, new)| (old.to_owned(), new.to_owned()));
+    } else if text.starts_with("Binary files ") || text == "GIT binary patch" {
+        return Err(unsupported("binary patch"));
+    }
+    Ok(())
+}
+
+/// Parses a Git file mode, returning whether it's executable.
+fn parse_mode(mode: &str) -> Option<bool> {
+    match mode.trim() {
+        "100644" => Some(false),
+        "100755" => Some(true),
+        _ => None,
+    }
+}
+
+/// Parses the `a/<old> b/<new>` part of a `diff --git` line.
+fn parse_git_diff_paths(paths: &str) -> Result<(RepoPathBuf, RepoPathBuf), PatchParseError> {
+    // Both paths are the same except for renames and copies, in which case the
+    // "rename from/to" headers override them.
+    let invalid = || PatchParseError::InvalidPath(paths.to_owned());
+    let paths = paths.strip_prefix("a/").ok_or_else(invalid)?;
+    let (old, new) = match paths.len().checked_sub(3).map(|len| len / 2) {
+        Some(half) if paths.get(half..half + 3) == Some(" b/") => {
+            (&paths[..half], &paths[half + 3..])
+        }
+        _ => paths.split_once(" b/").ok_or_else(invalid)?,
+    };
+    Ok((parse_repo_path(old)?, parse_repo_path(new)?))
+}
+
+/// Parses the path in a `---` or `+++` line, which is `None` for `/dev/null`.
+fn parse_diff_path(text: &str) -> Result<Option<RepoPathBuf>, PatchParseError> {
+    // The path may be followed by a tab and a timestamp.
+    let path = text.split('\t').next().unwrap_or_default().trim_end();
+    if path == "/dev/null" {
+        return Ok(None);
+    }
+    // Strip the first path component, like `patch -p1`.
+    let path = path.split_once('/').map_or(path, |(_, rest)| rest);
+    parse_repo_path(path).map(Some)
+}
+
+fn parse_repo_path(path: &str) -> Result<RepoPathBuf, PatchParseError> {
+    if path.is_empty()
+        || path
+            .split('/')
+            .any(|component| matches!(component, "" | "." | ".."))
+    {
+        return Err(PatchParseError::InvalidPath(path.to_owned()));
+    }
+    Ok(RepoPathBuf::from_internal_string(path))
+}
+
+/// Parses a hunk starting with its header, and returns it with the number of
+/// lines it spans.
+fn parse_hunk(lines: &[&[u8]], line_number: usize) -> Result<(Hunk, usize), PatchParseError> {
+    let header = lines[0].to_str_lossy();
+    let invalid_header = || PatchParseError::InvalidHunkHeader { line: line_number };
+    let ranges = header
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split_once(" @@"))
+        .map(|(ranges, _)| ranges)
+        .ok_or_else(invalid_header)?;
+    let (old_range, new_range) = ranges.split_once(' ').ok_or_else(invalid_header)?;
+    let parse_range = |range: Option<&str>| -> Option<(usize, usize)> {
+        let range = range?;
+        let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+        Some((start.parse().ok()?, count.parse().ok()?))
+    };
+    let (old_start, mut old_remaining) =
+        parse_range(old_range.strip_prefix('-')).ok_or_else(invalid_header)?;
+    let (_, mut new_remaining) =
+        parse_range(new_range.strip_prefix('+')).ok_or_else(invalid_header)?;
+
+    let mut hunk = Hunk {
+        old_start,
+        ..Default::default()
+    };
+    let mut i = 1;
+    // Which sides the previous line belongs to, for "\ No newline" markers.
+    let mut last_sides = (false, false);
+    while old_remaining > 0 || new_remaining > 0 || lines.get(i).is_some_and(|l| l[0] == b'\\') {
+        let Some(line) = lines.get(i) else {
+            return Err(PatchParseError::TruncatedHunk {
+                line: line_number + i,
+            });
+        };
+        // Some mailers strip the trailing space of empty context lines.
+        let (kind, content) = match line.split_first() {
+            Some((b'\n', _)) | None => (b' ', &b"\n"[..]),
+            Some((b'\r', rest)) if rest == b"\n" => (b' ', *line),
+            Some((&kind, content)) => (kind, content),
+        };
+        match kind {
+            b' ' if old_remaining > 0 && new_remaining > 0 => {
+                hunk.old_lines.push(content.to_vec());
+                hunk.new_lines.push(content.to_vec());
+                old_remaining -= 1;
+                new_remaining -= 1;
+                last_sides = (true, true);
+            }
+            b'-' if old_remaining > 0 => {
+                hunk.old_lines.push(content.to_vec());
+                old_remaining -= 1;
+                last_sides = (true, false);
+            }
+            b'+' if new_remaining > 0 => {
+                hunk.new_lines.push(content.to_vec());
+                new_remaining -= 1;
+                last_sides = (false, true);
+            }
+            b'\\' => {
+                // "\ No newline at end of file"
+                let strip = |lines: &mut Vec<Vec<u8>>| {
+                    if let Some(last) = lines.last_mut() {
+                        if last.ends_with(b"\r\n") {
+                            last.truncate(last.len() - 2);
+                        } else if last.ends_with(b"\n") {
+                            last.pop();
+                        }
+                    }
+                };
+                if last_sides.0 {
+                    strip(&mut hunk.old_lines);
+                }
+                if last_sides.1 {
+                    strip(&mut hunk.new_lines);
+                }
+            }
+            _ => {
+                return Err(PatchParseError::TruncatedHunk {
+                    line: line_number + i,
+                });
+            }
+        }
+        i += 1;
+    }
+    Ok((hunk, i))
+}
+
+/// Applies the `patch` to the `base` tree.
+///
+/// Hunks which don't apply at their position or at an offset are recorded as
+/// conflicts between the file content and the change in the hunks.
+pub fn apply_patch(
+    store: &Arc<Store>,
+    base: &MergedTree,
+    patch: &Patch,
+) -> Result<ApplyPatchResult, PatchApplyError> {
+    let mut tree_builder = MergedTreeBuilder::new(base.id());
+    let mut conflicted_paths = vec![];
+    for file in &patch.files {
+        let path = file.new_path.as_ref().or(file.old_path.as_ref()).unwrap();
+        let (old_content, old_executable) = match &file.old_path {
+            Some(old_path) => match read_file(store, base, old_path)? {
+                Some((content, executable)) => (Some(content), executable),
+                None => (None, false),
+            },
+            None => match read_file(store, base, path)? {
+                Some((content, executable)) => (Some(content), executable),
+                None => (None, false),
+            },
+        };
+        let executable = file.new_executable.unwrap_or(old_executable);
+        if let Some(old_path) = &file.old_path {
+            if file.new_path.as_ref() != Some(old_path) && !file.is_copy {
+                tree_builder.set_or_remove(old_path.clone(), Merge::absent());
+            }
+        }
+
+        let value = match (&file.old_path, &old_content) {
+            // Modification of an existing file, or creation of a file which
+            // exists with the same content.
+            (Some(old_path), Some(content)) => {
+                apply_hunks(content, &file.hunks).map_err(|conflict| {
+                    // If the blobs the patch was made from are available, use
+                    // them instead of the reconstructed file contents.
+                    match &file.blob_ids {
+                        Some((old_id, new_id)) => {
+                            match (
+                                read_blob(store, old_path, old_id),
+                                read_blob(store, path, new_id),
+                            ) {
+                                (Some(base), Some(patched)) => Conflict {
+                                    base: Some(base),
+                                    side: conflict.side,
+                                    patched: Some(patched),
+                                },
+                                _ => conflict,
+                            }
+                        }
+                        None => conflict,
+                    }
+                })
+            }
+            (None, Some(content)) => {
+                let new_content = file
+                    .hunks
+                    .iter()
+                    .flat_map(|h| h.new_lines.concat())
+                    .collect_vec();
+                if *content == new_content {
+                    Ok(new_content)
+                } else {
+                    Err(Conflict {
+                        base: None,
+                        side: content.clone(),
+                        patched: Some(new_content),
+                    })
+                }
+            }
+            (None, None) => Ok(file
+                .hunks
+                .iter()
+                .flat_map(|h| h.new_lines.concat())
+                .collect_vec()),
+            (Some(_), None) => Err(Conflict {
+                base: Some(
+                    file.hunks
+                        .iter()
+                        .flat_map(|h| h.old_lines.concat())
+                        .collect_vec(),
+                ),
+                side: vec![],
+                patched: Some(
+                    file.hunks
+                        .iter()
+                        .flat_map(|h| h.new_lines.concat())
+                        .collect_vec(),
+                ),
+            }),
+        };
+        // Deleting a file which has more content than the patch removes
+        // conflicts with the changes to that content.
+        let value = match value {
+            Ok(content) if file.new_path.is_none() && !content.is_empty() => Err(Conflict {
+                base: Some(
+                    file.hunks
+                        .iter()
+                        .flat_map(|h| h.old_lines.concat())
+                        .collect_vec(),
+                ),
+                side: old_content.clone().unwrap_or_default(),
+                patched: None,
+            }),
+            value => value,
+        };
+        let target_path = file.new_path.as_ref().unwrap_or(path);
+        let merged_value = match value {
+            Ok(_) if file.new_path.is_none() => Merge::absent(),
+            Ok(content) => Merge::normal(write_file(store, target_path, content, executable)?),
+            Err(conflict) => {
+                let write = |content: Option<Vec<u8>>| {
+                    content
+                        .map(|content| write_file(store, target_path, content, executable))
+                        .transpose()
+                };
+                let side = old_content.is_some().then_some(conflict.side);
+                let patched = if file.new_path.is_some() {
+                    conflict.patched
+                } else {
+                    None
+                };
+                conflicted_paths.push(target_path.clone());
+                Merge::from_vec(vec![write(side)?, write(conflict.base)?, write(patched)?])
+            }
+        };
+        tree_builder.set_or_remove(target_path.clone(), merged_value);
+    }
+    let tree_id = tree_builder.write_tree(store)?;
+    let tree = store.get_root_tree(&tree_id)?;
+    // Conflicts may have been resolved when writing the tree.
+    let mut remaining_conflicts = vec![];
+    for path in conflicted_paths {
+        if !tree.path_value(&path)?.is_resolved() {
+            remaining_conflicts.push(path);
+        }
+    }
+    Ok(ApplyPatchResult {
+        tree,
+        conflicted_paths: remaining_conflicts,
+    })
+}
+
+/// Conflicting contents of a file for which some hunks didn't apply.
+struct Conflict {
+    /// The file content the patch was made against.
+    base: Option<Vec<u8>>,
+    /// The file content with the hunks which did apply.
+    side: Vec<u8>,
+    /// The file content with the patch applied.
+    patched: Option<Vec<u8>>,
+}
+
+fn read_file(
+    store: &Store,
+    tree: &MergedTree,
+    path: &RepoPath,
+) -> Result<Option<(Vec<u8>, bool)>, PatchApplyError> {
+    let value = tree.path_value(path)?;
+    match value.into_resolved() {
+        Ok(None) => Ok(None),
+        Ok(Some(TreeValue::File { id, executable })) => {
+            let mut content = vec![];
+            store
+                .read_file(path, &id)?
+                .read_to_end(&mut content)
+                .map_err(|err| BackendError::ReadFile {
+                    path: path.to_owned(),
+                    id: id.clone(),
+                    source: err.into(),
+                })?;
+            Ok(Some((content, executable)))
+        }
+        _ => Err(PatchApplyError::NotAFile {
+            path: path.as_internal_file_string().to_owned(),
+        }),
+    }
+}
+
+/// Reads the file content with the given hex id if it exists in the store.
+fn read_blob(store: &Store, path: &RepoPath, hex: &str) -> Option<Vec<u8>> {
+    let id = FileId::try_from_hex(hex).ok()?;
+    let mut content = vec![];
+    store
+        .read_file(path, &id)
+        .ok()?
+        .read_to_end(&mut content)
+        .ok()?;
+    Some(content)
+}
+
+fn write_file(
+    store: &Store,
+    path: &RepoPath,
+    content: Vec<u8>,
+    executable: bool,
+) -> BackendResult<TreeValue> {
+    let id = store.write_file(path, &mut content.as_slice()).block_on()?;
+    Ok(TreeValue::File { id, executable })
+}
+
+/// Applies the hunks to the content. If some hunks don't apply, returns the
+/// content with the other hunks applied as one side of the conflict.
+///
+/// The base and the patched side of the conflict are reconstructed from the
+/// content by putting the old and new lines of the failed hunks at their
+/// expected positions.
+fn apply_hunks(content: &[u8], hunks: &[Hunk]) -> Result<Vec<u8>, Conflict> {
+    let original_lines: Vec<&[u8]> = content.lines_with_terminator().collect();
+    let mut lines = original_lines.clone();
+    let mut failed_hunks = vec![];
+    // Difference between the line numbers in the patched and the old content.
+    let mut offset: isize = 0;
+    // Hunks must not overlap with the already applied ones.
+    let mut min_start = 0;
+    for hunk in hunks {
+        // Hunks which only add lines start after the given line.
+        let old_start = if hunk.old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected_start = old_start.saturating_add_signed(offset);
+        match find_lines(&lines, &hunk.old_lines, expected_start, min_start) {
+            Some(start) => {
+                let end = start + hunk.old_lines.len();
+                lines.splice(
+                    start..end,
+                    hunk.new_lines.iter().map(|line| line.as_slice()),
+                );
+                offset += hunk.new_lines.len() as isize - hunk.old_lines.len() as isize;
+                min_start = start + hunk.new_lines.len();
+            }
+            None => failed_hunks.push(hunk),
+        }
+    }
+    let new_content = lines.concat();
+    if failed_hunks.is_empty() {
+        Ok(new_content)
+    } else {
+        Err(Conflict {
+            base: Some(splice_hunks(&original_lines, &failed_hunks, |h| {
+                &h.old_lines
+            })),
+            side: new_content,
+            patched: Some(splice_hunks(&original_lines, &failed_hunks, |h| {
+                &h.new_lines
+            })),
+        })
+    }
+}
+
+/// Replaces the regions of the `hunks` in the original `lines` with the lines
+/// selected from each hunk. The regions are at the positions given in the hunk
+/// headers.
+fn splice_hunks(
+    lines: &[&[u8]],
+    hunks: &[&Hunk],
+    hunk_lines: impl Fn(&Hunk) -> &[Vec<u8>],
+) -> Vec<u8> {
+    let mut lines = lines.to_vec();
+    // Splice from the end so the positions of the earlier hunks don't move.
+    for hunk in hunks.iter().rev() {
+        let start = if hunk.old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let start = start.min(lines.len());
+        let end = (start + hunk.old_lines.len()).min(lines.len());
+        lines.splice(start..end, hunk_lines(hunk).iter().map(Vec::as_slice));
+    }
+    lines.concat()
+}
+
+/// Finds the position of `needle` in `lines` closest to `expected_start`.
+fn find_lines(
+    lines: &[&[u8]],
+    needle: &[Vec<u8>],
+    expected_start: usize,
+    min_start: usize,
+) -> Option<usize> {
+    let matches_at = |start: usize| {
+        start >= min_start
+            && start + needle.len() <= lines.len()
+            && needle
+                .iter()
+                .zip(&lines[start..])
+                .all(|(a, b)| a.as_slice() == *b)
+    };
+    (0..=lines.len()).find_map(|distance| {
+        [
+            expected_start.checked_add(distance),
+            expected_start.checked_sub(distance),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|&start| matches_at(start))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(old_start: usize, old_lines: &[&str], new_lines: &[&str]) -> Hunk {
+        Hunk {
+            old_start,
+            old_lines: old_lines.iter().map(|s| s.as_bytes().to_vec()).collect(),
+            new_lines: new_lines.iter().map(|s| s.as_bytes().to_vec()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_plain_diff() {
+        let input = indoc::indoc! {b"
+            --- a/dir/file\t2001-02-03 04:05:06
+            +++ b/dir/file
+            @@ -1,3 +1,3 @@
+             a
+            -b
+            +c
+             d
+            @@ -10 +10,0 @@
+            -x
+            \\ No newline at end of file
+        "};
+        let patches = parse_patches(input).unwrap();
+        assert_eq!(
+            patches,
+            vec![Patch {
+                author: None,
+                description: "".to_owned(),
+                files: vec![FilePatch {
+                    old_path: Some(RepoPathBuf::from_internal_string("dir/file")),
+                    new_path: Some(RepoPathBuf::from_internal_string("dir/file")),
+                    is_copy: false,
+                    new_executable: None,
+                    blob_ids: None,
+                    hunks: vec![
+                        hunk(1, &["a\n", "b\n", "d\n"], &["a\n", "c\n", "d\n"]),
+                        hunk(10, &["x"], &[]),
+                    ],
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_mbox() {
+        let input = indoc::indoc! {b"
+            From 1234 Mon Sep 17 00:00:00 2001
+            From: =?UTF-8?q?Caf=C3=A9?= <cafe@example.com>
+            Date: Sat, 3 Feb 2001 04:05:06 +0700
+            Subject: [PATCH v2 1/2] add
+             file
+
+            Body.
+            ---
+             file | 1 +
+             1 file changed, 1 insertion(+)
+
+            diff --git a/file b/file
+            new file mode 100755
+            index 0000000..7898192
+            --- /dev/null
+            +++ b/file
+            @@ -0,0 +1 @@
+            +a
+
+            From 5678 Mon Sep 17 00:00:00 2001
+            From: Someone <someone@example.com>
+            Date: Sat, 3 Feb 2001 04:05:07 +0700
+            Subject: [PATCH v2 2/2] rename
+
+            ---
+            diff --git a/file b/new file
+            similarity index 100%
+            rename from file
+            rename to new file
+            --
+            2.40.0
+        "};
+        let patches = parse_patches(input).unwrap();
+        assert_eq!(patches.len(), 2);
+        let author = patches[0].author.as_ref().unwrap();
+        assert_eq!(author.name, "Café");
+        assert_eq!(author.email, "cafe@example.com");
+        assert_eq!(author.timestamp.tz_offset, 7 * 60);
+        assert_eq!(patches[0].description, "add file\n\nBody.\n");
+        assert_eq!(
+            patches[0].files,
+            vec![FilePatch {
+                old_path: None,
+                new_path: Some(RepoPathBuf::from_internal_string("file")),
+                is_copy: false,
+                new_executable: Some(true),
+                blob_ids: Some(("0000000".to_owned(), "7898192".to_owned())),
+                hunks: vec![hunk(0, &[], &["a\n"])],
+            }]
+        );
+        assert_eq!(patches[1].description, "rename\n");
+        assert_eq!(
+            patches[1].files,
+            vec![FilePatch {
+                old_path: Some(RepoPathBuf::from_internal_string("file")),
+                new_path: Some(RepoPathBuf::from_internal_string("new file")),
+                is_copy: false,
+                new_executable: None,
+                blob_ids: None,
+                hunks: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(parse_patches(b""), Err(PatchParseError::Empty)));
+        assert!(matches!(
+            parse_patches(b"--- a/file\n+++ b/file\n@@ -1 +1 @@\n-a\n"),
+            Err(PatchParseError::TruncatedHunk { line: 5 })
+        ));
+        assert!(matches!(
+            parse_patches(b"--- a/../file\n+++ b/../file\n"),
+            Err(PatchParseError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            parse_patches(b"diff --git a/f b/f\nBinary files a/f and b/f differ\n"),
+            Err(PatchParseError::Unsupported { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        let content = b"a\nb\nc\nd\ne\n";
+        // At the expected position, and with an offset
+        assert_eq!(
+            apply_hunks(
+                content,
+                &[hunk(1, &["b\n"], &["B\n"]), hunk(3, &["e\n"], &[])]
+            )
+            .ok(),
+            Some(b"a\nB\nc\nd\n".to_vec())
+        );
+        // Insertions
+        assert_eq!(
+            apply_hunks(content, &[hunk(0, &[], &["0\n"]), hunk(5, &[], &["f\n"])]).ok(),
+            Some(b"0\na\nb\nc\nd\ne\nf\n".to_vec())
+        );
+        // A failed hunk doesn't prevent the other hunks from applying
+        let Err(conflict) = apply_hunks(
+            content,
+            &[hunk(1, &["x\n"], &["y\n"]), hunk(4, &["d\n"], &["D\n"])],
+        ) else {
+            panic!("hunk shouldn't apply");
+        };
+        assert_eq!(conflict.base, Some(b"x\nb\nc\nd\ne\n".to_vec()));
+        assert_eq!(conflict.side, b"a\nb\nc\nD\ne\n");
+        assert_eq!(conflict.patched, Some(b"y\nb\nc\nd\ne\n".to_vec()));
+    }
+}
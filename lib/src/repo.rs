@@ -64,6 +64,7 @@ use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -846,6 +847,9 @@ pub struct MutableRepo {
     //   commits. However, if the type is `Abandoned`, a new working-copy commit should be created
     //   on top of all of the new commits instead.
     parent_mapping: HashMap<CommitId, Rewrite>,
+    // Whether the index was rebuilt because the parents of existing commits
+    // changed.
+    index_rebuilt: bool,
 }
 
 impl MutableRepo {
@@ -861,6 +865,7 @@ impl MutableRepo {
             index: mut_index,
             view: DirtyCell::with_clean(mut_view),
             parent_mapping: Default::default(),
+            index_rebuilt: false,
         }
     }
 
@@ -877,7 +882,9 @@ impl MutableRepo {
     }
 
     pub fn has_changes(&self) -> bool {
-        !(self.parent_mapping.is_empty() && self.view() == &self.base_repo.view)
+        !(self.parent_mapping.is_empty()
+            && !self.index_rebuilt
+            && self.view() == &self.base_repo.view)
     }
 
     pub(crate) fn consume(self) -> (Box<dyn MutableIndex>, View) {
@@ -1429,6 +1436,47 @@ impl MutableRepo {
         Ok(())
     }
 
+    /// Rebuilds the index from scratch after the parents of the given commits
+    /// changed in the backend, e.g. because a shallow Git repository was
+    /// deepened. The index is append-only, so the new parents can't be
+    /// recorded incrementally.
+    ///
+    /// Returns `false` if the index implementation can't be rebuilt.
+    pub fn reindex_reparented_commits(&mut self, commit_ids: &[CommitId]) -> BackendResult<bool> {
+        let Ok(all_heads) = self.index().all_heads_for_gc() else {
+            return Ok(false);
+        };
+        if !self.index.as_any().is::<DefaultMutableIndex>() {
+            return Ok(false);
+        }
+        let store = self.store().clone();
+        store.forget_cached_commits(commit_ids);
+        let all_heads: Vec<_> = all_heads.map(|id| store.get_commit(&id)).try_collect()?;
+        let all_commits = dag_walk::topo_order_reverse_ord_ok(
+            all_heads
+                .into_iter()
+                .map(CommitByCommitterTimestamp)
+                .map(Ok),
+            |CommitByCommitterTimestamp(commit)| commit.id().clone(),
+            |CommitByCommitterTimestamp(commit)| {
+                commit
+                    .parent_ids()
+                    .iter()
+                    .map(|id| store.get_commit(id))
+                    .map_ok(CommitByCommitterTimestamp)
+                    .collect_vec()
+            },
+        )?;
+        let mut index =
+            DefaultMutableIndex::full(store.commit_id_length(), store.change_id_length());
+        for CommitByCommitterTimestamp(commit) in all_commits.iter().rev() {
+            index.add_commit(commit);
+        }
+        self.index = Box::new(index);
+        self.index_rebuilt = true;
+        Ok(true)
+    }
+
     fn enforce_view_invariants(&self, view: &mut View) {
         let view = view.store_view_mut();
         let root_commit_id = self.store().root_commit_id();
@@ -1599,6 +1647,30 @@ impl MutableRepo {
         view.set_tag_target(name, new_target);
     }
 
+    pub fn get_remote_tag(&self, name: &str, remote_name: &str) -> RemoteRef {
+        self.view
+            .with_ref(|v| v.get_remote_tag(name, remote_name).clone())
+    }
+
+    pub fn set_remote_tag(&mut self, name: &str, remote_name: &str, remote_ref: RemoteRef) {
+        self.view_mut()
+            .set_remote_tag(name, remote_name, remote_ref);
+    }
+
+    fn merge_remote_tag(
+        &mut self,
+        name: &str,
+        remote_name: &str,
+        base_ref: &RemoteRef,
+        other_ref: &RemoteRef,
+    ) {
+        let view = self.view.get_mut();
+        let index = self.index.as_index();
+        let self_ref = view.get_remote_tag(name, remote_name);
+        let new_ref = merge_remote_refs(index, self_ref, base_ref, other_ref);
+        view.set_remote_tag(name, remote_name, new_ref);
+    }
+
     pub fn get_git_ref(&self, name: &str) -> RefTarget {
         self.view.with_ref(|v| v.get_git_ref(name).clone())
     }
@@ -1623,6 +1695,14 @@ impl MutableRepo {
         self.view_mut().set_git_head_target(target);
     }
 
+    pub fn bisect(&self) -> BisectState {
+        self.view.with_ref(|v| v.bisect().clone())
+    }
+
+    pub fn set_bisect(&mut self, state: BisectState) {
+        self.view_mut().set_bisect(state);
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.view_mut().set_view(data);
         self.view.mark_dirty();
@@ -1724,6 +1804,12 @@ impl MutableRepo {
             self.merge_remote_bookmark(name, remote_name, base_ref, other_ref);
         }
 
+        let changed_remote_tags =
+            diff_named_remote_refs(base.all_remote_tags(), other.all_remote_tags());
+        for ((name, remote_name), (base_ref, other_ref)) in changed_remote_tags {
+            self.merge_remote_tag(name, remote_name, base_ref, other_ref);
+        }
+
         let new_git_head_target = merge_ref_targets(
             self.index(),
             self.view().git_head(),
@@ -1732,6 +1818,12 @@ impl MutableRepo {
         );
         self.set_git_head_target(new_git_head_target);
 
+        // Bisection state can't be merged meaningfully. If there's a conflict,
+        // we keep the self side.
+        if other.bisect() != base.bisect() && self.view().bisect() == base.bisect() {
+            self.set_bisect(other.bisect().clone());
+        }
+
         Ok(())
     }
 
@@ -21,6 +21,7 @@ use std::convert::Infallible;
 use std::fmt;
 use std::ops::Range;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use itertools::Itertools;
@@ -46,6 +47,8 @@ use crate::object_id::PrefixResolution;
 use crate::op_store::RemoteRefState;
 use crate::op_store::WorkspaceId;
 use crate::op_walk;
+use crate::opset::OpsetAliasesMap;
+use crate::opset::OpsetParseContext;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo;
 use crate::repo::RepoLoaderError;
@@ -61,6 +64,7 @@ pub use crate::revset_parser::RevsetDiagnostics;
 pub use crate::revset_parser::RevsetParseError;
 pub use crate::revset_parser::RevsetParseErrorKind;
 pub use crate::revset_parser::UnaryOp;
+use crate::signing::SigStatus;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::time_util::DatePattern;
@@ -106,6 +110,31 @@ impl RevsetEvaluationError {
     }
 }
 
+/// Key by which `sort()` orders the resulting commits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RevsetSortKey {
+    AuthorName,
+    AuthorEmail,
+    AuthorDate,
+    CommitterDate,
+    Description,
+}
+
+impl FromStr for RevsetSortKey {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "author_name" => Ok(Self::AuthorName),
+            "author_email" => Ok(Self::AuthorEmail),
+            "author_date" => Ok(Self::AuthorDate),
+            "committer_date" => Ok(Self::CommitterDate),
+            "description" => Ok(Self::Description),
+            _ => Err(()),
+        }
+    }
+}
+
 // assumes index has less than u64::MAX entries.
 pub const GENERATION_RANGE_FULL: Range<u64> = 0..u64::MAX;
 pub const GENERATION_RANGE_EMPTY: Range<u64> = 0..0;
@@ -179,6 +208,15 @@ pub enum RevsetFilterPredicate {
     },
     /// Commits with conflicts
     HasConflict,
+    /// Commits with a cryptographic signature, whether or not it's valid.
+    Signed,
+    /// Commits with a signature whose key or signer matches the pattern.
+    SignedBy(StringPattern),
+    /// Commits with a signature of the given verification status.
+    SignatureStatus(SigStatus),
+    /// Commits with a description trailer of the key (compared
+    /// case-insensitively) and a value matching the pattern.
+    Trailer { key: String, value: StringPattern },
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
@@ -232,6 +270,11 @@ pub enum RevsetExpression<St: ExpressionState> {
         heads: Rc<Self>,
         generation: Range<u64>,
     },
+    /// Ancestors of `heads` reachable by following the first parents.
+    FirstAncestors {
+        heads: Rc<Self>,
+        generation: Range<u64>,
+    },
     Descendants {
         roots: Rc<Self>,
         generation: Range<u64>,
@@ -260,6 +303,19 @@ pub enum RevsetExpression<St: ExpressionState> {
         candidates: Rc<Self>,
         count: usize,
     },
+    /// Same set as `candidates`, iterated in the order of the sort key.
+    Sort {
+        candidates: Rc<Self>,
+        key: RevsetSortKey,
+        reverse: bool,
+    },
+    /// Commits that the given commits were rewritten from, transitively.
+    Predecessors(Rc<Self>),
+    /// Visible commits that the given commits were rewritten into,
+    /// transitively.
+    Successors(Rc<Self>),
+    /// Visible commits sharing their change id with other visible commits.
+    Divergent,
     Filter(RevsetFilterPredicate),
     /// Marker for subtree that should be intersected as filter.
     AsFilter(Rc<Self>),
@@ -301,6 +357,10 @@ impl<St: ExpressionState> RevsetExpression<St> {
         Rc::new(Self::Root)
     }
 
+    pub fn divergent() -> Rc<Self> {
+        Rc::new(Self::Divergent)
+    }
+
     pub fn commit(commit_id: CommitId) -> Rc<Self> {
         Self::commits(vec![commit_id])
     }
@@ -376,6 +436,15 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// Commits in `self`, iterated in the order of the given sort `key`.
+    pub fn sorted_by(self: &Rc<Self>, key: RevsetSortKey, reverse: bool) -> Rc<Self> {
+        Rc::new(Self::Sort {
+            candidates: self.clone(),
+            key,
+            reverse,
+        })
+    }
+
     /// Commits in `self` that don't have descendants in `self`.
     pub fn heads(self: &Rc<Self>) -> Rc<Self> {
         Rc::new(Self::Heads(self.clone()))
@@ -410,6 +479,31 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// First parents of `self`.
+    pub fn first_parent(self: &Rc<Self>) -> Rc<Self> {
+        self.first_ancestors_at(1)
+    }
+
+    /// Ancestors of `self` reachable by following the first parents, including
+    /// `self`.
+    pub fn first_ancestors(self: &Rc<Self>) -> Rc<Self> {
+        self.first_ancestors_range(GENERATION_RANGE_FULL)
+    }
+
+    /// First-parent ancestors of `self` at an offset of `generation` behind
+    /// `self`.
+    pub fn first_ancestors_at(self: &Rc<Self>, generation: u64) -> Rc<Self> {
+        self.first_ancestors_range(generation..(generation + 1))
+    }
+
+    /// First-parent ancestors of `self` in the given range.
+    pub fn first_ancestors_range(self: &Rc<Self>, generation_range: Range<u64>) -> Rc<Self> {
+        Rc::new(Self::FirstAncestors {
+            heads: self.clone(),
+            generation: generation_range,
+        })
+    }
+
     /// Children of `self`.
     pub fn children(self: &Rc<Self>) -> Rc<Self> {
         self.descendants_at(1)
@@ -439,6 +533,16 @@ impl<St: ExpressionState> RevsetExpression<St> {
         Rc::new(Self::ForkPoint(self.clone()))
     }
 
+    /// Commits that `self` were rewritten from, transitively.
+    pub fn predecessors(self: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Predecessors(self.clone()))
+    }
+
+    /// Visible commits that `self` were rewritten into, transitively.
+    pub fn successors(self: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Successors(self.clone()))
+    }
+
     /// Filter all commits by `predicate` in `self`.
     pub fn filtered(self: &Rc<Self>, predicate: RevsetFilterPredicate) -> Rc<Self> {
         self.intersection(&Self::filter(predicate))
@@ -608,6 +712,11 @@ pub enum ResolvedExpression {
         heads: Box<Self>,
         generation: Range<u64>,
     },
+    /// Ancestors of `heads` reachable by following the first parents.
+    FirstAncestors {
+        heads: Box<Self>,
+        generation: Range<u64>,
+    },
     /// Commits that are ancestors of `heads` but not ancestors of `roots`.
     Range {
         roots: Box<Self>,
@@ -632,6 +741,20 @@ pub enum ResolvedExpression {
         candidates: Box<Self>,
         count: usize,
     },
+    /// Same set as `candidates`. The ordering is only honored at the top
+    /// level of the expression.
+    Sort {
+        candidates: Box<Self>,
+        key: RevsetSortKey,
+        reverse: bool,
+    },
+    /// Commits that the given commits were rewritten from, transitively.
+    Predecessors(Box<Self>),
+    /// Commits that the given commits were rewritten into, transitively.
+    Successors(Box<Self>),
+    /// Commits in `candidates` sharing their change id with other commits in
+    /// `candidates`.
+    Divergent(Box<Self>),
     Coalesce(Box<Self>, Box<Self>),
     Union(Box<Self>, Box<Self>),
     /// Intersects `candidates` with `predicate` by filtering.
@@ -675,6 +798,27 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(heads.ancestors_range(generation))
     });
+    map.insert("first_parent", |diagnostics, function, context| {
+        let ([arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let expression = lower_expression(diagnostics, arg, context)?;
+        let depth = if let Some(depth_arg) = depth_opt_arg {
+            expect_literal(diagnostics, "integer", depth_arg)?
+        } else {
+            1
+        };
+        Ok(expression.first_ancestors_at(depth))
+    });
+    map.insert("first_ancestors", |diagnostics, function, context| {
+        let ([heads_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let heads = lower_expression(diagnostics, heads_arg, context)?;
+        let generation = if let Some(depth_arg) = depth_opt_arg {
+            let depth = expect_literal(diagnostics, "integer", depth_arg)?;
+            0..depth
+        } else {
+            GENERATION_RANGE_FULL
+        };
+        Ok(heads.first_ancestors_range(generation))
+    });
     map.insert("descendants", |diagnostics, function, context| {
         let ([roots_arg], [depth_opt_arg]) = function.expect_arguments()?;
         let roots = lower_expression(diagnostics, roots_arg, context)?;
@@ -691,6 +835,20 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         let candidates = lower_expression(diagnostics, arg, context)?;
         Ok(candidates.connected())
     });
+    map.insert("predecessors", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(diagnostics, arg, context)?;
+        Ok(expression.predecessors())
+    });
+    map.insert("successors", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(diagnostics, arg, context)?;
+        Ok(expression.successors())
+    });
+    map.insert("divergent", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::divergent())
+    });
     map.insert("reachable", |diagnostics, function, context| {
         let [source_arg, domain_arg] = function.expect_exact_arguments()?;
         let sources = lower_expression(diagnostics, source_arg, context)?;
@@ -818,6 +976,24 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(candidates.latest(count))
     });
+    map.insert("sort", |diagnostics, function, context| {
+        let ([candidates_arg, key_arg], [order_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let key = expect_literal(diagnostics, "sort key", key_arg)?;
+        let reverse = if let Some(order_arg) = order_opt_arg {
+            let order: String = expect_literal(diagnostics, "sort order", order_arg)?;
+            if order != "reverse" {
+                return Err(RevsetParseError::expression(
+                    "Expected sort order `reverse`",
+                    order_arg.span,
+                ));
+            }
+            true
+        } else {
+            false
+        };
+        Ok(candidates.sorted_by(key, reverse))
+    });
     map.insert("fork_point", |diagnostics, function, context| {
         let [expression_arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, expression_arg, context)?;
@@ -972,6 +1148,37 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
     });
     // TODO: Remove in jj 0.28+
     map.insert("conflict", map["conflicts"]);
+    map.insert("signed", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Signed))
+    });
+    map.insert("signed_by", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(diagnostics, arg)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::SignedBy(
+            pattern,
+        )))
+    });
+    map.insert("signature_status", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let status = expect_literal(diagnostics, "signature status", arg)?;
+        Ok(RevsetExpression::filter(
+            RevsetFilterPredicate::SignatureStatus(status),
+        ))
+    });
+    map.insert("trailer", |diagnostics, function, _context| {
+        let ([key_arg], [value_opt_arg]) = function.expect_arguments()?;
+        let key = expect_literal(diagnostics, "string", key_arg)?;
+        let value = if let Some(value_arg) = value_opt_arg {
+            expect_string_pattern(diagnostics, value_arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Trailer {
+            key,
+            value,
+        }))
+    });
     map.insert("present", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, arg, context)?;
@@ -1196,8 +1403,10 @@ pub fn parse(
 ) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
     let node = revset_parser::parse_program(revset_str)?;
     let node = dsl_util::expand_aliases(node, context.aliases_map)?;
-    lower_expression(diagnostics, &node, context)
-        .map_err(|err| err.extend_function_candidates(context.aliases_map.function_names()))
+    let expression = lower_expression(diagnostics, &node, context)
+        .map_err(|err| err.extend_function_candidates(context.aliases_map.function_names()))?;
+    warn_discarded_sort_order(diagnostics, &node, true);
+    Ok(expression)
 }
 
 pub fn parse_with_modifier(
@@ -1220,6 +1429,70 @@ pub fn parse_with_modifier(
         },
     )
     .map_err(|err| err.extend_function_candidates(context.aliases_map.function_names()))
+    .inspect(|_| warn_discarded_sort_order(diagnostics, &node, true))
+}
+
+/// Warns about `sort()` calls whose ordering is discarded because the sorted
+/// set is combined with other operators. `ordered` tells whether the ordering
+/// of the `node` would be preserved.
+fn warn_discarded_sort_order(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+    ordered: bool,
+) {
+    match &node.kind {
+        ExpressionKind::Identifier(_)
+        | ExpressionKind::String(_)
+        | ExpressionKind::StringPattern { .. }
+        | ExpressionKind::RemoteSymbol { .. }
+        | ExpressionKind::AtWorkspace(_)
+        | ExpressionKind::AtCurrentWorkspace
+        | ExpressionKind::DagRangeAll
+        | ExpressionKind::RangeAll => {}
+        ExpressionKind::Unary(_, arg_node) => {
+            warn_discarded_sort_order(diagnostics, arg_node, false);
+        }
+        ExpressionKind::Binary(_, lhs_node, rhs_node) => {
+            warn_discarded_sort_order(diagnostics, lhs_node, false);
+            warn_discarded_sort_order(diagnostics, rhs_node, false);
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            for node in nodes {
+                warn_discarded_sort_order(diagnostics, node, false);
+            }
+        }
+        ExpressionKind::FunctionCall(function) => {
+            if function.name == "sort" && !ordered {
+                diagnostics.add_warning(RevsetParseError::expression(
+                    "Ordering of sort() is discarded when combined with other operators",
+                    function.name_span,
+                ));
+            }
+            // latest() selects commits by the sort keys of its candidates.
+            // Nested sort() calls are reported once by the outermost one.
+            let candidates_ordered = match function.name {
+                "sort" | "latest" => true,
+                "present" => ordered,
+                _ => false,
+            };
+            for (i, arg) in function.args.iter().enumerate() {
+                warn_discarded_sort_order(diagnostics, arg, candidates_ordered && i == 0);
+            }
+            for arg in &function.keyword_args {
+                warn_discarded_sort_order(diagnostics, &arg.value, false);
+            }
+        }
+        ExpressionKind::Modifier(modifier) => {
+            warn_discarded_sort_order(diagnostics, &modifier.body, ordered);
+        }
+        ExpressionKind::AliasExpanded(id, subst) => {
+            let mut inner_diagnostics = RevsetDiagnostics::new();
+            warn_discarded_sort_order(&mut inner_diagnostics, subst, ordered);
+            diagnostics.extend_with(inner_diagnostics, |diag| {
+                diag.within_alias_expansion(*id, node.span)
+            });
+        }
+    }
 }
 
 /// `Some` for rewritten expression, or `None` to reuse the original expression.
@@ -1272,6 +1545,12 @@ fn try_transform_expression<St: ExpressionState, E>(
                     heads,
                     generation: generation.clone(),
                 }),
+            RevsetExpression::FirstAncestors { heads, generation } => {
+                transform_rec(heads, pre, post)?.map(|heads| RevsetExpression::FirstAncestors {
+                    heads,
+                    generation: generation.clone(),
+                })
+            }
             RevsetExpression::Descendants { roots, generation } => transform_rec(roots, pre, post)?
                 .map(|roots| RevsetExpression::Descendants {
                     roots,
@@ -1310,6 +1589,22 @@ fn try_transform_expression<St: ExpressionState, E>(
                     candidates,
                     count: *count,
                 }),
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => transform_rec(candidates, pre, post)?.map(|candidates| RevsetExpression::Sort {
+                candidates,
+                key: *key,
+                reverse: *reverse,
+            }),
+            RevsetExpression::Predecessors(commits) => {
+                transform_rec(commits, pre, post)?.map(RevsetExpression::Predecessors)
+            }
+            RevsetExpression::Successors(commits) => {
+                transform_rec(commits, pre, post)?.map(RevsetExpression::Successors)
+            }
+            RevsetExpression::Divergent => None,
             RevsetExpression::Filter(_) => None,
             RevsetExpression::AsFilter(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::AsFilter)
@@ -1457,6 +1752,11 @@ where
             let generation = generation.clone();
             RevsetExpression::Ancestors { heads, generation }.into()
         }
+        RevsetExpression::FirstAncestors { heads, generation } => {
+            let heads = folder.fold_expression(heads)?;
+            let generation = generation.clone();
+            RevsetExpression::FirstAncestors { heads, generation }.into()
+        }
         RevsetExpression::Descendants { roots, generation } => {
             let roots = folder.fold_expression(roots)?;
             let generation = generation.clone();
@@ -1504,6 +1804,30 @@ where
             let count = *count;
             RevsetExpression::Latest { candidates, count }.into()
         }
+        RevsetExpression::Sort {
+            candidates,
+            key,
+            reverse,
+        } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let key = *key;
+            let reverse = *reverse;
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            }
+            .into()
+        }
+        RevsetExpression::Predecessors(commits) => {
+            let commits = folder.fold_expression(commits)?;
+            RevsetExpression::Predecessors(commits).into()
+        }
+        RevsetExpression::Successors(commits) => {
+            let commits = folder.fold_expression(commits)?;
+            RevsetExpression::Successors(commits).into()
+        }
+        RevsetExpression::Divergent => RevsetExpression::Divergent.into(),
         RevsetExpression::Filter(predicate) => RevsetExpression::Filter(predicate.clone()).into(),
         RevsetExpression::AsFilter(candidates) => {
             let candidates = folder.fold_expression(candidates)?;
@@ -1867,7 +2191,10 @@ fn reload_repo_at_operation(
     // of the current operation. If it weren't, there might be commits unknown
     // to the outer repo.
     let base_repo = repo.base_repo();
-    let operation = op_walk::resolve_op_with_repo(base_repo, op_str)
+    // Aliases and revsets aren't available at resolution time.
+    let aliases_map = OpsetAliasesMap::new();
+    let context = OpsetParseContext::new(&aliases_map, chrono::Local::now().into(), None);
+    let operation = op_walk::resolve_op_with_repo(base_repo, &context, op_str)
         .map_err(|err| RevsetResolutionError::Other(err.into()))?;
     base_repo.reload_at(&operation).map_err(|err| match err {
         RepoLoaderError::Backend(err) => RevsetResolutionError::StoreError(err),
@@ -2350,6 +2677,12 @@ impl VisibilityResolutionContext<'_> {
                 heads: self.resolve(heads).into(),
                 generation: generation.clone(),
             },
+            RevsetExpression::FirstAncestors { heads, generation } => {
+                ResolvedExpression::FirstAncestors {
+                    heads: self.resolve(heads).into(),
+                    generation: generation.clone(),
+                }
+            }
             RevsetExpression::Descendants { roots, generation } => ResolvedExpression::DagRange {
                 roots: self.resolve(roots).into(),
                 heads: self.resolve_visible_heads().into(),
@@ -2386,6 +2719,27 @@ impl VisibilityResolutionContext<'_> {
                 candidates: self.resolve(candidates).into(),
                 count: *count,
             },
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => ResolvedExpression::Sort {
+                candidates: self.resolve(candidates).into(),
+                key: *key,
+                reverse: *reverse,
+            },
+            RevsetExpression::Predecessors(commits) => {
+                ResolvedExpression::Predecessors(self.resolve(commits).into())
+            }
+            RevsetExpression::Successors(commits) => {
+                // Successors are usually rewritten again and hidden. Only the
+                // visible ones are interesting.
+                ResolvedExpression::Intersection(
+                    ResolvedExpression::Successors(self.resolve(commits).into()).into(),
+                    self.resolve_all().into(),
+                )
+            }
+            RevsetExpression::Divergent => ResolvedExpression::Divergent(self.resolve_all().into()),
             RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
                 // Top-level filter without intersection: e.g. "~author(_)" is represented as
                 // `AsFilter(NotIn(Filter(Author(_))))`.
@@ -2480,6 +2834,7 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Commits(_)
             | RevsetExpression::CommitRef(_)
             | RevsetExpression::Ancestors { .. }
+            | RevsetExpression::FirstAncestors { .. }
             | RevsetExpression::Descendants { .. }
             | RevsetExpression::Range { .. }
             | RevsetExpression::DagRange { .. }
@@ -2487,13 +2842,18 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Heads(_)
             | RevsetExpression::Roots(_)
             | RevsetExpression::ForkPoint(_)
-            | RevsetExpression::Latest { .. } => {
+            | RevsetExpression::Latest { .. }
+            | RevsetExpression::Predecessors(_)
+            | RevsetExpression::Successors(_)
+            | RevsetExpression::Divergent => {
                 ResolvedPredicateExpression::Set(self.resolve(expression).into())
             }
             RevsetExpression::Filter(predicate) => {
                 ResolvedPredicateExpression::Filter(predicate.clone())
             }
             RevsetExpression::AsFilter(candidates) => self.resolve_predicate(candidates),
+            // Ordering doesn't matter to a predicate.
+            RevsetExpression::Sort { candidates, .. } => self.resolve_predicate(candidates),
             RevsetExpression::AtOperation { operation, .. } => match *operation {},
             // Filters should be intersected with all() within the at-op repo.
             RevsetExpression::WithinVisibility { .. } => {
@@ -3319,6 +3679,29 @@ mod tests {
             ),
         )
         "###);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, author_date)").unwrap(), @r#"
+        Sort {
+            candidates: CommitRef(Symbol("foo")),
+            key: AuthorDate,
+            reverse: false,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, \"description\", reverse)").unwrap(), @r#"
+        Sort {
+            candidates: CommitRef(Symbol("foo")),
+            key: Description,
+            reverse: true,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, size)").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type sort key")"#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, author_date, forward)").unwrap_err().kind(),
+            @r#"Expression("Expected sort order `reverse`")"#);
+        assert!(parse("sort(foo)").is_err());
     }
 
     #[test]
@@ -3357,6 +3740,38 @@ mod tests {
         insta::assert_debug_snapshot!(
             parse("mine()").unwrap(),
             @r#"Filter(AuthorEmail(ExactI("test.user@example.com")))"#);
+
+        insta::assert_debug_snapshot!(parse("signed()").unwrap(), @"Filter(Signed)");
+        insta::assert_debug_snapshot!(
+            parse("signed_by(foo)").unwrap(),
+            @r#"Filter(SignedBy(Substring("foo")))"#);
+        insta::assert_debug_snapshot!(
+            parse("signature_status(bad)").unwrap(),
+            @"Filter(SignatureStatus(Bad))");
+        insta::assert_debug_snapshot!(
+            parse("signature_status(foo)").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type signature status")"#);
+
+        insta::assert_debug_snapshot!(
+            parse("trailer(Change-Id)").unwrap(),
+            @r#"
+        Filter(
+            Trailer {
+                key: "Change-Id",
+                value: Substring(""),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("trailer('Signed-off-by', exact:'Some One')").unwrap(),
+            @r#"
+        Filter(
+            Trailer {
+                key: "Signed-off-by",
+                value: Exact("Some One"),
+            },
+        )
+        "#);
     }
 
     #[test]
@@ -18,6 +18,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use chrono::DateTime;
 use rand::prelude::*;
@@ -61,6 +62,8 @@ pub struct GitSettings {
     pub abandon_unreachable_commits: bool,
     pub subprocess: bool,
     pub executable_path: PathBuf,
+    pub lfs_connect_timeout: Duration,
+    pub lfs_read_timeout: Duration,
 }
 
 impl GitSettings {
@@ -70,6 +73,8 @@ impl GitSettings {
             abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
             subprocess: settings.get_bool("git.subprocess")?,
             executable_path: settings.get("git.executable-path")?,
+            lfs_connect_timeout: Duration::from_secs(settings.get("git.lfs.connect-timeout")?),
+            lfs_read_timeout: Duration::from_secs(settings.get("git.lfs.read-timeout")?),
         })
     }
 }
@@ -81,6 +86,8 @@ impl Default for GitSettings {
             abandon_unreachable_commits: true,
             subprocess: false,
             executable_path: PathBuf::from("git"),
+            lfs_connect_timeout: Duration::from_secs(30),
+            lfs_read_timeout: Duration::from_secs(300),
         }
     }
 }
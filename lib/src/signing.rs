@@ -17,6 +17,7 @@
 
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Mutex;
 
 use clru::CLruCache;
@@ -25,6 +26,7 @@ use thiserror::Error;
 use crate::backend::CommitId;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
+use crate::gpg_signing::GpgsmBackend;
 use crate::settings::UserSettings;
 use crate::ssh_signing::SshBackend;
 use crate::store::COMMIT_CACHE_CAPACITY;
@@ -53,6 +55,19 @@ impl Display for SigStatus {
     }
 }
 
+impl FromStr for SigStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "good" => Ok(SigStatus::Good),
+            "unknown" => Ok(SigStatus::Unknown),
+            "bad" => Ok(SigStatus::Bad),
+            _ => Err(()),
+        }
+    }
+}
+
 /// The result of a signature verification.
 /// Key and display are optional additional info that backends can or can not
 /// provide to add additional information for the templater to potentially show.
@@ -178,10 +193,10 @@ impl Signer {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, SignInitError> {
         let mut backends: Vec<Box<dyn SigningBackend>> = vec![
             Box::new(GpgBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
+            Box::new(GpgsmBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             Box::new(SshBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             #[cfg(feature = "testing")]
             Box::new(TestSigningBackend),
-            // Box::new(X509Backend::from_settings(settings).map_err(..)?),
         ];
 
         let main_backend = settings
@@ -45,6 +45,7 @@ use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -446,6 +447,15 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
         });
     }
 
+    for ((name, remote_name), remote_ref) in op_store::flatten_remote_tags(&view.remote_views) {
+        proto.remote_tags.push(crate::protos::op_store::RemoteTag {
+            remote_name: remote_name.to_owned(),
+            name: name.to_owned(),
+            target: ref_target_to_proto(&remote_ref.target),
+            state: remote_ref_state_to_proto(remote_ref.state),
+        });
+    }
+
     for (git_ref_name, target) in &view.git_refs {
         proto.git_refs.push(crate::protos::op_store::GitRef {
             name: git_ref_name.clone(),
@@ -456,6 +466,10 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
 
     proto.git_head = ref_target_to_proto(&view.git_head);
 
+    if view.bisect.is_active() {
+        proto.bisect = Some(bisect_state_to_proto(&view.bisect));
+    }
+
     proto
 }
 
@@ -485,6 +499,19 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
             .insert(tag_proto.name, ref_target_from_proto(tag_proto.target));
     }
 
+    for remote_tag_proto in proto.remote_tags {
+        let remote_view = view
+            .remote_views
+            .entry(remote_tag_proto.remote_name)
+            .or_default();
+        let remote_ref = RemoteRef {
+            target: ref_target_from_proto(remote_tag_proto.target),
+            state: remote_ref_state_from_proto(remote_tag_proto.state)
+                .unwrap_or(RemoteRefState::Tracking),
+        };
+        remote_view.tags.insert(remote_tag_proto.name, remote_ref);
+    }
+
     for git_ref in proto.git_refs {
         let target = if git_ref.target.is_some() {
             ref_target_from_proto(git_ref.target)
@@ -502,9 +529,31 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
         view.git_head = RefTarget::normal(CommitId::new(proto.git_head_legacy));
     }
 
+    if let Some(bisect) = proto.bisect {
+        view.bisect = bisect_state_from_proto(bisect);
+    }
+
     view
 }
 
+fn bisect_state_to_proto(state: &BisectState) -> crate::protos::op_store::BisectState {
+    let ids_to_proto = |ids: &[CommitId]| ids.iter().map(|id| id.to_bytes()).collect();
+    crate::protos::op_store::BisectState {
+        good_ids: ids_to_proto(&state.good_ids),
+        bad_ids: ids_to_proto(&state.bad_ids),
+        skipped_ids: ids_to_proto(&state.skipped_ids),
+    }
+}
+
+fn bisect_state_from_proto(proto: crate::protos::op_store::BisectState) -> BisectState {
+    let ids_from_proto = |ids: Vec<Vec<u8>>| ids.into_iter().map(CommitId::new).collect();
+    BisectState {
+        good_ids: ids_from_proto(proto.good_ids),
+        bad_ids: ids_from_proto(proto.bad_ids),
+        skipped_ids: ids_from_proto(proto.skipped_ids),
+    }
+}
+
 fn bookmark_views_to_proto_legacy(
     local_bookmarks: &BTreeMap<String, RefTarget>,
     remote_views: &BTreeMap<String, RemoteView>,
@@ -707,7 +756,7 @@ mod tests {
                 "main".to_string() => bookmark_main_local_target,
             },
             tags: btreemap! {
-                "v1.0".to_string() => tag_v1_target,
+                "v1.0".to_string() => tag_v1_target.clone(),
             },
             remote_views: btreemap! {
                 "origin".to_string() => RemoteView {
@@ -715,6 +764,9 @@ mod tests {
                         "main".to_string() => tracking_remote_ref(&bookmark_main_origin_target),
                         "deleted".to_string() => new_remote_ref(&bookmark_deleted_origin_target),
                     },
+                    tags: btreemap! {
+                        "v1.0".to_string() => tracking_remote_ref(&tag_v1_target),
+                    },
                 },
             },
             git_refs: btreemap! {
@@ -726,6 +778,7 @@ mod tests {
                 WorkspaceId::default() => default_wc_commit_id,
                 WorkspaceId::new("test".to_string()) => test_wc_commit_id,
             },
+            bisect: BisectState::default(),
         }
     }
 
@@ -762,7 +815,7 @@ mod tests {
         // Test exact output so we detect regressions in compatibility
         assert_snapshot!(
             ViewId::new(blake2b_hash(&create_view()).to_vec()).hex(),
-            @"f426676b3a2f7c6b9ec8677cb05ed249d0d244ab7e86a7c51117e2d8a4829db65e55970c761231e2107d303bf3d33a1f2afdd4ed2181f223e99753674b20a35e"
+            @"534caeb9dabce754318804c2a5ee2662b0a55b1227daaf012375cfc50b4f98d97531a0ea8a73cb74acae5be25a40c8f03eadbd0a6368e0ab89ec34860360302c"
         );
     }
 
@@ -788,6 +841,28 @@ mod tests {
         assert_eq!(read_view, view);
     }
 
+    #[test]
+    fn test_read_write_view_with_bisect() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data);
+        let view = View {
+            bisect: BisectState {
+                good_ids: vec![CommitId::from_hex("aaa111")],
+                bad_ids: vec![CommitId::from_hex("aaa222")],
+                skipped_ids: vec![CommitId::from_hex("aaa333"), CommitId::from_hex("aaa444")],
+            },
+            ..create_view()
+        };
+        let view_id = store.write_view(&view).unwrap();
+        // The bisection state contributes to the view id.
+        assert_ne!(view_id, store.write_view(&create_view()).unwrap());
+        let read_view = store.read_view(&view_id).unwrap();
+        assert_eq!(read_view, view);
+    }
+
     #[test]
     fn test_read_write_operation() {
         let temp_dir = testutils::new_temp_dir();
@@ -826,11 +901,13 @@ mod tests {
                 bookmarks: btreemap! {
                     "bookmark1".to_owned() => tracking_remote_ref(&git_bookmark1_target),
                 },
+                tags: btreemap! {},
             },
             "remote1".to_owned() => RemoteView {
                 bookmarks: btreemap! {
                     "bookmark1".to_owned() => tracking_remote_ref(&remote1_bookmark1_target),
                 },
+                tags: btreemap! {},
             },
             "remote2".to_owned() => RemoteView {
                 bookmarks: btreemap! {
@@ -838,6 +915,7 @@ mod tests {
                     "bookmark2".to_owned() => new_remote_ref(&remote2_bookmark2_target),
                     "bookmark4".to_owned() => tracking_remote_ref(&remote2_bookmark4_target),
                 },
+                tags: btreemap! {},
             },
         };
 
@@ -0,0 +1,278 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sparse patterns deciding which paths are present in a working copy.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use itertools::Either;
+use itertools::Itertools as _;
+
+use crate::dsl_util;
+use crate::fileset;
+use crate::fileset::FilePattern;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseResult;
+use crate::fileset_parser;
+use crate::matchers::DifferenceMatcher;
+use crate::matchers::Matcher;
+use crate::matchers::RecursiveMatcher;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathUiConverter;
+
+/// Pattern that decides which paths are present in a working copy.
+///
+/// The pattern is a fileset expression of workspace-relative paths, such as
+/// `lib`, `glob:services/*/api` or `root-file:README.md`. If the pattern
+/// matches a directory, the files in the directory are matched too. A negated
+/// pattern such as `~docs` excludes the paths from the ones matched by the
+/// other patterns.
+#[derive(Clone, Debug)]
+pub struct SparsePattern {
+    text: String,
+    /// Expression of the included paths, or the excluded paths if negated.
+    expression: FilesetExpression,
+    negated: bool,
+}
+
+impl SparsePattern {
+    /// Parses the text as a fileset expression of workspace-relative paths.
+    pub fn parse(diagnostics: &mut FilesetDiagnostics, text: &str) -> FilesetParseResult<Self> {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+        let text = text.trim();
+        let (expression, negated) =
+            match fileset::parse_maybe_bare(diagnostics, text, &path_converter)? {
+                FilesetExpression::Difference(all, excluded)
+                    if matches!(*all, FilesetExpression::All) =>
+                {
+                    (*excluded, true)
+                }
+                expression => (expression, false),
+            };
+        Ok(SparsePattern {
+            text: text.to_owned(),
+            expression,
+            negated,
+        })
+    }
+
+    /// Pattern that matches the path and all the paths under it.
+    pub fn from_path(path: &RepoPath) -> Self {
+        let path_str = path.as_internal_file_string();
+        let text = if path.is_root() {
+            ".".to_owned()
+        } else if fileset_parser::is_identifier(path_str) && !path_str.contains('\\') {
+            path_str.to_owned()
+        } else {
+            format!(r#""{}""#, dsl_util::escape_string(path_str))
+        };
+        SparsePattern {
+            text,
+            expression: FilesetExpression::prefix_path(path.to_owned()),
+            negated: false,
+        }
+    }
+
+    /// Pattern that matches all paths.
+    pub fn everything() -> Self {
+        Self::from_path(RepoPath::root())
+    }
+
+    /// The fileset expression text of this pattern.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether this pattern excludes paths instead of including them.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Returns the path if this pattern is a plain path prefix as created by
+    /// [`SparsePattern::from_path()`].
+    pub fn as_path(&self) -> Option<&RepoPath> {
+        match &self.expression {
+            FilesetExpression::Pattern(FilePattern::PrefixPath(path))
+                if !self.negated && Self::from_path(path).text == self.text =>
+            {
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SparsePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+// Patterns are compared by their text. The parsed expression is derived from
+// it.
+impl PartialEq for SparsePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for SparsePattern {}
+
+impl PartialOrd for SparsePattern {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SparsePattern {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.text.cmp(&other.text)
+    }
+}
+
+impl Hash for SparsePattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+/// Builds a matcher of the paths included by the `patterns` and not excluded
+/// by any of the negated patterns.
+pub fn sparse_matcher(patterns: &[SparsePattern]) -> Box<dyn Matcher> {
+    let (excluded, included): (Vec<_>, Vec<_>) = patterns.iter().partition_map(|pattern| {
+        if pattern.negated {
+            Either::Left(pattern.expression.clone())
+        } else {
+            Either::Right(pattern.expression.clone())
+        }
+    });
+    let included_matcher =
+        RecursiveMatcher::new(FilesetExpression::union_all(included).to_matcher());
+    if excluded.is_empty() {
+        return Box::new(included_matcher);
+    }
+    let excluded_matcher =
+        RecursiveMatcher::new(FilesetExpression::union_all(excluded).to_matcher());
+    Box::new(DifferenceMatcher::new(included_matcher, excluded_matcher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value)
+    }
+
+    fn parse(text: &str) -> SparsePattern {
+        SparsePattern::parse(&mut FilesetDiagnostics::new(), text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sparse_pattern() {
+        let pattern = parse("dir/sub");
+        assert_eq!(pattern.as_str(), "dir/sub");
+        assert!(!pattern.is_negated());
+
+        let pattern = parse(" ~docs ");
+        assert_eq!(pattern.as_str(), "~docs");
+        assert!(pattern.is_negated());
+
+        // Only the top-level negation excludes paths
+        assert!(!parse("~docs | lib").is_negated());
+
+        assert!(SparsePattern::parse(&mut FilesetDiagnostics::new(), "..").is_err());
+        assert!(SparsePattern::parse(&mut FilesetDiagnostics::new(), "foo(").is_err());
+    }
+
+    #[test]
+    fn test_sparse_pattern_from_path() {
+        assert_eq!(SparsePattern::everything().as_str(), ".");
+        assert_eq!(
+            SparsePattern::from_path(repo_path("dir/file.txt")).as_str(),
+            "dir/file.txt"
+        );
+        assert_eq!(
+            SparsePattern::from_path(repo_path("dir/with space")).as_str(),
+            r#""dir/with space""#
+        );
+        assert_eq!(
+            SparsePattern::from_path(repo_path(r#"a"b\c"#)).as_str(),
+            r#""a\"b\\c""#
+        );
+
+        // The text round-trips
+        for path in ["", "dir/file.txt", "dir/with space", "a~b|c"] {
+            let pattern = SparsePattern::from_path(repo_path(path));
+            let matcher = sparse_matcher(&[parse(pattern.as_str())]);
+            assert!(matcher.matches(repo_path(path)), "{path:?}");
+        }
+    }
+
+    #[test]
+    fn test_sparse_pattern_as_path() {
+        assert_eq!(
+            SparsePattern::everything().as_path(),
+            Some(RepoPath::root())
+        );
+        assert_eq!(parse("dir/sub").as_path(), Some(repo_path("dir/sub")));
+        assert_eq!(
+            parse(r#""dir/with space""#).as_path(),
+            Some(repo_path("dir/with space"))
+        );
+        // Equivalent expressions with a different text aren't plain paths
+        assert_eq!(parse(r#""dir""#).as_path(), None);
+        assert_eq!(parse("cwd:dir").as_path(), None);
+        assert_eq!(parse("~dir").as_path(), None);
+        assert_eq!(parse("glob:dir/*").as_path(), None);
+    }
+
+    #[test]
+    fn test_sparse_matcher() {
+        let matcher = sparse_matcher(&[]);
+        assert!(!matcher.matches(repo_path("file")));
+
+        let matcher = sparse_matcher(&[SparsePattern::everything()]);
+        assert!(matcher.matches(repo_path("file")));
+        assert!(matcher.matches(repo_path("dir/file")));
+
+        let matcher = sparse_matcher(&[
+            parse("glob:services/*/api"),
+            parse("root-file:README.md"),
+            parse("~services/legacy"),
+        ]);
+        assert!(matcher.matches(repo_path("README.md")));
+        assert!(matcher.matches(repo_path("services/foo/api/file")));
+        assert!(matcher.matches(repo_path("services/foo/api/sub/file")));
+        assert!(!matcher.matches(repo_path("services/foo/impl/file")));
+        assert!(!matcher.matches(repo_path("services/legacy/api/file")));
+
+        let matcher = sparse_matcher(&[SparsePattern::everything(), parse("~docs")]);
+        assert!(matcher.matches(repo_path("file")));
+        assert!(matcher.matches(repo_path("dir/docs/file")));
+        assert!(!matcher.matches(repo_path("docs")));
+        assert!(!matcher.matches(repo_path("docs/file")));
+
+        // Negated patterns only exclude paths
+        let matcher = sparse_matcher(&[parse("~docs")]);
+        assert!(!matcher.matches(repo_path("file")));
+    }
+}
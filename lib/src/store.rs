@@ -134,6 +134,16 @@ impl Store {
         self.get_commit_async(id).block_on()
     }
 
+    /// Drops the cached data of the given commits, so they are read from the
+    /// backend again. This is needed if the backend changed them in place,
+    /// e.g. when the parents of a shallow Git commit were fetched.
+    pub fn forget_cached_commits(&self, ids: &[CommitId]) {
+        let mut locked_cache = self.commit_cache.lock().unwrap();
+        for id in ids {
+            locked_cache.pop(id);
+        }
+    }
+
     pub async fn get_commit_async(self: &Arc<Self>, id: &CommitId) -> BackendResult<Commit> {
         let data = self.get_backend_commit(id).await?;
         Ok(Commit::new(self.clone(), id.clone(), data))
@@ -188,6 +188,34 @@ impl StringPattern {
         }
     }
 
+    /// Converts this pattern to a regular expression which finds the matching
+    /// parts of the input string. Returns `None` if the pattern can't be
+    /// represented as a regex.
+    ///
+    /// Unlike [`Self::matches()`], case‐insensitive patterns are translated to
+    /// regexes that fold Unicode case differences.
+    pub fn to_regex(&self) -> Option<regex::Regex> {
+        let build = |src: &str, case_insensitive: bool| {
+            regex::RegexBuilder::new(src)
+                .case_insensitive(case_insensitive)
+                .build()
+                .expect("escaped literal should be a valid regex")
+        };
+        match self {
+            StringPattern::Exact(literal) => {
+                Some(build(&format!("^{}$", regex::escape(literal)), false))
+            }
+            StringPattern::ExactI(literal) => {
+                Some(build(&format!("^{}$", regex::escape(literal)), true))
+            }
+            StringPattern::Substring(needle) => Some(build(&regex::escape(needle), false)),
+            StringPattern::SubstringI(needle) => Some(build(&regex::escape(needle), true)),
+            StringPattern::Glob(_) => None,
+            StringPattern::GlobI(_) => None,
+            StringPattern::Regex(pattern) => Some(pattern.clone()),
+        }
+    }
+
     /// Returns true if this pattern matches the `haystack`.
     ///
     /// When matching against a case‐insensitive pattern, only ASCII case
@@ -272,6 +300,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_string_pattern_to_regex() {
+        let to_regex_str =
+            |pattern: StringPattern| pattern.to_regex().map(|regex| regex.as_str().to_owned());
+        assert_eq!(
+            to_regex_str(StringPattern::exact("a.b")),
+            Some(r"^a\.b$".to_owned())
+        );
+        assert_eq!(
+            to_regex_str(StringPattern::substring("a*")),
+            Some(r"a\*".to_owned())
+        );
+        assert_eq!(
+            to_regex_str(StringPattern::regex("a+").unwrap()),
+            Some("a+".to_owned())
+        );
+        assert_eq!(to_regex_str(StringPattern::glob("a*").unwrap()), None);
+
+        let regex = StringPattern::substring_i("Foo").to_regex().unwrap();
+        assert!(regex.is_match("a fOO b"));
+        let regex = StringPattern::exact_i("Foo").to_regex().unwrap();
+        assert!(regex.is_match("fOO"));
+        assert!(!regex.is_match("a fOO b"));
+    }
+
     #[test]
     fn test_parse() {
         // Parse specific pattern kinds.
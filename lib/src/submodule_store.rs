@@ -15,7 +15,72 @@
 #![allow(missing_docs)]
 
 use std::fmt::Debug;
+use std::path::Path;
 
+use thiserror::Error;
+
+use crate::backend::CommitId;
+#[cfg(feature = "git")]
+use crate::git::RemoteCallbacks;
+
+#[derive(Debug, Error)]
+pub enum SubmoduleStoreError {
+    #[error("Invalid submodule name {0:?}")]
+    InvalidName(String),
+    #[error("Submodule {0} has not been fetched")]
+    NotFetched(String),
+    #[error("Commit {id} not found in submodule {name}")]
+    CommitNotFound { name: String, id: CommitId },
+    #[error("Submodule {0} has uncommitted changes")]
+    UncommittedChanges(String),
+    #[error("Submodules are not supported by this build")]
+    Unsupported,
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+pub type SubmoduleStoreResult<T> = Result<T, SubmoduleStoreError>;
+
+/// A commit in a submodule repository.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmoduleCommit {
+    pub id: CommitId,
+    pub description: String,
+}
+
+/// Stores clones of the repositories of submodules, keyed by the submodule
+/// name in `.gitmodules`.
 pub trait SubmoduleStore: Send + Sync + Debug {
     fn name(&self) -> &str;
+
+    /// Fetches the submodule `name` from `url`, cloning it first if it isn't
+    /// in the store yet.
+    #[cfg(feature = "git")]
+    fn fetch(
+        &self,
+        name: &str,
+        url: &str,
+        callbacks: RemoteCallbacks<'_>,
+    ) -> SubmoduleStoreResult<()>;
+
+    /// Looks up the commit `id` in all fetched submodule repositories. Returns
+    /// `None` if none of them contains the commit.
+    fn get_commit(&self, id: &CommitId) -> SubmoduleStoreResult<Option<SubmoduleCommit>>;
+
+    /// Checks out the commit `id` of the submodule `name` into `destination`.
+    /// Files which existed in `old_id` but not in `id` are removed.
+    ///
+    /// Fails with [`SubmoduleStoreError::UncommittedChanges`] if files in
+    /// `destination` differ from `old_id`, since they would be overwritten.
+    ///
+    /// Fails with [`SubmoduleStoreError::NotFetched`] or
+    /// [`SubmoduleStoreError::CommitNotFound`] if the submodule needs to be
+    /// fetched first.
+    fn check_out(
+        &self,
+        name: &str,
+        old_id: Option<&CommitId>,
+        id: &CommitId,
+        destination: &Path,
+    ) -> SubmoduleStoreResult<()>;
 }
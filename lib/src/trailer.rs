@@ -0,0 +1,177 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of `Key: value` trailers at the end of commit descriptions.
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+/// A `Key: value` line of the trailer block of a description, such as
+/// `Signed-off-by: Some One <someone@example.com>`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trailer {
+    /// Trailer key, without the `:` separator.
+    pub key: String,
+    /// Trailer value, with continuation lines joined by a space.
+    pub value: String,
+}
+
+/// Error occurred while parsing a block of trailers.
+#[derive(Debug, Error)]
+#[error("Invalid trailer line: {line}")]
+pub struct TrailerParseError {
+    /// The line that isn't a trailer.
+    pub line: String,
+}
+
+/// Parses the trailers of a commit description.
+///
+/// The trailers are the last paragraph of the description if all its lines
+/// are trailers. The first paragraph is the subject, so it's never parsed as
+/// trailers.
+pub fn parse_description_trailers(description: &str) -> Vec<Trailer> {
+    let lines = description.lines().collect_vec();
+    let paragraphs = lines
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect_vec();
+    match paragraphs.as_slice() {
+        [_subject, .., last] => parse_trailers(&last.join("\n")).unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Parses a block of trailers, one per line.
+///
+/// Lines starting with whitespace continue the value of the previous trailer.
+/// Empty lines are ignored.
+pub fn parse_trailers(text: &str) -> Result<Vec<Trailer>, TrailerParseError> {
+    let mut trailers: Vec<Trailer> = vec![];
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            if let Some(trailer) = trailers.last_mut() {
+                trailer.value.push(' ');
+                trailer.value.push_str(line.trim());
+                continue;
+            }
+        }
+        let trailer = line
+            .split_once(':')
+            .map(|(key, value)| (key.trim_end(), value.trim()))
+            .filter(|(key, _)| is_valid_key(key))
+            .map(|(key, value)| Trailer {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            })
+            .ok_or_else(|| TrailerParseError {
+                line: line.to_owned(),
+            })?;
+        trailers.push(trailer);
+    }
+    Ok(trailers)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn trailer(key: &str, value: &str) -> Trailer {
+        Trailer {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_description_trailers() {
+        let description = indoc! {"
+            subject
+
+            body: not a trailer
+            because of this line
+
+            Signed-off-by: Some One <someone@example.com>
+            Change-Id: I1234
+        "};
+        assert_eq!(
+            parse_description_trailers(description),
+            vec![
+                trailer("Signed-off-by", "Some One <someone@example.com>"),
+                trailer("Change-Id", "I1234"),
+            ]
+        );
+
+        // The subject is never a trailer
+        assert_eq!(parse_description_trailers("fix: something\n"), vec![]);
+        assert_eq!(parse_description_trailers(""), vec![]);
+
+        // All lines of the last paragraph must be trailers
+        let description = indoc! {"
+            subject
+
+            Change-Id: I1234
+            not a trailer
+        "};
+        assert_eq!(parse_description_trailers(description), vec![]);
+
+        // Trailing blank lines are ignored
+        let description = "subject\n\nKey: value\n\n  \n";
+        assert_eq!(
+            parse_description_trailers(description),
+            vec![trailer("Key", "value")]
+        );
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        let text = indoc! {"
+            Key: value
+            Multi-Line: first
+              second
+            Spaced-Key : value with spaces
+        "};
+        assert_eq!(
+            parse_trailers(text).unwrap(),
+            vec![
+                trailer("Key", "value"),
+                trailer("Multi-Line", "first second"),
+                trailer("Spaced-Key", "value with spaces"),
+            ]
+        );
+
+        assert_eq!(parse_trailers("").unwrap(), vec![]);
+        assert_eq!(
+            parse_trailers("Key with space: value").unwrap_err().line,
+            "Key with space: value"
+        );
+        assert_eq!(
+            parse_trailers("no separator").unwrap_err().line,
+            "no separator"
+        );
+        assert_eq!(parse_trailers(": value").unwrap_err().line, ": value");
+        assert_eq!(
+            parse_trailers("  continuation first").unwrap_err().line,
+            "  continuation first"
+        );
+    }
+}
@@ -23,6 +23,7 @@ use thiserror::Error;
 
 use crate::backend::CommitId;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::BookmarkTarget;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
@@ -80,6 +81,11 @@ impl View {
         &self.data.tags
     }
 
+    /// Iterates pair of local and remote tags by tag name.
+    pub fn tags_with_remotes(&self) -> impl Iterator<Item = (&str, BookmarkTarget<'_>)> {
+        op_store::merge_join_tag_views(&self.data.tags, &self.data.remote_views)
+    }
+
     pub fn git_refs(&self) -> &BTreeMap<String, RefTarget> {
         &self.data.git_refs
     }
@@ -88,6 +94,10 @@ impl View {
         &self.data.git_head
     }
 
+    pub fn bisect(&self) -> &BisectState {
+        &self.data.bisect
+    }
+
     pub fn set_wc_commit(&mut self, workspace_id: WorkspaceId, commit_id: CommitId) {
         self.data.wc_commit_ids.insert(workspace_id, commit_id);
     }
@@ -330,6 +340,72 @@ impl View {
         }
     }
 
+    /// Iterates over `((name, remote_name), remote_ref)`s for all remote tags
+    /// in lexicographical order.
+    pub fn all_remote_tags(&self) -> impl Iterator<Item = ((&str, &str), &RemoteRef)> {
+        op_store::flatten_remote_tags(&self.data.remote_views)
+    }
+
+    /// Iterates over `(name, remote_ref)`s for all remote tags of the
+    /// specified remote in lexicographical order.
+    pub fn remote_tags(&self, remote_name: &str) -> impl Iterator<Item = (&str, &RemoteRef)> {
+        let maybe_remote_view = self.data.remote_views.get(remote_name);
+        maybe_remote_view
+            .map(|remote_view| {
+                remote_view
+                    .tags
+                    .iter()
+                    .map(|(name, remote_ref)| (name.as_ref(), remote_ref))
+            })
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn get_remote_tag(&self, name: &str, remote_name: &str) -> &RemoteRef {
+        if let Some(remote_view) = self.data.remote_views.get(remote_name) {
+            remote_view.tags.get(name).flatten()
+        } else {
+            RemoteRef::absent_ref()
+        }
+    }
+
+    /// Sets remote-tracking tag to the given target and state. If the target
+    /// is absent, the tag will be removed.
+    pub fn set_remote_tag(&mut self, name: &str, remote_name: &str, remote_ref: RemoteRef) {
+        if remote_ref.is_present() {
+            let remote_view = self
+                .data
+                .remote_views
+                .entry(remote_name.to_owned())
+                .or_default();
+            remote_view.tags.insert(name.to_owned(), remote_ref);
+        } else if let Some(remote_view) = self.data.remote_views.get_mut(remote_name) {
+            remote_view.tags.remove(name);
+        }
+    }
+
+    /// Iterates over `(name, {local_ref, remote_ref})`s for every tag present
+    /// locally and/or on the specified remote, in lexicographical order.
+    pub fn local_remote_tags<'a>(
+        &'a self,
+        remote_name: &str,
+    ) -> impl Iterator<Item = (&'a str, LocalAndRemoteRef<'a>)> + 'a {
+        refs::iter_named_local_remote_refs(
+            self.data
+                .tags
+                .iter()
+                .map(|(name, target)| (name.as_ref(), target)),
+            self.remote_tags(remote_name),
+        )
+        .map(|(name, (local_target, remote_ref))| {
+            let targets = LocalAndRemoteRef {
+                local_target,
+                remote_ref,
+            };
+            (name, targets)
+        })
+    }
+
     pub fn get_git_ref(&self, name: &str) -> &RefTarget {
         self.data.git_refs.get(name).flatten()
     }
@@ -350,6 +426,12 @@ impl View {
         self.data.git_head = target;
     }
 
+    /// Replaces the bisection state. Pass the default state to end the
+    /// bisection.
+    pub fn set_bisect(&mut self, state: BisectState) {
+        self.data.bisect = state;
+    }
+
     /// Iterates all commit ids referenced by this view.
     ///
     /// This can include hidden commits referenced by remote bookmarks, previous
@@ -375,20 +457,23 @@ impl View {
             git_refs,
             git_head,
             wc_commit_ids,
+            bisect,
         } = &self.data;
         itertools::chain!(
             head_ids,
             local_bookmarks.values().flat_map(ref_target_ids),
             tags.values().flat_map(ref_target_ids),
             remote_views.values().flat_map(|remote_view| {
-                let op_store::RemoteView { bookmarks } = remote_view;
-                bookmarks
-                    .values()
+                let op_store::RemoteView { bookmarks, tags } = remote_view;
+                itertools::chain(bookmarks.values(), tags.values())
                     .flat_map(|remote_ref| ref_target_ids(&remote_ref.target))
             }),
             git_refs.values().flat_map(ref_target_ids),
             ref_target_ids(git_head),
-            wc_commit_ids.values()
+            wc_commit_ids.values(),
+            bisect.good_ids.iter(),
+            bisect.bad_ids.iter(),
+            bisect.skipped_ids.iter()
         )
     }
 
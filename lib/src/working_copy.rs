@@ -30,7 +30,9 @@ use crate::backend::MergedTreeId;
 use crate::commit::Commit;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::dag_walk;
+use crate::eol::EolConversionMode;
 use crate::fsmonitor::FsmonitorSettings;
+use crate::gitattributes::GitAttributesError;
 use crate::gitignore::GitIgnoreError;
 use crate::gitignore::GitIgnoreFile;
 use crate::matchers::EverythingMatcher;
@@ -46,6 +48,7 @@ use crate::repo::RewriteRootCommit;
 use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::sparse::SparsePattern;
 use crate::store::Store;
 
 /// The trait all working-copy implementations must implement.
@@ -68,9 +71,9 @@ pub trait WorkingCopy: Send {
 
     /// Patterns that decide which paths from the current tree should be checked
     /// out in the working copy. An empty list means that no paths should be
-    /// checked out in the working copy. A single `RepoPath::root()` entry means
-    /// that all files should be checked out.
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    /// checked out in the working copy. A single `SparsePattern::everything()`
+    /// entry means that all files should be checked out.
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
 
     /// Locks the working copy and returns an instance with methods for updating
     /// the working copy files and state.
@@ -136,7 +139,7 @@ pub trait LockedWorkingCopy {
     fn recover(&mut self, commit: &Commit) -> Result<(), ResetError>;
 
     /// See `WorkingCopy::sparse_patterns()`
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
 
     /// Updates the patterns that decide which paths from the current tree
     /// should be checked out in the working copy.
@@ -146,7 +149,7 @@ pub trait LockedWorkingCopy {
     // to use sparse).
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError>;
 
@@ -183,6 +186,9 @@ pub enum SnapshotError {
     /// Checking path with ignore patterns failed.
     #[error(transparent)]
     GitIgnoreError(#[from] GitIgnoreError),
+    /// Loading `.gitattributes` files failed.
+    #[error(transparent)]
+    GitAttributesError(#[from] GitAttributesError),
     /// Some other error happened while snapshotting the working copy.
     #[error("{message}")]
     Other {
@@ -221,6 +227,8 @@ pub struct SnapshotOptions<'a> {
     pub max_new_file_size: u64,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// How to normalize line endings of files without `.gitattributes`.
+    pub eol_conversion_mode: EolConversionMode,
 }
 
 impl SnapshotOptions<'_> {
@@ -233,6 +241,7 @@ impl SnapshotOptions<'_> {
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
             conflict_marker_style: ConflictMarkerStyle::default(),
+            eol_conversion_mode: EolConversionMode::default(),
         }
     }
 }
@@ -266,6 +275,8 @@ pub enum UntrackedReason {
 pub struct CheckoutOptions {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// How to convert line endings of files without `.gitattributes`.
+    pub eol_conversion_mode: EolConversionMode,
 }
 
 impl CheckoutOptions {
@@ -273,6 +284,7 @@ impl CheckoutOptions {
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            eol_conversion_mode: EolConversionMode::default(),
         }
     }
 }
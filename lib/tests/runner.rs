@@ -8,13 +8,16 @@ fn test_no_forgotten_test_files() {
 
 mod test_annotate;
 mod test_bad_locking;
+mod test_bisect;
 mod test_commit_builder;
 mod test_commit_concurrent;
 mod test_conflicts;
 mod test_default_revset_graph_iterator;
 mod test_git;
 mod test_git_backend;
+mod test_git_lfs;
 mod test_gpg;
+mod test_gpgsm;
 mod test_id_prefix;
 mod test_index;
 mod test_init;
@@ -33,5 +36,6 @@ mod test_rewrite_duplicate;
 mod test_rewrite_transform;
 mod test_signing;
 mod test_ssh_signing;
+mod test_submodule_store;
 mod test_view;
 mod test_workspace;
@@ -17,6 +17,7 @@ use std::rc::Rc;
 
 use jj_lib::annotate::get_annotation_for_file;
 use jj_lib::annotate::get_annotation_with_file_content;
+use jj_lib::annotate::get_first_parent_annotation_for_file;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::MergedTreeId;
@@ -212,6 +213,78 @@ fn test_annotate_merge_simple() {
     ");
 }
 
+#[test]
+fn test_annotate_merge_first_parent() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = RepoPath::from_internal_string("file");
+
+    // 6    "2 1 3 6"
+    // |
+    // 5    "2 1 3"
+    // |\
+    // | 4  "1 3"
+    // | |
+    // | 3  "1"
+    // | |
+    // 2 |  "2 1"
+    // |/
+    // 1    "1"
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "1\n";
+    let content2 = "2\n1\n";
+    let content4 = "1\n3\n";
+    let content5 = "2\n1\n3\n";
+    let content6 = "2\n1\n3\n6\n";
+    let tree1 = create_tree(repo, &[(file_path, content1)]);
+    let tree2 = create_tree(repo, &[(file_path, content2)]);
+    let tree4 = create_tree(repo, &[(file_path, content4)]);
+    let tree5 = create_tree(repo, &[(file_path, content5)]);
+    let tree6 = create_tree(repo, &[(file_path, content6)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit1.id()], tree1.id()); // empty commit
+    let commit4 = create_commit("commit4", &[commit3.id()], tree4.id());
+    let commit5 = create_commit("commit5", &[commit2.id(), commit4.id()], tree5.id());
+    let commit6 = create_commit("commit6", &[commit5.id()], tree6.id());
+    drop(create_commit);
+
+    insta::assert_snapshot!(annotate(tx.repo(), &commit6, file_path), @r"
+    commit2: 2
+    commit1: 1
+    commit4: 3
+    commit6: 6
+    ");
+
+    // Lines merged from the side branch are attributed to the merge commit
+    // even though the merge doesn't change the file.
+    let annotate_first_parent = |commit: &Commit| {
+        let domain = RevsetExpression::all();
+        let annotation =
+            get_first_parent_annotation_for_file(tx.repo(), commit, &domain, file_path).unwrap();
+        format_annotation(tx.repo(), &annotation)
+    };
+    insta::assert_snapshot!(annotate_first_parent(&commit6), @r"
+    commit2: 2
+    commit1: 1
+    commit5: 3
+    commit6: 6
+    ");
+    insta::assert_snapshot!(annotate_first_parent(&commit5), @r"
+    commit2: 2
+    commit1: 1
+    commit5: 3
+    ");
+    // The side branch can still be annotated by starting from it.
+    insta::assert_snapshot!(annotate_first_parent(&commit4), @r"
+    commit1: 1
+    commit4: 3
+    ");
+}
+
 #[test]
 fn test_annotate_merge_split() {
     let test_repo = TestRepo::init();
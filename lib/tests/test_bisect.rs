@@ -0,0 +1,171 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionError;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Bisector;
+use jj_lib::commit::Commit;
+use jj_lib::op_store::BisectState;
+use testutils::CommitGraphBuilder;
+use testutils::TestRepo;
+
+fn ids(commits: &[&Commit]) -> Vec<CommitId> {
+    commits.iter().map(|commit| commit.id().clone()).collect()
+}
+
+fn next_commit_id(bisector: &Bisector) -> CommitId {
+    match bisector.next_step().unwrap() {
+        BisectionStep::Evaluate { commit_id, .. } => commit_id,
+        step => panic!("unexpected step: {step:?}"),
+    }
+}
+
+#[test]
+fn test_bisect_linear() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_c]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_d]);
+    let commit_f = graph_builder.commit_with_parents(&[&commit_e]);
+    let repo = tx.commit("test").unwrap();
+
+    let mut bisector = Bisector::new(repo.as_ref(), BisectState::default());
+    assert_matches!(bisector.next_step(), Err(BisectionError::NoBadCommit));
+
+    // The root commit is implicitly good, so the range is A..F.
+    bisector.mark_bad(ids(&[&commit_f]));
+    assert_eq!(
+        bisector.next_step().unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_c.id().clone(),
+            remaining: 5,
+        }
+    );
+
+    bisector.mark_good(ids(&[&commit_c]));
+    assert_eq!(next_commit_id(&bisector), *commit_e.id());
+    bisector.mark_bad(ids(&[&commit_e]));
+    assert_eq!(next_commit_id(&bisector), *commit_d.id());
+    bisector.mark_good(ids(&[&commit_d]));
+    assert_eq!(
+        bisector.next_step().unwrap(),
+        BisectionStep::Found(commit_e.id().clone())
+    );
+    assert_eq!(bisector.state().good_ids, ids(&[&commit_c, &commit_d]));
+    assert_eq!(bisector.state().bad_ids, ids(&[&commit_f, &commit_e]));
+}
+
+#[test]
+fn test_bisect_skipped() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let repo = tx.commit("test").unwrap();
+
+    let mut bisector = Bisector::new(repo.as_ref(), BisectState::default());
+    bisector.mark_good(ids(&[&commit_a]));
+    bisector.mark_bad(ids(&[&commit_c]));
+    assert_eq!(next_commit_id(&bisector), *commit_b.id());
+    bisector.mark_skipped(ids(&[&commit_b]));
+    assert_eq!(
+        bisector.next_step().unwrap(),
+        BisectionStep::Indeterminate(ids(&[&commit_c, &commit_b]))
+    );
+
+    // Marking a skipped commit replaces the previous mark.
+    bisector.mark_good(ids(&[&commit_b]));
+    assert!(bisector.state().skipped_ids.is_empty());
+    assert_eq!(
+        bisector.next_step().unwrap(),
+        BisectionStep::Found(commit_c.id().clone())
+    );
+}
+
+#[test]
+fn test_bisect_merge() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // F
+    // |\
+    // C E
+    // B D
+    // |/
+    // A
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_d]);
+    let commit_f = graph_builder.commit_with_parents(&[&commit_c, &commit_e]);
+    let repo = tx.commit("test").unwrap();
+
+    let mut bisector = Bisector::new(repo.as_ref(), BisectState::default());
+    bisector.mark_good(ids(&[&commit_a]));
+    bisector.mark_bad(ids(&[&commit_f]));
+    // Either branch splits the range B..F in halves.
+    let next = next_commit_id(&bisector);
+    assert!(next == *commit_c.id() || next == *commit_e.id());
+    bisector.mark_good(ids(&[&commit_c]));
+    let next = next_commit_id(&bisector);
+    assert!(next == *commit_d.id() || next == *commit_e.id());
+    bisector.mark_bad(ids(&[&commit_d]));
+    assert_eq!(
+        bisector.next_step().unwrap(),
+        BisectionStep::Found(commit_d.id().clone())
+    );
+}
+
+#[test]
+fn test_bisect_inconsistent() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.initial_commit();
+    let repo = tx.commit("test").unwrap();
+
+    let mut bisector = Bisector::new(repo.as_ref(), BisectState::default());
+    bisector.mark_good(ids(&[&commit_b]));
+    bisector.mark_bad(ids(&[&commit_a]));
+    assert_matches!(
+        bisector.next_step(),
+        Err(BisectionError::GoodDescendantOfBad { good, bad })
+            if good == *commit_b.id() && bad == *commit_a.id()
+    );
+
+    // Unrelated bad commits have no common bad ancestor.
+    let mut bisector = Bisector::new(repo.as_ref(), BisectState::default());
+    bisector.mark_bad(ids(&[&commit_b, &commit_c]));
+    assert_eq!(bisector.next_step().unwrap(), BisectionStep::NotFound);
+    assert_eq!(repo.view().bisect(), &BisectState::default());
+}
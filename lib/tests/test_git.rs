@@ -44,6 +44,7 @@ use jj_lib::git::GitFetch;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitRefUpdate;
 use jj_lib::git::RefName;
 use jj_lib::git::SubmoduleConfig;
@@ -113,6 +114,10 @@ fn git_id(commit: &Commit) -> Oid {
     Oid::from_bytes(commit.id().as_bytes()).unwrap()
 }
 
+fn git_object_id(commit_id: &CommitId) -> gix::ObjectId {
+    gix::ObjectId::from_bytes_or_panic(commit_id.as_bytes())
+}
+
 fn get_git_backend(repo: &Arc<ReadonlyRepo>) -> &GitBackend {
     repo.store()
         .backend_impl()
@@ -142,7 +147,7 @@ fn git_fetch(
         remote_name,
         branch_names,
         git::RemoteCallbacks::default(),
-        None,
+        git::GitFetchDepth::Unchanged,
     )?;
     let default_branch =
         git_fetch.get_default_branch(remote_name, git::RemoteCallbacks::default())?;
@@ -2148,6 +2153,156 @@ fn test_export_undo_reexport() {
     );
 }
 
+#[test]
+fn test_import_export_tags() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    git_repo
+        .reference("refs/tags/v1", commit1.id(), false, "test")
+        .unwrap();
+
+    // Tags imported from Git are recorded as Git-tracking tags
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    let target1 = RefTarget::normal(jj_id(&commit1));
+    assert_eq!(mut_repo.get_tag("v1"), target1);
+    assert_eq!(
+        mut_repo.get_remote_tag("v1", "git"),
+        RemoteRef {
+            target: target1.clone(),
+            state: RemoteRefState::Tracking,
+        },
+    );
+
+    // Tags created in jj are kept on import, and exported
+    let commit2 = write_random_commit(mut_repo);
+    let target2 = RefTarget::normal(commit2.id().clone());
+    mut_repo.set_tag_target("v2", target2.clone());
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    assert_eq!(mut_repo.get_tag("v2"), target2);
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v2").unwrap().target(),
+        Some(git_id(&commit2))
+    );
+    assert_eq!(mut_repo.get_git_ref("refs/tags/v2"), target2);
+    assert_eq!(
+        mut_repo.get_remote_tag("v2", "git"),
+        RemoteRef {
+            target: target2.clone(),
+            state: RemoteRefState::Tracking,
+        },
+    );
+
+    // Moved and deleted tags are exported
+    mut_repo.set_tag_target("v1", target2.clone());
+    mut_repo.set_tag_target("v2", RefTarget::absent());
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1").unwrap().target(),
+        Some(git_id(&commit2))
+    );
+    assert!(git_repo.find_reference("refs/tags/v2").is_err());
+    assert!(mut_repo.get_remote_tag("v2", "git").is_absent());
+
+    // Re-import is a no-op
+    let repo = tx.commit("test").unwrap();
+    let mut tx = repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    assert!(!tx.repo().has_changes());
+}
+
+#[test]
+fn test_export_annotated_tags() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    git_repo
+        .tag("v1", commit1.as_object(), &signature, "message", false)
+        .unwrap();
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    assert_eq!(mut_repo.get_tag("v1"), RefTarget::normal(jj_id(&commit1)));
+
+    // Annotated tags can be created
+    let commit2 = write_random_commit(mut_repo);
+    git::create_annotated_tag(
+        mut_repo,
+        "v2",
+        commit2.id(),
+        "release v2",
+        &commit2.committer().clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        mut_repo.get_tag("v2"),
+        RefTarget::normal(commit2.id().clone())
+    );
+    // The Git tag isn't created until exported
+    assert!(git_repo.find_reference("refs/tags/v2").is_err());
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    let git_tag = git_repo
+        .find_reference("refs/tags/v2")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(git_tag.message(), Some("release v2\n"));
+    assert_eq!(git_tag.target_id(), git_id(&commit2));
+
+    // Annotated tags can be moved and deleted
+    mut_repo.set_tag_target("v1", RefTarget::normal(commit2.id().clone()));
+    mut_repo.set_tag_target("v2", RefTarget::absent());
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1").unwrap().target(),
+        Some(git_id(&commit2))
+    );
+    assert!(git_repo.find_reference("refs/tags/v2").is_err());
+}
+
+#[test]
+fn test_create_annotated_tag_discarded() {
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+
+    // No Git tag is left behind if the transaction is discarded
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit = write_random_commit(mut_repo);
+    git::create_annotated_tag(
+        mut_repo,
+        "v1",
+        commit.id(),
+        "release v1",
+        &commit.committer().clone(),
+    )
+    .unwrap();
+    drop(tx);
+    assert!(git_repo.find_reference("refs/tags/v1").is_err());
+
+    // A lightweight tag is exported later even if an annotated tag of the same
+    // name was discarded
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit = write_random_commit(mut_repo);
+    mut_repo.set_tag_target("v1", RefTarget::normal(commit.id().clone()));
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1").unwrap().target(),
+        Some(git_id(&commit))
+    );
+    assert!(git_repo
+        .find_reference("refs/jj/annotated-tags/v1")
+        .is_err());
+}
+
 #[test]
 fn test_reset_head_to_root() {
     // Create colocated workspace
@@ -2726,8 +2881,8 @@ fn test_fetch_success(subprocess: bool) {
     assert_eq!(
         *view.git_refs(),
         btreemap! {
+            "refs/jj/remote-tags/origin/v1.0".to_string() => new_commit_target.clone(),
             "refs/remotes/origin/main".to_string() => new_commit_target.clone(),
-            "refs/tags/v1.0".to_string() => new_commit_target.clone(),
         }
     );
     assert_eq!(
@@ -2747,6 +2902,10 @@ fn test_fetch_success(subprocess: bool) {
             "v1.0".to_string() => new_commit_target.clone(),
         }
     );
+    assert_eq!(
+        view.all_remote_tags().collect_vec(),
+        vec![(("v1.0", "origin"), &new_commit_remote_ref)]
+    );
 }
 
 #[test_case(false; "use git2 for remote calls")]
@@ -3019,15 +3178,17 @@ fn test_push_bookmarks_success(subprocess: bool) {
                 new_target: Some(setup.child_of_main_commit.id().clone()),
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &git_settings,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_matches!(result, Ok(()));
+    assert_matches!(result, Ok(stats) if stats.all_ok());
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -3068,6 +3229,90 @@ fn test_push_bookmarks_success(subprocess: bool) {
     assert!(!tx.repo().has_changes());
 }
 
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_push_bookmarks_partially_unexpected_location(subprocess: bool) {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let mut tx = setup.jj_repo.start_transaction();
+    let git_settings = get_git_settings(subprocess);
+
+    // The main bookmark isn't at `sideways_commit` on the remote, but the new
+    // bookmark can be pushed.
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![
+            (
+                "main".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: Some(setup.sideways_commit.id().clone()),
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+            (
+                "topic".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+        ],
+        tag_updates: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &git_settings,
+        "origin",
+        &targets,
+        &GitPushOptions::default(),
+        git::RemoteCallbacks::default(),
+    );
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let view = tx.repo().view();
+    if subprocess {
+        // The remote accepted the new bookmark, which must be recorded
+        let stats = result.unwrap();
+        assert_eq!(stats.pushed, ["refs/heads/topic"]);
+        assert!(stats.rejected.is_empty());
+        assert_eq!(stats.unexpected_location, ["refs/heads/main"]);
+        assert_eq!(
+            source_repo
+                .find_reference("refs/heads/topic")
+                .unwrap()
+                .target(),
+            Some(git_id(&setup.child_of_main_commit))
+        );
+        assert_eq!(
+            *view.get_remote_bookmark("topic", "origin"),
+            RemoteRef {
+                target: RefTarget::normal(setup.child_of_main_commit.id().clone()),
+                state: RemoteRefState::Tracking,
+            },
+        );
+    } else {
+        // git2 checks all refs before pushing anything
+        assert_matches!(result, Err(GitPushError::RefInUnexpectedLocation(refs))
+            if refs == ["refs/heads/main"]);
+        assert!(source_repo.find_reference("refs/heads/topic").is_err());
+        assert!(view.get_remote_bookmark("topic", "origin").is_absent());
+    }
+    // The main bookmark wasn't updated
+    assert_eq!(
+        source_repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .target(),
+        Some(git_id(&setup.main_commit))
+    );
+    assert_eq!(
+        *view.get_remote_bookmark("main", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.main_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_push_bookmarks_deletion(subprocess: bool) {
@@ -3090,15 +3335,17 @@ fn test_push_bookmarks_deletion(subprocess: bool) {
                 new_target: None,
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &git_settings,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_matches!(result, Ok(()));
+    assert_matches!(result, Ok(stats) if stats.all_ok());
 
     // Check that the ref got deleted in the source repo
     assert!(source_repo.find_reference("refs/heads/main").is_err());
@@ -3148,15 +3395,17 @@ fn test_push_bookmarks_mixed_deletion_and_addition(subprocess: bool) {
                 },
             ),
         ],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &git_settings,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_matches!(result, Ok(()));
+    assert_matches!(result, Ok(stats) if stats.all_ok());
 
     // Check that the topic ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -3192,6 +3441,125 @@ fn test_push_bookmarks_mixed_deletion_and_addition(subprocess: bool) {
     assert!(!tx.repo().has_changes());
 }
 
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_push_tags(subprocess: bool) {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let mut setup = set_up_push_repos(&settings, &temp_dir);
+    let clone_repo = get_git_repo(&setup.jj_repo);
+    let mut tx = setup.jj_repo.start_transaction();
+    let git_settings = get_git_settings(subprocess);
+
+    // A lightweight tag and an annotated tag
+    tx.repo_mut()
+        .set_tag_target("v1", RefTarget::normal(setup.main_commit.id().clone()));
+    git::create_annotated_tag(
+        tx.repo_mut(),
+        "v2",
+        setup.child_of_main_commit.id(),
+        "release v2",
+        &setup.child_of_main_commit.committer().clone(),
+    )
+    .unwrap();
+    git::export_refs(tx.repo_mut()).unwrap();
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![],
+        tag_updates: vec![
+            (
+                "v1".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.main_commit.id().clone()),
+                },
+            ),
+            (
+                "v2".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+        ],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &git_settings,
+        "origin",
+        &targets,
+        &GitPushOptions::default(),
+        git::RemoteCallbacks::default(),
+    );
+    assert_matches!(result, Ok(stats) if stats.all_ok());
+
+    // Check that the tags got created in the source repo, keeping the tag
+    // object of the annotated tag
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let v1_ref = source_repo.find_reference("refs/tags/v1").unwrap();
+    assert_eq!(v1_ref.target(), Some(git_id(&setup.main_commit)));
+    let v2_tag = source_repo
+        .find_reference("refs/tags/v2")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(v2_tag.target_id(), git_id(&setup.child_of_main_commit));
+    assert_eq!(
+        clone_repo
+            .find_reference("refs/jj/remote-tags/origin/v2")
+            .unwrap()
+            .target(),
+        Some(v2_tag.id())
+    );
+
+    // Check that the repo view got updated
+    let view = tx.repo().view();
+    assert_eq!(
+        *view.get_remote_tag("v1", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.main_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+    assert_eq!(
+        *view.get_remote_tag("v2", "origin"),
+        RemoteRef {
+            target: RefTarget::normal(setup.child_of_main_commit.id().clone()),
+            state: RemoteRefState::Tracking,
+        },
+    );
+
+    // Delete a tag
+    tx.repo_mut().set_tag_target("v1", RefTarget::absent());
+    git::export_refs(tx.repo_mut()).unwrap();
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![],
+        tag_updates: vec![(
+            "v1".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: None,
+            },
+        )],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &git_settings,
+        "origin",
+        &targets,
+        &GitPushOptions::default(),
+        git::RemoteCallbacks::default(),
+    );
+    assert_matches!(result, Ok(stats) if stats.all_ok());
+    assert!(source_repo.find_reference("refs/tags/v1").is_err());
+    assert!(tx.repo().view().get_remote_tag("v1", "origin").is_absent());
+
+    // Check that the repo view reflects the changes in the Git repo
+    setup.jj_repo = tx.commit("test").unwrap();
+    let mut tx = setup.jj_repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &GitSettings::default()).unwrap();
+    assert!(!tx.repo().has_changes());
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_push_bookmarks_not_fast_forward(subprocess: bool) {
@@ -3209,15 +3577,17 @@ fn test_push_bookmarks_not_fast_forward(subprocess: bool) {
                 new_target: Some(setup.sideways_commit.id().clone()),
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &git_settings,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_matches!(result, Ok(()));
+    assert_matches!(result, Ok(stats) if stats.all_ok());
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -3254,14 +3624,15 @@ fn test_push_updates_unexpectedly_moved_sideways_on_remote(subprocess: bool) {
     let attempt_push_expecting_sideways = |target: Option<CommitId>| {
         let targets = [GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
-            expected_current_target: Some(setup.sideways_commit.id().clone()),
-            new_target: target,
+            expected_current_target: Some(git_object_id(setup.sideways_commit.id())),
+            new_target: target.as_ref().map(git_object_id),
         }];
         git::push_updates(
             setup.jj_repo.as_ref(),
             &git_settings,
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -3295,7 +3666,7 @@ fn test_push_updates_unexpectedly_moved_sideways_on_remote(subprocess: bool) {
     // Moving the bookmark to the same place it already is is OK.
     assert_matches!(
         attempt_push_expecting_sideways(Some(setup.main_commit.id().clone())),
-        Ok(())
+        Ok(stats) if stats.all_ok()
     );
 }
 
@@ -3323,14 +3694,15 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote(subprocess: bool) {
     let attempt_push_expecting_parent = |target: Option<CommitId>| {
         let targets = [GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
-            expected_current_target: Some(setup.parent_of_main_commit.id().clone()),
-            new_target: target,
+            expected_current_target: Some(git_object_id(setup.parent_of_main_commit.id())),
+            new_target: target.as_ref().map(git_object_id),
         }];
         git::push_updates(
             setup.jj_repo.as_ref(),
             &git_settings,
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -3366,7 +3738,7 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote(subprocess: bool) {
         // conflict resolution rules
         assert_matches!(
             attempt_push_expecting_parent(Some(setup.child_of_main_commit.id().clone())),
-            Ok(())
+            Ok(stats) if stats.all_ok()
         );
     }
 }
@@ -3392,13 +3764,14 @@ fn test_push_updates_unexpectedly_exists_on_remote(subprocess: bool) {
         let targets = [GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
             expected_current_target: None,
-            new_target: target,
+            new_target: target.as_ref().map(git_object_id),
         }];
         git::push_updates(
             setup.jj_repo.as_ref(),
             &git_settings,
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -3419,7 +3792,7 @@ fn test_push_updates_unexpectedly_exists_on_remote(subprocess: bool) {
         // exist
         assert_matches!(
             attempt_push_expecting_absence(Some(setup.child_of_main_commit.id().clone())),
-            Ok(())
+            Ok(stats) if stats.all_ok()
         );
     }
 }
@@ -3438,12 +3811,13 @@ fn test_push_updates_success(subprocess: bool) {
         "origin",
         &[GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
-            expected_current_target: Some(setup.main_commit.id().clone()),
-            new_target: Some(setup.child_of_main_commit.id().clone()),
+            expected_current_target: Some(git_object_id(setup.main_commit.id())),
+            new_target: Some(git_object_id(setup.child_of_main_commit.id())),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_matches!(result, Ok(()));
+    assert_matches!(result, Ok(stats) if stats.all_ok());
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -3477,9 +3851,10 @@ fn test_push_updates_no_such_remote(subprocess: bool) {
         "invalid-remote",
         &[GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
-            expected_current_target: Some(setup.main_commit.id().clone()),
-            new_target: Some(setup.child_of_main_commit.id().clone()),
+            expected_current_target: Some(git_object_id(setup.main_commit.id())),
+            new_target: Some(git_object_id(setup.child_of_main_commit.id())),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
@@ -3498,14 +3873,39 @@ fn test_push_updates_invalid_remote(subprocess: bool) {
         "http://invalid-remote",
         &[GitRefUpdate {
             qualified_name: "refs/heads/main".to_string(),
-            expected_current_target: Some(setup.main_commit.id().clone()),
-            new_target: Some(setup.child_of_main_commit.id().clone()),
+            expected_current_target: Some(git_object_id(setup.main_commit.id())),
+            new_target: Some(git_object_id(setup.child_of_main_commit.id())),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
 }
 
+#[test]
+fn test_push_updates_atomic_unsupported_with_git2() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let git_settings = get_git_settings(false);
+    let result = git::push_updates(
+        setup.jj_repo.as_ref(),
+        &git_settings,
+        "origin",
+        &[GitRefUpdate {
+            qualified_name: "refs/heads/main".to_string(),
+            expected_current_target: Some(git_object_id(setup.main_commit.id())),
+            new_target: Some(git_object_id(setup.child_of_main_commit.id())),
+        }],
+        &GitPushOptions {
+            atomic: true,
+            ..Default::default()
+        },
+        git::RemoteCallbacks::default(),
+    );
+    assert_matches!(result, Err(GitPushError::AtomicPushUnsupported));
+}
+
 #[test]
 fn test_bulk_update_extra_on_import_refs() {
     let git_settings = GitSettings::default();
@@ -3953,4 +4353,16 @@ fn test_shallow_commits_lack_parents() {
     );
     // FIXME: new ancestors should be indexed
     assert!(!repo.index().has_id(&jj_id(&a)));
+
+    // Rebuilding the index records the new ancestors
+    let mut tx = repo.start_transaction();
+    assert!(tx
+        .repo_mut()
+        .reindex_reparented_commits(&[jj_id(&b), jj_id(&c)])
+        .unwrap());
+    assert!(tx.repo().has_changes());
+    let repo = tx.commit("reindex").unwrap();
+    assert!(repo.index().has_id(&jj_id(&a)));
+    assert!(repo.index().is_ancestor(&jj_id(&a), &jj_id(&d)));
+    assert!(repo.index().is_ancestor(&jj_id(&a), &jj_id(&e)));
 }
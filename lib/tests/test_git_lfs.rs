@@ -0,0 +1,219 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::TreeValue;
+use jj_lib::git_lfs;
+use jj_lib::git_lfs::LfsClient;
+use jj_lib::git_lfs::LfsError;
+use jj_lib::git_lfs::LfsObjectStore;
+use jj_lib::git_lfs::LfsPointer;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::working_copy::CheckoutOptions;
+use testutils::commit_with_tree;
+use testutils::create_tree;
+use testutils::test_lfs_server::TestLfsServer;
+use testutils::TestRepoBackend;
+use testutils::TestWorkspace;
+
+fn read_tree_file(tree: &MergedTree, path: &RepoPath) -> Vec<u8> {
+    let Ok(Some(TreeValue::File { id, .. })) = tree.path_value(path).unwrap().into_resolved()
+    else {
+        panic!("{path:?} is not a file");
+    };
+    testutils::read_file(tree.store(), path, &id)
+}
+
+fn read_to_end(mut reader: Box<dyn std::io::Read>) -> Vec<u8> {
+    let mut content = vec![];
+    reader.read_to_end(&mut content).unwrap();
+    content
+}
+
+#[test]
+fn test_lfs_object_store_clean_smudge() {
+    let temp_dir = testutils::new_temp_dir();
+    let lfs_objects = LfsObjectStore::new(temp_dir.path().join("objects"));
+
+    let pointer_content = lfs_objects.clean(&mut b"hello".as_slice()).unwrap();
+    let pointer = LfsPointer::parse(&pointer_content).unwrap();
+    assert_eq!(
+        pointer,
+        LfsPointer {
+            oid: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_owned(),
+            size: 5,
+        }
+    );
+    assert!(lfs_objects.contains(&pointer));
+
+    // Pointers are stored as is
+    assert_eq!(
+        lfs_objects.clean(&mut pointer_content.as_slice()).unwrap(),
+        pointer_content
+    );
+
+    // Pointers to available objects are replaced by the objects
+    let smudged = lfs_objects
+        .smudge(Box::new(std::io::Cursor::new(pointer_content.clone())))
+        .unwrap();
+    assert_eq!(read_to_end(smudged), b"hello");
+
+    // Other content is left alone
+    let missing_pointer = LfsPointer {
+        oid: "0".repeat(64),
+        size: 1,
+    };
+    let smudged = lfs_objects
+        .smudge(Box::new(std::io::Cursor::new(missing_pointer.to_bytes())))
+        .unwrap();
+    assert_eq!(read_to_end(smudged), missing_pointer.to_bytes());
+    let smudged = lfs_objects
+        .smudge(Box::new(std::io::Cursor::new(b"not a pointer".to_vec())))
+        .unwrap();
+    assert_eq!(read_to_end(smudged), b"not a pointer");
+}
+
+#[test]
+fn test_lfs_working_copy() {
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let lfs_objects = LfsObjectStore::for_store(repo.store()).unwrap();
+
+    let gitattributes_path = RepoPath::from_internal_string(".gitattributes");
+    let lfs_path = RepoPath::from_internal_string("image.bin");
+    let other_path = RepoPath::from_internal_string("file.txt");
+
+    // Files with filter=lfs are stored as pointers on snapshot
+    testutils::write_working_copy_file(&workspace_root, gitattributes_path, "*.bin filter=lfs\n");
+    testutils::write_working_copy_file(&workspace_root, lfs_path, "large\r\ncontent");
+    testutils::write_working_copy_file(&workspace_root, other_path, "small");
+    let tree = test_workspace.snapshot().unwrap();
+    let pointer = LfsPointer::parse(&read_tree_file(&tree, lfs_path)).unwrap();
+    assert_eq!(pointer.size, 14);
+    assert!(lfs_objects.contains(&pointer));
+    assert_eq!(read_tree_file(&tree, other_path), b"small");
+    assert_eq!(
+        git_lfs::changed_lfs_pointers(&repo.store().root_commit().tree().unwrap(), &tree).unwrap(),
+        vec![pointer.clone()]
+    );
+
+    // The object is written on checkout
+    let commit = commit_with_tree(repo.store(), tree.id());
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &repo.store().root_commit(),
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    let read_disk_file =
+        |path: &RepoPath| std::fs::read(path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    assert_eq!(read_disk_file(lfs_path), b"large\r\ncontent");
+
+    // Pointers to missing objects are checked out as is, and snapshotted back
+    let missing_pointer = LfsPointer {
+        oid: "0".repeat(64),
+        size: 1,
+    };
+    let tree2 = create_tree(
+        &repo,
+        &[
+            (gitattributes_path, "*.bin filter=lfs\n"),
+            (
+                lfs_path,
+                std::str::from_utf8(&missing_pointer.to_bytes()).unwrap(),
+            ),
+        ],
+    );
+    let commit2 = commit_with_tree(repo.store(), tree2.id());
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit2,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert_eq!(read_disk_file(lfs_path), missing_pointer.to_bytes());
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree2.id());
+}
+
+#[test]
+fn test_lfs_client_upload_download() {
+    let server = TestLfsServer::start();
+    let client = LfsClient::new(server.url());
+    let temp_dir = testutils::new_temp_dir();
+    let local_objects = LfsObjectStore::new(temp_dir.path().join("local"));
+    let other_objects = LfsObjectStore::new(temp_dir.path().join("other"));
+
+    let pointer1 = local_objects.write(&mut b"content 1".as_slice()).unwrap();
+    let pointer2 = local_objects.write(&mut b"content 2".as_slice()).unwrap();
+    client
+        .upload(&local_objects, &[pointer1.clone(), pointer2.clone()])
+        .unwrap();
+    assert_eq!(server.object(&pointer1.oid).unwrap(), b"content 1");
+    assert_eq!(server.object(&pointer2.oid).unwrap(), b"content 2");
+
+    // Objects which the server already has aren't uploaded again, so they
+    // don't need to be available locally
+    client
+        .upload(&other_objects, std::slice::from_ref(&pointer1))
+        .unwrap();
+
+    client
+        .download(&other_objects, &[pointer1.clone(), pointer2.clone()])
+        .unwrap();
+    assert!(other_objects.contains(&pointer1));
+    assert!(other_objects.contains(&pointer2));
+
+    // Missing objects on either side are errors
+    let missing_pointer = LfsPointer {
+        oid: "0".repeat(64),
+        size: 1,
+    };
+    assert_matches!(
+        client.download(&other_objects, std::slice::from_ref(&missing_pointer)),
+        Err(LfsError::ObjectRejected { .. })
+    );
+    assert_matches!(
+        client.upload(&other_objects, &[missing_pointer]),
+        Err(LfsError::ObjectNotFound { .. })
+    );
+
+    // Downloaded objects are verified
+    let pointer3 = LfsPointer {
+        oid: "1".repeat(64),
+        size: 3,
+    };
+    server.insert_object(&pointer3.oid, b"bad");
+    assert_matches!(
+        client.download(&other_objects, std::slice::from_ref(&pointer3)),
+        Err(LfsError::CorruptObject { .. })
+    );
+    assert!(!other_objects.contains(&pointer3));
+}
@@ -17,6 +17,7 @@ use std::fs;
 use std::sync::Arc;
 
 use assert_matches::assert_matches;
+use itertools::Itertools as _;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
@@ -493,6 +494,132 @@ fn test_index_commits_incremental_already_indexed() {
     assert_eq!(as_mutable_composite(mut_repo).num_commits(), 1 + 1);
 }
 
+#[test]
+fn test_index_commits_predecessors() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let predecessor_ids = |index: &CompositeIndex, commit_id: &CommitId| {
+        let entry = index.entry_by_id(commit_id).unwrap();
+        entry
+            .predecessors()
+            .map(|entry| entry.commit_id())
+            .collect_vec()
+    };
+    let successor_ids = |index: &CompositeIndex, commit_id: &CommitId| {
+        let pos = index.commit_id_to_pos(commit_id).unwrap();
+        index
+            .successor_positions(pos)
+            .into_iter()
+            .map(|pos| index.entry_by_pos(pos).commit_id())
+            .collect_vec()
+    };
+
+    // Rewrite A into B in one operation, then B into C and D concurrently
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    let mut tx = repo.start_transaction();
+    let commit_b = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("b")
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo = tx.commit("test").unwrap();
+    let mut txs = vec![];
+    for description in ["c", "d"] {
+        let mut tx = repo.start_transaction();
+        tx.repo_mut()
+            .rewrite_commit(&commit_b)
+            .set_description(description)
+            .write()
+            .unwrap();
+        tx.repo_mut().rebase_descendants().unwrap();
+        txs.push(tx);
+    }
+    let repo = commit_transactions(txs);
+    let mut commits = repo
+        .view()
+        .heads()
+        .iter()
+        .map(|id| repo.store().get_commit(id).unwrap())
+        .filter(|commit| commit.change_id() == commit_a.change_id())
+        .collect_vec();
+    commits.sort_by_key(|commit| commit.description().to_owned());
+    let [commit_c, commit_d] = commits.try_into().unwrap();
+
+    let index = as_readonly_composite(&repo);
+    assert_eq!(predecessor_ids(index, commit_a.id()), vec![]);
+    assert_eq!(
+        predecessor_ids(index, commit_b.id()),
+        vec![commit_a.id().clone()]
+    );
+    assert_eq!(
+        predecessor_ids(index, commit_c.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        predecessor_ids(index, commit_d.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        successor_ids(index, commit_a.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        successor_ids(index, commit_b.id())
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        [commit_c.id(), commit_d.id()]
+            .into_iter()
+            .cloned()
+            .sorted()
+            .collect_vec()
+    );
+    assert_eq!(successor_ids(index, commit_c.id()), vec![]);
+
+    // Delete index from disk
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    default_index_store.reinit().unwrap();
+
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let index = as_readonly_composite(&repo);
+    assert_eq!(
+        predecessor_ids(index, commit_b.id()),
+        vec![commit_a.id().clone()]
+    );
+    assert_eq!(
+        predecessor_ids(index, commit_c.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        predecessor_ids(index, commit_d.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        successor_ids(index, commit_a.id()),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        successor_ids(index, commit_b.id())
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        [commit_c.id(), commit_d.id()]
+            .into_iter()
+            .cloned()
+            .sorted()
+            .collect_vec()
+    );
+    assert_eq!(successor_ids(index, commit_c.id()), vec![]);
+}
+
 #[must_use]
 fn create_n_commits(repo: &Arc<ReadonlyRepo>, num_commits: i32) -> Arc<ReadonlyRepo> {
     let mut tx = repo.start_transaction();
@@ -631,7 +758,8 @@ fn test_reindex_corrupt_segment_files() {
         // u32: number of local change ids
         // u32: number of overflow parent entries
         // u32: number of overflow change id positions
-        fs::write(entry.path(), b"\0".repeat(24)).unwrap();
+        // u32: number of predecessor entries
+        fs::write(entry.path(), b"\0".repeat(28)).unwrap();
     }
 
     let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
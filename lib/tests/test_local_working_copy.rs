@@ -26,6 +26,7 @@ use itertools::Itertools;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
+use jj_lib::eol::EolConversionMode;
 use jj_lib::file_util::check_symlink_support;
 use jj_lib::file_util::try_symlink;
 use jj_lib::fsmonitor::FsmonitorSettings;
@@ -45,6 +46,7 @@ use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathComponent;
 use jj_lib::secret_backend::SecretBackend;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
@@ -111,7 +113,10 @@ fn test_root() {
     let mut test_workspace = TestWorkspace::init();
 
     let wc = test_workspace.workspace.working_copy();
-    assert_eq!(wc.sparse_patterns().unwrap(), vec![RepoPathBuf::root()]);
+    assert_eq!(
+        wc.sparse_patterns().unwrap(),
+        vec![SparsePattern::everything()]
+    );
     let new_tree = test_workspace.snapshot().unwrap();
     let repo = &test_workspace.repo;
     let wc_commit_id = repo
@@ -1249,6 +1254,143 @@ fn test_gitignores_ignored_directory_already_tracked() {
     assert_eq!(tree_entries(&new_tree), tree_entries(&expected_tree));
 }
 
+#[test]
+fn test_gitattributes_eol_snapshot() {
+    // Tests that line endings are normalized according to .gitattributes
+
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let gitattributes_path = RepoPath::from_internal_string(".gitattributes");
+    let text_path = RepoPath::from_internal_string("file.txt");
+    let auto_text_path = RepoPath::from_internal_string("text.auto");
+    let auto_binary_path = RepoPath::from_internal_string("binary.auto");
+    let binary_path = RepoPath::from_internal_string("file.bin");
+    let other_path = RepoPath::from_internal_string("file.other");
+    let subdir_gitattributes_path = RepoPath::from_internal_string("dir/.gitattributes");
+    let subdir_other_path = RepoPath::from_internal_string("dir/file.other");
+
+    testutils::write_working_copy_file(
+        &workspace_root,
+        gitattributes_path,
+        "*.txt text\n*.auto text=auto\n*.bin binary\n",
+    );
+    testutils::write_working_copy_file(&workspace_root, text_path, "a\r\nb\r\n");
+    testutils::write_working_copy_file(&workspace_root, auto_text_path, "a\r\n");
+    testutils::write_working_copy_file(&workspace_root, auto_binary_path, "\0\r\n");
+    testutils::write_working_copy_file(&workspace_root, binary_path, "a\r\n");
+    testutils::write_working_copy_file(&workspace_root, other_path, "a\r\n");
+    std::fs::create_dir(workspace_root.join("dir")).unwrap();
+    testutils::write_working_copy_file(&workspace_root, subdir_gitattributes_path, "* text\n");
+    testutils::write_working_copy_file(&workspace_root, subdir_other_path, "a\r\n");
+
+    let tree = test_workspace.snapshot().unwrap();
+    let expected_tree = create_tree(
+        &test_workspace.repo,
+        &[
+            (
+                gitattributes_path,
+                "*.txt text\n*.auto text=auto\n*.bin binary\n",
+            ),
+            (text_path, "a\nb\n"),
+            (auto_text_path, "a\n"),
+            (auto_binary_path, "\0\r\n"),
+            (binary_path, "a\r\n"),
+            (other_path, "a\r\n"),
+            (subdir_gitattributes_path, "* text\n"),
+            (subdir_other_path, "a\n"),
+        ],
+    );
+    assert_eq!(tree_entries(&tree), tree_entries(&expected_tree));
+
+    // Files without attributes are normalized only if configured to
+    let options = SnapshotOptions {
+        eol_conversion_mode: EolConversionMode::Input,
+        ..SnapshotOptions::empty_for_test()
+    };
+    testutils::write_working_copy_file(&workspace_root, other_path, "b\r\n");
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(
+        tree.path_value(other_path).unwrap(),
+        create_tree(&test_workspace.repo, &[(other_path, "b\n")])
+            .path_value(other_path)
+            .unwrap()
+    );
+    assert_eq!(
+        tree.path_value(binary_path).unwrap(),
+        expected_tree.path_value(binary_path).unwrap()
+    );
+}
+
+#[test]
+fn test_gitattributes_eol_checkout() {
+    // Tests that line endings are converted on checkout according to
+    // .gitattributes
+
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let gitattributes_path = RepoPath::from_internal_string(".gitattributes");
+    let crlf_path = RepoPath::from_internal_string("file.bat");
+    let other_path = RepoPath::from_internal_string("file.sh");
+    let binary_path = RepoPath::from_internal_string("file.bin");
+    let subdir_crlf_path = RepoPath::from_internal_string("dir/file.bat");
+
+    let tree = create_tree(
+        &repo,
+        &[
+            (gitattributes_path, "*.bat eol=crlf\n*.bin binary\n"),
+            (crlf_path, "a\nb\n"),
+            (other_path, "a\n"),
+            (binary_path, "a\n"),
+            (subdir_crlf_path, "a\n"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+    let read_disk_file =
+        |path: &RepoPath| std::fs::read(path.to_fs_path_unchecked(&workspace_root)).unwrap();
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert_eq!(read_disk_file(crlf_path), b"a\r\nb\r\n");
+    assert_eq!(read_disk_file(other_path), b"a\n");
+    assert_eq!(read_disk_file(binary_path), b"a\n");
+    assert_eq!(read_disk_file(subdir_crlf_path), b"a\r\n");
+
+    // The converted files aren't modified
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(tree_entries(&new_tree), tree_entries(&tree));
+
+    // Files without attributes are converted only if configured to
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &repo.store().root_commit(),
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions {
+            eol_conversion_mode: EolConversionMode::InputOutput,
+            ..CheckoutOptions::empty_for_test()
+        },
+    )
+    .unwrap();
+    assert_eq!(read_disk_file(other_path), b"a\r\n");
+    assert_eq!(read_disk_file(binary_path), b"a\n");
+}
+
 #[test]
 fn test_dotgit_ignored() {
     // Tests that .git directories and files are always ignored (we could accept
@@ -2015,7 +2157,7 @@ fn test_fsmonitor() {
     let ws = &mut test_workspace.workspace;
     assert_eq!(
         ws.working_copy().sparse_patterns().unwrap(),
-        vec![RepoPathBuf::root()]
+        vec![SparsePattern::everything()]
     );
 
     let foo_path = RepoPath::from_internal_string("foo");
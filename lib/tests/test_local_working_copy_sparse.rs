@@ -12,23 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+
 use futures::StreamExt as _;
 use itertools::Itertools;
+use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::local_working_copy::LocalWorkingCopy;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::WorkingCopy;
 use pollster::FutureExt as _;
+use prost::Message as _;
 use testutils::commit_with_tree;
 use testutils::create_tree;
 use testutils::TestWorkspace;
 
-fn to_owned_path_vec(paths: &[&RepoPath]) -> Vec<RepoPathBuf> {
-    paths.iter().map(|&path| path.to_owned()).collect()
+fn to_sparse_patterns(paths: &[&RepoPath]) -> Vec<SparsePattern> {
+    paths
+        .iter()
+        .map(|&path| SparsePattern::from_path(path))
+        .collect()
 }
 
 #[test]
@@ -73,7 +80,7 @@ fn test_sparse_checkout() {
 
     // Set sparse patterns to only dir1/
     let mut locked_ws = ws.start_working_copy_mutation().unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_sparse_patterns(&[dir1_path]);
     let stats = locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
@@ -133,7 +140,7 @@ fn test_sparse_checkout() {
 
     // Set sparse patterns to file2, dir1/subdir1/ and dir2/
     let mut locked_wc = wc.start_mutation().unwrap();
-    let sparse_patterns = to_owned_path_vec(&[root_file1_path, dir1_subdir1_path, dir2_path]);
+    let sparse_patterns = to_sparse_patterns(&[root_file1_path, dir1_subdir1_path, dir2_path]);
     let stats = locked_wc
         .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
         .unwrap();
@@ -173,6 +180,202 @@ fn test_sparse_checkout() {
     );
 }
 
+#[test]
+fn test_sparse_checkout_fileset_patterns() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let working_copy_path = test_workspace.workspace.workspace_root().to_owned();
+
+    let readme_path = RepoPath::from_internal_string("README.md");
+    let docs_file_path = RepoPath::from_internal_string("docs/file");
+    let foo_api_file_path = RepoPath::from_internal_string("services/foo/api/file");
+    let foo_impl_file_path = RepoPath::from_internal_string("services/foo/impl/file");
+    let bar_api_file_path = RepoPath::from_internal_string("services/bar/api/file");
+
+    let tree = create_tree(
+        repo,
+        &[
+            (readme_path, "contents"),
+            (docs_file_path, "contents"),
+            (foo_api_file_path, "contents"),
+            (foo_impl_file_path, "contents"),
+            (bar_api_file_path, "contents"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    let ws = &mut test_workspace.workspace;
+
+    let parse = |text: &str| SparsePattern::parse(&mut FilesetDiagnostics::new(), text).unwrap();
+
+    // Exclude docs/ with a negated pattern
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![SparsePattern::everything(), parse("~docs")];
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 0,
+            removed_files: 1,
+            skipped_files: 0,
+        }
+    );
+    assert_eq!(
+        locked_ws.locked_wc().sparse_patterns().unwrap(),
+        sparse_patterns
+    );
+    assert!(!docs_file_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+
+    // Files that stay in the sparse patterns aren't touched
+    std::fs::write(
+        readme_path.to_fs_path_unchecked(&working_copy_path),
+        "modified",
+    )
+    .unwrap();
+
+    // Include only the APIs of the services and README.md
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![
+        parse("glob:services/*/api"),
+        parse("root-file:README.md"),
+        parse("~services/bar"),
+    ];
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 0,
+            removed_files: 2,
+            skipped_files: 0,
+        }
+    );
+    assert_eq!(
+        std::fs::read_to_string(readme_path.to_fs_path_unchecked(&working_copy_path)).unwrap(),
+        "modified"
+    );
+    assert!(foo_api_file_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!foo_impl_file_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!bar_api_file_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert_eq!(
+        wc.file_states().unwrap().paths().collect_vec(),
+        vec![readme_path, foo_api_file_path]
+    );
+
+    // Including docs/ again checks out only the files under it
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![
+        parse("glob:services/*/api"),
+        parse("root-file:README.md"),
+        parse("~services/bar"),
+        parse("docs"),
+    ];
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 1,
+            removed_files: 0,
+            skipped_files: 0,
+        }
+    );
+    assert!(docs_file_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+}
+
+#[test]
+fn test_sparse_patterns_saved_as_prefixes() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let ws = &mut test_workspace.workspace;
+
+    let parse = |text: &str| SparsePattern::parse(&mut FilesetDiagnostics::new(), text).unwrap();
+    let read_proto = |state_path: &Path| {
+        let buf = std::fs::read(state_path.join("tree_state")).unwrap();
+        jj_lib::protos::working_copy::TreeState::decode(&*buf)
+            .unwrap()
+            .sparse_patterns
+            .unwrap()
+    };
+
+    // Leading plain paths are written as prefixes
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![
+        parse("dir1"),
+        parse(r#""dir 2""#),
+        parse("glob:services/*/api"),
+        parse("dir3"),
+    ];
+    locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    let state_path = wc.state_path().to_owned();
+    let proto = read_proto(&state_path);
+    assert_eq!(proto.prefixes, ["dir1", "dir 2"]);
+    assert_eq!(proto.patterns, ["glob:services/*/api", "dir3"]);
+
+    // The patterns round-trip in order
+    let reloaded_wc = LocalWorkingCopy::load(
+        repo.store().clone(),
+        ws.workspace_root().to_owned(),
+        state_path.clone(),
+    );
+    assert_eq!(reloaded_wc.sparse_patterns().unwrap(), sparse_patterns);
+
+    // Plain paths alone are readable through the prefixes only
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![parse("dir1"), parse(r#""dir 2""#)];
+    locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    let proto = read_proto(&state_path);
+    assert_eq!(proto.prefixes, ["dir1", "dir 2"]);
+    assert!(proto.patterns.is_empty());
+    let reloaded_wc = LocalWorkingCopy::load(
+        repo.store().clone(),
+        ws.workspace_root().to_owned(),
+        state_path,
+    );
+    assert_eq!(reloaded_wc.sparse_patterns().unwrap(), sparse_patterns);
+}
+
 /// Test that sparse patterns are respected on commit
 #[test]
 fn test_sparse_commit() {
@@ -212,7 +415,7 @@ fn test_sparse_commit() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_sparse_patterns(&[dir1_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
@@ -253,7 +456,7 @@ fn test_sparse_commit() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path, dir2_path]);
+    let sparse_patterns = to_sparse_patterns(&[dir1_path, dir2_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
@@ -288,7 +491,7 @@ fn test_sparse_commit_gitignore() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_sparse_patterns(&[dir1_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
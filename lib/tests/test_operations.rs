@@ -27,8 +27,14 @@ use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
+use jj_lib::opset::OpsetAliasesMap;
+use jj_lib::opset::OpsetParseContext;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetAliasesMap;
+use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetParseContext;
 use jj_lib::settings::UserSettings;
 use testutils::create_random_commit;
 use testutils::write_random_commit;
@@ -428,6 +434,23 @@ fn stable_op_id_settings() -> UserSettings {
     UserSettings::from_config(config).unwrap()
 }
 
+fn opset_parse_context<'a>(
+    aliases_map: &'a OpsetAliasesMap,
+    revset_parse_context: Option<&'a RevsetParseContext<'a>>,
+) -> OpsetParseContext<'a> {
+    let now = chrono::DateTime::UNIX_EPOCH.with_timezone(&chrono::Local);
+    OpsetParseContext::new(aliases_map, now.into(), revset_parse_context)
+}
+
+fn resolve_op_with_repo(
+    repo: &ReadonlyRepo,
+    op_str: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    let aliases_map = OpsetAliasesMap::new();
+    let context = opset_parse_context(&aliases_map, None);
+    op_walk::resolve_op_with_repo(repo, &context, op_str)
+}
+
 #[test]
 fn test_resolve_op_id() {
     let settings = stable_op_id_settings();
@@ -456,7 +479,9 @@ fn test_resolve_op_id() {
     "#);
 
     let repo_loader = repo.loader();
-    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str);
+    let aliases_map = OpsetAliasesMap::new();
+    let context = opset_parse_context(&aliases_map, None);
+    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, &context, op_str);
 
     // Full id
     assert_eq!(resolve(&operations[0].id().hex()).unwrap(), operations[0]);
@@ -478,8 +503,10 @@ fn test_resolve_op_id() {
         ))
     );
     // Empty id
+    assert_matches!(resolve(""), Err(OpsetEvaluationError::OpsetParse(_)));
+    // Not a hexadecimal id
     assert_matches!(
-        resolve(""),
+        resolve("xyz"),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::InvalidIdPrefix(_)
         ))
@@ -510,10 +537,7 @@ fn test_resolve_current_op() {
     let test_repo = TestRepo::init_with_settings(&settings);
     let repo = test_repo.repo;
 
-    assert_eq!(
-        op_walk::resolve_op_with_repo(&repo, "@").unwrap(),
-        *repo.operation()
-    );
+    assert_eq!(resolve_op_with_repo(&repo, "@").unwrap(), *repo.operation());
 }
 
 #[test]
@@ -534,16 +558,16 @@ fn test_resolve_op_parents_children() {
     // Parent
     let op2_id_hex = operations[2].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-")).unwrap(),
+        resolve_op_with_repo(repo, &format!("{op2_id_hex}-")).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--")).unwrap(),
+        resolve_op_with_repo(repo, &format!("{op2_id_hex}--")).unwrap(),
         *operations[0]
     );
     // "{op2_id_hex}----" is the root operation
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----")),
+        resolve_op_with_repo(repo, &format!("{op2_id_hex}-----")),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -552,15 +576,15 @@ fn test_resolve_op_parents_children() {
     // Child
     let op0_id_hex = operations[0].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+")).unwrap(),
+        resolve_op_with_repo(repo, &format!("{op0_id_hex}+")).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++")).unwrap(),
+        resolve_op_with_repo(repo, &format!("{op0_id_hex}++")).unwrap(),
         *operations[2]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++")),
+        resolve_op_with_repo(repo, &format!("{op0_id_hex}+++")),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -568,17 +592,17 @@ fn test_resolve_op_parents_children() {
 
     // Child of parent
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+")).unwrap(),
+        resolve_op_with_repo(repo, &format!("{op2_id_hex}--+")).unwrap(),
         *operations[1]
     );
 
     // Child at old repo: new operations shouldn't be visible
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+")).unwrap(),
+        resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+")).unwrap(),
         *operations[1]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+")),
+        resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+")),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -590,20 +614,124 @@ fn test_resolve_op_parents_children() {
     let repo = testutils::commit_transactions(vec![tx1, tx2]);
     let op5_id_hex = repo.operation().id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op5_id_hex}-")),
+        resolve_op_with_repo(&repo, &format!("{op5_id_hex}-")),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
     let op2_id_hex = operations[2].id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op2_id_hex}+")),
+        resolve_op_with_repo(&repo, &format!("{op2_id_hex}+")),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
 }
 
+#[test]
+fn test_resolve_opset() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo0 = &test_repo.repo;
+
+    let mut tx = repo0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    tx.set_tag("args".to_owned(), "jj new".to_owned());
+    let repo1 = tx.commit("add commit").unwrap();
+    let mut tx = repo1.start_transaction();
+    tx.set_is_snapshot(true);
+    let repo2 = tx.commit("snapshot working copy").unwrap();
+    let mut tx = repo2.start_transaction();
+    tx.repo_mut().record_abandoned_commit(&commit_a);
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo3 = tx.commit("abandon commit").unwrap();
+
+    let root_op = repo0.operation();
+    assert_eq!(root_op.id(), repo0.op_store().root_operation_id());
+    let [op1, op2, op3] = [&repo1, &repo2, &repo3].map(|repo| repo.operation());
+
+    let revset_aliases_map = RevsetAliasesMap::new();
+    let revset_extensions = RevsetExtensions::new();
+    let now = chrono::DateTime::UNIX_EPOCH.with_timezone(&chrono::Local);
+    let revset_context = RevsetParseContext::new(
+        &revset_aliases_map,
+        "",
+        now.into(),
+        &revset_extensions,
+        None,
+    );
+    let mut aliases_map = OpsetAliasesMap::new();
+    aliases_map.insert("recent", "@--..@").unwrap();
+    aliases_map.insert("by(x)", "user(x) ~ snapshot()").unwrap();
+    let context = opset_parse_context(&aliases_map, Some(&revset_context));
+    let resolve = |op_str: &str| -> Vec<Operation> {
+        op_walk::resolve_ops_at(repo3.loader(), slice::from_ref(op3), &context, op_str).unwrap()
+    };
+    let op1_id_hex = op1.id().hex();
+
+    // Ranges
+    assert_eq!(resolve("@"), vec![op3.clone()]);
+    assert_eq!(resolve("@--..@"), [op3.clone(), op2.clone()]);
+    assert_eq!(resolve("recent"), [op3.clone(), op2.clone()]);
+    assert_eq!(resolve("..@"), [op3.clone(), op2.clone(), op1.clone()]);
+    assert_eq!(
+        resolve("::@"),
+        [op3.clone(), op2.clone(), op1.clone(), root_op.clone()]
+    );
+    assert_eq!(
+        resolve(&format!("{op1_id_hex}::")),
+        [op3.clone(), op2.clone(), op1.clone()]
+    );
+    assert_eq!(resolve(&format!("{op1_id_hex}+")), vec![op2.clone()]);
+    assert_eq!(
+        resolve(&format!("~::{op1_id_hex}")),
+        [op3.clone(), op2.clone()]
+    );
+    assert_eq!(
+        resolve(&format!("{op1_id_hex} | @")),
+        [op3.clone(), op1.clone()]
+    );
+    assert_eq!(resolve("none()"), []);
+
+    // Filters
+    assert_eq!(resolve("snapshot()"), vec![op2.clone()]);
+    assert_eq!(resolve("~snapshot() & @--::"), [op3.clone(), op1.clone()]);
+    assert_eq!(resolve("description(abandon)"), vec![op3.clone()]);
+    assert_eq!(
+        resolve(r#"description(exact:"add commit")"#),
+        vec![op1.clone()]
+    );
+    assert_eq!(resolve(r#"tags(args=glob:"jj *")"#), vec![op1.clone()]);
+    assert_eq!(
+        resolve(r#"by("test-username@host")"#),
+        [op3.clone(), op1.clone()]
+    );
+    assert_eq!(
+        resolve(r#"time(before:"2000-01-01")"#),
+        vec![root_op.clone()]
+    );
+    assert_eq!(resolve("latest(description(commit))"), vec![op3.clone()]);
+    assert_eq!(
+        resolve(&format!("touched({})", commit_a.id().hex())),
+        [op3.clone(), op1.clone()]
+    );
+
+    // Single operation is expected
+    assert_matches!(
+        op_walk::resolve_op_at(repo3.loader(), slice::from_ref(op3), &context, "..@"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::MultipleOperations { .. }
+        ))
+    );
+    // Revsets can't be resolved without context
+    let context = opset_parse_context(&aliases_map, None);
+    assert_matches!(
+        op_walk::resolve_op_at(repo3.loader(), slice::from_ref(op3), &context, "touched(@)"),
+        Err(OpsetEvaluationError::OpsetParse(err))
+            if *err.kind() == OpsetParseErrorKind::RevsetWithoutRepo
+    );
+}
+
 #[test]
 fn test_gc() {
     let settings = stable_op_id_settings();
@@ -52,6 +52,13 @@ use jj_lib::revset::RevsetWorkspaceContext;
 use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::settings::GitSettings;
+use jj_lib::signing::SignBehavior;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SignResult;
+use jj_lib::signing::Signer;
+use jj_lib::signing::SigningBackend;
+use jj_lib::signing::Verification;
+use jj_lib::test_signing_backend::TestSigningBackend;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
 use testutils::create_random_commit;
@@ -1368,6 +1375,79 @@ fn test_evaluate_expression_ancestors() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_first_ancestors() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let mut graph_builder = CommitGraphBuilder::new(mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit2, &commit3]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit3, &commit4]);
+
+    // The root commit has no first parent
+    assert_eq!(resolve_commit_ids(mut_repo, "first_parent(root())"), vec![]);
+
+    // Only the first parent of a merge commit is returned
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_parent({})", commit4.id())),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_parent({}, 2)", commit4.id())),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("first_parent({} | {})", commit4.id(), commit5.id())
+        ),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+
+    // Ancestors reachable via the second parents are omitted
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({})", commit4.id())),
+        vec![
+            commit4.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+            root_commit.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({})", commit5.id())),
+        vec![
+            commit5.id().clone(),
+            commit3.id().clone(),
+            commit1.id().clone(),
+            root_commit.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({}, 2)", commit4.id())),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({}, 0)", commit4.id())),
+        vec![]
+    );
+
+    // Can be combined with other ancestor queries
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("first_ancestors({}) ~ ::{}", commit5.id(), commit1.id())
+        ),
+        vec![commit5.id().clone(), commit3.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_range() {
     let test_repo = TestRepo::init();
@@ -2447,6 +2527,119 @@ fn test_evaluate_expression_latest() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_sort() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let mut write_commit = |name: &str, author_sec: i64, committer_sec: i64, description: &str| {
+        let builder = create_random_commit(mut_repo).set_description(description);
+        let mut author = builder.author().clone();
+        author.name = name.to_owned();
+        author.timestamp.timestamp = MillisSinceEpoch(author_sec * 1000);
+        let mut committer = builder.committer().clone();
+        committer.timestamp.timestamp = MillisSinceEpoch(committer_sec * 1000);
+        builder
+            .set_author(author)
+            .set_committer(committer)
+            .write()
+            .unwrap()
+    };
+    let commit1 = write_commit("bob", 2, 4, "b");
+    let commit2 = write_commit("alice", 1, 1, "c");
+    let commit3 = write_commit("carol", 3, 3, "a");
+    let commit4 = write_commit("alice", 2, 2, "d");
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(~root(), author_date)"),
+        vec![
+            commit2.id().clone(),
+            commit4.id().clone(),
+            commit1.id().clone(),
+            commit3.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(~root(), author_date, reverse)"),
+        vec![
+            commit3.id().clone(),
+            commit4.id().clone(),
+            commit1.id().clone(),
+            commit2.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(~root(), committer_date, reverse)"),
+        vec![
+            commit1.id().clone(),
+            commit3.id().clone(),
+            commit4.id().clone(),
+            commit2.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(~root(), description)"),
+        vec![
+            commit3.id().clone(),
+            commit1.id().clone(),
+            commit2.id().clone(),
+            commit4.id().clone(),
+        ],
+    );
+
+    // Nested sort acts as a secondary key
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "sort(sort(~root(), author_date, reverse), author_name)"
+        ),
+        vec![
+            commit4.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+            commit3.id().clone(),
+        ],
+    );
+
+    // latest() takes the greatest commits by the sort keys, and preserves the
+    // ordering of its candidates. Ties are resolved by the index position.
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(sort(~root(), author_date), 2)"),
+        vec![commit4.id().clone(), commit3.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "latest(sort(~root(), description, reverse), 2)"),
+        vec![commit4.id().clone(), commit2.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(latest(~root(), 2), description)"),
+        vec![commit3.id().clone(), commit1.id().clone()],
+    );
+
+    // Ordering is discarded when combined with other operators
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "sort(~root(), description) & ~root()"),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ],
+    );
+
+    // Ordering doesn't affect filters
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            "~root() & sort(author(alice), author_date, reverse)"
+        ),
+        vec![commit4.id().clone(), commit2.id().clone()],
+    );
+}
+
 #[test]
 fn test_evaluate_expression_fork_point() {
     let test_repo = TestRepo::init();
@@ -2625,6 +2818,129 @@ fn test_evaluate_expression_fork_point_merge_with_ancestor() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_predecessors_and_successors() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Rewrite 1 into 1b, then 1b into 1c and 1d
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit1 = write_random_commit(mut_repo);
+    let commit2 = write_random_commit(mut_repo);
+    let commit1b = mut_repo
+        .rewrite_commit(&commit1)
+        .set_description("1b")
+        .write()
+        .unwrap();
+    mut_repo.rebase_descendants().unwrap();
+    let commit1c = mut_repo
+        .rewrite_commit(&commit1b)
+        .set_description("1c")
+        .write()
+        .unwrap();
+    mut_repo.rebase_descendants().unwrap();
+    let commit1d = create_random_commit(mut_repo)
+        .set_change_id(commit1b.change_id().clone())
+        .set_predecessors(vec![commit1b.id().clone()])
+        .write()
+        .unwrap();
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit1c.id())),
+        vec![commit1b.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("predecessors({} | {})", commit1c.id(), commit1d.id())
+        ),
+        vec![commit1b.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit1.id())),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit2.id())),
+        vec![]
+    );
+
+    // Hidden successors are omitted
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit1.id())),
+        vec![commit1d.id().clone(), commit1c.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit1b.id())),
+        vec![commit1d.id().clone(), commit1c.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit1c.id())),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit2.id())),
+        vec![]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "successors(none())"), vec![]);
+
+    // Predecessors and successors are looked up in the saved index too
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        resolve_commit_ids(repo.as_ref(), &format!("predecessors({})", commit1d.id())),
+        vec![commit1b.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(repo.as_ref(), &format!("successors({})", commit1.id())),
+        vec![commit1d.id().clone(), commit1c.id().clone()]
+    );
+}
+
+#[test]
+fn test_evaluate_expression_divergent() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit1 = write_random_commit(mut_repo);
+    let commit2 = write_random_commit(mut_repo);
+    let commit3 = create_random_commit(mut_repo)
+        .set_change_id(commit1.change_id().clone())
+        .write()
+        .unwrap();
+    let commit4 = create_random_commit(mut_repo)
+        .set_change_id(commit2.change_id().clone())
+        .write()
+        .unwrap();
+    let commit5 = write_random_commit(mut_repo);
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "divergent()"),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("divergent() & ({} | {})", commit1.id(), commit5.id())
+        ),
+        vec![commit1.id().clone()]
+    );
+
+    // Hidden commits don't make the change divergent
+    mut_repo.remove_head(commit4.id());
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "divergent()"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_merges() {
     let test_repo = TestRepo::init();
@@ -2729,6 +3045,51 @@ fn test_evaluate_expression_description() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_trailer() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = create_random_commit(mut_repo)
+        .set_description("commit 1\n\nChange-Id: I1111\n")
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_description(
+            "commit 2\n\nSigned-off-by: Some One <someone@example.com>\nchange-id: I2222\n",
+        )
+        .write()
+        .unwrap();
+    create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_description("Change-Id: I3333\n")
+        .write()
+        .unwrap();
+
+    // Keys are compared case-insensitively, and the subject isn't a trailer
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Change-Id)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer('change-id', exact:'I1111')"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Signed-off-by, glob:'*@example.com>')"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailer(Signed-off-by, 'Other')"),
+        vec![]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "trailer(Reviewed-by)"), vec![]);
+}
+
 #[test]
 fn test_evaluate_expression_author() {
     let test_repo = TestRepo::init();
@@ -3952,6 +4313,133 @@ fn test_evaluate_expression_conflict() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_signed() {
+    let settings = testutils::user_settings();
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Local, signer, &settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit1 = create_random_commit(mut_repo)
+        .set_sign_behavior(SignBehavior::Force)
+        .set_sign_key(Some("key1".to_owned()))
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .write()
+        .unwrap();
+    let commit3 = create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_sign_behavior(SignBehavior::Force)
+        .set_sign_key(Some("key2".to_owned()))
+        .write()
+        .unwrap();
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed()"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("::{} ~ signed() ~ root()", commit3.id().hex())
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed_by(key2)"),
+        vec![commit3.id().clone()]
+    );
+    // The signer's display name is matched too
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed_by(exact:test-display)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signature_status(good)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signature_status(bad)"),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signature_status(unknown)"),
+        vec![]
+    );
+}
+
+/// Signing backend which fails to verify signatures made with the "broken" key.
+#[derive(Debug)]
+struct BrokenKeySigningBackend;
+
+impl SigningBackend for BrokenKeySigningBackend {
+    fn name(&self) -> &str {
+        "broken-key"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        TestSigningBackend.can_read(signature)
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> SignResult<Vec<u8>> {
+        TestSigningBackend.sign(data, key)
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let verification = TestSigningBackend.verify(data, signature)?;
+        if verification.key.as_deref() == Some("broken") {
+            return Err(SignError::Backend("cannot verify".into()));
+        }
+        Ok(verification)
+    }
+}
+
+#[test]
+fn test_evaluate_expression_signed_backend_error() {
+    let settings = testutils::user_settings();
+    let signer = Signer::new(Some(Box::new(BrokenKeySigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Local, signer, &settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit1 = create_random_commit(mut_repo)
+        .set_sign_behavior(SignBehavior::Force)
+        .set_sign_key(Some("key1".to_owned()))
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_sign_behavior(SignBehavior::Force)
+        .set_sign_key(Some("broken".to_owned()))
+        .write()
+        .unwrap();
+
+    // A commit which fails to verify doesn't abort the evaluation
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed()"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed_by(glob:\"*\")"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signature_status(good)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signature_status(unknown)"),
+        vec![commit2.id().clone()]
+    );
+}
+
 #[test]
 fn test_reverse_graph() {
     let test_repo = TestRepo::init();
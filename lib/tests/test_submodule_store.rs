@@ -0,0 +1,169 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
+use jj_lib::default_submodule_store::DefaultSubmoduleStore;
+use jj_lib::git::RemoteCallbacks;
+use jj_lib::submodule_store::SubmoduleStore;
+use jj_lib::submodule_store::SubmoduleStoreError;
+use testutils::new_temp_dir;
+
+/// Commits the given files to `refs/heads/main`, replacing the previous tree.
+fn git_commit(git_repo: &git2::Repository, message: &str, files: &[(&str, &str)]) -> CommitId {
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    for (name, contents) in files {
+        let blob_id = git_repo.blob(contents.as_bytes()).unwrap();
+        tree_builder.insert(name, blob_id, 0o100644).unwrap();
+    }
+    let tree = git_repo.find_tree(tree_builder.write().unwrap()).unwrap();
+    let parent = git_repo
+        .find_reference("refs/heads/main")
+        .and_then(|reference| reference.peel_to_commit())
+        .ok();
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    let oid = git_repo
+        .commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+    CommitId::from_bytes(oid.as_bytes())
+}
+
+fn read_dir_names(path: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_fetch_and_check_out() {
+    let temp_dir = new_temp_dir();
+    let upstream_path = temp_dir.path().join("upstream");
+    let upstream_repo = git2::Repository::init(&upstream_path).unwrap();
+    let commit1 = git_commit(&upstream_repo, "first\n", &[("a", "a1\n"), ("b", "b1\n")]);
+    let store_path = temp_dir.path().join("store");
+    fs::create_dir(&store_path).unwrap();
+    let store = DefaultSubmoduleStore::init(&store_path);
+    let url = upstream_path.to_str().unwrap();
+
+    assert_eq!(store.get_commit(&commit1).unwrap(), None);
+    let destination = temp_dir.path().join("checkout");
+    assert_matches!(
+        store.check_out("sub", None, &commit1, &destination),
+        Err(SubmoduleStoreError::NotFetched(name)) if name == "sub"
+    );
+
+    store.fetch("sub", url, RemoteCallbacks::default()).unwrap();
+    let commit = store.get_commit(&commit1).unwrap().unwrap();
+    assert_eq!(commit.id, commit1);
+    assert_eq!(commit.description, "first\n");
+    store
+        .check_out("sub", None, &commit1, &destination)
+        .unwrap();
+    assert_eq!(read_dir_names(&destination), ["a", "b"]);
+    assert_eq!(fs::read_to_string(destination.join("a")).unwrap(), "a1\n");
+
+    // New commits aren't available until they're fetched.
+    let commit2 = git_commit(&upstream_repo, "second\n", &[("a", "a2\n"), ("c", "c2\n")]);
+    assert_matches!(
+        store.check_out("sub", Some(&commit1), &commit2, &destination),
+        Err(SubmoduleStoreError::CommitNotFound { .. })
+    );
+    store.fetch("sub", url, RemoteCallbacks::default()).unwrap();
+    store
+        .check_out("sub", Some(&commit1), &commit2, &destination)
+        .unwrap();
+    assert_eq!(read_dir_names(&destination), ["a", "c"]);
+    assert_eq!(fs::read_to_string(destination.join("a")).unwrap(), "a2\n");
+
+    // The store can be reloaded from disk.
+    let store = DefaultSubmoduleStore::load(&store_path);
+    assert_eq!(
+        store.get_commit(&commit2).unwrap().unwrap().description,
+        "second\n"
+    );
+}
+
+#[test]
+fn test_check_out_uncommitted_changes() {
+    let temp_dir = new_temp_dir();
+    let upstream_path = temp_dir.path().join("upstream");
+    let upstream_repo = git2::Repository::init(&upstream_path).unwrap();
+    let commit1 = git_commit(&upstream_repo, "first\n", &[("a", "a1\n")]);
+    let commit2 = git_commit(&upstream_repo, "second\n", &[("a", "a2\n")]);
+    let store_path = temp_dir.path().join("store");
+    fs::create_dir(&store_path).unwrap();
+    let store = DefaultSubmoduleStore::init(&store_path);
+    let url = upstream_path.to_str().unwrap();
+    store.fetch("sub", url, RemoteCallbacks::default()).unwrap();
+
+    let destination = temp_dir.path().join("checkout");
+    store
+        .check_out("sub", None, &commit1, &destination)
+        .unwrap();
+    fs::write(destination.join("a"), "modified\n").unwrap();
+
+    // Modified files aren't overwritten.
+    assert_matches!(
+        store.check_out("sub", Some(&commit1), &commit2, &destination),
+        Err(SubmoduleStoreError::UncommittedChanges(name)) if name == "sub"
+    );
+    assert_eq!(
+        fs::read_to_string(destination.join("a")).unwrap(),
+        "modified\n"
+    );
+
+    // Neither are files of an unknown commit.
+    assert_matches!(
+        store.check_out("sub", None, &commit2, &destination),
+        Err(SubmoduleStoreError::UncommittedChanges(_))
+    );
+
+    fs::write(destination.join("a"), "a1\n").unwrap();
+    store
+        .check_out("sub", Some(&commit1), &commit2, &destination)
+        .unwrap();
+    assert_eq!(fs::read_to_string(destination.join("a")).unwrap(), "a2\n");
+}
+
+#[test]
+fn test_invalid_name() {
+    let temp_dir = new_temp_dir();
+    let store = DefaultSubmoduleStore::init(temp_dir.path());
+    let url = temp_dir.path().to_str().unwrap();
+    assert_matches!(
+        store.fetch("../escape", url, RemoteCallbacks::default()),
+        Err(SubmoduleStoreError::InvalidName(_))
+    );
+    assert_matches!(
+        store.fetch("", url, RemoteCallbacks::default()),
+        Err(SubmoduleStoreError::InvalidName(_))
+    );
+    assert_matches!(
+        store.fetch("/absolute", url, RemoteCallbacks::default()),
+        Err(SubmoduleStoreError::InvalidName(_))
+    );
+}
@@ -70,6 +70,7 @@ use tempfile::TempDir;
 use crate::test_backend::TestBackendFactory;
 
 pub mod test_backend;
+pub mod test_lfs_server;
 
 pub fn hermetic_libgit2() {
     // libgit2 respects init.defaultBranch (and possibly other config
@@ -0,0 +1,154 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal Git LFS server implementing the batch API with the "basic"
+//! transfer adapter, for testing.
+
+use std::collections::HashMap;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+use serde_json::json;
+
+type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// LFS server listening on localhost which keeps the objects in memory.
+pub struct TestLfsServer {
+    url: String,
+    objects: Objects,
+}
+
+impl TestLfsServer {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let objects = Objects::default();
+        {
+            let url = url.clone();
+            let objects = objects.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else {
+                        break;
+                    };
+                    handle_request(stream, &url, &objects);
+                }
+            });
+        }
+        TestLfsServer { url, objects }
+    }
+
+    /// The URL to use as the LFS server URL of a remote.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn object(&self, oid: &str) -> Option<Vec<u8>> {
+        self.objects.lock().unwrap().get(oid).cloned()
+    }
+
+    pub fn insert_object(&self, oid: &str, content: &[u8]) {
+        self.objects
+            .lock()
+            .unwrap()
+            .insert(oid.to_owned(), content.to_vec());
+    }
+}
+
+fn handle_request(mut stream: TcpStream, url: &str, objects: &Objects) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    let mut parts = request_line.split_ascii_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let (status, response) = match (method, path.strip_prefix("/objects/")) {
+        ("POST", Some("batch")) => (200, batch(&body, url, objects)),
+        ("GET", Some(oid)) => match objects.lock().unwrap().get(oid) {
+            Some(content) => (200, content.clone()),
+            None => (404, vec![]),
+        },
+        ("PUT", Some(oid)) => {
+            objects.lock().unwrap().insert(oid.to_owned(), body);
+            (200, vec![])
+        }
+        _ => (404, vec![]),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.len()
+    )
+    .unwrap();
+    stream.write_all(&response).unwrap();
+}
+
+fn batch(body: &[u8], url: &str, objects: &Objects) -> Vec<u8> {
+    let request: serde_json::Value = serde_json::from_slice(body).unwrap();
+    let operation = request["operation"].as_str().unwrap();
+    let objects = objects.lock().unwrap();
+    let response_objects = request["objects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|object| {
+            let oid = object["oid"].as_str().unwrap();
+            let size = &object["size"];
+            let href = format!("{url}/objects/{oid}");
+            match (operation, objects.contains_key(oid)) {
+                ("download", true) => json!({
+                    "oid": oid,
+                    "size": size,
+                    "actions": {"download": {"href": href}},
+                }),
+                ("download", false) => json!({
+                    "oid": oid,
+                    "size": size,
+                    "error": {"code": 404, "message": "Object does not exist"},
+                }),
+                (_, true) => json!({"oid": oid, "size": size}),
+                (_, false) => json!({
+                    "oid": oid,
+                    "size": size,
+                    "actions": {"upload": {"href": href}},
+                }),
+            }
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_vec(&json!({"transfer": "basic", "objects": response_objects})).unwrap()
+}